@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Check Python files for syntax errors and lint diagnostics",
+    args_conflicts_with_subcommands = true
+)]
+pub struct CmdArgs {
+    #[command(subcommand)]
+    pub command: Option<DebugCommand>,
+
+    /// Files or directories to check
+    #[arg(default_value = ".")]
+    pub paths: Vec<PathBuf>,
+
+    /// Only check files matching these globs, relative to the checked directory
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// Skip files and directories matching these globs, relative to the checked directory
+    #[arg(long)]
+    pub exclude: Vec<String>,
+
+    /// Output format
+    #[arg(long, short, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Python version the files are parsed as, such as `3.12`
+    #[arg(long)]
+    pub python_version: Option<String>,
+
+    /// Fail on warnings as well as errors
+    #[arg(long)]
+    pub warnings_as_errors: bool,
+
+    /// Colored text output
+    #[arg(long, default_value = "auto")]
+    pub color: ColorChoice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
+    Github,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Dumps of the parser's view of a single file, for debugging the parser
+#[derive(Debug, Subcommand)]
+pub enum DebugCommand {
+    /// Print the tokens produced by the lexer
+    Tokens(DebugArgs),
+    /// Print the concrete syntax tree
+    Cst(DebugArgs),
+    /// Print the typed statement and expression tree
+    Ast(DebugArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DebugArgs {
+    /// File to read, or `-` for stdin
+    #[arg(default_value = "-")]
+    pub file: PathBuf,
+
+    /// Python version the file is parsed as, such as `3.12`
+    #[arg(long)]
+    pub level: Option<String>,
+
+    /// Print JSON instead of text
+    #[arg(long)]
+    pub json: bool,
+}
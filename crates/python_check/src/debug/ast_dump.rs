@@ -0,0 +1,94 @@
+use python_parser::{
+    ParserConfig, PyAst, PyAstNode, PyExpr, PyParser, PyPattern, PyStat, PySyntaxKind, PySyntaxNode,
+};
+use serde_json::{Value, json};
+
+use super::{errors_json, format_errors, to_json_string};
+
+/// A node of the typed view. Nodes without a typed wrapper are left out and
+/// their children take their place.
+struct AstNode {
+    name: String,
+    start: u32,
+    end: u32,
+    text: Option<String>,
+    children: Vec<AstNode>,
+}
+
+/// The nodes which have a typed `PyAst` wrapper, statements and expressions
+/// marked as such
+pub fn dump_ast(text: &str, config: ParserConfig, as_json: bool) -> String {
+    let tree = PyParser::parse(text, config, None);
+    let nodes = collect_nodes(&tree.get_red_root());
+
+    if as_json {
+        return to_json_string(&json!({
+            "ast": nodes.iter().map(node_json).collect::<Vec<_>>(),
+            "errors": errors_json(tree.get_errors()),
+        }));
+    }
+
+    let mut output = String::new();
+    for node in &nodes {
+        write_node(node, 0, &mut output);
+    }
+    output.push_str(&format_errors(tree.get_errors()));
+    output
+}
+
+fn collect_nodes(node: &PySyntaxNode) -> Vec<AstNode> {
+    let kind = node.kind().to_syntax();
+    let children: Vec<AstNode> = node
+        .children()
+        .flat_map(|child| collect_nodes(&child))
+        .collect();
+    let name = if PyStat::can_cast(kind) {
+        format!("Stat::{:?}", kind)
+    } else if PyExpr::can_cast(kind) {
+        format!("Expr::{:?}", kind)
+    } else if PyPattern::can_cast(kind) {
+        format!("Pattern::{:?}", kind)
+    } else if PyAst::can_cast(kind) {
+        format!("{:?}", kind)
+    } else {
+        return children;
+    };
+    let range = node.text_range();
+    // names and literals are leaves, their text is the interesting part
+    let text = matches!(kind, PySyntaxKind::NameExpr | PySyntaxKind::LiteralExpr)
+        .then(|| node.text().to_string());
+    vec![AstNode {
+        name,
+        start: range.start().into(),
+        end: range.end().into(),
+        text,
+        children,
+    }]
+}
+
+fn write_node(node: &AstNode, depth: usize, output: &mut String) {
+    output.push_str(&format!(
+        "{}{}@{}..{}",
+        "  ".repeat(depth),
+        node.name,
+        node.start,
+        node.end
+    ));
+    if let Some(text) = &node.text {
+        output.push_str(&format!(" {:?}", text));
+    }
+    output.push('\n');
+    for child in &node.children {
+        write_node(child, depth + 1, output);
+    }
+}
+
+fn node_json(node: &AstNode) -> Value {
+    json!({
+        "kind": node.name,
+        "start": node.start,
+        "end": node.end,
+        "text": node.text,
+        "children": node.children.iter().map(node_json).collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,74 @@
+use python_parser::{ParserConfig, PyParser, PySyntaxElement, PySyntaxNode};
+use serde_json::{Value, json};
+
+use super::{errors_json, format_errors, to_json_string};
+
+/// The rowan tree with every node and token, indented by depth
+pub fn dump_cst(text: &str, config: ParserConfig, as_json: bool) -> String {
+    let tree = PyParser::parse(text, config, None);
+    let root = tree.get_red_root();
+
+    if as_json {
+        return to_json_string(&json!({
+            "tree": node_json(&root),
+            "errors": errors_json(tree.get_errors()),
+        }));
+    }
+
+    let mut output = String::new();
+    write_node(&root, 0, &mut output);
+    output.push_str(&format_errors(tree.get_errors()));
+    output
+}
+
+fn write_node(node: &PySyntaxNode, depth: usize, output: &mut String) {
+    let range = node.text_range();
+    output.push_str(&format!(
+        "{}{:?}@{}..{}\n",
+        "  ".repeat(depth),
+        node.kind().to_syntax(),
+        u32::from(range.start()),
+        u32::from(range.end())
+    ));
+    for child in node.children_with_tokens() {
+        match child {
+            PySyntaxElement::Node(node) => write_node(&node, depth + 1, output),
+            PySyntaxElement::Token(token) => {
+                let range = token.text_range();
+                output.push_str(&format!(
+                    "{}{:?}@{}..{} {:?}\n",
+                    "  ".repeat(depth + 1),
+                    token.kind().to_token(),
+                    u32::from(range.start()),
+                    u32::from(range.end()),
+                    token.text()
+                ));
+            }
+        }
+    }
+}
+
+fn node_json(node: &PySyntaxNode) -> Value {
+    let range = node.text_range();
+    let children: Vec<Value> = node
+        .children_with_tokens()
+        .map(|child| match child {
+            PySyntaxElement::Node(node) => node_json(&node),
+            PySyntaxElement::Token(token) => {
+                let range = token.text_range();
+                json!({
+                    "kind": format!("{:?}", token.kind().to_token()),
+                    "start": u32::from(range.start()),
+                    "end": u32::from(range.end()),
+                    "text": token.text(),
+                })
+            }
+        })
+        .collect();
+    json!({
+        "kind": format!("{:?}", node.kind().to_syntax()),
+        "start": u32::from(range.start()),
+        "end": u32::from(range.end()),
+        "children": children,
+    })
+}
@@ -0,0 +1,135 @@
+mod ast_dump;
+mod cst_dump;
+mod token_dump;
+
+use std::io::Read;
+
+use python_parser::{ParserConfig, PyParseError};
+use serde_json::{Value, json};
+
+use crate::cmd_args::{DebugArgs, DebugCommand};
+use crate::workspace::parser_config;
+
+type DumpFn = fn(&str, ParserConfig, bool) -> String;
+
+/// Print the dump a debug command asks for
+pub fn run_debug(command: DebugCommand) -> Result<(), String> {
+    let (args, dump): (&DebugArgs, DumpFn) = match &command {
+        DebugCommand::Tokens(args) => (args, token_dump::dump_tokens),
+        DebugCommand::Cst(args) => (args, cst_dump::dump_cst),
+        DebugCommand::Ast(args) => (args, ast_dump::dump_ast),
+    };
+    let config = match &args.level {
+        Some(level) => parser_config(level)?,
+        None => ParserConfig::default(),
+    };
+    let text = read_source(args)?;
+    print!("{}", dump(&text, config, args.json));
+    Ok(())
+}
+
+fn read_source(args: &DebugArgs) -> Result<String, String> {
+    if args.file.as_os_str() == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|err| format!("failed to read stdin: {}", err))?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(&args.file)
+            .map_err(|err| format!("failed to read {}: {}", args.file.display(), err))
+    }
+}
+
+fn format_errors(errors: &[PyParseError]) -> String {
+    errors
+        .iter()
+        .map(|error| {
+            format!(
+                "{:?}@{}..{}: {}\n",
+                error.kind,
+                u32::from(error.range.start()),
+                u32::from(error.range.end()),
+                error.message
+            )
+        })
+        .collect()
+}
+
+fn errors_json(errors: &[PyParseError]) -> Value {
+    errors
+        .iter()
+        .map(|error| {
+            json!({
+                "kind": format!("{:?}", error.kind),
+                "message": error.message,
+                "start": u32::from(error.range.start()),
+                "end": u32::from(error.range.end()),
+            })
+        })
+        .collect()
+}
+
+fn to_json_string(value: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(value).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_tokens() {
+        let text = token_dump::dump_tokens("x = 1\n", ParserConfig::default(), false);
+        assert_eq!(
+            text,
+            r#"TkName@0..1 "x"
+TkWhitespace@1..2 " "
+TkAssign@2..3 "="
+TkWhitespace@3..4 " "
+TkInt@4..5 "1"
+TkNewline@5..6 "\n"
+"#
+        );
+    }
+
+    #[test]
+    fn test_dump_cst() {
+        let text = cst_dump::dump_cst("pass\n", ParserConfig::default(), false);
+        assert_eq!(
+            text,
+            r#"Module@0..5
+  Suite@0..5
+    PassStmt@0..4
+      TkPass@0..4 "pass"
+    TkNewline@4..5 "\n"
+"#
+        );
+    }
+
+    #[test]
+    fn test_dump_ast() {
+        let text = ast_dump::dump_ast("print(x)\n", ParserConfig::default(), false);
+        assert_eq!(
+            text,
+            r#"Module@0..9
+  Suite@0..9
+    Stat::ExprStmt@0..8
+      Expr::CallExpr@0..8
+        Expr::NameExpr@0..5 "print"
+        Arguments@5..8
+          Expr::NameExpr@6..7 "x"
+"#
+        );
+    }
+
+    #[test]
+    fn test_dump_errors_json() {
+        let text = cst_dump::dump_cst("def f(:\n", ParserConfig::default(), true);
+        let value: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["tree"]["kind"], "Module");
+        assert_eq!(value["errors"][0]["kind"], "SyntaxError");
+    }
+}
@@ -0,0 +1,49 @@
+use python_parser::{ParserConfig, PyLexer, PyParseError, PyTokenData, Reader};
+use serde_json::json;
+
+use super::{errors_json, format_errors, to_json_string};
+
+/// The lexer's tokens with their kinds, byte ranges and text
+pub fn dump_tokens(text: &str, config: ParserConfig, as_json: bool) -> String {
+    let mut errors: Vec<PyParseError> = Vec::new();
+    let tokens =
+        PyLexer::new(Reader::new(text), config.lexer_config(), Some(&mut errors)).tokenize();
+
+    if as_json {
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                json!({
+                    "kind": format!("{:?}", token.kind),
+                    "start": token.range.start_offset,
+                    "end": token.range.end_offset(),
+                    "text": token_text(text, token),
+                })
+            })
+            .collect();
+        return to_json_string(&json!({
+            "tokens": tokens,
+            "errors": errors_json(&errors),
+        }));
+    }
+
+    let mut output: String = tokens
+        .iter()
+        .map(|token| {
+            format!(
+                "{:?}@{}..{} {:?}\n",
+                token.kind,
+                token.range.start_offset,
+                token.range.end_offset(),
+                token_text(text, token)
+            )
+        })
+        .collect();
+    output.push_str(&format_errors(&errors));
+    output
+}
+
+fn token_text<'a>(text: &'a str, token: &PyTokenData) -> &'a str {
+    text.get(token.range.start_offset..token.range.end_offset())
+        .unwrap_or_default()
+}
@@ -0,0 +1,90 @@
+pub mod cmd_args;
+mod debug;
+mod output;
+mod workspace;
+
+use std::io::{IsTerminal, Write};
+
+use cmd_args::{CmdArgs, ColorChoice};
+use lsp_types::DiagnosticSeverity;
+use python_code_analysis::{FileId, PyAnalysis, uri_to_file_path};
+
+pub use debug::run_debug;
+pub use output::CheckedFile;
+
+/// Check the files named by `cmd_args` and print the diagnostics. Returns
+/// whether any of them should fail the run.
+pub fn run_check(cmd_args: CmdArgs) -> Result<bool, String> {
+    let (analysis, file_ids) = workspace::load_workspace(&cmd_args)?;
+    let files = diagnose_files(&analysis, &file_ids);
+
+    let color = match cmd_args.color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => std::io::stdout().is_terminal(),
+    };
+    let mut stdout = std::io::stdout().lock();
+    let registry = analysis.get_lint_registry();
+    output::write_output(cmd_args.format, &files, registry, color, &mut stdout)
+        .and_then(|_| stdout.flush())
+        .map_err(|err| format!("failed to write output: {}", err))?;
+
+    let has_errors = files
+        .iter()
+        .flat_map(|file| &file.diagnostics)
+        .any(|diagnostic| match diagnostic.severity {
+            Some(DiagnosticSeverity::ERROR) => true,
+            Some(DiagnosticSeverity::WARNING) => cmd_args.warnings_as_errors,
+            _ => false,
+        });
+    Ok(has_errors)
+}
+
+/// Diagnose the files on all cores, in the order they were given
+fn diagnose_files(analysis: &PyAnalysis, file_ids: &[FileId]) -> Vec<CheckedFile> {
+    let threads = std::thread::available_parallelism()
+        .map(|it| it.get())
+        .unwrap_or(1);
+    let chunk_size = file_ids.len().div_ceil(threads).max(1);
+    let current_dir = std::env::current_dir().ok();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = file_ids
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let current_dir = current_dir.as_deref();
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter_map(|file_id| check_file(analysis, *file_id, current_dir))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+fn check_file(
+    analysis: &PyAnalysis,
+    file_id: FileId,
+    current_dir: Option<&std::path::Path>,
+) -> Option<CheckedFile> {
+    let vfs = analysis.compilation.get_db().get_vfs();
+    let path = uri_to_file_path(&vfs.get_uri(&file_id)?)?;
+    // paths under the current directory are shown relative to it
+    let display_path = current_dir
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let mut diagnostics = analysis.diagnose_file(file_id)?;
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.range.start, diagnostic.range.end));
+    Some(CheckedFile {
+        path: display_path,
+        text: vfs.get_file_content(&file_id)?.clone(),
+        diagnostics,
+    })
+}
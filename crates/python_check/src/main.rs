@@ -0,0 +1,18 @@
+use clap::Parser;
+use python_check::cmd_args::CmdArgs;
+
+fn main() {
+    let mut cmd_args = CmdArgs::parse();
+    let result = match cmd_args.command.take() {
+        Some(command) => python_check::run_debug(command).map(|_| false),
+        None => python_check::run_check(cmd_args),
+    };
+    let exit_code = match result {
+        Ok(has_errors) => i32::from(has_errors),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            2
+        }
+    };
+    std::process::exit(exit_code);
+}
@@ -0,0 +1,42 @@
+use std::io::{self, Write};
+
+use lsp_types::DiagnosticSeverity;
+
+use super::{CheckedFile, code_text};
+
+/// GitHub Actions workflow commands, which annotate the lines of a pull request
+pub fn write_github(files: &[CheckedFile], out: &mut dyn Write) -> io::Result<()> {
+    for file in files {
+        for diagnostic in &file.diagnostics {
+            let command = match diagnostic.severity {
+                Some(DiagnosticSeverity::ERROR) => "error",
+                Some(DiagnosticSeverity::WARNING) => "warning",
+                _ => "notice",
+            };
+            let range = diagnostic.range;
+            writeln!(
+                out,
+                "::{} title={},file={},line={},col={},endLine={},endColumn={}::{}",
+                command,
+                escape_property(&code_text(diagnostic)),
+                escape_property(&file.path),
+                range.start.line + 1,
+                range.start.character + 1,
+                range.end.line + 1,
+                range.end.character + 1,
+                escape_data(&diagnostic.message)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
@@ -0,0 +1,42 @@
+use std::io::{self, Write};
+
+use lsp_types::Position;
+use python_code_analysis::LintRegistry;
+use serde_json::{Value, json};
+
+use super::{CheckedFile, code_text, find_meta, severity_name};
+
+/// A flat list of diagnostics with 1-based lines and columns
+pub fn write_json(
+    files: &[CheckedFile],
+    registry: &LintRegistry,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let diagnostics: Vec<Value> = files
+        .iter()
+        .flat_map(|file| {
+            file.diagnostics.iter().map(|diagnostic| {
+                json!({
+                    "path": file.path,
+                    "code": code_text(diagnostic),
+                    "name": find_meta(registry, diagnostic).map(|meta| meta.name),
+                    "severity": severity_name(diagnostic.severity),
+                    "message": diagnostic.message,
+                    "range": {
+                        "start": position_json(diagnostic.range.start),
+                        "end": position_json(diagnostic.range.end),
+                    },
+                })
+            })
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut *out, &diagnostics)?;
+    writeln!(out)
+}
+
+fn position_json(position: Position) -> Value {
+    json!({
+        "line": position.line + 1,
+        "column": position.character + 1,
+    })
+}
@@ -0,0 +1,174 @@
+mod github_output;
+mod json_output;
+mod sarif_output;
+mod text_output;
+
+use std::io::{self, Write};
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use python_code_analysis::{LintRegistry, LintRuleMeta};
+
+use crate::cmd_args::OutputFormat;
+
+/// A checked file and its diagnostics, ready to print
+#[derive(Debug)]
+pub struct CheckedFile {
+    /// The path as shown to the user, relative to the current directory when possible
+    pub path: String,
+    pub text: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// `registry` describes the codes the diagnostics are reported under
+pub fn write_output(
+    format: OutputFormat,
+    files: &[CheckedFile],
+    registry: &LintRegistry,
+    color: bool,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => text_output::write_text(files, color, out),
+        OutputFormat::Json => json_output::write_json(files, registry, out),
+        OutputFormat::Sarif => sarif_output::write_sarif(files, registry, out),
+        OutputFormat::Github => github_output::write_github(files, out),
+    }
+}
+
+fn severity_name(severity: Option<DiagnosticSeverity>) -> &'static str {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "information",
+        _ => "hint",
+    }
+}
+
+fn code_text(diagnostic: &Diagnostic) -> String {
+    match &diagnostic.code {
+        Some(NumberOrString::String(code)) => code.clone(),
+        Some(NumberOrString::Number(code)) => code.to_string(),
+        None => String::new(),
+    }
+}
+
+fn find_meta<'a>(registry: &'a LintRegistry, diagnostic: &Diagnostic) -> Option<&'a LintRuleMeta> {
+    registry.find_meta(&code_text(diagnostic))
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use super::*;
+
+    fn checked_file() -> CheckedFile {
+        CheckedFile {
+            path: "pkg/main.py".to_string(),
+            text: "import os\nprint(\tname)\n".to_string(),
+            diagnostics: vec![Diagnostic {
+                range: Range::new(Position::new(1, 7), Position::new(1, 11)),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String("PY004".to_string())),
+                message: "`name` is not defined, 100%".to_string(),
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn render(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_output(
+            format,
+            &[checked_file()],
+            &LintRegistry::default(),
+            false,
+            &mut out,
+        )
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_text_output() {
+        let expected = "\
+error[PY004]: `name` is not defined, 100%
+ --> pkg/main.py:2:8
+  |
+2 | print(\tname)
+  |       \t^^^^
+  |
+
+Found 1 error in 1 file
+";
+        assert_eq!(render(OutputFormat::Text), expected);
+    }
+
+    #[test]
+    fn test_text_summary_severities() {
+        let mut file = checked_file();
+        let hint = Diagnostic {
+            range: Range::new(Position::new(0, 7), Position::new(0, 9)),
+            severity: Some(DiagnosticSeverity::HINT),
+            code: Some(NumberOrString::String("PY005".to_string())),
+            message: "`os` is imported but never used".to_string(),
+            ..Default::default()
+        };
+        file.diagnostics.push(hint.clone());
+        file.diagnostics.push(Diagnostic {
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            ..hint.clone()
+        });
+        file.diagnostics.push(Diagnostic {
+            severity: None,
+            ..hint
+        });
+        let mut out = Vec::new();
+        write_output(
+            OutputFormat::Text,
+            &[file],
+            &LintRegistry::default(),
+            false,
+            &mut out,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("hint[PY005]: `os` is imported but never used"));
+        assert!(text.ends_with("Found 1 error, 1 information, 2 hints in 1 file\n"));
+    }
+
+    #[test]
+    fn test_github_output() {
+        assert_eq!(
+            render(OutputFormat::Github),
+            "::error title=PY004,file=pkg/main.py,line=2,col=8,endLine=2,endColumn=12::`name` is not defined, 100%25\n"
+        );
+    }
+
+    #[test]
+    fn test_json_output() {
+        let value: serde_json::Value = serde_json::from_str(&render(OutputFormat::Json)).unwrap();
+        assert_eq!(value[0]["name"], "undefined-name");
+        assert_eq!(value[0]["range"]["start"]["line"], 2);
+        assert_eq!(value[0]["range"]["end"]["column"], 12);
+    }
+
+    #[test]
+    fn test_sarif_output() {
+        let value: serde_json::Value = serde_json::from_str(&render(OutputFormat::Sarif)).unwrap();
+        let run = &value["runs"][0];
+        assert_eq!(run["results"][0]["ruleId"], "PY004");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            8
+        );
+        assert!(
+            run["tool"]["driver"]["rules"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|rule| rule["id"] == "PY004")
+        );
+    }
+}
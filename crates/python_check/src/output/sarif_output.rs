@@ -0,0 +1,78 @@
+use std::io::{self, Write};
+
+use lsp_types::DiagnosticSeverity;
+use python_code_analysis::LintRegistry;
+use serde_json::{Value, json};
+
+use super::{CheckedFile, code_text};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A SARIF 2.1.0 log, as consumed by code scanning services
+pub fn write_sarif(
+    files: &[CheckedFile],
+    registry: &LintRegistry,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let rules: Vec<Value> = registry
+        .get_metas()
+        .into_iter()
+        .map(|meta| {
+            json!({
+                "id": meta.code,
+                "name": meta.name,
+                "shortDescription": { "text": meta.description },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = files
+        .iter()
+        .flat_map(|file| {
+            file.diagnostics.iter().map(|diagnostic| {
+                let range = diagnostic.range;
+                json!({
+                    "ruleId": code_text(diagnostic),
+                    "level": sarif_level(diagnostic.severity),
+                    "message": { "text": diagnostic.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": file.path },
+                            "region": {
+                                "startLine": range.start.line + 1,
+                                "startColumn": range.start.character + 1,
+                                "endLine": range.end.line + 1,
+                                "endColumn": range.end.character + 1,
+                            },
+                        },
+                    }],
+                })
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *out, &log)?;
+    writeln!(out)
+}
+
+fn sarif_level(severity: Option<DiagnosticSeverity>) -> &'static str {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        _ => "note",
+    }
+}
@@ -0,0 +1,170 @@
+use std::io::{self, Write};
+
+use ansi_term::{Color, Style};
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+
+use super::{CheckedFile, code_text, severity_name};
+
+pub fn write_text(files: &[CheckedFile], color: bool, out: &mut dyn Write) -> io::Result<()> {
+    let paint = Painter { color };
+    // the count of each severity, named in the footer as in the headers
+    let severities = [
+        DiagnosticSeverity::ERROR,
+        DiagnosticSeverity::WARNING,
+        DiagnosticSeverity::INFORMATION,
+        DiagnosticSeverity::HINT,
+    ];
+    let mut counts = [0; 4];
+    let mut failed_files = 0;
+    for file in files {
+        if !file.diagnostics.is_empty() {
+            failed_files += 1;
+        }
+        let lines: Vec<&str> = file.text.lines().collect();
+        for diagnostic in &file.diagnostics {
+            let name = severity_name(diagnostic.severity);
+            if let Some(index) = severities
+                .iter()
+                .position(|severity| severity_name(Some(*severity)) == name)
+            {
+                counts[index] += 1;
+            }
+            write_diagnostic(&paint, &file.path, &lines, diagnostic, out)?;
+        }
+    }
+
+    if counts.iter().all(|count| *count == 0) {
+        writeln!(
+            out,
+            "{}",
+            paint.paint(Color::Green.bold(), "No problems found")
+        )?;
+        return Ok(());
+    }
+    let counts: Vec<String> = severities
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(severity, count)| plural(count, severity_name(Some(*severity))))
+        .collect();
+    writeln!(
+        out,
+        "{}",
+        paint.paint(
+            Style::new().bold(),
+            &format!(
+                "Found {} in {}",
+                counts.join(", "),
+                plural(failed_files, "file")
+            )
+        )
+    )
+}
+
+fn write_diagnostic(
+    paint: &Painter,
+    path: &str,
+    lines: &[&str],
+    diagnostic: &Diagnostic,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let severity_style = severity_style(diagnostic.severity);
+    let gutter_style = Color::Blue.bold();
+    let start = diagnostic.range.start;
+    let end = diagnostic.range.end;
+    let line_number = (start.line + 1).to_string();
+    let pad = " ".repeat(line_number.len());
+
+    let code = code_text(diagnostic);
+    let header = if code.is_empty() {
+        severity_name(diagnostic.severity).to_string()
+    } else {
+        format!("{}[{}]", severity_name(diagnostic.severity), code)
+    };
+    writeln!(
+        out,
+        "{}: {}",
+        paint.paint(severity_style, &header),
+        paint.paint(Style::new().bold(), &diagnostic.message)
+    )?;
+    writeln!(
+        out,
+        "{}{} {}:{}:{}",
+        pad,
+        paint.paint(gutter_style, "-->"),
+        path,
+        start.line + 1,
+        start.character + 1
+    )?;
+
+    let Some(source) = lines.get(start.line as usize) else {
+        return writeln!(out);
+    };
+    let source = source.trim_end_matches('\r');
+    let bar = paint.paint(gutter_style, "|");
+    writeln!(out, "{} {}", pad, bar)?;
+    writeln!(
+        out,
+        "{} {} {}",
+        paint.paint(gutter_style, &line_number),
+        bar,
+        source
+    )?;
+
+    let line_len = source.chars().count();
+    let start_col = (start.character as usize).min(line_len);
+    // ranges running past the first line are underlined to its end
+    let end_col = if end.line == start.line {
+        (end.character as usize).min(line_len)
+    } else {
+        line_len
+    };
+    let caret_count = end_col.saturating_sub(start_col).max(1);
+    // keep tabs so the carets line up with the source however tabs are rendered
+    let indent: String = source
+        .chars()
+        .take(start_col)
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect();
+    writeln!(
+        out,
+        "{} {} {}{}",
+        pad,
+        bar,
+        indent,
+        paint.paint(severity_style, &"^".repeat(caret_count))
+    )?;
+    writeln!(out, "{} {}", pad, bar)?;
+    writeln!(out)
+}
+
+fn severity_style(severity: Option<DiagnosticSeverity>) -> Style {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => Color::Red.bold(),
+        Some(DiagnosticSeverity::WARNING) => Color::Yellow.bold(),
+        Some(DiagnosticSeverity::INFORMATION) => Color::Cyan.bold(),
+        _ => Color::Green.bold(),
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 || word == "information" {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: Style, text: &str) -> String {
+        if self.color {
+            style.paint(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
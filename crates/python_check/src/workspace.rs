@@ -0,0 +1,102 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use python_code_analysis::{
+    FileId, PyAnalysis, PyConfig, WorkspaceFilter, discover_environment, file_path_to_uri,
+    load_config_file, read_source_file,
+};
+use python_parser::{ParserConfig, PyLanguageLevel, PyVersionNumber};
+
+use crate::cmd_args::CmdArgs;
+
+/// Load every file to check into a fresh analysis
+pub fn load_workspace(cmd_args: &CmdArgs) -> Result<(PyAnalysis, Vec<FileId>), String> {
+    // the first directory checked is the project root
+    let root = cmd_args.paths.iter().find(|path| path.is_dir());
+    let mut config = match root {
+        Some(root) => load_config(root)?,
+        None => PyConfig::default(),
+    };
+    if let Some(version) = &cmd_args.python_version {
+        parser_config(version)?;
+        config.python_version = Some(version.clone());
+    }
+    // globs given on the command line replace the included files of the
+    // config, and skip files in addition to its excluded ones
+    let include = if cmd_args.include.is_empty() {
+        &config.workspace.include
+    } else {
+        &cmd_args.include
+    };
+    let exclude = [config.workspace.exclude.as_slice(), &cmd_args.exclude].concat();
+    let paths = collect_files(&cmd_args.paths, include, &exclude)?;
+
+    let mut analysis = PyAnalysis::new();
+    if let Some(root) = root {
+        analysis.set_environment(discover_environment(root, config.workspace.venv.as_deref()));
+    }
+    analysis.update_config(Arc::new(config));
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        let content = read_source_file(&path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        let uri = file_path_to_uri(&path)
+            .ok_or_else(|| format!("invalid file path {}", path.display()))?;
+        files.push((uri, Some(content)));
+    }
+    let file_ids = analysis.update_files_by_uri(files);
+    Ok((analysis, file_ids))
+}
+
+/// The parser config for a version such as `3.12`
+pub fn parser_config(version: &str) -> Result<ParserConfig, String> {
+    let number = PyVersionNumber::from_str(version)
+        .filter(|number| number.major == 3)
+        .ok_or_else(|| format!("unsupported Python version `{}`", version))?;
+    Ok(ParserConfig::new(PyLanguageLevel::Py3(number)))
+}
+
+/// The settings of the config files in `root`, the default ones when there
+/// are none. Their paths and globs are relative to it.
+fn load_config(root: &Path) -> Result<PyConfig, String> {
+    let mut config: PyConfig = match load_config_file(root) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|err| format!("invalid config in {}: {}", root.display(), err))?,
+        None => PyConfig::default(),
+    };
+    config
+        .set_root(Some(root))
+        .map_err(|err| format!("invalid config in {}: {}", root.display(), err))?;
+    Ok(config)
+}
+
+/// The Python files under `paths`. Files which are named directly are
+/// checked even when the globs or a `.gitignore` would skip them.
+pub fn collect_files(
+    paths: &[PathBuf],
+    include: &[String],
+    exclude: &[String],
+) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for root in paths {
+        if root.is_file() {
+            files.push(absolute(root)?);
+            continue;
+        }
+        if !root.is_dir() {
+            return Err(format!("no such file or directory: {}", root.display()));
+        }
+        let filter = WorkspaceFilter::new(root, include, exclude)?;
+        for path in filter.collect_files() {
+            files.push(absolute(&path)?);
+        }
+    }
+    files.dedup();
+    Ok(files)
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+    std::path::absolute(path).map_err(|err| format!("invalid path {}: {}", path.display(), err))
+}
@@ -0,0 +1,224 @@
+mod stats;
+
+use python_parser::{
+    PyAstNode, PyAstToken, PyExpr, PyModule, PySyntaxId, PySyntaxKind, PySyntaxNode, PySyntaxToken,
+};
+use rowan::TextRange;
+
+pub(crate) use stats::collect_import_items;
+
+use crate::{
+    FileId,
+    db_index::{PyDecl, PyDeclKind, PyDeclTree, PyScopeKind, ScopeId},
+};
+
+pub fn analyze_decl(file_id: FileId, root: &PyModule) -> PyDeclTree {
+    let mut analyzer = DeclAnalyzer::new(file_id, root.get_range());
+    analyzer.walk(root.syntax());
+    analyzer.finish()
+}
+
+struct DeclAnalyzer {
+    decl_tree: PyDeclTree,
+    scopes: Vec<ScopeId>,
+}
+
+impl DeclAnalyzer {
+    fn new(file_id: FileId, range: TextRange) -> Self {
+        let mut decl_tree = PyDeclTree::new(file_id);
+        let root_scope = decl_tree.create_scope(PyScopeKind::Module, range, None);
+        DeclAnalyzer {
+            decl_tree,
+            scopes: vec![root_scope],
+        }
+    }
+
+    fn finish(self) -> PyDeclTree {
+        self.decl_tree
+    }
+
+    fn current_scope(&self) -> ScopeId {
+        *self.scopes.last().unwrap()
+    }
+
+    fn push_scope(&mut self, kind: PyScopeKind, range: TextRange) {
+        let parent = self.current_scope();
+        let scope = self.decl_tree.create_scope(kind, range, Some(parent));
+        self.scopes.push(scope);
+    }
+
+    fn pop_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    fn add_decl(
+        &mut self,
+        name: &str,
+        kind: PyDeclKind,
+        range: TextRange,
+        syntax_id: PySyntaxId,
+    ) -> DeclBuilder<'_> {
+        // a name declared `global` binds in the module scope
+        let mut scope = self.current_scope();
+        if self
+            .decl_tree
+            .get_scope(scope)
+            .is_some_and(|it| it.is_global(name))
+        {
+            scope = self.scopes[0];
+        }
+        self.add_decl_in_scope(scope, name, kind, range, syntax_id)
+    }
+
+    fn add_decl_in_scope(
+        &mut self,
+        scope: ScopeId,
+        name: &str,
+        kind: PyDeclKind,
+        range: TextRange,
+        syntax_id: PySyntaxId,
+    ) -> DeclBuilder<'_> {
+        let id = self.decl_tree.next_decl_id();
+        let decl = PyDecl::new(id, name.to_string(), kind, scope, range).with_syntax_id(syntax_id);
+        DeclBuilder {
+            decl_tree: &mut self.decl_tree,
+            decl: Some(decl),
+        }
+    }
+
+    fn add_name_token_decl(
+        &mut self,
+        token: &PySyntaxToken,
+        annotation: Option<PyExpr>,
+        syntax_id: PySyntaxId,
+    ) {
+        self.add_decl(
+            token.text(),
+            PyDeclKind::Variable,
+            token.text_range(),
+            syntax_id,
+        )
+        .with_annotation(annotation.map(|it| it.get_syntax_id()))
+        .finish();
+    }
+
+    fn walk(&mut self, node: &PySyntaxNode) {
+        match node.kind().into() {
+            PySyntaxKind::FuncDef => stats::analyze_func_def(self, node),
+            PySyntaxKind::ClassDef => stats::analyze_class_def(self, node),
+            PySyntaxKind::LambdaExpr => stats::analyze_lambda(self, node),
+            PySyntaxKind::ListCompExpr
+            | PySyntaxKind::SetCompExpr
+            | PySyntaxKind::DictCompExpr
+            | PySyntaxKind::GeneratorExpr => stats::analyze_comprehension(self, node),
+            PySyntaxKind::AssignStmt => stats::analyze_assign(self, node),
+            PySyntaxKind::AnnAssignStmt => stats::analyze_ann_assign(self, node),
+            PySyntaxKind::AssignExpr => stats::analyze_assign_expr(self, node),
+            PySyntaxKind::ForStmt => stats::analyze_for(self, node),
+            PySyntaxKind::WithStmt | PySyntaxKind::ExceptClause => {
+                stats::analyze_as_names(self, node)
+            }
+            PySyntaxKind::ImportStmt => stats::analyze_import(self, node),
+            PySyntaxKind::ImportFromStmt => stats::analyze_import_from(self, node),
+            PySyntaxKind::GlobalStmt => stats::analyze_global(self, node, true),
+            PySyntaxKind::NonlocalStmt => stats::analyze_global(self, node, false),
+            PySyntaxKind::TypeStatement => stats::analyze_type_statement(self, node),
+            _ => self.walk_children(node),
+        }
+    }
+
+    fn walk_children(&mut self, node: &PySyntaxNode) {
+        for child in node.children() {
+            self.walk(&child);
+        }
+    }
+
+    /// Bind every name in an assignment target. When the value is a tuple or
+    /// list of the same shape its elements are paired with the target names.
+    fn bind_target(
+        &mut self,
+        target: PyExpr,
+        value: Option<PyExpr>,
+        annotation: Option<PyExpr>,
+        syntax_id: PySyntaxId,
+    ) {
+        match target {
+            PyExpr::NameExpr(name_expr) => {
+                let Some(name) = name_expr.get_name_token() else {
+                    return;
+                };
+                self.add_decl(
+                    name.get_name_text(),
+                    PyDeclKind::Variable,
+                    name.get_range(),
+                    syntax_id,
+                )
+                .with_value(value.map(|it| it.get_syntax_id()))
+                .with_annotation(annotation.map(|it| it.get_syntax_id()))
+                .finish();
+            }
+            PyExpr::TupleExpr(_) | PyExpr::ListExpr(_) => {
+                let targets: Vec<PyExpr> = target.children().collect();
+                let values: Option<Vec<PyExpr>> = match value {
+                    Some(PyExpr::TupleExpr(tuple)) => Some(tuple.get_exprs().collect()),
+                    Some(PyExpr::ListExpr(list)) => Some(list.get_exprs().collect()),
+                    _ => None,
+                };
+                let has_starred = targets
+                    .iter()
+                    .any(|it| matches!(it, PyExpr::StarredExpr(_)));
+                match values {
+                    Some(values) if values.len() == targets.len() && !has_starred => {
+                        for (target, value) in targets.into_iter().zip(values) {
+                            self.bind_target(target, Some(value), None, syntax_id);
+                        }
+                    }
+                    _ => {
+                        for target in targets {
+                            self.bind_target(target, None, None, syntax_id);
+                        }
+                    }
+                }
+            }
+            PyExpr::ParenExpr(paren) => {
+                if let Some(inner) = paren.child::<PyExpr>() {
+                    self.bind_target(inner, value, annotation, syntax_id);
+                }
+            }
+            PyExpr::StarredExpr(starred) => {
+                if let Some(inner) = starred.get_value() {
+                    self.bind_target(inner, None, None, syntax_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct DeclBuilder<'a> {
+    decl_tree: &'a mut PyDeclTree,
+    decl: Option<PyDecl>,
+}
+
+impl DeclBuilder<'_> {
+    fn with_value(mut self, value: Option<PySyntaxId>) -> Self {
+        self.decl = self.decl.take().map(|decl| decl.with_value(value));
+        self
+    }
+
+    fn with_annotation(mut self, annotation: Option<PySyntaxId>) -> Self {
+        self.decl = self
+            .decl
+            .take()
+            .map(|decl| decl.with_annotation(annotation));
+        self
+    }
+
+    fn finish(mut self) {
+        if let Some(decl) = self.decl.take() {
+            self.decl_tree.add_decl(decl);
+        }
+    }
+}
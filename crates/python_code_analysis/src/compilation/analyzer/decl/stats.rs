@@ -0,0 +1,416 @@
+use python_parser::{
+    PyAnnAssignStmt, PyAssignExpr, PyAssignStmt, PyAstNode, PyAstToken, PyClassDef, PyExpr,
+    PyForStmt, PyFuncDef, PyLambdaExpr, PySyntaxElement, PySyntaxId, PySyntaxKind, PySyntaxNode,
+    PySyntaxToken, PyTokenKind, PyTypeAnnotation, PyWithStmt,
+};
+use rowan::TextRange;
+
+use super::DeclAnalyzer;
+use crate::db_index::{PyDeclKind, PyScopeKind};
+
+pub fn analyze_func_def(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let Some(func) = PyFuncDef::cast(node.clone()) else {
+        return;
+    };
+
+    if let Some(name) = func.get_name() {
+        analyzer
+            .add_decl(
+                name.get_name_text(),
+                PyDeclKind::Function,
+                name.get_range(),
+                func.get_syntax_id(),
+            )
+            .finish();
+    }
+
+    // defaults and annotations are evaluated in the enclosing scope
+    let params = func.get_parameters();
+    if let Some(params) = &params {
+        for param in params.get_args() {
+            if let Some(annotation) = param.child::<PyTypeAnnotation>() {
+                analyzer.walk(annotation.syntax());
+            }
+            if let Some(default) = param.get_default_value() {
+                analyzer.walk(default.syntax());
+            }
+        }
+    }
+    if let Some(return_annotation) = func.child::<PyTypeAnnotation>() {
+        analyzer.walk(return_annotation.syntax());
+    }
+    if let Some(type_comment) = func.get_type_comment() {
+        analyzer.walk(type_comment.syntax());
+    }
+
+    let scope_start = match &params {
+        Some(params) => params.get_position(),
+        None => func.get_range().end(),
+    };
+    analyzer.push_scope(
+        PyScopeKind::Function,
+        TextRange::new(scope_start, func.get_range().end()),
+    );
+    if let Some(params) = &params {
+        for param in params.get_args() {
+            let Some(name) = param.get_name() else {
+                continue;
+            };
+            analyzer
+                .add_decl(
+                    name.get_name_text(),
+                    PyDeclKind::Parameter,
+                    name.get_range(),
+                    param.get_syntax_id(),
+                )
+                .with_value(param.get_default_value().map(|it| it.get_syntax_id()))
+                .with_annotation(
+                    param
+                        .get_annotation()
+                        .or_else(|| param.get_type_comment_annotation())
+                        .map(|it| it.get_syntax_id()),
+                )
+                .finish();
+        }
+    }
+    if let Some(body) = func.get_body() {
+        analyzer.walk(body.syntax());
+    }
+    analyzer.pop_scope();
+}
+
+pub fn analyze_class_def(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let Some(class) = PyClassDef::cast(node.clone()) else {
+        return;
+    };
+
+    if let Some(name) = class.get_name() {
+        analyzer
+            .add_decl(
+                name.get_name_text(),
+                PyDeclKind::Class,
+                name.get_range(),
+                class.get_syntax_id(),
+            )
+            .finish();
+    }
+
+    if let Some(arguments) = class.get_arguments() {
+        analyzer.walk(arguments.syntax());
+    }
+
+    if let Some(body) = class.get_body() {
+        analyzer.push_scope(PyScopeKind::Class, body.get_range());
+        analyzer.walk(body.syntax());
+        analyzer.pop_scope();
+    }
+}
+
+pub fn analyze_lambda(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let Some(lambda) = PyLambdaExpr::cast(node.clone()) else {
+        return;
+    };
+
+    analyzer.push_scope(PyScopeKind::Lambda, lambda.get_range());
+    if let Some(params) = lambda.get_parameters() {
+        for param in params.get_args() {
+            let Some(name) = param.get_name() else {
+                continue;
+            };
+            analyzer
+                .add_decl(
+                    name.get_name_text(),
+                    PyDeclKind::Parameter,
+                    name.get_range(),
+                    param.get_syntax_id(),
+                )
+                .with_value(param.get_default_value().map(|it| it.get_syntax_id()))
+                .finish();
+        }
+    }
+    for child in node.children() {
+        if child.kind() != PySyntaxKind::Parameters.into() {
+            analyzer.walk(&child);
+        }
+    }
+    analyzer.pop_scope();
+}
+
+pub fn analyze_comprehension(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    analyzer.push_scope(PyScopeKind::Comprehension, node.text_range());
+    analyze_for_targets(analyzer, node, None);
+    analyzer.pop_scope();
+}
+
+pub fn analyze_for(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let annotation =
+        PyForStmt::cast(node.clone()).and_then(|for_stmt| for_stmt.get_type_comment()?.get_type());
+    analyze_for_targets(analyzer, node, annotation);
+}
+
+/// The targets of `for` loops and comprehension clauses sit between the
+/// `for` and `in` keywords, either as bare name tokens or target expressions
+fn analyze_for_targets(
+    analyzer: &mut DeclAnalyzer,
+    node: &PySyntaxNode,
+    annotation: Option<PyExpr>,
+) {
+    let syntax_id = PySyntaxId::from_node(node);
+    let mut in_target = false;
+    for element in node.children_with_tokens() {
+        match element {
+            PySyntaxElement::Token(token) => match token.kind().into() {
+                PyTokenKind::TkFor => in_target = true,
+                PyTokenKind::TkIn => in_target = false,
+                PyTokenKind::TkName if in_target => {
+                    analyzer.add_name_token_decl(&token, annotation.clone(), syntax_id);
+                }
+                _ => {}
+            },
+            PySyntaxElement::Node(child) => {
+                if in_target {
+                    if let Some(target) = PyExpr::cast(child) {
+                        analyzer.bind_target(target, None, annotation.clone(), syntax_id);
+                    }
+                } else {
+                    analyzer.walk(&child);
+                }
+            }
+        }
+    }
+}
+
+/// Names bound by `with ... as name` and `except ... as name`
+pub fn analyze_as_names(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let syntax_id = PySyntaxId::from_node(node);
+    let annotation = PyWithStmt::cast(node.clone())
+        .and_then(|with_stmt| with_stmt.get_type_comment()?.get_type());
+    let mut after_as = false;
+    for element in node.children_with_tokens() {
+        match element {
+            PySyntaxElement::Token(token) => match token.kind().into() {
+                PyTokenKind::TkAs => after_as = true,
+                PyTokenKind::TkName if after_as => {
+                    analyzer.add_name_token_decl(&token, annotation.clone(), syntax_id);
+                    after_as = false;
+                }
+                PyTokenKind::TkWhitespace | PyTokenKind::TkComment => {}
+                _ => after_as = false,
+            },
+            PySyntaxElement::Node(child) => {
+                if after_as {
+                    if let Some(target) = PyExpr::cast(child.clone()) {
+                        analyzer.bind_target(target, None, annotation.clone(), syntax_id);
+                    }
+                    after_as = false;
+                } else {
+                    analyzer.walk(&child);
+                }
+            }
+        }
+    }
+}
+
+pub fn analyze_assign(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let Some(assign) = PyAssignStmt::cast(node.clone()) else {
+        return;
+    };
+
+    let exprs: Vec<PyExpr> = assign.get_targets().collect();
+    let annotation = assign
+        .get_type_comment()
+        .and_then(|type_comment| type_comment.get_type());
+    if let Some((value, targets)) = exprs.split_last() {
+        for target in targets {
+            analyzer.bind_target(
+                target.clone(),
+                Some(value.clone()),
+                annotation.clone(),
+                assign.get_syntax_id(),
+            );
+        }
+    }
+    analyzer.walk_children(node);
+}
+
+pub fn analyze_ann_assign(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let Some(assign) = PyAnnAssignStmt::cast(node.clone()) else {
+        return;
+    };
+
+    if let Some(target) = assign.get_target() {
+        analyzer.bind_target(
+            target,
+            assign.get_value(),
+            assign.get_annotation(),
+            assign.get_syntax_id(),
+        );
+    }
+    analyzer.walk_children(node);
+}
+
+/// The walrus operator binds in the nearest enclosing non-comprehension scope
+pub fn analyze_assign_expr(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let Some(assign) = PyAssignExpr::cast(node.clone()) else {
+        return;
+    };
+
+    if let Some(PyExpr::NameExpr(name_expr)) = assign.get_target()
+        && let Some(name) = name_expr.get_name_token()
+    {
+        let scope = analyzer
+            .scopes
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| {
+                analyzer
+                    .decl_tree
+                    .get_scope(*id)
+                    .is_some_and(|scope| scope.get_kind() != PyScopeKind::Comprehension)
+            })
+            .unwrap_or(analyzer.current_scope());
+        analyzer
+            .add_decl_in_scope(
+                scope,
+                name.get_name_text(),
+                PyDeclKind::Variable,
+                name.get_range(),
+                assign.get_syntax_id(),
+            )
+            .with_value(assign.get_value().map(|it| it.get_syntax_id()))
+            .finish();
+    }
+    analyzer.walk_children(node);
+}
+
+pub fn analyze_import(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let syntax_id = PySyntaxId::from_node(node);
+    for item in collect_import_items(node, PyTokenKind::TkImport) {
+        let module = item.path_text();
+        let (name, range) = match &item.alias {
+            Some(alias) => (alias.text().to_string(), alias.text_range()),
+            // `import a.b` binds the top level package `a`
+            None => (item.path[0].text().to_string(), item.path[0].text_range()),
+        };
+        analyzer
+            .add_decl(&name, PyDeclKind::Import { module }, range, syntax_id)
+            .finish();
+    }
+}
+
+pub fn analyze_import_from(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let syntax_id = PySyntaxId::from_node(node);
+    let mut module = String::new();
+    for element in node.children_with_tokens() {
+        let Some(token) = element.into_token() else {
+            continue;
+        };
+        match token.kind().into() {
+            PyTokenKind::TkImport => break,
+            PyTokenKind::TkName | PyTokenKind::TkDot => module.push_str(token.text()),
+            PyTokenKind::TkEllipsis => module.push_str("..."),
+            _ => {}
+        }
+    }
+
+    for item in collect_import_items(node, PyTokenKind::TkImport) {
+        let name = item.path_text();
+        let binding = item.alias.as_ref().unwrap_or(&item.path[0]);
+        analyzer
+            .add_decl(
+                binding.text(),
+                PyDeclKind::ImportFrom {
+                    module: module.clone(),
+                    name,
+                },
+                binding.text_range(),
+                syntax_id,
+            )
+            .finish();
+    }
+}
+
+pub fn analyze_global(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode, is_global: bool) {
+    let scope = analyzer.current_scope();
+    for element in node.children_with_tokens() {
+        let Some(token) = element.into_token() else {
+            continue;
+        };
+        if token.kind() == PyTokenKind::TkName.into() {
+            let scope = analyzer.decl_tree.get_scope_mut(scope);
+            if is_global {
+                scope.add_global(token.text().to_string());
+            } else {
+                scope.add_nonlocal(token.text().to_string());
+            }
+        }
+    }
+}
+
+/// `type X = ...` binds `X`, its value is evaluated lazily
+pub fn analyze_type_statement(analyzer: &mut DeclAnalyzer, node: &PySyntaxNode) {
+    let name = node
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|token| token.kind() == PyTokenKind::TkName.into());
+    if let Some(name) = name {
+        analyzer.add_name_token_decl(&name, None, PySyntaxId::from_node(node));
+    }
+    analyzer.walk_children(node);
+}
+
+pub struct ImportItem {
+    pub path: Vec<PySyntaxToken>,
+    pub alias: Option<PySyntaxToken>,
+}
+
+impl ImportItem {
+    pub fn path_text(&self) -> String {
+        self.path
+            .iter()
+            .map(|it| it.text())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+/// Split the names after `start_kind` into dotted paths with optional aliases
+pub fn collect_import_items(node: &PySyntaxNode, start_kind: PyTokenKind) -> Vec<ImportItem> {
+    let mut items = Vec::new();
+    let mut started = false;
+    let mut path = Vec::new();
+    let mut alias = None;
+    let mut after_as = false;
+    let tokens = node
+        .children_with_tokens()
+        .filter_map(|element| element.into_token());
+    for token in tokens {
+        let kind: PyTokenKind = token.kind().into();
+        if !started {
+            started = kind == start_kind;
+            continue;
+        }
+
+        match kind {
+            PyTokenKind::TkName if after_as => {
+                alias = Some(token);
+                after_as = false;
+            }
+            PyTokenKind::TkName => path.push(token),
+            PyTokenKind::TkAs => after_as = true,
+            PyTokenKind::TkComma if !path.is_empty() => {
+                items.push(ImportItem {
+                    path: std::mem::take(&mut path),
+                    alias: alias.take(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if !path.is_empty() {
+        items.push(ImportItem { path, alias });
+    }
+    items
+}
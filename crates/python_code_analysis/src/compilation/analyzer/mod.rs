@@ -0,0 +1,4 @@
+mod decl;
+
+pub use decl::analyze_decl;
+pub(crate) use decl::collect_import_items;
@@ -0,0 +1,46 @@
+mod analyzer;
+
+pub(crate) use analyzer::{analyze_decl, collect_import_items};
+
+use crate::{FileId, QueryKey, db_index::DbIndex, semantic::SemanticModel};
+
+#[derive(Debug, Default)]
+pub struct PyCompilation {
+    db: DbIndex,
+}
+
+impl PyCompilation {
+    pub fn new() -> Self {
+        PyCompilation { db: DbIndex::new() }
+    }
+
+    pub fn get_semantic_model(&self, file_id: FileId) -> Option<SemanticModel<'_>> {
+        SemanticModel::new(file_id, &self.db)
+    }
+
+    /// Start a new revision with the changes made to the files since the
+    /// last one, the trees and declarations are derived again when asked for
+    pub fn update_index(&mut self) {
+        self.db.get_query_db_mut().new_revision();
+        for (file_id, change) in self.db.get_vfs_mut().take_changes() {
+            let queries = self.db.get_query_db_mut();
+            if change.text {
+                queries.set_changed(QueryKey::FileText(file_id));
+            }
+            if change.loaded {
+                queries.set_changed(QueryKey::Files);
+                if !self.db.get_vfs().is_loaded(&file_id) {
+                    self.db.get_query_db_mut().remove_file(file_id);
+                }
+            }
+        }
+    }
+
+    pub fn get_db(&self) -> &DbIndex {
+        &self.db
+    }
+
+    pub fn get_db_mut(&mut self) -> &mut DbIndex {
+        &mut self.db
+    }
+}
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use python_parser::PyVersionNumber;
+use serde_json::{Map, Value};
+
+use super::setup_cfg::parse_setup_cfg;
+
+/// The config files of a workspace root, from the lowest precedence to the
+/// highest. The settings of all of them are merged, and the settings the
+/// client sends are merged on top.
+pub const CONFIG_FILE_NAMES: &[&str] = &["setup.cfg", "pyproject.toml", ".python_ls.toml"];
+
+/// Tables whose keys are data rather than setting names, so they are kept as written
+const DATA_TABLE_KEYS: &[&str] = &["severity"];
+
+/// The settings in the config files of the workspace rooted at `root`, as
+/// the same JSON the client sends so both can be merged
+pub fn load_config_file(root: &Path) -> Option<Value> {
+    let mut config: Option<Value> = None;
+    for file_name in CONFIG_FILE_NAMES {
+        let path = root.join(file_name);
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        match parse_config_file(file_name, &text) {
+            Ok(Some(value)) => {
+                log::info!("Loaded config from {}", path.display());
+                match &mut config {
+                    Some(config) => merge_config_value(config, value),
+                    None => config = Some(value),
+                }
+            }
+            Ok(None) => {}
+            Err(err) => log::error!("Invalid config file {}: {}", path.display(), err),
+        }
+    }
+    config
+}
+
+/// The settings in one of `CONFIG_FILE_NAMES`, `None` when it has none
+pub fn parse_config_file(file_name: &str, text: &str) -> Result<Option<Value>, String> {
+    if file_name == "setup.cfg" {
+        return Ok(parse_setup_cfg(text).map(normalize_keys));
+    }
+    parse_config_toml(file_name, text)
+}
+
+/// `.python_ls.toml` holds the settings at the top level, `pyproject.toml`
+/// in its `[tool.python_ls]` table, under the Python version of its
+/// `requires-python`
+pub fn parse_config_toml(file_name: &str, text: &str) -> Result<Option<Value>, String> {
+    let value: Value = toml_edit::de::from_str(text).map_err(|err| err.to_string())?;
+    if file_name != "pyproject.toml" {
+        return Ok(Some(normalize_keys(value)));
+    }
+
+    let mut config = value
+        .get("project")
+        .and_then(|project| project.get("requires-python"))
+        .and_then(Value::as_str)
+        .and_then(requires_python_version)
+        .map(|version| {
+            let mut config = Map::new();
+            config.insert("pythonVersion".to_string(), Value::String(version));
+            Value::Object(config)
+        });
+    if let Some(settings) = value.get("tool").and_then(|tool| tool.get("python_ls")) {
+        let settings = normalize_keys(settings.clone());
+        match &mut config {
+            Some(config) => merge_config_value(config, settings),
+            None => config = Some(settings),
+        }
+    }
+    Ok(config)
+}
+
+/// The oldest Python version a `requires-python` specifier such as
+/// `>=3.9, <4` allows, which is the version the code must parse with
+pub fn requires_python_version(specifier: &str) -> Option<String> {
+    specifier
+        .split(',')
+        .filter_map(|clause| {
+            let clause = clause.trim();
+            let (operator, version) = ["~=", "==", ">=", ">"]
+                .iter()
+                .find_map(|operator| Some((*operator, clause.strip_prefix(operator)?)))?;
+            let version = version.trim().trim_end_matches(".*");
+            let mut number = PyVersionNumber::from_str(version).filter(|it| it.major == 3)?;
+            // `>3.9` excludes 3.9 itself, but 3.9.2 is above `>3.9.1`
+            if operator == ">" && version.split('.').count() == 2 {
+                number.minor += 1;
+            }
+            Some(number)
+        })
+        .max()
+        .map(|number| format!("{}.{}", number.major, number.minor))
+}
+
+/// Config files use `kebab-case` or `snake_case` keys as is usual for them,
+/// the client and `PyConfig` use `camelCase`
+fn normalize_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = if DATA_TABLE_KEYS.contains(&key.as_str()) {
+                        value
+                    } else {
+                        normalize_keys(value)
+                    };
+                    (to_camel_case(&key), value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize_keys).collect()),
+        value => value,
+    }
+}
+
+pub(super) fn to_camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper = false;
+    for ch in key.chars() {
+        if ch == '-' || ch == '_' {
+            upper = !result.is_empty();
+        } else if upper {
+            result.extend(ch.to_uppercase());
+            upper = false;
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+/// Recursively merge `overlay` into `base`, values of `overlay` win
+pub fn merge_config_value(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_config_value(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::DiagnosticSeverity;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use wax::{Glob, Pattern};
+
+use crate::LintRuleMeta;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PyDiagnosticConfig {
+    /// Whether diagnostics are published at all
+    #[serde(default = "default_true")]
+    pub enable: bool,
+    /// Codes which are turned off, a rule code such as `PY005` or its name
+    /// such as `unused-import`
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// Codes which are off by default and turned on
+    #[serde(default)]
+    pub enables: Vec<String>,
+    /// Severity of a code in place of its default one
+    #[serde(default)]
+    pub severity: HashMap<String, PyDiagnosticSeverity>,
+    /// Settings for the files matching a glob, applied in order after the
+    /// settings above
+    #[serde(default)]
+    pub overrides: Vec<PyDiagnosticOverride>,
+    /// The globs of `overrides`, compiled when the config is loaded
+    #[serde(skip)]
+    #[schemars(skip)]
+    override_globs: OverrideGlobs,
+}
+
+impl Default for PyDiagnosticConfig {
+    fn default() -> Self {
+        PyDiagnosticConfig {
+            enable: default_true(),
+            disable: Vec::new(),
+            enables: Vec::new(),
+            severity: HashMap::new(),
+            overrides: Vec::new(),
+            override_globs: OverrideGlobs::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PyDiagnosticOverride {
+    /// Globs such as `tests/**` or `**/*_pb2.py`, matched against the path
+    /// relative to the workspace root
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub disable: Vec<String>,
+    #[serde(default)]
+    pub enables: Vec<String>,
+    #[serde(default)]
+    pub severity: HashMap<String, PyDiagnosticSeverity>,
+}
+
+/// The globs of each override and the root they match paths relative to
+#[derive(Debug, Clone, Default)]
+struct OverrideGlobs {
+    root: Option<PathBuf>,
+    globs: Vec<Vec<Glob<'static>>>,
+}
+
+impl OverrideGlobs {
+    fn matches(&self, index: usize, path: &Path) -> bool {
+        let relative = match &self.root {
+            Some(root) => path.strip_prefix(root).unwrap_or(path),
+            None => path,
+        };
+        self.globs
+            .get(index)
+            .is_some_and(|globs| globs.iter().any(|glob| glob.is_match(relative)))
+    }
+}
+
+// the globs are made from the overrides, which are compared on their own
+impl PartialEq for OverrideGlobs {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PyDiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl From<PyDiagnosticSeverity> for DiagnosticSeverity {
+    fn from(severity: PyDiagnosticSeverity) -> Self {
+        match severity {
+            PyDiagnosticSeverity::Error => DiagnosticSeverity::ERROR,
+            PyDiagnosticSeverity::Warning => DiagnosticSeverity::WARNING,
+            PyDiagnosticSeverity::Information => DiagnosticSeverity::INFORMATION,
+            PyDiagnosticSeverity::Hint => DiagnosticSeverity::HINT,
+        }
+    }
+}
+
+impl PyDiagnosticConfig {
+    /// Compile the globs of the overrides, which match paths relative to
+    /// `root`. A config is compiled once after it is loaded, before it is
+    /// used to check files.
+    pub fn compile_overrides(&mut self, root: Option<&Path>) -> Result<(), String> {
+        let globs = self
+            .overrides
+            .iter()
+            .map(|diagnostic_override| {
+                diagnostic_override
+                    .files
+                    .iter()
+                    .map(|pattern| {
+                        Glob::new(pattern.trim_start_matches("./"))
+                            .map(Glob::into_owned)
+                            .map_err(|err| format!("invalid glob `{}`: {}", pattern, err))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, String>>()?;
+        self.override_globs = OverrideGlobs {
+            root: root.map(Path::to_path_buf),
+            globs,
+        };
+        Ok(())
+    }
+
+    /// Whether the code of `meta` is reported in the file at `path`, `None`
+    /// for files which are not on disk
+    pub fn is_enabled(&self, meta: &LintRuleMeta, path: Option<&Path>) -> bool {
+        if !self.enable {
+            return false;
+        }
+        let mut enabled =
+            (meta.default_enabled || names(&self.enables, meta)) && !names(&self.disable, meta);
+        for diagnostic_override in self.get_overrides(path) {
+            if names(&diagnostic_override.enables, meta) {
+                enabled = true;
+            }
+            if names(&diagnostic_override.disable, meta) {
+                enabled = false;
+            }
+        }
+        enabled
+    }
+
+    pub fn get_severity(&self, meta: &LintRuleMeta, path: Option<&Path>) -> DiagnosticSeverity {
+        self.get_overrides(path)
+            .filter_map(|diagnostic_override| find_severity(&diagnostic_override.severity, meta))
+            .next_back()
+            .or_else(|| find_severity(&self.severity, meta))
+            .map(|severity| severity.into())
+            .unwrap_or(meta.default_severity)
+    }
+
+    fn get_overrides<'a>(
+        &'a self,
+        path: Option<&'a Path>,
+    ) -> impl DoubleEndedIterator<Item = &'a PyDiagnosticOverride> {
+        self.overrides
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| {
+                path.is_some_and(|path| self.override_globs.matches(*index, path))
+            })
+            .map(|(_, diagnostic_override)| diagnostic_override)
+    }
+}
+
+/// Whether a code list of the config names the code of `meta`
+fn names(codes: &[String], meta: &LintRuleMeta) -> bool {
+    codes.iter().any(|code| meta.matches(code))
+}
+
+fn find_severity(
+    severity: &HashMap<String, PyDiagnosticSeverity>,
+    meta: &LintRuleMeta,
+) -> Option<PyDiagnosticSeverity> {
+    severity
+        .iter()
+        .find(|(code, _)| meta.matches(code))
+        .map(|(_, severity)| *severity)
+}
+
+fn default_true() -> bool {
+    true
+}
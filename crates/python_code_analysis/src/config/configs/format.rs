@@ -0,0 +1,44 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PyFormatConfig {
+    /// The column lines are wrapped at
+    #[serde(default = "default_line_length")]
+    pub line_length: u32,
+    /// Spaces per indentation level
+    #[serde(default = "default_indent_width")]
+    pub indent_width: u32,
+    /// Quotes of string literals
+    #[serde(default)]
+    pub quote_style: PyQuoteStyle,
+}
+
+impl Default for PyFormatConfig {
+    fn default() -> Self {
+        PyFormatConfig {
+            line_length: default_line_length(),
+            indent_width: default_indent_width(),
+            quote_style: PyQuoteStyle::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PyQuoteStyle {
+    #[default]
+    Double,
+    Single,
+    /// Keep the quotes each literal was written with
+    Preserve,
+}
+
+fn default_line_length() -> u32 {
+    88
+}
+
+fn default_indent_width() -> u32 {
+    4
+}
@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PyHintConfig {
+    /// Whether inlay hints are shown at all
+    #[serde(default = "default_true")]
+    pub enable: bool,
+    /// Inferred types of unannotated assignment targets
+    #[serde(default = "default_true")]
+    pub variable_types: bool,
+    /// Inferred types of `for` and `with ... as` targets
+    #[serde(default = "default_true")]
+    pub target_types: bool,
+    /// Inferred return types of unannotated functions
+    #[serde(default = "default_true")]
+    pub return_types: bool,
+    /// Parameter names before positional literal arguments
+    #[serde(default = "default_true")]
+    pub parameter_names: bool,
+}
+
+impl Default for PyHintConfig {
+    fn default() -> Self {
+        PyHintConfig {
+            enable: default_true(),
+            variable_types: default_true(),
+            target_types: default_true(),
+            return_types: default_true(),
+            parameter_names: default_true(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
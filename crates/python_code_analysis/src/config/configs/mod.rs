@@ -0,0 +1,9 @@
+mod diagnostics;
+mod format;
+mod hint;
+mod workspace;
+
+pub use diagnostics::{PyDiagnosticConfig, PyDiagnosticOverride, PyDiagnosticSeverity};
+pub use format::{PyFormatConfig, PyQuoteStyle};
+pub use hint::PyHintConfig;
+pub use workspace::PyWorkspaceConfig;
@@ -0,0 +1,51 @@
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Where the files of the project are, paths are relative to the workspace root
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PyWorkspaceConfig {
+    /// Directories imports are resolved from, the workspace root when empty
+    #[serde(default)]
+    pub source_roots: Vec<String>,
+    /// Globs of the files which belong to the project, every Python file when empty
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs of the files and directories which are skipped
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Directories searched for `.pyi` stubs before the source roots
+    #[serde(default)]
+    pub stub_paths: Vec<String>,
+    /// The virtual environment or conda prefix of the project, found
+    /// automatically when unset
+    #[serde(default)]
+    pub venv: Option<String>,
+    /// The workspace root the paths are relative to, set when the config is loaded
+    #[serde(skip)]
+    #[schemars(skip)]
+    root: Option<PathBuf>,
+}
+
+impl PyWorkspaceConfig {
+    pub fn set_root(&mut self, root: Option<&Path>) {
+        self.root = root.map(Path::to_path_buf);
+    }
+
+    /// The directories modules are imported from, none when the workspace
+    /// root is unknown
+    pub fn get_source_roots(&self) -> Vec<PathBuf> {
+        let Some(root) = &self.root else {
+            return Vec::new();
+        };
+        if self.source_roots.is_empty() {
+            return vec![root.clone()];
+        }
+        self.source_roots
+            .iter()
+            .map(|source_root| root.join(source_root))
+            .collect()
+    }
+}
@@ -0,0 +1,53 @@
+mod config_loader;
+mod configs;
+mod setup_cfg;
+
+pub use config_loader::*;
+pub use configs::*;
+
+use std::path::Path;
+
+use python_parser::{PyLanguageLevel, PyVersionNumber};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The user facing settings of the language server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PyConfig {
+    /// The Python version files are parsed and checked as, such as `3.12`
+    #[serde(default)]
+    pub python_version: Option<String>,
+    #[serde(default, flatten)]
+    pub workspace: PyWorkspaceConfig,
+    #[serde(default)]
+    pub hint: PyHintConfig,
+    #[serde(default)]
+    pub diagnostics: PyDiagnosticConfig,
+    #[serde(default)]
+    pub format: PyFormatConfig,
+}
+
+impl PyConfig {
+    /// Resolve the paths and globs of the settings against the workspace
+    /// `root`. A config is resolved once after it is loaded, before it is used.
+    pub fn set_root(&mut self, root: Option<&Path>) -> Result<(), String> {
+        self.workspace.set_root(root);
+        self.diagnostics.compile_overrides(root)
+    }
+
+    /// The language level of `python_version`, the newest supported one when
+    /// it is unset or invalid
+    pub fn get_language_level(&self) -> PyLanguageLevel {
+        let Some(version) = &self.python_version else {
+            return PyLanguageLevel::default();
+        };
+        match PyVersionNumber::from_str(version).filter(|number| number.major == 3) {
+            Some(number) => PyLanguageLevel::Py3(number),
+            None => {
+                log::warn!("Unsupported Python version in config: {}", version);
+                PyLanguageLevel::default()
+            }
+        }
+    }
+}
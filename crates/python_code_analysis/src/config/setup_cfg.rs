@@ -0,0 +1,125 @@
+use serde_json::{Map, Value};
+
+use super::config_loader::{merge_config_value, requires_python_version, to_camel_case};
+
+/// Settings which hold a list, written one per line or comma separated
+const LIST_KEYS: &[&str] = &[
+    "include",
+    "exclude",
+    "sourceRoots",
+    "stubPaths",
+    "disable",
+    "enables",
+];
+
+/// The settings in the `[tool:python_ls]` section of a `setup.cfg`. Nested
+/// tables are sections such as `[tool:python_ls.diagnostics]`, and
+/// `python_requires` of `[options]` sets the Python version.
+pub fn parse_setup_cfg(text: &str) -> Option<Value> {
+    let mut requires_python = None;
+    let mut settings: Option<Value> = None;
+    for (section, entries) in parse_ini(text) {
+        if section == "options" {
+            requires_python = entries
+                .iter()
+                .find(|(key, _)| key == "python_requires")
+                .and_then(|(_, value)| requires_python_version(value))
+                .or(requires_python);
+            continue;
+        }
+        let Some(path) = section
+            .strip_prefix("tool:python_ls")
+            .or_else(|| section.strip_prefix("python_ls"))
+            .filter(|path| path.is_empty() || path.starts_with('.'))
+        else {
+            continue;
+        };
+        let mut map = Map::new();
+        for (key, value) in entries {
+            let value = parse_value(&key, &value);
+            map.insert(key, value);
+        }
+        // `[tool:python_ls.diagnostics]` nests its keys under `diagnostics`
+        let value = path.split('.').filter(|name| !name.is_empty()).rev().fold(
+            Value::Object(map),
+            |value, name| {
+                let mut map = Map::new();
+                map.insert(name.to_string(), value);
+                Value::Object(map)
+            },
+        );
+        match &mut settings {
+            Some(settings) => merge_config_value(settings, value),
+            None => settings = Some(value),
+        }
+    }
+
+    let Some(version) = requires_python else {
+        return settings;
+    };
+    let mut config = Map::new();
+    config.insert("pythonVersion".to_string(), Value::String(version));
+    let mut config = Value::Object(config);
+    if let Some(settings) = settings {
+        merge_config_value(&mut config, settings);
+    }
+    Some(config)
+}
+
+fn parse_value(key: &str, value: &str) -> Value {
+    if LIST_KEYS.contains(&to_camel_case(key).as_str()) {
+        return Value::Array(
+            value
+                .split(['\n', ','])
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        );
+    }
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => match value.parse::<u64>() {
+            Ok(number) => Value::from(number),
+            Err(_) => Value::String(value.to_string()),
+        },
+    }
+}
+
+/// The sections of an INI file with their entries in order. Indented lines
+/// continue the value above them.
+fn parse_ini(text: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            sections.push((name.trim().to_string(), Vec::new()));
+            continue;
+        }
+        let Some((_, entries)) = sections.last_mut() else {
+            continue;
+        };
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = entries.last_mut() {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(trimmed);
+            }
+            continue;
+        }
+        if let Some(index) = trimmed.find(['=', ':']) {
+            let key = trimmed[..index].trim().to_string();
+            let value = trimmed[index + 1..].trim().to_string();
+            entries.push((key, value));
+        }
+    }
+    sections
+}
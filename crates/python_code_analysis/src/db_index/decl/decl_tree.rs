@@ -0,0 +1,163 @@
+use rowan::{TextRange, TextSize};
+
+use super::{DeclId, PyDecl, PyScope, PyScopeKind, ScopeId};
+use crate::FileId;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyDeclTree {
+    file_id: FileId,
+    scopes: Vec<PyScope>,
+    decls: Vec<PyDecl>,
+}
+
+impl PyDeclTree {
+    pub fn new(file_id: FileId) -> Self {
+        PyDeclTree {
+            file_id,
+            scopes: Vec::new(),
+            decls: Vec::new(),
+        }
+    }
+
+    pub fn get_file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    pub(crate) fn create_scope(
+        &mut self,
+        kind: PyScopeKind,
+        range: TextRange,
+        parent: Option<ScopeId>,
+    ) -> ScopeId {
+        let id = ScopeId {
+            id: self.scopes.len() as u32,
+        };
+        self.scopes.push(PyScope::new(id, kind, range, parent));
+        if let Some(parent) = parent {
+            self.scopes[parent.id as usize].add_child(id);
+        }
+        id
+    }
+
+    pub(crate) fn next_decl_id(&self) -> DeclId {
+        DeclId {
+            id: self.decls.len() as u32,
+        }
+    }
+
+    pub(crate) fn add_decl(&mut self, decl: PyDecl) -> DeclId {
+        let id = decl.get_id();
+        self.scopes[decl.get_scope().id as usize].add_decl(id);
+        self.decls.push(decl);
+        id
+    }
+
+    pub(crate) fn get_scope_mut(&mut self, id: ScopeId) -> &mut PyScope {
+        &mut self.scopes[id.id as usize]
+    }
+
+    pub fn get_scope(&self, id: ScopeId) -> Option<&PyScope> {
+        self.scopes.get(id.id as usize)
+    }
+
+    pub fn get_root_scope(&self) -> Option<&PyScope> {
+        self.scopes.first()
+    }
+
+    pub fn get_scopes(&self) -> &[PyScope] {
+        &self.scopes
+    }
+
+    pub fn get_decl(&self, id: DeclId) -> Option<&PyDecl> {
+        self.decls.get(id.id as usize)
+    }
+
+    pub fn get_decls(&self) -> &[PyDecl] {
+        &self.decls
+    }
+
+    /// The innermost scope which contains `position`
+    pub fn find_scope(&self, position: TextSize) -> Option<&PyScope> {
+        let mut scope = self.get_root_scope()?;
+        'outer: loop {
+            for child in scope.get_children() {
+                let child = &self.scopes[child.id as usize];
+                if child.contains(position) {
+                    scope = child;
+                    continue 'outer;
+                }
+            }
+
+            return Some(scope);
+        }
+    }
+
+    /// The declaration whose name is at exactly `range`
+    pub fn find_decl_by_range(&self, range: TextRange) -> Option<&PyDecl> {
+        self.decls.iter().find(|decl| decl.get_range() == range)
+    }
+
+    /// Resolve `name` as seen from `position`, following Python's LEGB rule
+    pub fn find_decl(&self, name: &str, position: TextSize) -> Option<&PyDecl> {
+        let start_scope = self.find_scope(position)?;
+        let mut scope = Some(start_scope);
+        while let Some(current) = scope {
+            if current.is_global(name) {
+                let root = self.get_root_scope()?;
+                return self.find_decl_in_scope(root, name, position);
+            }
+
+            // class bodies are not visible from nested scopes
+            let visible = current.get_kind() != PyScopeKind::Class
+                || current.get_id() == start_scope.get_id();
+            if visible
+                && !current.is_nonlocal(name)
+                && let Some(decl) = self.find_decl_in_scope(current, name, position)
+            {
+                return Some(decl);
+            }
+
+            scope = current.get_parent().and_then(|id| self.get_scope(id));
+        }
+
+        None
+    }
+
+    fn find_decl_in_scope(
+        &self,
+        scope: &PyScope,
+        name: &str,
+        position: TextSize,
+    ) -> Option<&PyDecl> {
+        let mut first = None;
+        let mut last_before = None;
+        for decl_id in scope.get_decls() {
+            let decl = &self.decls[decl_id.id as usize];
+            if decl.get_name() != name {
+                continue;
+            }
+
+            if first.is_none() {
+                first = Some(decl);
+            }
+            if decl.get_position() <= position {
+                last_before = Some(decl);
+            }
+        }
+
+        // a name used before its binding still refers to the binding in this scope
+        last_before.or(first)
+    }
+
+    pub fn get_scope_decls_by_name(&self, scope: ScopeId, name: &str) -> Vec<&PyDecl> {
+        let Some(scope) = self.get_scope(scope) else {
+            return Vec::new();
+        };
+        scope
+            .get_decls()
+            .iter()
+            .map(|id| &self.decls[id.id as usize])
+            .filter(|decl| decl.get_name() == name)
+            .collect()
+    }
+}
@@ -0,0 +1,7 @@
+mod decl_tree;
+mod py_decl;
+mod scope;
+
+pub use decl_tree::PyDeclTree;
+pub use py_decl::*;
+pub use scope::*;
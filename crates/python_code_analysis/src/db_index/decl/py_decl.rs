@@ -0,0 +1,128 @@
+use python_parser::PySyntaxId;
+use rowan::{TextRange, TextSize};
+
+use super::ScopeId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeclId {
+    pub id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PyDeclKind {
+    Function,
+    Class,
+    Parameter,
+    Variable,
+    /// `import a.b` binds `a`, `import a.b as c` binds `c`
+    Import {
+        module: String,
+    },
+    /// `from m import n as alias`
+    ImportFrom {
+        module: String,
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyDecl {
+    id: DeclId,
+    name: String,
+    kind: PyDeclKind,
+    scope: ScopeId,
+    // range of the name which introduces the declaration
+    range: TextRange,
+    // the defining node, e.g. FuncDef, ClassDef, Parameter, AssignStmt
+    syntax_id: Option<PySyntaxId>,
+    // the value bound to the name when it is known
+    value: Option<PySyntaxId>,
+    annotation: Option<PySyntaxId>,
+}
+
+impl PyDecl {
+    pub fn new(
+        id: DeclId,
+        name: String,
+        kind: PyDeclKind,
+        scope: ScopeId,
+        range: TextRange,
+    ) -> Self {
+        PyDecl {
+            id,
+            name,
+            kind,
+            scope,
+            range,
+            syntax_id: None,
+            value: None,
+            annotation: None,
+        }
+    }
+
+    pub fn with_syntax_id(mut self, syntax_id: PySyntaxId) -> Self {
+        self.syntax_id = Some(syntax_id);
+        self
+    }
+
+    pub fn with_value(mut self, value: Option<PySyntaxId>) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn with_annotation(mut self, annotation: Option<PySyntaxId>) -> Self {
+        self.annotation = annotation;
+        self
+    }
+
+    pub fn get_id(&self) -> DeclId {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_kind(&self) -> &PyDeclKind {
+        &self.kind
+    }
+
+    pub fn get_scope(&self) -> ScopeId {
+        self.scope
+    }
+
+    pub fn get_range(&self) -> TextRange {
+        self.range
+    }
+
+    pub fn get_position(&self) -> TextSize {
+        self.range.start()
+    }
+
+    pub fn get_syntax_id(&self) -> Option<PySyntaxId> {
+        self.syntax_id
+    }
+
+    pub fn get_value(&self) -> Option<PySyntaxId> {
+        self.value
+    }
+
+    pub fn get_annotation(&self) -> Option<PySyntaxId> {
+        self.annotation
+    }
+
+    pub fn is_function(&self) -> bool {
+        matches!(self.kind, PyDeclKind::Function)
+    }
+
+    pub fn is_class(&self) -> bool {
+        matches!(self.kind, PyDeclKind::Class)
+    }
+
+    pub fn is_import(&self) -> bool {
+        matches!(
+            self.kind,
+            PyDeclKind::Import { .. } | PyDeclKind::ImportFrom { .. }
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use rowan::{TextRange, TextSize};
+
+use super::DeclId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScopeId {
+    pub id: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyScopeKind {
+    Module,
+    Class,
+    Function,
+    Lambda,
+    Comprehension,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyScope {
+    id: ScopeId,
+    kind: PyScopeKind,
+    range: TextRange,
+    parent: Option<ScopeId>,
+    children: Vec<ScopeId>,
+    decls: Vec<DeclId>,
+    globals: Vec<String>,
+    nonlocals: Vec<String>,
+}
+
+impl PyScope {
+    pub fn new(id: ScopeId, kind: PyScopeKind, range: TextRange, parent: Option<ScopeId>) -> Self {
+        PyScope {
+            id,
+            kind,
+            range,
+            parent,
+            children: Vec::new(),
+            decls: Vec::new(),
+            globals: Vec::new(),
+            nonlocals: Vec::new(),
+        }
+    }
+
+    pub fn get_id(&self) -> ScopeId {
+        self.id
+    }
+
+    pub fn get_kind(&self) -> PyScopeKind {
+        self.kind
+    }
+
+    pub fn get_range(&self) -> TextRange {
+        self.range
+    }
+
+    pub fn get_parent(&self) -> Option<ScopeId> {
+        self.parent
+    }
+
+    pub fn get_children(&self) -> &[ScopeId] {
+        &self.children
+    }
+
+    pub fn get_decls(&self) -> &[DeclId] {
+        &self.decls
+    }
+
+    pub fn contains(&self, position: TextSize) -> bool {
+        self.range.contains_inclusive(position)
+    }
+
+    pub fn is_global(&self, name: &str) -> bool {
+        self.globals.iter().any(|it| it == name)
+    }
+
+    pub fn is_nonlocal(&self, name: &str) -> bool {
+        self.nonlocals.iter().any(|it| it == name)
+    }
+
+    pub(crate) fn add_child(&mut self, child: ScopeId) {
+        self.children.push(child);
+    }
+
+    pub(crate) fn add_decl(&mut self, decl_id: DeclId) {
+        self.decls.push(decl_id);
+    }
+
+    pub(crate) fn add_global(&mut self, name: String) {
+        self.globals.push(name);
+    }
+
+    pub(crate) fn add_nonlocal(&mut self, name: String) {
+        self.nonlocals.push(name);
+    }
+}
@@ -0,0 +1,73 @@
+mod decl;
+
+pub use decl::*;
+
+use std::sync::Arc;
+
+use crate::{PyConfig, PyEnvironment, QueryDb, QueryKey, Vfs};
+
+#[derive(Debug)]
+pub struct DbIndex {
+    vfs: Vfs,
+    config: Arc<PyConfig>,
+    environment: Option<Arc<PyEnvironment>>,
+    query_db: QueryDb,
+}
+
+impl Default for DbIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DbIndex {
+    pub fn new() -> Self {
+        DbIndex {
+            vfs: Vfs::new(),
+            config: Arc::new(PyConfig::default()),
+            environment: None,
+            query_db: QueryDb::new(),
+        }
+    }
+
+    pub fn get_vfs(&self) -> &Vfs {
+        &self.vfs
+    }
+
+    pub fn get_vfs_mut(&mut self) -> &mut Vfs {
+        &mut self.vfs
+    }
+
+    pub fn get_config(&self) -> Arc<PyConfig> {
+        self.config.clone()
+    }
+
+    pub fn set_config(&mut self, config: Arc<PyConfig>) {
+        if self.config.diagnostics != config.diagnostics {
+            self.query_db.new_revision();
+            self.query_db.set_changed(QueryKey::DiagnosticConfig);
+        }
+        // the source roots decide the module names files are imported by
+        if self.config.workspace != config.workspace {
+            self.query_db.new_revision();
+            self.query_db.set_changed(QueryKey::Files);
+        }
+        self.config = config;
+    }
+
+    pub fn get_environment(&self) -> Option<Arc<PyEnvironment>> {
+        self.environment.clone()
+    }
+
+    pub fn set_environment(&mut self, environment: Option<Arc<PyEnvironment>>) {
+        self.environment = environment;
+    }
+
+    pub fn get_query_db(&self) -> &QueryDb {
+        &self.query_db
+    }
+
+    pub fn get_query_db_mut(&mut self) -> &mut QueryDb {
+        &mut self.query_db
+    }
+}
@@ -0,0 +1,149 @@
+use lsp_types::DiagnosticSeverity;
+use python_parser::{
+    PyAstNode, PyAstToken, PyClassDef, PyDocstring, PyFuncDef, PySyntaxKind, PySyntaxNode,
+};
+use rowan::{TextRange, TextSize};
+
+use super::{
+    DiagnosticContext, LintRule,
+    unused_argument::{has_decorator, is_method},
+};
+use crate::{LintRuleMeta, parse_docstring};
+
+pub const DOC_ERROR: LintRuleMeta = LintRuleMeta {
+    code: "PY003",
+    name: "doc-error",
+    default_severity: DiagnosticSeverity::WARNING,
+    description: "Malformed docstrings",
+    default_enabled: true,
+    tags: &[],
+};
+
+/// Docstrings which break the rules of their own style, and documented
+/// parameters which do not match the signature. A docstring which
+/// documents no parameter at all is not asked to.
+pub struct DocErrorRule;
+
+impl LintRule for DocErrorRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[DOC_ERROR]
+    }
+
+    fn get_node_kinds(&self) -> &'static [PySyntaxKind] {
+        &[PySyntaxKind::FuncDef, PySyntaxKind::ClassDef]
+    }
+
+    fn check_node(&self, context: &mut DiagnosticContext, node: &PySyntaxNode) {
+        if let Some(func) = PyFuncDef::cast(node.clone()) {
+            if let Some(docstring) = func.get_docstring() {
+                check_docstring(context, &docstring, Some(signature_params(&func)));
+            }
+        } else if let Some(class) = PyClassDef::cast(node.clone())
+            && let Some(docstring) = class.get_docstring()
+        {
+            // a class documents the parameters of its `__init__`
+            let params = find_init(&class).map(|init| signature_params(&init));
+            check_docstring(context, &docstring, params);
+        }
+    }
+}
+
+fn check_docstring(
+    context: &mut DiagnosticContext,
+    docstring: &PyDocstring,
+    params: Option<Vec<(String, TextRange)>>,
+) {
+    let Some(content) = docstring.get_content() else {
+        return;
+    };
+    let doc = parse_docstring(&content);
+    for error in &doc.errors {
+        context.add_diagnostic(
+            &DOC_ERROR,
+            line_range(docstring, error.line),
+            error.message.clone(),
+            Vec::new(),
+        );
+    }
+
+    let Some(params) = params else {
+        return;
+    };
+    if doc.params.is_empty() {
+        return;
+    }
+    for documented in &doc.params {
+        let name = documented.name.trim_start_matches('*');
+        if !params.iter().any(|(param, _)| param == name) {
+            context.add_diagnostic(
+                &DOC_ERROR,
+                line_range(docstring, documented.line),
+                format!(
+                    "Parameter `{}` is documented but not in the signature",
+                    name
+                ),
+                Vec::new(),
+            );
+        }
+    }
+    for (name, range) in &params {
+        if doc.get_param(name).is_none() {
+            context.add_diagnostic(
+                &DOC_ERROR,
+                *range,
+                format!("Parameter `{}` is not documented", name),
+                Vec::new(),
+            );
+        }
+    }
+}
+
+/// The names of the parameters a caller passes, without the receiver of a
+/// method
+fn signature_params(func: &PyFuncDef) -> Vec<(String, TextRange)> {
+    let Some(params) = func.get_parameters() else {
+        return Vec::new();
+    };
+    let mut names: Vec<(String, TextRange)> = params
+        .get_args()
+        .filter_map(|param| param.get_name())
+        .map(|name| (name.get_name_text().to_string(), name.get_range()))
+        .collect();
+    if is_method(func) && !has_decorator(func, &["staticmethod"]) && !names.is_empty() {
+        names.remove(0);
+    }
+    names
+}
+
+fn find_init(class: &PyClassDef) -> Option<PyFuncDef> {
+    class
+        .get_body()?
+        .syntax()
+        .children()
+        .filter_map(|stat| match PyFuncDef::cast(stat.clone()) {
+            Some(func) => Some(func),
+            None => stat.children().find_map(PyFuncDef::cast),
+        })
+        .find(|func| {
+            func.get_name()
+                .is_some_and(|name| name.get_name_text() == "__init__")
+        })
+}
+
+/// The text of a line of the docstring in the source, the lines of its
+/// content are the lines of its token
+fn line_range(docstring: &PyDocstring, line: usize) -> TextRange {
+    let range = docstring.syntax().text_range();
+    let text = docstring.syntax().text().to_string();
+    let mut offset = 0;
+    for (i, source_line) in text.split('\n').enumerate() {
+        let trimmed = source_line.trim();
+        if i == line && !trimmed.is_empty() {
+            let indent = source_line.len() - source_line.trim_start().len();
+            let start = range.start() + TextSize::from((offset + indent) as u32);
+            return TextRange::at(start, TextSize::of(trimmed));
+        }
+        offset += source_line.len() + 1;
+    }
+    range
+}
@@ -0,0 +1,47 @@
+use python_parser::{PyAstNode, PyDocstring, PyParseErrorKind};
+
+use super::{DiagnosticContext, LintRule, doc_error::DOC_ERROR, syntax_error::VERSION_WARNING};
+use crate::{LintRuleMeta, extract_doc_examples};
+
+/// Syntax errors in the doctest and code block examples of docstrings
+pub struct DocExampleRule;
+
+impl LintRule for DocExampleRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[DOC_ERROR, VERSION_WARNING]
+    }
+
+    fn check_file(&self, context: &mut DiagnosticContext) {
+        let semantic_model = context.get_semantic_model();
+        let config = semantic_model.get_db().get_vfs().get_parser_config();
+        let docstrings: Vec<PyDocstring> = semantic_model.get_root().descendants().collect();
+        for docstring in docstrings {
+            for example in extract_doc_examples(&docstring, config) {
+                let errors = example.get_tree().get_errors();
+                // like Python the first syntax error is reported, the errors
+                // after it follow from it more often than not
+                let first_error = errors
+                    .iter()
+                    .find(|error| error.kind != PyParseErrorKind::VersionWarning);
+                let warnings = errors
+                    .iter()
+                    .filter(|error| error.kind == PyParseErrorKind::VersionWarning);
+                for error in first_error.into_iter().chain(warnings) {
+                    let Some(range) = example.to_host_range(error.range) else {
+                        continue;
+                    };
+                    let meta = match error.kind {
+                        PyParseErrorKind::VersionWarning => &VERSION_WARNING,
+                        _ => &DOC_ERROR,
+                    };
+                    context.add_diagnostic(
+                        meta,
+                        range,
+                        format!("In example: {}", error.message),
+                        Vec::new(),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use python_parser::{PyAstNode, PySyntaxKind, PySyntaxNode};
+
+use super::{DiagnosticContext, SYNTAX_ERROR};
+use crate::{LintRuleMeta, diagnostic::suppression::UNUSED_SUPPRESSION};
+
+/// A check reporting under the codes of its `get_metas`, which also give
+/// their default severity, description and default state. Rules may share
+/// a code, the first rule registered with it describes it.
+///
+/// File rules override `check_file` and look at the whole semantic model.
+/// Node rules list the kinds they want in `get_node_kinds` and are handed
+/// those nodes by `check_node` during a single walk of the tree shared by
+/// all rules.
+pub trait LintRule: Send + Sync {
+    fn get_metas(&self) -> &[LintRuleMeta];
+
+    fn get_node_kinds(&self) -> &'static [PySyntaxKind] {
+        &[]
+    }
+
+    fn check_node(&self, _context: &mut DiagnosticContext, _node: &PySyntaxNode) {}
+
+    fn check_file(&self, _context: &mut DiagnosticContext) {}
+}
+
+/// The rules run on every file, `Default` holds the builtin ones
+pub struct LintRegistry {
+    rules: Vec<Box<dyn LintRule>>,
+}
+
+impl LintRegistry {
+    pub fn new() -> Self {
+        LintRegistry { rules: Vec::new() }
+    }
+
+    pub fn register(&mut self, rule: Box<dyn LintRule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn get_rules(&self) -> &[Box<dyn LintRule>] {
+        &self.rules
+    }
+
+    /// Every code reported, once each in the order the rules were
+    /// registered, then the one of unused suppressions
+    pub fn get_metas(&self) -> Vec<&LintRuleMeta> {
+        let mut metas: Vec<&LintRuleMeta> = Vec::new();
+        let all = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.get_metas())
+            .chain([&UNUSED_SUPPRESSION]);
+        for meta in all {
+            if !metas.iter().any(|it| it.code == meta.code) {
+                metas.push(meta);
+            }
+        }
+        metas
+    }
+
+    /// Look up a code by its rule code or its name
+    pub fn find_meta(&self, name: &str) -> Option<&LintRuleMeta> {
+        self.get_metas().into_iter().find(|meta| meta.matches(name))
+    }
+
+    /// Like `find_meta`, but `None` for syntax errors: the code can not run
+    /// at all, suppressing the error hides that
+    pub fn find_suppressible(&self, name: &str) -> Option<&LintRuleMeta> {
+        self.find_meta(name)
+            .filter(|meta| meta.code != SYNTAX_ERROR.code)
+    }
+
+    pub fn run(&self, context: &mut DiagnosticContext) {
+        let rules: Vec<&dyn LintRule> = self
+            .rules
+            .iter()
+            .map(|rule| rule.as_ref())
+            .filter(|rule| rule.get_metas().iter().any(|meta| context.is_enabled(meta)))
+            .collect();
+
+        for rule in &rules {
+            rule.check_file(context);
+        }
+
+        let node_rules: Vec<&dyn LintRule> = rules
+            .into_iter()
+            .filter(|rule| !rule.get_node_kinds().is_empty())
+            .collect();
+        if node_rules.is_empty() {
+            return;
+        }
+        let root = context.get_semantic_model().get_root().syntax().clone();
+        for node in root.descendants() {
+            let kind: PySyntaxKind = node.kind().into();
+            for rule in &node_rules {
+                if rule.get_node_kinds().contains(&kind) {
+                    rule.check_node(context, &node);
+                }
+            }
+        }
+    }
+}
+
+impl Default for LintRegistry {
+    fn default() -> Self {
+        let mut registry = LintRegistry::new();
+        super::register_builtin_rules(&mut registry);
+        registry
+    }
+}
+
+impl std::fmt::Debug for LintRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.get_metas().iter().map(|meta| meta.code))
+            .finish()
+    }
+}
@@ -0,0 +1,112 @@
+mod doc_error;
+mod doc_example;
+mod lint_rule;
+mod none_comparison;
+mod syntax_error;
+mod undefined_name;
+mod unused_argument;
+mod unused_import;
+mod unused_variable;
+
+use std::{path::PathBuf, sync::Arc};
+
+use lsp_types::{Diagnostic, NumberOrString};
+use rowan::TextRange;
+
+pub use lint_rule::{LintRegistry, LintRule};
+use syntax_error::SYNTAX_ERROR;
+pub(crate) use undefined_name::UNDEFINED_NAME;
+pub(crate) use unused_import::{UNUSED_IMPORT, collect_dunder_all};
+pub(crate) use unused_variable::UNUSED_VARIABLE;
+
+use super::{DiagnosticData, DiagnosticFix, LintRuleMeta};
+use crate::{PyConfig, SemanticModel, uri_to_file_path};
+
+/// Collects the diagnostics of one file while the checkers run
+pub struct DiagnosticContext<'a> {
+    semantic_model: &'a SemanticModel<'a>,
+    config: Arc<PyConfig>,
+    file_path: Option<PathBuf>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> DiagnosticContext<'a> {
+    pub fn new(semantic_model: &'a SemanticModel<'a>) -> Self {
+        let db = semantic_model.get_db();
+        let file_path = db
+            .get_vfs()
+            .get_uri(&semantic_model.get_file_id())
+            .and_then(|uri| uri_to_file_path(&uri));
+        DiagnosticContext {
+            semantic_model,
+            config: db.get_config(),
+            file_path,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn get_semantic_model(&self) -> &'a SemanticModel<'a> {
+        self.semantic_model
+    }
+
+    /// Checkers skip the work for codes which would be dropped anyway
+    pub fn is_enabled(&self, meta: &LintRuleMeta) -> bool {
+        self.config
+            .diagnostics
+            .is_enabled(meta, self.file_path.as_deref())
+    }
+
+    pub fn add_diagnostic(
+        &mut self,
+        meta: &LintRuleMeta,
+        range: TextRange,
+        message: String,
+        fixes: Vec<DiagnosticFix>,
+    ) {
+        if !self.is_enabled(meta) {
+            return;
+        }
+        let Some(range) = self.semantic_model.get_document().to_lsp_range(range) else {
+            return;
+        };
+        let data = if fixes.is_empty() {
+            None
+        } else {
+            serde_json::to_value(DiagnosticData { fixes }).ok()
+        };
+        self.diagnostics.push(Diagnostic {
+            range,
+            severity: Some(
+                self.config
+                    .diagnostics
+                    .get_severity(meta, self.file_path.as_deref()),
+            ),
+            tags: meta.get_tags(),
+            code: Some(NumberOrString::String(meta.code.to_string())),
+            source: Some("python_ls".to_string()),
+            message,
+            data,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn retain_diagnostics(&mut self, f: impl FnMut(&Diagnostic) -> bool) {
+        self.diagnostics.retain(f);
+    }
+
+    pub fn finish(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
+/// New checks are added here, each with the `LintRuleMeta` of its codes
+fn register_builtin_rules(registry: &mut LintRegistry) {
+    registry.register(Box::new(syntax_error::SyntaxErrorRule));
+    registry.register(Box::new(undefined_name::UndefinedNameRule));
+    registry.register(Box::new(unused_import::UnusedImportRule));
+    registry.register(Box::new(unused_variable::UnusedVariableRule));
+    registry.register(Box::new(unused_argument::UnusedArgumentRule));
+    registry.register(Box::new(none_comparison::NoneComparisonRule));
+    registry.register(Box::new(doc_error::DocErrorRule));
+    registry.register(Box::new(doc_example::DocExampleRule));
+}
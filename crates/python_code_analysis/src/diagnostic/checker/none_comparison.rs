@@ -0,0 +1,78 @@
+use lsp_types::{DiagnosticSeverity, TextEdit};
+use python_parser::{PyAstNode, PyBinaryExpr, PyExpr, PySyntaxKind, PySyntaxNode, PyTokenKind};
+
+use super::{DiagnosticContext, LintRule};
+use crate::{DiagnosticFix, LintRuleMeta};
+
+pub const NONE_COMPARISON: LintRuleMeta = LintRuleMeta {
+    code: "PY008",
+    name: "none-comparison",
+    default_severity: DiagnosticSeverity::WARNING,
+    description: "Comparisons to `None` with `==` or `!=`",
+    default_enabled: true,
+    tags: &[],
+};
+
+/// `x == None` and `x != None`, which call `__eq__` where an identity check is meant
+pub struct NoneComparisonRule;
+
+impl LintRule for NoneComparisonRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[NONE_COMPARISON]
+    }
+
+    fn get_node_kinds(&self) -> &'static [PySyntaxKind] {
+        &[PySyntaxKind::BinaryExpr]
+    }
+
+    fn check_node(&self, context: &mut DiagnosticContext, node: &PySyntaxNode) {
+        let Some(binary) = PyBinaryExpr::cast(node.clone()) else {
+            return;
+        };
+        let Some(op) = node
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|token| matches!(token.kind().into(), PyTokenKind::TkEq | PyTokenKind::TkNe))
+        else {
+            return;
+        };
+        let is_none = |expr: Option<PyExpr>| match expr {
+            Some(PyExpr::LiteralExpr(literal)) => literal
+                .syntax()
+                .first_token()
+                .is_some_and(|token| token.kind() == PyTokenKind::TkNone.into()),
+            _ => false,
+        };
+        if !is_none(binary.get_left()) && !is_none(binary.get_right()) {
+            return;
+        }
+
+        let replacement = if op.kind() == PyTokenKind::TkEq.into() {
+            "is"
+        } else {
+            "is not"
+        };
+        let semantic_model = context.get_semantic_model();
+        let fix = semantic_model
+            .get_db()
+            .get_vfs()
+            .get_uri(&semantic_model.get_file_id())
+            .zip(semantic_model.get_document().to_lsp_range(op.text_range()))
+            .map(|(uri, range)| {
+                DiagnosticFix::new(
+                    format!("Replace `{}` with `{}`", op.text(), replacement),
+                    uri,
+                    vec![TextEdit {
+                        range,
+                        new_text: replacement.to_string(),
+                    }],
+                )
+            });
+        context.add_diagnostic(
+            &NONE_COMPARISON,
+            binary.get_range(),
+            format!("Comparison to `None` should use `{}`", replacement),
+            fix.into_iter().collect(),
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use lsp_types::DiagnosticSeverity;
+use python_parser::PyParseErrorKind;
+
+use super::{DiagnosticContext, LintRule, doc_error::DOC_ERROR};
+use crate::LintRuleMeta;
+
+pub const SYNTAX_ERROR: LintRuleMeta = LintRuleMeta {
+    code: "PY001",
+    name: "syntax-error",
+    default_severity: DiagnosticSeverity::ERROR,
+    description: "Code which does not parse",
+    default_enabled: true,
+    tags: &[],
+};
+
+pub const VERSION_WARNING: LintRuleMeta = LintRuleMeta {
+    code: "PY002",
+    name: "version-warning",
+    default_severity: DiagnosticSeverity::WARNING,
+    description: "Syntax which the configured Python version does not support",
+    default_enabled: true,
+    tags: &[],
+};
+
+/// Reports the errors the parser recorded
+pub struct SyntaxErrorRule;
+
+impl LintRule for SyntaxErrorRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[SYNTAX_ERROR, VERSION_WARNING, DOC_ERROR]
+    }
+
+    fn check_file(&self, context: &mut DiagnosticContext) {
+        check(context);
+    }
+}
+
+fn check(context: &mut DiagnosticContext) {
+    let errors = context
+        .get_semantic_model()
+        .get_syntax_tree()
+        .get_errors()
+        .to_vec();
+    for error in errors {
+        let meta = match error.kind {
+            PyParseErrorKind::SyntaxError
+            | PyParseErrorKind::IndentationError
+            | PyParseErrorKind::TabError => &SYNTAX_ERROR,
+            PyParseErrorKind::VersionWarning => &VERSION_WARNING,
+            PyParseErrorKind::DocError => &DOC_ERROR,
+        };
+        context.add_diagnostic(meta, error.range, error.message, Vec::new());
+    }
+}
@@ -0,0 +1,126 @@
+use lsp_types::DiagnosticSeverity;
+use python_parser::{PyAstNode, PyAstToken, PyNameExpr, PySyntaxKind, PySyntaxNode, PyTokenKind};
+use rowan::TextRange;
+
+use super::{DiagnosticContext, LintRule};
+use crate::{LintRuleMeta, add_import_fixes, is_builtin_name, is_keyword_name};
+
+pub const UNDEFINED_NAME: LintRuleMeta = LintRuleMeta {
+    code: "PY004",
+    name: "undefined-name",
+    default_severity: DiagnosticSeverity::ERROR,
+    description: "Names which are not bound in any enclosing scope",
+    default_enabled: true,
+    tags: &[],
+};
+
+pub struct UndefinedNameRule;
+
+impl LintRule for UndefinedNameRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[UNDEFINED_NAME]
+    }
+
+    fn check_file(&self, context: &mut DiagnosticContext) {
+        check(context);
+    }
+}
+
+fn check(context: &mut DiagnosticContext) {
+    let semantic_model = context.get_semantic_model();
+    let root = semantic_model.get_root();
+    // a star import may bind any name
+    if has_star_import(root.syntax()) {
+        return;
+    }
+    let error_ranges: Vec<TextRange> = semantic_model
+        .get_syntax_tree()
+        .get_errors()
+        .iter()
+        .map(|error| error.range)
+        .collect();
+
+    for name_expr in root.descendants::<PyNameExpr>() {
+        if is_keyword_name(&name_expr) {
+            continue;
+        }
+        let Some(name) = name_expr.get_name_token() else {
+            continue;
+        };
+        let text = name.get_name_text();
+        if is_builtin_name(text)
+            || semantic_model
+                .find_decl(text, name.get_position())
+                .is_some()
+            || is_implicit_name(&name_expr, text)
+            || is_type_param(&name_expr, text)
+            || in_error_statement(name_expr.syntax(), &error_ranges)
+        {
+            continue;
+        }
+        context.add_diagnostic(
+            &UNDEFINED_NAME,
+            name.get_range(),
+            format!("`{}` is not defined", text),
+            add_import_fixes(semantic_model, text),
+        );
+    }
+}
+
+fn has_star_import(root: &PySyntaxNode) -> bool {
+    root.descendants()
+        .filter(|node| node.kind() == PySyntaxKind::ImportFromStmt.into())
+        .any(|node| {
+            node.children_with_tokens()
+                .any(|it| it.kind() == PyTokenKind::TkMul.into())
+        })
+}
+
+/// `__class__` is bound in every function defined in a class body
+fn is_implicit_name(name_expr: &PyNameExpr, name: &str) -> bool {
+    name == "__class__"
+        && name_expr
+            .syntax()
+            .ancestors()
+            .any(|node| node.kind() == PySyntaxKind::FuncDef.into())
+}
+
+/// Type parameters, `T` in `def f[T](x: T)`, `class C[T]` and `type A[T] = ...`
+fn is_type_param(name_expr: &PyNameExpr, name: &str) -> bool {
+    name_expr
+        .syntax()
+        .ancestors()
+        .filter(|node| {
+            matches!(
+                node.kind().into(),
+                PySyntaxKind::FuncDef | PySyntaxKind::ClassDef | PySyntaxKind::TypeStatement
+            )
+        })
+        .any(|node| {
+            let mut in_brackets = false;
+            for token in node.children_with_tokens().filter_map(|it| it.into_token()) {
+                match token.kind().into() {
+                    PyTokenKind::TkLeftBracket => in_brackets = true,
+                    PyTokenKind::TkRightBracket => return false,
+                    PyTokenKind::TkName if in_brackets && token.text() == name => return true,
+                    _ => {}
+                }
+            }
+            false
+        })
+}
+
+/// Names in a statement the parser could not make sense of are not reported,
+/// the syntax error already is
+fn in_error_statement(node: &PySyntaxNode, error_ranges: &[TextRange]) -> bool {
+    let Some(stmt) = node.ancestors().find(|node| {
+        node.parent()
+            .is_some_and(|parent| parent.kind() == PySyntaxKind::Suite.into())
+    }) else {
+        return false;
+    };
+    let range = stmt.text_range();
+    error_ranges
+        .iter()
+        .any(|error| range.start() <= error.start() && error.start() <= range.end())
+}
@@ -0,0 +1,125 @@
+use lsp_types::{DiagnosticSeverity, DiagnosticTag};
+use python_parser::{PyAstNode, PyExpr, PyFuncDef, PyStat, PySyntaxKind};
+
+use super::{DiagnosticContext, LintRule};
+use crate::{LintRuleMeta, PyDeclKind, SemanticModel};
+
+pub const UNUSED_ARGUMENT: LintRuleMeta = LintRuleMeta {
+    code: "PY007",
+    name: "unused-argument",
+    default_severity: DiagnosticSeverity::HINT,
+    description: "Function arguments which are never read",
+    default_enabled: false,
+    tags: &[DiagnosticTag::UNNECESSARY],
+};
+
+/// Decorators whose functions only declare a signature
+const SIGNATURE_DECORATORS: &[&str] = &["overload", "abstractmethod", "override"];
+
+/// Parameters of a function which its body never reads. Off by default, an
+/// unused parameter is often required by the signature of a callback.
+pub struct UnusedArgumentRule;
+
+impl LintRule for UnusedArgumentRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[UNUSED_ARGUMENT]
+    }
+
+    fn check_file(&self, context: &mut DiagnosticContext) {
+        check(context);
+    }
+}
+
+fn check(context: &mut DiagnosticContext) {
+    let semantic_model = context.get_semantic_model();
+    for decl in semantic_model.get_decl_tree().get_decls() {
+        if *decl.get_kind() != PyDeclKind::Parameter || decl.get_name().starts_with('_') {
+            continue;
+        }
+        let Some(param) = decl
+            .get_syntax_id()
+            .and_then(|syntax_id| semantic_model.get_node(syntax_id))
+        else {
+            continue;
+        };
+        // lambdas are left alone
+        let Some(func) = param
+            .parent()
+            .and_then(|params| params.parent())
+            .and_then(PyFuncDef::cast)
+        else {
+            continue;
+        };
+        let is_receiver = is_method(&func)
+            && !has_decorator(&func, &["staticmethod"])
+            && param.prev_sibling().is_none();
+        if is_receiver
+            || is_stub(&func)
+            || has_decorator(&func, SIGNATURE_DECORATORS)
+            || calls_locals(semantic_model, &func)
+            || semantic_model.is_decl_referenced(decl)
+        {
+            continue;
+        }
+        context.add_diagnostic(
+            &UNUSED_ARGUMENT,
+            decl.get_range(),
+            format!("Argument `{}` is never used", decl.get_name()),
+            Vec::new(),
+        );
+    }
+}
+
+/// A function defined directly in a class body
+pub(super) fn is_method(func: &PyFuncDef) -> bool {
+    let definition = match func.syntax().parent() {
+        Some(parent) if parent.kind() == PySyntaxKind::AsyncFuncDef.into() => parent,
+        _ => func.syntax().clone(),
+    };
+    definition
+        .parent()
+        .and_then(|suite| suite.parent())
+        .is_some_and(|node| node.kind() == PySyntaxKind::ClassDef.into())
+}
+
+pub(super) fn has_decorator(func: &PyFuncDef, names: &[&str]) -> bool {
+    func.get_decorators().any(|decorator| {
+        let Some(expr) = decorator.get_name() else {
+            return false;
+        };
+        // `typing.overload` as well as `overload`
+        let text = expr.syntax().text().to_string();
+        let name = text.rsplit('.').next().unwrap_or(&text);
+        names.contains(&name)
+    })
+}
+
+/// A body of only a docstring, `pass`, `...` or `raise`
+fn is_stub(func: &PyFuncDef) -> bool {
+    let Some(body) = func.get_body() else {
+        return true;
+    };
+    body.children::<PyStat>()
+        .all(|stat| match stat.syntax().kind().into() {
+            PySyntaxKind::PassStmt | PySyntaxKind::RaiseStmt => true,
+            PySyntaxKind::ExprStmt => stat
+                .syntax()
+                .first_child()
+                .and_then(PyExpr::cast)
+                .is_some_and(|expr| matches!(expr, PyExpr::LiteralExpr(_))),
+            _ => false,
+        })
+}
+
+fn calls_locals(semantic_model: &SemanticModel, func: &PyFuncDef) -> bool {
+    func.syntax()
+        .descendants()
+        .filter(|node| node.kind() == PySyntaxKind::CallExpr.into())
+        .filter_map(|call| call.first_child())
+        .any(|callee| {
+            callee.text() == "locals"
+                && semantic_model
+                    .find_decl("locals", callee.text_range().start())
+                    .is_none()
+        })
+}
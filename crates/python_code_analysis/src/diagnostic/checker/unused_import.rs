@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use lsp_types::{DiagnosticSeverity, DiagnosticTag};
+use python_parser::{
+    PyAnnAssignStmt, PyAstNode, PyExpr, PySyntaxKind, PySyntaxNode, PySyntaxToken, PyTokenKind,
+};
+use rowan::NodeOrToken;
+
+use super::{DiagnosticContext, LintRule};
+use crate::{LintRuleMeta, PyDecl, PyDeclKind, remove_import_fix};
+
+pub const UNUSED_IMPORT: LintRuleMeta = LintRuleMeta {
+    code: "PY005",
+    name: "unused-import",
+    default_severity: DiagnosticSeverity::HINT,
+    description: "Imports which are never used",
+    default_enabled: true,
+    tags: &[DiagnosticTag::UNNECESSARY],
+};
+
+pub struct UnusedImportRule;
+
+impl LintRule for UnusedImportRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[UNUSED_IMPORT]
+    }
+
+    fn check_file(&self, context: &mut DiagnosticContext) {
+        check(context);
+    }
+}
+
+fn check(context: &mut DiagnosticContext) {
+    let semantic_model = context.get_semantic_model();
+    let root = semantic_model.get_root().syntax();
+    let exported = collect_dunder_all(root);
+    let annotation_names = collect_string_annotation_names(root);
+    let root_scope = semantic_model
+        .get_decl_tree()
+        .get_root_scope()
+        .map(|scope| scope.get_id());
+
+    for decl in semantic_model.get_decl_tree().get_decls() {
+        let module = match decl.get_kind() {
+            // `from __future__ import ...` changes how the module compiles
+            PyDeclKind::ImportFrom { module, .. } if module == "__future__" => continue,
+            PyDeclKind::Import { module } | PyDeclKind::ImportFrom { module, .. } => module,
+            _ => continue,
+        };
+        let name = decl.get_name();
+        if (Some(decl.get_scope()) == root_scope && exported.contains(name))
+            || annotation_names.contains(name)
+            || is_explicit_reexport(root, decl)
+            || semantic_model.is_decl_referenced(decl)
+        {
+            continue;
+        }
+        let Some(stmt) = decl
+            .get_syntax_id()
+            .and_then(|syntax_id| semantic_model.get_node(syntax_id))
+        else {
+            continue;
+        };
+        let imported = match decl.get_kind() {
+            PyDeclKind::Import { .. } if !has_alias(root, decl) => module.clone(),
+            _ => name.to_string(),
+        };
+        context.add_diagnostic(
+            &UNUSED_IMPORT,
+            decl.get_range(),
+            format!("`{}` is imported but never used", imported),
+            remove_import_fix(semantic_model, &stmt, &[decl])
+                .into_iter()
+                .collect(),
+        );
+    }
+}
+
+/// The names listed in a module level `__all__`
+pub(crate) fn collect_dunder_all(root: &PySyntaxNode) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let Some(suite) = root.first_child() else {
+        return names;
+    };
+    for stmt in suite.children() {
+        let is_all_assign = matches!(
+            stmt.kind().into(),
+            PySyntaxKind::AssignStmt | PySyntaxKind::AugAssignStmt | PySyntaxKind::AnnAssignStmt
+        ) && stmt
+            .first_child()
+            .and_then(PyExpr::cast)
+            .is_some_and(|target| target.syntax().text() == "__all__");
+        if !is_all_assign {
+            continue;
+        }
+        for token in stmt
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+        {
+            if token.kind() == PyTokenKind::TkString.into() {
+                names.insert(string_value(&token).to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Identifiers mentioned in quoted annotations such as `x: "Path"`, which is
+/// how names imported under `if TYPE_CHECKING:` are usually used
+fn collect_string_annotation_names(root: &PySyntaxNode) -> HashSet<String> {
+    root.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| token.kind() == PyTokenKind::TkString.into() && in_annotation(token))
+        .flat_map(|token| {
+            string_value(&token)
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .filter(|word| !word.is_empty())
+                .map(|word| word.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn in_annotation(token: &PySyntaxToken) -> bool {
+    let mut child = NodeOrToken::Token(token.clone());
+    for node in token.parent_ancestors() {
+        if node.kind() == PySyntaxKind::TypeAnnotation.into()
+            || node.kind() == PySyntaxKind::TypeComment.into()
+        {
+            return true;
+        }
+        if let Some(assign) = PyAnnAssignStmt::cast(node.clone()) {
+            return assign
+                .get_annotation()
+                .is_some_and(|annotation| child.as_node() == Some(annotation.syntax()));
+        }
+        child = NodeOrToken::Node(node);
+    }
+    false
+}
+
+fn string_value(token: &PySyntaxToken) -> &str {
+    token
+        .text()
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .trim_matches(['"', '\''])
+}
+
+/// `import a as a` and `from m import a as a` mark `a` as re-exported, see PEP 484
+fn is_explicit_reexport(root: &PySyntaxNode, decl: &PyDecl) -> bool {
+    let imported = match decl.get_kind() {
+        PyDeclKind::Import { module } => module,
+        PyDeclKind::ImportFrom { name, .. } => name,
+        _ => return false,
+    };
+    imported == decl.get_name() && has_alias(root, decl)
+}
+
+/// Whether the binding of an import comes after `as`
+fn has_alias(root: &PySyntaxNode, decl: &PyDecl) -> bool {
+    let NodeOrToken::Token(token) = root.covering_element(decl.get_range()) else {
+        return false;
+    };
+    let mut previous = token.prev_token();
+    while let Some(token) = previous {
+        match token.kind().into() {
+            PyTokenKind::TkWhitespace => previous = token.prev_token(),
+            PyTokenKind::TkAs => return true,
+            _ => return false,
+        }
+    }
+    false
+}
@@ -0,0 +1,175 @@
+use lsp_types::{DiagnosticSeverity, DiagnosticTag, TextEdit};
+use python_parser::{
+    PyAssignStmt, PyAstNode, PyExpr, PyNameExpr, PySyntaxKind, PySyntaxNode, PyTokenKind,
+};
+use rowan::{NodeOrToken, TextRange};
+
+use super::{DiagnosticContext, LintRule};
+use crate::{
+    DiagnosticFix, LintRuleMeta, PyDecl, PyDeclKind, PyScopeKind, SemanticModel,
+    remove_statement_edit,
+};
+
+pub const UNUSED_VARIABLE: LintRuleMeta = LintRuleMeta {
+    code: "PY006",
+    name: "unused-variable",
+    default_severity: DiagnosticSeverity::HINT,
+    description: "Local variables which are assigned but never read",
+    default_enabled: true,
+    tags: &[DiagnosticTag::UNNECESSARY],
+};
+
+/// Locals of a function which are bound by an assignment or `except ... as`
+/// and never read. Unpacking targets and `_` prefixed names are exempt.
+pub struct UnusedVariableRule;
+
+impl LintRule for UnusedVariableRule {
+    fn get_metas(&self) -> &[LintRuleMeta] {
+        &[UNUSED_VARIABLE]
+    }
+
+    fn check_file(&self, context: &mut DiagnosticContext) {
+        check(context);
+    }
+}
+
+fn check(context: &mut DiagnosticContext) {
+    let semantic_model = context.get_semantic_model();
+    let decl_tree = semantic_model.get_decl_tree();
+    let locals_calls = find_locals_calls(semantic_model);
+
+    for decl in decl_tree.get_decls() {
+        if *decl.get_kind() != PyDeclKind::Variable || decl.get_name().starts_with('_') {
+            continue;
+        }
+        let Some(scope) = decl_tree.get_scope(decl.get_scope()) else {
+            continue;
+        };
+        if scope.get_kind() != PyScopeKind::Function
+            || scope.is_global(decl.get_name())
+            || scope.is_nonlocal(decl.get_name())
+            // `locals()` reads every local
+            || locals_calls.iter().any(|range| scope.get_range().contains_range(*range))
+        {
+            continue;
+        }
+        let Some(stmt) = decl
+            .get_syntax_id()
+            .and_then(|syntax_id| semantic_model.get_node(syntax_id))
+        else {
+            continue;
+        };
+        if !is_simple_binding(semantic_model, decl, &stmt) {
+            continue;
+        }
+        let is_read = decl_tree
+            .get_scope_decls_by_name(scope.get_id(), decl.get_name())
+            .into_iter()
+            .any(|decl| semantic_model.is_decl_referenced(decl));
+        if is_read {
+            continue;
+        }
+        context.add_diagnostic(
+            &UNUSED_VARIABLE,
+            decl.get_range(),
+            format!(
+                "Local variable `{}` is assigned to but never used",
+                decl.get_name()
+            ),
+            remove_binding_fix(semantic_model, decl, &stmt)
+                .into_iter()
+                .collect(),
+        );
+    }
+}
+
+fn find_locals_calls(semantic_model: &SemanticModel) -> Vec<TextRange> {
+    semantic_model
+        .get_root()
+        .descendants::<PyNameExpr>()
+        .filter(|name_expr| name_expr.syntax().text() == "locals")
+        .filter(|name_expr| {
+            name_expr
+                .syntax()
+                .parent()
+                .is_some_and(|parent| parent.kind() == PySyntaxKind::CallExpr.into())
+        })
+        .map(|name_expr| name_expr.get_range())
+        .collect()
+}
+
+/// `x = ...`, `x: T = ...` and `except E as x`, but not `a, b = ...`
+fn is_simple_binding(semantic_model: &SemanticModel, decl: &PyDecl, stmt: &PySyntaxNode) -> bool {
+    match stmt.kind().into() {
+        PySyntaxKind::AssignStmt | PySyntaxKind::AnnAssignStmt => {
+            let target = semantic_model
+                .get_root()
+                .syntax()
+                .covering_element(decl.get_range());
+            let Some(name_expr) = target.parent() else {
+                return false;
+            };
+            // an annotation alone binds nothing at runtime
+            let has_value = stmt.children().filter_map(PyExpr::cast).count() > 1;
+            name_expr.kind() == PySyntaxKind::NameExpr.into()
+                && name_expr.parent().as_ref() == Some(stmt)
+                && has_value
+        }
+        PySyntaxKind::ExceptClause => true,
+        _ => false,
+    }
+}
+
+fn remove_binding_fix(
+    semantic_model: &SemanticModel,
+    decl: &PyDecl,
+    stmt: &PySyntaxNode,
+) -> Option<DiagnosticFix> {
+    let uri = semantic_model
+        .get_db()
+        .get_vfs()
+        .get_uri(&semantic_model.get_file_id())?;
+    let document = semantic_model.get_document();
+    let edit = match stmt.kind().into() {
+        PySyntaxKind::AssignStmt => {
+            let assign = PyAssignStmt::cast(stmt.clone())?;
+            let exprs: Vec<PyExpr> = assign.get_targets().collect();
+            let [_, value] = exprs.as_slice() else {
+                return None;
+            };
+            if matches!(value, PyExpr::LiteralExpr(_) | PyExpr::NameExpr(_)) {
+                remove_statement_edit(semantic_model, stmt)?
+            } else {
+                // keep the value, it may have side effects
+                let range = TextRange::new(stmt.text_range().start(), value.get_position());
+                TextEdit {
+                    range: document.to_lsp_range(range)?,
+                    new_text: String::new(),
+                }
+            }
+        }
+        PySyntaxKind::ExceptClause => {
+            // ` as e` from the end of the exception type to the name
+            let as_token = stmt
+                .children_with_tokens()
+                .filter_map(|it| it.into_token())
+                .find(|token| token.kind() == PyTokenKind::TkAs.into())?;
+            let mut start = as_token.text_range().start();
+            if let Some(NodeOrToken::Token(previous)) = as_token.prev_sibling_or_token()
+                && previous.kind() == PyTokenKind::TkWhitespace.into()
+            {
+                start = previous.text_range().start();
+            }
+            TextEdit {
+                range: document.to_lsp_range(TextRange::new(start, decl.get_range().end()))?,
+                new_text: String::new(),
+            }
+        }
+        _ => return None,
+    };
+    Some(DiagnosticFix::new(
+        format!("Remove assignment to unused variable `{}`", decl.get_name()),
+        uri,
+        vec![edit],
+    ))
+}
@@ -0,0 +1,431 @@
+use lsp_types::TextEdit;
+use python_parser::{PyAstNode, PyExpr, PySyntaxKind, PySyntaxNode, PyTokenKind};
+use rowan::{TextRange, TextSize};
+
+use super::remove_statement_edit;
+use crate::{
+    DiagnosticFix, PyDecl, PyDeclKind, QueryKey, SemanticModel, collect_import_items,
+    module_exports, semantic::module_name,
+};
+
+/// Standard library modules which are usually imported as a whole
+const STDLIB_MODULES: &[&str] = &[
+    "abc",
+    "argparse",
+    "asyncio",
+    "base64",
+    "collections",
+    "copy",
+    "csv",
+    "dataclasses",
+    "datetime",
+    "enum",
+    "functools",
+    "glob",
+    "hashlib",
+    "inspect",
+    "io",
+    "itertools",
+    "json",
+    "logging",
+    "math",
+    "operator",
+    "os",
+    "pathlib",
+    "pickle",
+    "random",
+    "re",
+    "shutil",
+    "socket",
+    "string",
+    "subprocess",
+    "sys",
+    "tempfile",
+    "textwrap",
+    "threading",
+    "time",
+    "traceback",
+    "typing",
+    "unittest",
+    "uuid",
+    "warnings",
+];
+
+/// Names which are usually imported from a standard library module
+const STDLIB_NAMES: &[(&str, &str)] = &[
+    ("ABC", "abc"),
+    ("abstractmethod", "abc"),
+    ("Counter", "collections"),
+    ("OrderedDict", "collections"),
+    ("defaultdict", "collections"),
+    ("deque", "collections"),
+    ("namedtuple", "collections"),
+    ("dataclass", "dataclasses"),
+    ("field", "dataclasses"),
+    ("date", "datetime"),
+    ("datetime", "datetime"),
+    ("timedelta", "datetime"),
+    ("Enum", "enum"),
+    ("IntEnum", "enum"),
+    ("cache", "functools"),
+    ("lru_cache", "functools"),
+    ("partial", "functools"),
+    ("reduce", "functools"),
+    ("wraps", "functools"),
+    ("Path", "pathlib"),
+    ("Any", "typing"),
+    ("Callable", "typing"),
+    ("ClassVar", "typing"),
+    ("Dict", "typing"),
+    ("Generic", "typing"),
+    ("Iterable", "typing"),
+    ("Iterator", "typing"),
+    ("List", "typing"),
+    ("Literal", "typing"),
+    ("Mapping", "typing"),
+    ("Optional", "typing"),
+    ("Protocol", "typing"),
+    ("Sequence", "typing"),
+    ("Set", "typing"),
+    ("Tuple", "typing"),
+    ("TYPE_CHECKING", "typing"),
+    ("TypeVar", "typing"),
+    ("Union", "typing"),
+    ("cast", "typing"),
+    ("overload", "typing"),
+];
+
+/// Fixes which import the unresolved `name`, from the workspace modules which
+/// define it at module level first and then from the standard library
+pub fn add_import_fixes(semantic_model: &SemanticModel, name: &str) -> Vec<DiagnosticFix> {
+    let db = semantic_model.get_db();
+    let Some(uri) = db.get_vfs().get_uri(&semantic_model.get_file_id()) else {
+        return Vec::new();
+    };
+
+    // the fixes change when a file is added or removed or when its exports
+    // change, reading them makes the diagnostics depend on them
+    let queries = db.get_query_db();
+    if queries.read(QueryKey::Files).is_err() {
+        return Vec::new();
+    }
+    let source_roots = db.get_config().workspace.get_source_roots();
+    let mut modules = Vec::new();
+    let mut file_ids = db.get_vfs().get_all_file_ids();
+    file_ids.sort();
+    for file_id in file_ids {
+        if file_id == semantic_model.get_file_id() {
+            continue;
+        }
+        let Ok(exports) = module_exports(db, file_id) else {
+            return Vec::new();
+        };
+        let defines_name = exports.iter().any(|export| {
+            export.name == name
+                && matches!(
+                    export.kind,
+                    PyDeclKind::Function | PyDeclKind::Class | PyDeclKind::Variable
+                )
+        });
+        if defines_name
+            && let Some(module) = db
+                .get_vfs()
+                .get_uri(&file_id)
+                .and_then(|it| module_name(&it, &source_roots))
+            && !modules.contains(&module)
+        {
+            modules.push(module);
+        }
+    }
+    for (stdlib_name, module) in STDLIB_NAMES {
+        if *stdlib_name == name && !modules.iter().any(|it| it == module) {
+            modules.push(module.to_string());
+        }
+    }
+
+    let mut fixes = Vec::new();
+    if STDLIB_MODULES.contains(&name) {
+        let edit = insert_import_edit(semantic_model, format!("import {}", name));
+        fixes.push(DiagnosticFix::new(
+            format!("Add `import {}`", name),
+            uri.clone(),
+            vec![edit],
+        ));
+    }
+    for module in modules {
+        let edit = extend_import_from_edit(semantic_model, &module, name).unwrap_or_else(|| {
+            insert_import_edit(semantic_model, format!("from {} import {}", module, name))
+        });
+        fixes.push(DiagnosticFix::new(
+            format!("Add `from {} import {}`", module, name),
+            uri.clone(),
+            vec![edit],
+        ));
+    }
+    fixes
+}
+
+/// Removes the bindings of `unused` from the import statement `stmt`, or the
+/// whole statement when none of its bindings are used
+pub fn remove_import_fix(
+    semantic_model: &SemanticModel,
+    stmt: &PySyntaxNode,
+    unused: &[&PyDecl],
+) -> Option<DiagnosticFix> {
+    let uri = semantic_model
+        .get_db()
+        .get_vfs()
+        .get_uri(&semantic_model.get_file_id())?;
+    let items = collect_import_items(stmt, PyTokenKind::TkImport);
+    let is_removed: Vec<bool> = items
+        .iter()
+        .map(|item| {
+            let binding = item.alias.as_ref().unwrap_or(&item.path[0]);
+            unused
+                .iter()
+                .any(|decl| decl.get_range() == binding.text_range())
+        })
+        .collect();
+    let removed: Vec<_> = items
+        .iter()
+        .zip(&is_removed)
+        .filter_map(|(item, removed)| removed.then_some(item))
+        .collect();
+    if removed.is_empty() {
+        return None;
+    }
+
+    let title = match removed.as_slice() {
+        [item] => format!(
+            "Remove unused import `{}`",
+            item.alias
+                .as_ref()
+                .map(|alias| alias.text().to_string())
+                .unwrap_or_else(|| item.path_text())
+        ),
+        _ => "Remove unused imports".to_string(),
+    };
+    let edits = if removed.len() == items.len() {
+        vec![remove_statement_edit(semantic_model, stmt)?]
+    } else {
+        let item_ranges: Vec<TextRange> = items
+            .iter()
+            .map(|item| {
+                let last = item.alias.as_ref().or(item.path.last())?;
+                Some(TextRange::new(
+                    item.path.first()?.text_range().start(),
+                    last.text_range().end(),
+                ))
+            })
+            .collect::<Option<_>>()?;
+        let document = semantic_model.get_document();
+        remove_import_items_ranges(semantic_model, stmt, &item_ranges, &is_removed)?
+            .into_iter()
+            .map(|range| {
+                Some(TextEdit {
+                    range: document.to_lsp_range(range)?,
+                    new_text: String::new(),
+                })
+            })
+            .collect::<Option<_>>()?
+    };
+    Some(DiagnosticFix::new(title, uri, edits))
+}
+
+/// The ranges to delete for the removed names of an import which keeps some
+/// of them. The comments of the kept names stay where they are, and a line
+/// left empty goes away.
+fn remove_import_items_ranges(
+    semantic_model: &SemanticModel,
+    stmt: &PySyntaxNode,
+    items: &[TextRange],
+    is_removed: &[bool],
+) -> Option<Vec<TextRange>> {
+    let document = semantic_model.get_document();
+    let commas: Vec<TextRange> = stmt
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| token.kind() == PyTokenKind::TkComma.into())
+        .map(|token| token.text_range())
+        .collect();
+    let comma_after =
+        |offset: TextSize| commas.iter().find(|comma| comma.start() >= offset).copied();
+    // only spaces between two tokens, no comment or line break
+    let is_inline_space = |start: TextSize, end: TextSize| {
+        document
+            .get_text_slice(TextRange::new(start, end))
+            .chars()
+            .all(|c| c == ' ' || c == '\t')
+    };
+
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < items.len() {
+        if !is_removed[i] {
+            i += 1;
+            continue;
+        }
+        let mut j = i;
+        while is_removed.get(j + 1) == Some(&true) {
+            j += 1;
+        }
+        let (start, end) = (items[i].start(), items[j].end());
+        if let Some(next) = items.get(j + 1) {
+            // up to the kept name after them, the comments between go with the removed names
+            ranges.push(TextRange::new(start, next.start()));
+        } else {
+            // the names end the list, the separator before them goes instead
+            let previous_comma = comma_after(items.get(i.checked_sub(1)?)?.end())?;
+            match comma_after(end) {
+                // a trailing comma stays with the name before
+                Some(comma) if is_inline_space(previous_comma.end(), start) => {
+                    ranges.push(TextRange::new(previous_comma.end(), comma.end()))
+                }
+                Some(comma) => ranges.push(TextRange::new(start, comma.end())),
+                None if is_inline_space(previous_comma.end(), start) => {
+                    ranges.push(TextRange::new(previous_comma.start(), end))
+                }
+                None => {
+                    ranges.push(previous_comma);
+                    ranges.push(TextRange::new(start, end));
+                }
+            }
+        }
+        i = j + 1;
+    }
+    Some(
+        ranges
+            .into_iter()
+            .map(|range| extend_to_empty_line(semantic_model, range))
+            .collect(),
+    )
+}
+
+/// The whole line of `range` when nothing else but whitespace is on it
+fn extend_to_empty_line(semantic_model: &SemanticModel, range: TextRange) -> TextRange {
+    let document = semantic_model.get_document();
+    let (Some(start_line), Some(end_line)) = (
+        document.get_line(range.start()),
+        document.get_line(range.end()),
+    ) else {
+        return range;
+    };
+    let (Some(first), Some(last)) = (
+        document.get_line_range(start_line),
+        document.get_line_range(end_line),
+    ) else {
+        return range;
+    };
+    let before = document.get_text_slice(TextRange::new(first.start(), range.start()));
+    let after = document.get_text_slice(TextRange::new(range.end(), last.end()));
+    if before.trim().is_empty() && after.trim().is_empty() {
+        TextRange::new(first.start(), last.end())
+    } else {
+        range
+    }
+}
+
+/// Add `name` to an existing module level `from module import ...`
+fn extend_import_from_edit(
+    semantic_model: &SemanticModel,
+    module: &str,
+    name: &str,
+) -> Option<TextEdit> {
+    let suite = semantic_model.get_root().syntax().first_child()?;
+    let stmt = suite.children().find(|stmt| {
+        stmt.kind() == PySyntaxKind::ImportFromStmt.into() && import_from_module(stmt) == module
+    })?;
+    let items = collect_import_items(&stmt, PyTokenKind::TkImport);
+    let last = items.last()?;
+    let end = last
+        .alias
+        .as_ref()
+        .unwrap_or(last.path.last()?)
+        .text_range()
+        .end();
+    let position = semantic_model.get_document().to_lsp_position(end)?;
+    Some(TextEdit {
+        range: lsp_types::Range::new(position, position),
+        new_text: format!(", {}", name),
+    })
+}
+
+fn import_from_module(stmt: &PySyntaxNode) -> String {
+    let mut module = String::new();
+    for token in stmt.children_with_tokens().filter_map(|it| it.into_token()) {
+        match token.kind().into() {
+            PyTokenKind::TkImport => break,
+            PyTokenKind::TkName | PyTokenKind::TkDot => module.push_str(token.text()),
+            PyTokenKind::TkEllipsis => module.push_str("..."),
+            _ => {}
+        }
+    }
+    module
+}
+
+/// Insert `import_text` on its own line after the leading imports of the
+/// module, or after the module docstring when there are none
+fn insert_import_edit(semantic_model: &SemanticModel, import_text: String) -> TextEdit {
+    let document = semantic_model.get_document();
+    let mut anchor: Option<PySyntaxNode> = None;
+    let mut first_stmt: Option<PySyntaxNode> = None;
+    if let Some(suite) = semantic_model.get_root().syntax().first_child() {
+        for stmt in suite.children() {
+            if first_stmt.is_none() {
+                first_stmt = Some(stmt.clone());
+            }
+            let is_import = matches!(
+                stmt.kind().into(),
+                PySyntaxKind::ImportStmt | PySyntaxKind::ImportFromStmt
+            );
+            if is_import || (anchor.is_none() && is_docstring_stmt(&stmt)) {
+                anchor = Some(stmt);
+            } else if stmt.text_range().is_empty() {
+                continue;
+            } else {
+                break;
+            }
+        }
+    }
+
+    let (offset, new_text) = match (anchor, first_stmt) {
+        (Some(anchor), _) => {
+            let end = anchor.text_range().end();
+            match document
+                .get_line(end)
+                .and_then(|line| document.get_line_range(line + 1))
+            {
+                Some(next_line) => (next_line.start(), format!("{}\n", import_text)),
+                None => (end, format!("\n{}", import_text)),
+            }
+        }
+        (None, Some(first)) => {
+            let start = first.text_range().start();
+            let line_start = document
+                .get_line(start)
+                .and_then(|line| document.get_line_range(line))
+                .map(|range| range.start())
+                .unwrap_or(start);
+            (line_start, format!("{}\n", import_text))
+        }
+        (None, None) => (TextSize::from(0), format!("{}\n", import_text)),
+    };
+    let position = document
+        .to_lsp_position(offset)
+        .unwrap_or(lsp_types::Position::new(0, 0));
+    TextEdit {
+        range: lsp_types::Range::new(position, position),
+        new_text,
+    }
+}
+
+fn is_docstring_stmt(stmt: &PySyntaxNode) -> bool {
+    stmt.kind() == PySyntaxKind::ExprStmt.into()
+        && stmt
+            .first_child()
+            .and_then(PyExpr::cast)
+            .is_some_and(|expr| matches!(expr, PyExpr::LiteralExpr(_)))
+        && stmt
+            .first_token()
+            .is_some_and(|token| token.kind() == PyTokenKind::TkString.into())
+}
@@ -0,0 +1,55 @@
+mod import_fix;
+
+use lsp_types::TextEdit;
+use python_parser::{PySyntaxKind, PySyntaxNode};
+use rowan::TextRange;
+
+pub use import_fix::{add_import_fixes, remove_import_fix};
+
+use crate::SemanticModel;
+
+/// Delete a statement together with its line, or replace it with `pass` when
+/// it is the only statement of its block
+pub fn remove_statement_edit(
+    semantic_model: &SemanticModel,
+    stmt: &PySyntaxNode,
+) -> Option<TextEdit> {
+    let document = semantic_model.get_document();
+    let is_only_stmt = stmt.parent().is_some_and(|suite| {
+        suite.kind() == PySyntaxKind::Suite.into()
+            && suite
+                .parent()
+                .is_some_and(|it| it.kind() != PySyntaxKind::Module.into())
+            && suite.children().count() == 1
+    });
+    if is_only_stmt {
+        return Some(TextEdit {
+            range: document.to_lsp_range(stmt.text_range())?,
+            new_text: "pass".to_string(),
+        });
+    }
+
+    let range = stmt.text_range();
+    let start_line = document.get_line(range.start())?;
+    let end_line = document.get_line(range.end())?;
+    let line_start = document.get_line_range(start_line)?.start();
+    let before = document.get_text_slice(TextRange::new(line_start, range.start()));
+    let line_end = document.get_line_range(end_line)?.end();
+    let after = document.get_text_slice(TextRange::new(range.end(), line_end));
+    // a trailing comment is about the statement and goes with it
+    let after = after.trim();
+    let alone = before.trim().is_empty() && (after.is_empty() || after.starts_with('#'));
+    let range = if alone {
+        let end = document
+            .get_line_range(end_line + 1)
+            .map(|next| next.start())
+            .unwrap_or(line_end);
+        TextRange::new(line_start, end)
+    } else {
+        range
+    };
+    Some(TextEdit {
+        range: document.to_lsp_range(range)?,
+        new_text: String::new(),
+    })
+}
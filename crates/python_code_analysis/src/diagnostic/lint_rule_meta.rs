@@ -0,0 +1,27 @@
+use lsp_types::{DiagnosticSeverity, DiagnosticTag};
+
+/// What a lint rule reports under. Both the code (`PY005`) and the name
+/// (`unused-import`) are accepted wherever users write one, neither changes
+/// once released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LintRuleMeta {
+    /// The rule code, reported as `Diagnostic.code`
+    pub code: &'static str,
+    pub name: &'static str,
+    pub default_severity: DiagnosticSeverity,
+    pub description: &'static str,
+    /// Rules which are off by default are turned on by the config
+    pub default_enabled: bool,
+    pub tags: &'static [DiagnosticTag],
+}
+
+impl LintRuleMeta {
+    /// Whether `text` is the code or the name of the rule
+    pub fn matches(&self, text: &str) -> bool {
+        self.name == text || self.code.eq_ignore_ascii_case(text)
+    }
+
+    pub fn get_tags(&self) -> Option<Vec<DiagnosticTag>> {
+        (!self.tags.is_empty()).then(|| self.tags.to_vec())
+    }
+}
@@ -0,0 +1,55 @@
+mod checker;
+mod fix;
+mod lint_rule_meta;
+mod suppression;
+
+use std::collections::HashMap;
+
+use lsp_types::{Diagnostic, TextEdit, Uri, WorkspaceEdit};
+use serde::{Deserialize, Serialize};
+
+pub(crate) use checker::collect_dunder_all;
+pub use checker::{DiagnosticContext, LintRegistry, LintRule};
+pub use fix::*;
+pub use lint_rule_meta::LintRuleMeta;
+
+use crate::SemanticModel;
+
+/// A quick fix computed together with its diagnostic. It travels to the
+/// client in `Diagnostic.data` and comes back with the code action request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticFix {
+    pub title: String,
+    pub edit: WorkspaceEdit,
+}
+
+impl DiagnosticFix {
+    pub fn new(title: String, uri: Uri, edits: Vec<TextEdit>) -> Self {
+        DiagnosticFix {
+            title,
+            edit: WorkspaceEdit {
+                changes: Some(HashMap::from([(uri, edits)])),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DiagnosticData {
+    pub fixes: Vec<DiagnosticFix>,
+}
+
+impl DiagnosticData {
+    /// The fixes attached to a diagnostic published by this server
+    pub fn from_diagnostic(diagnostic: &Diagnostic) -> Option<DiagnosticData> {
+        serde_json::from_value(diagnostic.data.clone()?).ok()
+    }
+}
+
+pub fn diagnose_file(semantic_model: &SemanticModel, registry: &LintRegistry) -> Vec<Diagnostic> {
+    let mut context = DiagnosticContext::new(semantic_model);
+    registry.run(&mut context);
+    suppression::apply_suppressions(&mut context, registry);
+    context.finish()
+}
@@ -0,0 +1,446 @@
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, TextEdit};
+use python_parser::{PyAstNode, PySyntaxToken, PyTokenKind};
+use rowan::{TextRange, TextSize};
+
+use super::{
+    DiagnosticContext, DiagnosticFix, LintRegistry, LintRuleMeta,
+    checker::{UNDEFINED_NAME, UNUSED_IMPORT, UNUSED_VARIABLE},
+};
+use crate::SemanticModel;
+
+pub const UNUSED_SUPPRESSION: LintRuleMeta = LintRuleMeta {
+    code: "PY009",
+    name: "unused-suppression",
+    default_severity: DiagnosticSeverity::HINT,
+    description: "Suppression comments which suppress nothing",
+    default_enabled: true,
+    tags: &[DiagnosticTag::UNNECESSARY],
+};
+
+/// Drop the diagnostics covered by suppression comments, then report the
+/// suppressions of known codes which covered nothing
+pub fn apply_suppressions(context: &mut DiagnosticContext, registry: &LintRegistry) {
+    let semantic_model = context.get_semantic_model();
+    let mut suppressions = collect_suppressions(semantic_model, registry);
+    if suppressions.is_empty() {
+        return;
+    }
+    context.retain_diagnostics(|diagnostic| {
+        let Some(NumberOrString::String(code)) = &diagnostic.code else {
+            return true;
+        };
+        let Some(meta) = registry.find_suppressible(code) else {
+            return true;
+        };
+        // every suppression covering it counts as used, not only the first
+        let mut suppressed = false;
+        for suppression in suppressions.iter_mut() {
+            suppressed |= suppression.suppress(meta.code, diagnostic);
+        }
+        !suppressed
+    });
+
+    for suppression in &suppressions {
+        for unused in suppression.get_unused_codes() {
+            let fix = remove_code_fix(semantic_model, suppression, unused);
+            context.add_diagnostic(
+                &UNUSED_SUPPRESSION,
+                unused.range,
+                format!("Unused suppression of `{}`", unused.text),
+                fix.into_iter().collect(),
+            );
+        }
+        if suppression.is_unused_pragma() {
+            context.add_diagnostic(
+                &UNUSED_SUPPRESSION,
+                suppression.range,
+                "Unused suppression comment".to_string(),
+                Vec::new(),
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SuppressionScope {
+    /// A trailing `# noqa`, `# type: ignore` or `# pyright: ignore`
+    Line(u32),
+    /// `# ruff: noqa` and friends, or a bare `# type: ignore` before the
+    /// first statement
+    File,
+    /// `# python_ls: disable` up to the next matching `# python_ls: enable`
+    Region { start: u32, end: u32 },
+}
+
+#[derive(Debug)]
+struct SuppressedCode {
+    text: String,
+    /// The rule code it names, `None` for the codes of other tools
+    code: Option<&'static str>,
+    /// Named by the code of another checker, which may still need it when it
+    /// suppressed nothing here
+    is_alias: bool,
+    range: TextRange,
+    used: bool,
+}
+
+#[derive(Debug)]
+struct Suppression {
+    scope: SuppressionScope,
+    /// Empty when every code is suppressed
+    codes: Vec<SuppressedCode>,
+    /// The pragma within the comment
+    range: TextRange,
+    comment: PySyntaxToken,
+    /// Pragmas of this server are reported when unused even without codes,
+    /// a bare `# noqa` may be there for another tool
+    is_own: bool,
+    used: bool,
+}
+
+impl Suppression {
+    fn suppress(&mut self, code: &str, diagnostic: &Diagnostic) -> bool {
+        let (start, end) = (diagnostic.range.start.line, diagnostic.range.end.line);
+        let covers = match self.scope {
+            SuppressionScope::Line(line) => line == start || line == end,
+            SuppressionScope::File => true,
+            SuppressionScope::Region {
+                start: region_start,
+                end: region_end,
+            } => region_start <= start && start <= region_end,
+        };
+        if !covers {
+            return false;
+        }
+        if self.codes.is_empty() {
+            self.used = true;
+            return true;
+        }
+        match self
+            .codes
+            .iter_mut()
+            .find(|suppressed| suppressed.code == Some(code))
+        {
+            Some(suppressed) => {
+                suppressed.used = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Codes of this server which suppressed nothing, foreign codes are left alone
+    fn get_unused_codes(&self) -> impl Iterator<Item = &SuppressedCode> {
+        self.codes.iter().filter(|suppressed| {
+            suppressed.code.is_some() && !suppressed.is_alias && !suppressed.used
+        })
+    }
+
+    fn is_unused_pragma(&self) -> bool {
+        self.is_own && self.codes.is_empty() && !self.used
+    }
+}
+
+fn collect_suppressions(
+    semantic_model: &SemanticModel,
+    registry: &LintRegistry,
+) -> Vec<Suppression> {
+    let document = semantic_model.get_document();
+    let mut suppressions = Vec::new();
+    // disable regions which are still open, closed by `enable` or the end of the file
+    let mut open_regions: Vec<Suppression> = Vec::new();
+    let comments = semantic_model
+        .get_root()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| token.kind() == PyTokenKind::TkComment.into());
+    let first_statement = semantic_model
+        .get_root()
+        .get_suite()
+        .and_then(|suite| suite.get_statements().next())
+        .map(|statement| statement.get_position());
+
+    for comment in comments {
+        let Some(line) = document.get_line(comment.text_range().start()) else {
+            continue;
+        };
+        let line = line as u32;
+        for Pragma { kind, codes, range } in parse_pragmas(&comment, registry) {
+            let (scope, is_own) = match kind {
+                PragmaKind::Line => (SuppressionScope::Line(line), false),
+                // mypy takes a bare one at the top of the module for the whole module
+                PragmaKind::TypeIgnore
+                    if codes.is_empty()
+                        && first_statement
+                            .is_none_or(|start| comment.text_range().start() < start) =>
+                {
+                    (SuppressionScope::File, false)
+                }
+                PragmaKind::TypeIgnore => (SuppressionScope::Line(line), false),
+                PragmaKind::File { is_own } => (SuppressionScope::File, is_own),
+                PragmaKind::Disable => (
+                    SuppressionScope::Region {
+                        start: line,
+                        end: u32::MAX,
+                    },
+                    true,
+                ),
+                PragmaKind::Enable => {
+                    let enabled: Vec<&str> = codes.iter().filter_map(|code| code.code).collect();
+                    close_regions(&mut open_regions, &enabled, line, &mut suppressions);
+                    continue;
+                }
+            };
+            let suppression = Suppression {
+                scope,
+                codes,
+                range,
+                comment: comment.clone(),
+                is_own,
+                used: false,
+            };
+            if matches!(scope, SuppressionScope::Region { .. }) {
+                open_regions.push(suppression);
+            } else {
+                suppressions.push(suppression);
+            }
+        }
+    }
+    suppressions.extend(open_regions);
+    suppressions
+}
+
+/// End the open regions of `enabled` at `line`, or every region for a bare `enable`
+fn close_regions(
+    open_regions: &mut Vec<Suppression>,
+    enabled: &[&str],
+    line: u32,
+    suppressions: &mut Vec<Suppression>,
+) {
+    for mut region in std::mem::take(open_regions) {
+        let SuppressionScope::Region { start, .. } = region.scope else {
+            continue;
+        };
+        let closed_scope = SuppressionScope::Region { start, end: line };
+        if enabled.is_empty() {
+            region.scope = closed_scope;
+            suppressions.push(region);
+            continue;
+        }
+        // a bare `disable` is only ended by a bare `enable`
+        if region.codes.is_empty() {
+            open_regions.push(region);
+            continue;
+        }
+        let (closed, open): (Vec<_>, Vec<_>) = std::mem::take(&mut region.codes)
+            .into_iter()
+            .partition(|code| code.code.is_some_and(|code| enabled.contains(&code)));
+        if !closed.is_empty() {
+            suppressions.push(Suppression {
+                scope: closed_scope,
+                codes: closed,
+                range: region.range,
+                comment: region.comment.clone(),
+                is_own: region.is_own,
+                used: false,
+            });
+        }
+        if !open.is_empty() {
+            region.codes = open;
+            open_regions.push(region);
+        }
+    }
+}
+
+enum PragmaKind {
+    Line,
+    /// `# type: ignore`, which is a line pragma unless it comes first
+    TypeIgnore,
+    File {
+        is_own: bool,
+    },
+    Disable,
+    Enable,
+}
+
+struct Pragma {
+    kind: PragmaKind,
+    codes: Vec<SuppressedCode>,
+    range: TextRange,
+}
+
+/// A comment may hold several pragmas, `# type: ignore  # noqa: PY005`
+fn parse_pragmas(comment: &PySyntaxToken, registry: &LintRegistry) -> Vec<Pragma> {
+    let text = comment.text();
+    let comment_start = comment.text_range().start();
+    let mut pragmas = Vec::new();
+    let mut segment_start = 0;
+    for segment in text.split('#') {
+        let start = segment_start;
+        segment_start += segment.len() + 1;
+        let trimmed = segment.trim_start();
+        let offset = start + (segment.len() - trimmed.len());
+        let body = trimmed.trim_end();
+        if body.is_empty() {
+            continue;
+        }
+        let Some((kind, codes_start, codes)) = parse_pragma(body) else {
+            continue;
+        };
+        let mut suppressed_codes = Vec::new();
+        let mut position = 0;
+        for code in codes.split(|c: char| c == ',' || c.is_whitespace()) {
+            if !code.is_empty() {
+                let code_offset = offset + codes_start + position;
+                let alias = alias_code(code);
+                suppressed_codes.push(SuppressedCode {
+                    text: code.to_string(),
+                    code: registry.find_meta(code).map(|meta| meta.code).or(alias),
+                    is_alias: alias.is_some(),
+                    range: TextRange::at(
+                        comment_start + TextSize::from(code_offset as u32),
+                        TextSize::from(code.len() as u32),
+                    ),
+                    used: false,
+                });
+            }
+            position += code.len() + 1;
+        }
+        pragmas.push(Pragma {
+            kind,
+            codes: suppressed_codes,
+            range: TextRange::at(
+                comment_start + TextSize::from(offset as u32),
+                TextSize::from(body.len() as u32),
+            ),
+        });
+    }
+    pragmas
+}
+
+/// The kind of a pragma, and the code list with its offset in `body`
+fn parse_pragma(body: &str) -> Option<(PragmaKind, usize, &str)> {
+    // lowercasing ASCII keeps the byte offsets of `body`
+    let lower = body.to_ascii_lowercase();
+    for tool in ["ruff", "flake8", "python_ls"] {
+        if let Some(rest) = strip_marker(&lower, &[tool, ":", "noqa"]) {
+            let (start, codes) = code_list(body, rest, ':');
+            let kind = PragmaKind::File {
+                is_own: tool == "python_ls",
+            };
+            return Some((kind, start, codes));
+        }
+    }
+    for (marker, kind) in [
+        ("disable", PragmaKind::Disable),
+        ("enable", PragmaKind::Enable),
+    ] {
+        if let Some(rest) = strip_marker(&lower, &["python_ls", ":", marker]) {
+            let (start, codes) = code_list(body, rest, '=');
+            return Some((kind, start, codes));
+        }
+    }
+    // anything but `:` after `noqa` still makes it a bare one
+    if let Some(rest) = strip_marker(&lower, &["noqa"]) {
+        let (start, codes) = code_list(body, rest, ':');
+        return Some((PragmaKind::Line, start, codes));
+    }
+    for (tool, kind) in [
+        ("type", PragmaKind::TypeIgnore),
+        ("pyright", PragmaKind::Line),
+    ] {
+        if let Some(rest) = strip_marker(&lower, &[tool, ":", "ignore"]) {
+            let (start, codes) = code_list(body, rest, '[');
+            let codes = codes.split(']').next().unwrap_or_default();
+            return Some((kind, start, codes));
+        }
+    }
+    None
+}
+
+/// The code of this server for the same check of mypy or pyright, the
+/// codes of checks it does not have suppress nothing here
+fn alias_code(code: &str) -> Option<&'static str> {
+    let meta = match code {
+        "name-defined" | "reportUndefinedVariable" => UNDEFINED_NAME,
+        "reportUnusedImport" => UNUSED_IMPORT,
+        "reportUnusedVariable" => UNUSED_VARIABLE,
+        _ => return None,
+    };
+    Some(meta.code)
+}
+
+/// The code list of a pragma when `rest` starts with `separator`, and its
+/// offset in `body`
+fn code_list<'a>(body: &'a str, rest: &str, separator: char) -> (usize, &'a str) {
+    match rest.strip_prefix(separator) {
+        Some(codes) => {
+            let start = body.len() - codes.len();
+            (start, &body[start..])
+        }
+        None => (body.len(), ""),
+    }
+}
+
+/// Match the words of a marker allowing whitespace between them, the rest
+/// of the text is returned with leading whitespace removed
+fn strip_marker<'a>(text: &'a str, words: &[&str]) -> Option<&'a str> {
+    let mut rest = text;
+    for word in words {
+        rest = rest.trim_start().strip_prefix(word)?;
+    }
+    Some(rest.trim_start())
+}
+
+/// Remove an unused code from its list, or the whole comment when it is the
+/// only code of the only pragma in it
+fn remove_code_fix(
+    semantic_model: &SemanticModel,
+    suppression: &Suppression,
+    unused: &SuppressedCode,
+) -> Option<DiagnosticFix> {
+    let document = semantic_model.get_document();
+    let uri = semantic_model
+        .get_db()
+        .get_vfs()
+        .get_uri(&semantic_model.get_file_id())?;
+    let codes = &suppression.codes;
+    let index = codes.iter().position(|code| code.range == unused.range)?;
+    let comment_range = suppression.comment.text_range();
+    let range = if let Some(next) = codes.get(index + 1) {
+        // the code and the separator up to the next one
+        TextRange::new(unused.range.start(), next.range.start())
+    } else if let Some(previous) = index.checked_sub(1).and_then(|index| codes.get(index)) {
+        TextRange::new(previous.range.end(), unused.range.end())
+    } else if suppression.comment.text()[1..].trim().len() == usize::from(suppression.range.len()) {
+        let line = document.get_line(comment_range.start())?;
+        let line_range = document.get_line_range(line)?;
+        let before =
+            document.get_text_slice(TextRange::new(line_range.start(), comment_range.start()));
+        if before.trim().is_empty() {
+            // a comment on its own line goes with its line
+            let end = document
+                .get_line_range(line + 1)
+                .map(|range| range.start())
+                .unwrap_or(comment_range.end());
+            TextRange::new(line_range.start(), end)
+        } else {
+            let whitespace = before.len() - before.trim_end().len();
+            TextRange::new(
+                comment_range.start() - TextSize::from(whitespace as u32),
+                comment_range.end(),
+            )
+        }
+    } else {
+        return None;
+    };
+    Some(DiagnosticFix::new(
+        format!("Remove unused suppression of `{}`", unused.text),
+        uri,
+        vec![TextEdit {
+            range: document.to_lsp_range(range)?,
+            new_text: String::new(),
+        }],
+    ))
+}
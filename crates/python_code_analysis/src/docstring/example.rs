@@ -0,0 +1,174 @@
+use python_parser::{ParserConfig, PyAstToken, PyDocstring, PyParser, PySyntaxTree};
+use rowan::{TextRange, TextSize};
+
+/// A code example of a docstring parsed on its own: a doctest block of
+/// `>>>` prompts or a fenced Python code block. Its source is the code
+/// without prompts and indentation, the segments map it back to the file.
+#[derive(Debug)]
+pub struct PyDocExample {
+    tree: PySyntaxTree,
+    segments: Vec<PyDocSegment>,
+}
+
+/// A piece of the example source which is a piece of the file as well
+#[derive(Debug, Clone, Copy)]
+struct PyDocSegment {
+    example: TextSize,
+    host: TextSize,
+    len: TextSize,
+}
+
+impl PyDocExample {
+    pub fn get_tree(&self) -> &PySyntaxTree {
+        &self.tree
+    }
+
+    /// The offset in the file of an offset in the example source, the
+    /// newline ending a line maps to the end of the line
+    pub fn to_host_offset(&self, offset: TextSize) -> Option<TextSize> {
+        let segment = self
+            .segments
+            .iter()
+            .rev()
+            .find(|segment| segment.example <= offset)?;
+        Some(segment.host + (offset - segment.example).min(segment.len))
+    }
+
+    pub fn to_host_range(&self, range: TextRange) -> Option<TextRange> {
+        let start = self.to_host_offset(range.start())?;
+        let end = self.to_host_offset(range.end())?.max(start);
+        Some(TextRange::new(start, end))
+    }
+
+    /// The offset in the example source of an offset in the file, `None`
+    /// outside of the code, on a prompt for example
+    pub fn to_example_offset(&self, offset: TextSize) -> Option<TextSize> {
+        self.segments
+            .iter()
+            .find(|segment| TextRange::at(segment.host, segment.len).contains_inclusive(offset))
+            .map(|segment| segment.example + (offset - segment.host))
+    }
+}
+
+/// Collects the source of an example line by line
+#[derive(Default)]
+struct ExampleBuilder {
+    source: String,
+    segments: Vec<PyDocSegment>,
+}
+
+impl ExampleBuilder {
+    fn push_line(&mut self, host: TextSize, code: &str) {
+        self.segments.push(PyDocSegment {
+            example: TextSize::of(self.source.as_str()),
+            host,
+            len: TextSize::of(code),
+        });
+        self.source.push_str(code);
+        self.source.push('\n');
+    }
+
+    fn finish(&mut self, config: ParserConfig, examples: &mut Vec<PyDocExample>) {
+        if self.segments.is_empty() {
+            return;
+        }
+        let builder = std::mem::take(self);
+        examples.push(PyDocExample {
+            tree: PyParser::parse(&builder.source, config, None),
+            segments: builder.segments,
+        });
+    }
+}
+
+/// What the lines of a docstring belong to while scanning it
+enum ExampleState {
+    Text,
+    /// `>>>` prompts and their output, until a blank line
+    Doctest,
+    /// A fence and the indentation of its opening line
+    Fence {
+        indent: usize,
+        is_python: bool,
+    },
+}
+
+/// The examples of a docstring. Escape sequences are read as written, the
+/// examples are taken from the source of the docstring rather than its
+/// value so that their ranges are ranges of the file.
+pub fn extract_doc_examples(docstring: &PyDocstring, config: ParserConfig) -> Vec<PyDocExample> {
+    let Some(token) = docstring.get_string_token() else {
+        return Vec::new();
+    };
+    let text = token.syntax().text();
+    let start = token.syntax().text_range().start();
+    // the closing quotes do not belong to the code on the last line
+    let quotes = if text.ends_with("\"\"\"") || text.ends_with("'''") {
+        3
+    } else {
+        1
+    };
+    let text = &text[..text.len().saturating_sub(quotes)];
+
+    let mut examples = Vec::new();
+    let mut builder = ExampleBuilder::default();
+    let mut state = ExampleState::Text;
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let host = start + TextSize::from(line_start as u32);
+        line_start += line.len() + 1;
+        let line = line.trim_end_matches('\r');
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let code_start = host + TextSize::from(indent as u32);
+
+        match state {
+            ExampleState::Fence { indent, is_python } => {
+                if trimmed.starts_with("```") {
+                    builder.finish(config, &mut examples);
+                    state = ExampleState::Text;
+                } else if is_python {
+                    // lines of the fence keep their indentation past its own
+                    let offset = indent.min(line.len() - trimmed.len());
+                    builder.push_line(host + TextSize::from(offset as u32), &line[offset..]);
+                }
+                continue;
+            }
+            ExampleState::Doctest if trimmed.is_empty() => {
+                builder.finish(config, &mut examples);
+                state = ExampleState::Text;
+                continue;
+            }
+            ExampleState::Doctest => {
+                if let Some(code) = strip_prompt(trimmed, "...") {
+                    let prompt = TextSize::of(trimmed) - TextSize::of(code);
+                    builder.push_line(code_start + prompt, code);
+                    continue;
+                }
+            }
+            ExampleState::Text => {}
+        }
+
+        if let Some(code) = strip_prompt(trimmed, ">>>") {
+            let prompt = TextSize::of(trimmed) - TextSize::of(code);
+            builder.push_line(code_start + prompt, code);
+            state = ExampleState::Doctest;
+        } else if let Some(language) = trimmed.strip_prefix("```") {
+            builder.finish(config, &mut examples);
+            let is_python = matches!(language.trim(), "" | "python" | "py" | "python3");
+            state = ExampleState::Fence { indent, is_python };
+        }
+        // anything else is text, or the expected output of a doctest
+    }
+    builder.finish(config, &mut examples);
+    examples
+}
+
+/// The code after a `>>>` or `...` prompt, which is followed by a space
+/// unless the line is empty
+fn strip_prompt<'a>(line: &'a str, prompt: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(prompt)?;
+    if rest.is_empty() {
+        return Some(rest);
+    }
+    rest.strip_prefix(' ')
+}
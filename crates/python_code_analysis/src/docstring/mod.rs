@@ -0,0 +1,42 @@
+mod example;
+mod model;
+mod parser;
+mod render;
+
+pub use example::{PyDocExample, extract_doc_examples};
+pub use model::*;
+pub use parser::parse_docstring;
+pub use render::{render_docstring, render_docstring_markdown};
+
+/// Strip the common indentation of a docstring like `inspect.cleandoc`
+pub fn clean_docstring(doc: &str) -> String {
+    let doc = doc.replace("\r\n", "\n").replace('\t', "        ");
+    let lines: Vec<&str> = doc.lines().collect();
+    let Some((first, rest)) = lines.split_first() else {
+        return String::new();
+    };
+
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut result = vec![first.trim().to_string()];
+    for line in rest {
+        if line.len() >= indent {
+            result.push(line[indent..].trim_end().to_string());
+        } else {
+            result.push(line.trim().to_string());
+        }
+    }
+
+    while result.first().is_some_and(|line| line.is_empty()) {
+        result.remove(0);
+    }
+    while result.last().is_some_and(|line| line.is_empty()) {
+        result.pop();
+    }
+    result.join("\n")
+}
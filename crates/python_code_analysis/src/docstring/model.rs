@@ -0,0 +1,76 @@
+/// The docstring conventions `parse_docstring` tells apart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PyDocStyle {
+    /// Free text without any sections or fields
+    #[default]
+    Plain,
+    /// `Args:` sections with indented entries
+    Google,
+    /// `Parameters` sections underlined with dashes
+    Numpy,
+    /// reStructuredText `:param name:` fields
+    Sphinx,
+    /// `@param name:` fields
+    Epydoc,
+}
+
+/// A documented parameter or attribute
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyDocParam {
+    pub name: String,
+    pub type_name: Option<String>,
+    pub description: String,
+    /// The line of the docstring it is documented on
+    pub line: usize,
+}
+
+/// A documented return or yield value, only NumPy style names it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyDocReturn {
+    pub name: Option<String>,
+    pub type_name: Option<String>,
+    pub description: String,
+    pub line: usize,
+}
+
+/// A documented exception
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyDocRaise {
+    pub exception: String,
+    pub description: String,
+    pub line: usize,
+}
+
+/// A part of a docstring which does not follow its style
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyDocError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// The structure of a docstring. Lines count from the line of the opening
+/// quotes, texts have the indentation of the docstring removed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyDocString {
+    pub style: PyDocStyle,
+    /// The first paragraph, joined into one line
+    pub summary: String,
+    /// The text after the summary which is not part of a known section
+    pub description: String,
+    pub params: Vec<PyDocParam>,
+    pub returns: Vec<PyDocReturn>,
+    pub yields: Vec<PyDocReturn>,
+    pub raises: Vec<PyDocRaise>,
+    pub examples: Vec<String>,
+    pub attributes: Vec<PyDocParam>,
+    pub errors: Vec<PyDocError>,
+}
+
+impl PyDocString {
+    /// The documentation of a parameter, `*args` is found by `args` too
+    pub fn get_param(&self, name: &str) -> Option<&PyDocParam> {
+        self.params
+            .iter()
+            .find(|param| param.name.trim_start_matches('*') == name)
+    }
+}
@@ -0,0 +1,225 @@
+use super::{
+    DocLine, doctest_blocks, error, is_exception_name, is_param_name, join_description, join_lines,
+};
+use crate::{PyDocParam, PyDocRaise, PyDocReturn, PyDocString, PyDocStyle};
+
+const SPHINX_FIELDS: &[&str] = &[
+    "param",
+    "parameter",
+    "arg",
+    "argument",
+    "key",
+    "keyword",
+    "type",
+    "returns",
+    "return",
+    "rtype",
+    "yields",
+    "yield",
+    "ytype",
+    "raises",
+    "raise",
+    "except",
+    "exception",
+    "ivar",
+    "var",
+    "cvar",
+    "vartype",
+];
+
+const EPYDOC_FIELDS: &[&str] = &[
+    "param",
+    "keyword",
+    "kwarg",
+    "type",
+    "return",
+    "returns",
+    "rtype",
+    "yield",
+    "yields",
+    "raise",
+    "raises",
+    "except",
+    "exception",
+    "ivar",
+    "var",
+    "cvar",
+];
+
+/// `:param name: description`
+pub(super) fn is_sphinx_field(text: &str) -> bool {
+    text.strip_prefix(':')
+        .is_some_and(|field| SPHINX_FIELDS.contains(&field_tag(field)) && field.contains(':'))
+}
+
+/// `@param name: description`
+pub(super) fn is_epydoc_field(text: &str) -> bool {
+    text.strip_prefix('@')
+        .is_some_and(|field| EPYDOC_FIELDS.contains(&field_tag(field)))
+}
+
+fn field_tag(field: &str) -> &str {
+    let end = field
+        .find(|c: char| c.is_whitespace() || c == ':')
+        .unwrap_or(field.len());
+    &field[..end]
+}
+
+/// The types given by `:type name:` fields, applied once all fields are read
+#[derive(Default)]
+struct FieldTypes {
+    params: Vec<(String, String, usize)>,
+    attributes: Vec<(String, String, usize)>,
+    returns: Option<(String, usize)>,
+    yields: Option<(String, usize)>,
+}
+
+/// Parse the fields into `doc` and return the text outside of them. A
+/// field starts at the indentation of the docstring and runs while the
+/// lines below it are indented.
+pub(super) fn parse(lines: &[DocLine], doc: &mut PyDocString) -> String {
+    let prefix = match doc.style {
+        PyDocStyle::Epydoc => '@',
+        _ => ':',
+    };
+    let mut description: Vec<&DocLine> = Vec::new();
+    let mut types = FieldTypes::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let first = &lines[i];
+        i += 1;
+        if first.indent != 0 || !first.text.starts_with(prefix) {
+            description.push(first);
+            continue;
+        }
+        let start = i;
+        while i < lines.len() && (lines[i].is_blank() || lines[i].indent > 0) {
+            i += 1;
+        }
+        let rest = join_lines(lines[start..i].iter());
+        parse_field(first, &rest, doc, &mut types);
+    }
+    apply_types(doc, types);
+    doc.examples = doctest_blocks(&description);
+    join_lines(description.into_iter())
+}
+
+fn parse_field(first: &DocLine, rest: &str, doc: &mut PyDocString, types: &mut FieldTypes) {
+    let field = &first.text[1..];
+    let Some((field, text)) = field.split_once(':') else {
+        doc.errors.push(error(
+            first,
+            format!("Expected `{}:` to end the field name", first.text),
+        ));
+        return;
+    };
+    let words: Vec<&str> = field.split_whitespace().collect();
+    let Some((tag, args)) = words.split_first() else {
+        doc.errors
+            .push(error(first, "Expected a field name".to_string()));
+        return;
+    };
+    let description = join_description(text, rest);
+    match *tag {
+        "param" | "parameter" | "arg" | "argument" | "key" | "keyword" | "kwarg" | "ivar"
+        | "var" | "cvar" => {
+            // `:param int count:` gives the type before the name
+            let Some((name, type_words)) =
+                args.split_last().filter(|(name, _)| is_param_name(name))
+            else {
+                doc.errors
+                    .push(error(first, format!("Expected a name after `{}`", tag)));
+                return;
+            };
+            let param = PyDocParam {
+                name: name.to_string(),
+                type_name: (!type_words.is_empty()).then(|| type_words.join(" ")),
+                description,
+                line: first.line,
+            };
+            if matches!(*tag, "ivar" | "var" | "cvar") {
+                doc.attributes.push(param);
+            } else {
+                doc.params.push(param);
+            }
+        }
+        "type" | "vartype" => {
+            let Some(name) = args.last().filter(|name| is_param_name(name)) else {
+                doc.errors
+                    .push(error(first, format!("Expected a name after `{}`", tag)));
+                return;
+            };
+            let entry = (name.to_string(), description, first.line);
+            match *tag {
+                "type" => types.params.push(entry),
+                _ => types.attributes.push(entry),
+            }
+        }
+        "returns" | "return" => doc.returns.push(PyDocReturn {
+            description,
+            line: first.line,
+            ..Default::default()
+        }),
+        "yields" | "yield" => doc.yields.push(PyDocReturn {
+            description,
+            line: first.line,
+            ..Default::default()
+        }),
+        "rtype" => types.returns = Some((description, first.line)),
+        "ytype" => types.yields = Some((description, first.line)),
+        "raises" | "raise" | "except" | "exception" => {
+            let exception = args.join(" ");
+            if !is_exception_name(&exception) {
+                doc.errors.push(error(
+                    first,
+                    format!("Expected an exception name after `{}`", tag),
+                ));
+                return;
+            }
+            doc.raises.push(PyDocRaise {
+                exception,
+                description,
+                line: first.line,
+            });
+        }
+        // `:meta:`, `:note:` and other fields say nothing about the signature
+        _ => {}
+    }
+}
+
+/// Set the types given by separate fields, a type of a name which has no
+/// other field documents it as well
+fn apply_types(doc: &mut PyDocString, types: FieldTypes) {
+    for (params, types) in [
+        (&mut doc.params, types.params),
+        (&mut doc.attributes, types.attributes),
+    ] {
+        for (name, type_name, line) in types {
+            match params.iter_mut().find(|param| param.name == name) {
+                Some(param) => param.type_name = Some(type_name),
+                None => params.push(PyDocParam {
+                    name,
+                    type_name: Some(type_name),
+                    description: String::new(),
+                    line,
+                }),
+            }
+        }
+    }
+    for (values, type_name) in [
+        (&mut doc.returns, types.returns),
+        (&mut doc.yields, types.yields),
+    ] {
+        let Some((type_name, line)) = type_name else {
+            continue;
+        };
+        match values.first_mut() {
+            Some(value) => value.type_name = Some(type_name),
+            None => values.push(PyDocReturn {
+                type_name: Some(type_name),
+                line,
+                ..Default::default()
+            }),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use super::{
+    DocLine, SectionKind, error, is_exception_name, is_param_name, join_description, join_lines,
+    looks_like_type, section_kind, split_items,
+};
+use crate::{PyDocError, PyDocParam, PyDocRaise, PyDocReturn, PyDocString};
+
+/// A section title at the indentation of the docstring, `Args:`
+pub(super) fn section_header(line: &DocLine) -> Option<SectionKind> {
+    if line.indent != 0 {
+        return None;
+    }
+    section_kind(line.text.strip_suffix(':')?.trim_end())
+}
+
+/// Parse the sections into `doc` and return the text around them. A
+/// section runs while its lines are indented.
+pub(super) fn parse(lines: &[DocLine], doc: &mut PyDocString) -> String {
+    let mut description: Vec<&DocLine> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let header = &lines[i];
+        i += 1;
+        let Some(kind) = section_header(header) else {
+            description.push(header);
+            continue;
+        };
+        let start = i;
+        while i < lines.len() && (lines[i].is_blank() || lines[i].indent > 0) {
+            i += 1;
+        }
+        let body = &lines[start..i];
+        if kind == SectionKind::Other {
+            description.push(header);
+            description.extend(body);
+            continue;
+        }
+        let title = header.text.trim_end_matches(':');
+        if body.iter().all(DocLine::is_blank) {
+            doc.errors
+                .push(error(header, format!("Section `{}` is empty", title)));
+            continue;
+        }
+        match kind {
+            SectionKind::Params => {
+                let params = parse_params(body, title, &mut doc.errors);
+                doc.params.extend(params);
+            }
+            SectionKind::Attributes => {
+                let attributes = parse_params(body, title, &mut doc.errors);
+                doc.attributes.extend(attributes);
+            }
+            SectionKind::Returns => doc.returns.push(parse_return(body)),
+            SectionKind::Yields => doc.yields.push(parse_return(body)),
+            SectionKind::Raises => {
+                let raises = parse_raises(body, title, &mut doc.errors);
+                doc.raises.extend(raises);
+            }
+            SectionKind::Examples => doc.examples.push(join_lines(body.iter())),
+            SectionKind::Other => {}
+        }
+    }
+    join_lines(description.into_iter())
+}
+
+/// `name (type): description` entries
+fn parse_params(body: &[DocLine], title: &str, errors: &mut Vec<PyDocError>) -> Vec<PyDocParam> {
+    let mut params = Vec::new();
+    for item in split_items(body).0 {
+        let parsed = split_entry(&item.first.text).and_then(|(head, text)| {
+            let (name, type_name) = match head.split_once('(') {
+                Some((name, rest)) => (name.trim(), Some(rest.strip_suffix(')')?.trim())),
+                None => (head.trim(), None),
+            };
+            is_param_name(name).then_some((name, type_name, text))
+        });
+        let Some((name, type_name, text)) = parsed else {
+            errors.push(error(
+                item.first,
+                format!("Expected `name (type): description` in section `{}`", title),
+            ));
+            continue;
+        };
+        params.push(PyDocParam {
+            name: name.to_string(),
+            type_name: type_name.map(str::to_string),
+            description: join_description(text, &item.get_rest_text()),
+            line: item.first.line,
+        });
+    }
+    params
+}
+
+/// `type: description`, or a description alone
+fn parse_return(body: &[DocLine]) -> PyDocReturn {
+    let first = body
+        .iter()
+        .find(|line| !line.is_blank())
+        .unwrap_or(&body[0]);
+    let rest = join_lines(body.iter().filter(|line| line.line > first.line));
+    match split_entry(&first.text).filter(|(head, _)| looks_like_type(head)) {
+        Some((head, text)) => PyDocReturn {
+            name: None,
+            type_name: Some(head.trim().to_string()),
+            description: join_description(text, &rest),
+            line: first.line,
+        },
+        None => PyDocReturn {
+            name: None,
+            type_name: None,
+            description: join_lines(body.iter()),
+            line: first.line,
+        },
+    }
+}
+
+/// `Exception: description` entries
+fn parse_raises(body: &[DocLine], title: &str, errors: &mut Vec<PyDocError>) -> Vec<PyDocRaise> {
+    let mut raises = Vec::new();
+    for item in split_items(body).0 {
+        let (exception, text) = split_entry(&item.first.text).unwrap_or((&item.first.text, ""));
+        let exception = exception.trim();
+        if !is_exception_name(exception) {
+            errors.push(error(
+                item.first,
+                format!("Expected `Exception: description` in section `{}`", title),
+            ));
+            continue;
+        }
+        raises.push(PyDocRaise {
+            exception: exception.to_string(),
+            description: join_description(text, &item.get_rest_text()),
+            line: item.first.line,
+        });
+    }
+    raises
+}
+
+/// Split an entry at its first colon outside of brackets
+fn split_entry(text: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ':' if depth == 0 => return Some((&text[..i], &text[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,276 @@
+mod field_list;
+mod google;
+mod numpy;
+
+use super::{PyDocError, PyDocString, PyDocStyle};
+
+/// A line of a docstring with the common indentation removed
+#[derive(Debug, Clone)]
+struct DocLine {
+    /// The index of the line in the docstring
+    line: usize,
+    indent: usize,
+    text: String,
+}
+
+impl DocLine {
+    fn is_blank(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// The entries of a section, an entry is a line and the lines indented
+/// below it
+struct DocItem<'a> {
+    first: &'a DocLine,
+    rest: &'a [DocLine],
+}
+
+impl DocItem<'_> {
+    /// The text of the continuation lines
+    fn get_rest_text(&self) -> String {
+        join_lines(self.rest.iter())
+    }
+}
+
+/// Parse a docstring into its summary, description and sections, the style
+/// is detected from the sections it uses
+pub fn parse_docstring(doc: &str) -> PyDocString {
+    let lines = doc_lines(doc);
+    let style = detect_style(&lines);
+    let mut result = PyDocString {
+        style,
+        ..Default::default()
+    };
+    let description = match style {
+        PyDocStyle::Plain => {
+            result.examples = doctest_blocks(&lines.iter().collect::<Vec<_>>());
+            join_lines(lines.iter())
+        }
+        PyDocStyle::Google => google::parse(&lines, &mut result),
+        PyDocStyle::Numpy => numpy::parse(&lines, &mut result),
+        PyDocStyle::Sphinx | PyDocStyle::Epydoc => field_list::parse(&lines, &mut result),
+    };
+    let (summary, description) = split_summary(&description);
+    result.summary = summary;
+    result.description = description;
+    result
+}
+
+fn doc_lines(doc: &str) -> Vec<DocLine> {
+    let raw: Vec<(usize, String)> = doc
+        .lines()
+        .map(|line| {
+            let line = line.replace('\t', "        ");
+            let text = line.trim_start();
+            (line.len() - text.len(), text.trim_end().to_string())
+        })
+        .collect();
+    // the first line follows the quotes, its indentation is not counted
+    let common_indent = raw
+        .iter()
+        .skip(1)
+        .filter(|(_, text)| !text.is_empty())
+        .map(|(indent, _)| *indent)
+        .min()
+        .unwrap_or(0);
+    raw.into_iter()
+        .enumerate()
+        .map(|(line, (indent, text))| DocLine {
+            line,
+            indent: if line == 0 {
+                0
+            } else {
+                indent.saturating_sub(common_indent)
+            },
+            text,
+        })
+        .collect()
+}
+
+fn detect_style(lines: &[DocLine]) -> PyDocStyle {
+    for (i, line) in lines.iter().enumerate() {
+        if line.indent != 0 {
+            continue;
+        }
+        if field_list::is_sphinx_field(&line.text) {
+            return PyDocStyle::Sphinx;
+        }
+        if field_list::is_epydoc_field(&line.text) {
+            return PyDocStyle::Epydoc;
+        }
+        if numpy::section_header(lines, i).is_some() {
+            return PyDocStyle::Numpy;
+        }
+        if google::section_header(line).is_some() {
+            return PyDocStyle::Google;
+        }
+    }
+    PyDocStyle::Plain
+}
+
+/// The kinds of sections Google and NumPy style share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionKind {
+    Params,
+    Returns,
+    Yields,
+    Raises,
+    Examples,
+    Attributes,
+    /// Notes, warnings and the like, kept in the description
+    Other,
+}
+
+fn section_kind(title: &str) -> Option<SectionKind> {
+    let kind = match title.to_ascii_lowercase().as_str() {
+        "args" | "arguments" | "parameters" | "params" | "keyword args" | "keyword arguments"
+        | "other parameters" => SectionKind::Params,
+        "returns" | "return" => SectionKind::Returns,
+        "yields" | "yield" => SectionKind::Yields,
+        "raises" | "raise" | "exceptions" => SectionKind::Raises,
+        "example" | "examples" => SectionKind::Examples,
+        "attributes" => SectionKind::Attributes,
+        "note" | "notes" | "warning" | "warnings" | "warns" | "see also" | "todo"
+        | "references" | "methods" => SectionKind::Other,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Split the lines of a section into its entries. An entry starts at the
+/// smallest indentation of the section, a deeper line continues it.
+/// Returns the lines before the first entry as well, they belong to none.
+fn split_items(lines: &[DocLine]) -> (Vec<DocItem<'_>>, &[DocLine]) {
+    let item_indent = lines
+        .iter()
+        .filter(|line| !line.is_blank())
+        .map(|line| line.indent)
+        .min()
+        .unwrap_or(0);
+    let starts: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.is_blank() && line.indent == item_indent)
+        .map(|(i, _)| i)
+        .collect();
+    let leading = &lines[..starts.first().copied().unwrap_or(lines.len())];
+    let items = starts
+        .iter()
+        .enumerate()
+        .map(|(n, start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(lines.len());
+            DocItem {
+                first: &lines[*start],
+                rest: &lines[start + 1..end],
+            }
+        })
+        .collect();
+    (items, leading)
+}
+
+/// Join lines keeping their indentation relative to the least indented
+/// one, without the blank lines around them
+fn join_lines<'a>(lines: impl Iterator<Item = &'a DocLine>) -> String {
+    let lines: Vec<&DocLine> = lines.collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_blank())
+        .map(|line| line.indent)
+        .min()
+        .unwrap_or(0);
+    let text: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            if line.is_blank() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(line.indent - indent), line.text)
+            }
+        })
+        .collect();
+    text.join("\n").trim_matches('\n').to_string()
+}
+
+/// Join the first line of an entry after its colon with its continuation
+fn join_description(first: &str, rest: &str) -> String {
+    match (first.trim(), rest.is_empty()) {
+        ("", _) => rest.to_string(),
+        (first, true) => first.to_string(),
+        (first, false) => format!("{}\n{}", first, rest),
+    }
+}
+
+/// The first paragraph joined into one line, and the text after it
+fn split_summary(text: &str) -> (String, String) {
+    let text = text.trim_matches('\n');
+    let (summary, rest) = text.split_once("\n\n").unwrap_or((text, ""));
+    let summary: Vec<&str> = summary.lines().map(str::trim).collect();
+    (summary.join(" "), rest.trim_matches('\n').to_string())
+}
+
+/// The doctest examples of free text, each runs until a blank line
+fn doctest_blocks(lines: &[&DocLine]) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if !lines[i].text.starts_with(">>>") {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < lines.len() && !lines[i].is_blank() {
+            i += 1;
+        }
+        blocks.push(join_lines(lines[start..i].iter().copied()));
+    }
+    blocks
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// `ValueError` or `json.JSONDecodeError`
+fn is_exception_name(name: &str) -> bool {
+    name.split('.').all(is_identifier)
+}
+
+/// A parameter name as documented, `*args` and `**kwargs` included
+fn is_param_name(name: &str) -> bool {
+    let stars = name.len() - name.trim_start_matches('*').len();
+    stars <= 2 && is_identifier(&name[stars..])
+}
+
+/// Whether the text before a colon reads like a type, `list[int] | None`
+/// rather than a sentence
+fn looks_like_type(text: &str) -> bool {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let mut depth = 0i32;
+    for (i, c) in chars.iter().enumerate() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            // spaces only around `|` and after `,`
+            ' ' if depth == 0 => {
+                let prev = chars[..i].iter().rev().find(|c| **c != ' ');
+                let next = chars[i + 1..].iter().find(|c| **c != ' ');
+                if !matches!(prev, Some(',' | '|')) && next != Some(&'|') {
+                    return false;
+                }
+            }
+            c if !(c.is_alphanumeric() || "_.,|'\" ".contains(*c)) => return false,
+            _ => {}
+        }
+    }
+    !chars.is_empty() && depth == 0
+}
+
+fn error(line: &DocLine, message: String) -> PyDocError {
+    PyDocError {
+        line: line.line,
+        message,
+    }
+}
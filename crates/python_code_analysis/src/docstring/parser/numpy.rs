@@ -0,0 +1,150 @@
+use super::{
+    DocLine, SectionKind, error, is_exception_name, is_param_name, join_lines, section_kind,
+    split_items,
+};
+use crate::{PyDocError, PyDocParam, PyDocRaise, PyDocReturn, PyDocString};
+
+/// A known section title underlined with dashes
+pub(super) fn section_header(lines: &[DocLine], i: usize) -> Option<SectionKind> {
+    section_kind(underlined_title(lines, i)?)
+}
+
+/// Any title underlined with dashes, sections this parser does not know
+/// end the one before them as well
+fn underlined_title(lines: &[DocLine], i: usize) -> Option<&str> {
+    let title = lines.get(i)?;
+    let underline = lines.get(i + 1)?;
+    let is_underline = title.indent == 0
+        && !title.is_blank()
+        && underline.indent == 0
+        && underline.text.len() >= 3
+        && underline.text.chars().all(|c| c == '-');
+    is_underline.then_some(title.text.as_str())
+}
+
+/// Parse the sections into `doc` and return the text before and between
+/// them. A section runs until the next underlined title.
+pub(super) fn parse(lines: &[DocLine], doc: &mut PyDocString) -> String {
+    let mut description: Vec<&DocLine> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(title) = underlined_title(lines, i) else {
+            description.push(&lines[i]);
+            i += 1;
+            continue;
+        };
+        let header = &lines[i];
+        let start = i + 2;
+        i = start;
+        while i < lines.len() && underlined_title(lines, i).is_none() {
+            i += 1;
+        }
+        let body = &lines[start..i];
+        let kind = section_kind(title).unwrap_or(SectionKind::Other);
+        if kind == SectionKind::Other {
+            description.push(header);
+            description.extend(body);
+            continue;
+        }
+        if body.iter().all(DocLine::is_blank) {
+            doc.errors
+                .push(error(header, format!("Section `{}` is empty", title)));
+            continue;
+        }
+        match kind {
+            SectionKind::Params => {
+                let params = parse_params(body, title, &mut doc.errors);
+                doc.params.extend(params);
+            }
+            SectionKind::Attributes => {
+                let attributes = parse_params(body, title, &mut doc.errors);
+                doc.attributes.extend(attributes);
+            }
+            SectionKind::Returns => doc.returns.extend(parse_returns(body)),
+            SectionKind::Yields => doc.yields.extend(parse_returns(body)),
+            SectionKind::Raises => {
+                let raises = parse_raises(body, title, &mut doc.errors);
+                doc.raises.extend(raises);
+            }
+            SectionKind::Examples => doc.examples.push(join_lines(body.iter())),
+            SectionKind::Other => {}
+        }
+    }
+    join_lines(description.into_iter())
+}
+
+/// `name : type` entries with the description indented below, several
+/// names may share one entry
+fn parse_params(body: &[DocLine], title: &str, errors: &mut Vec<PyDocError>) -> Vec<PyDocParam> {
+    let mut params = Vec::new();
+    for item in split_items(body).0 {
+        let (names, type_name) = split_entry(&item.first.text);
+        let names: Vec<&str> = names.split(',').map(str::trim).collect();
+        if !names.iter().all(|name| is_param_name(name)) {
+            errors.push(error(
+                item.first,
+                format!("Expected `name : type` in section `{}`", title),
+            ));
+            continue;
+        }
+        let description = item.get_rest_text();
+        params.extend(names.into_iter().map(|name| PyDocParam {
+            name: name.to_string(),
+            type_name: type_name.clone(),
+            description: description.clone(),
+            line: item.first.line,
+        }));
+    }
+    params
+}
+
+/// `name : type` or a type alone
+fn parse_returns(body: &[DocLine]) -> Vec<PyDocReturn> {
+    split_items(body)
+        .0
+        .into_iter()
+        .map(|item| {
+            let (name, type_name) = match split_entry(&item.first.text) {
+                (name, Some(type_name)) => (Some(name.to_string()), Some(type_name)),
+                (type_name, None) => (None, Some(type_name.to_string())),
+            };
+            PyDocReturn {
+                name,
+                type_name,
+                description: item.get_rest_text(),
+                line: item.first.line,
+            }
+        })
+        .collect()
+}
+
+/// An exception per entry with the description indented below
+fn parse_raises(body: &[DocLine], title: &str, errors: &mut Vec<PyDocError>) -> Vec<PyDocRaise> {
+    let mut raises = Vec::new();
+    for item in split_items(body).0 {
+        if !is_exception_name(&item.first.text) {
+            errors.push(error(
+                item.first,
+                format!("Expected an exception name in section `{}`", title),
+            ));
+            continue;
+        }
+        raises.push(PyDocRaise {
+            exception: item.first.text.clone(),
+            description: item.get_rest_text(),
+            line: item.first.line,
+        });
+    }
+    raises
+}
+
+/// Split `name : type` at its colon, the type is `None` without one
+fn split_entry(text: &str) -> (&str, Option<String>) {
+    match text.split_once(':') {
+        Some((name, type_name)) if !type_name.trim().is_empty() => {
+            (name.trim(), Some(type_name.trim().to_string()))
+        }
+        Some((name, _)) => (name.trim(), None),
+        None => (text.trim(), None),
+    }
+}
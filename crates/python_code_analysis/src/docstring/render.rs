@@ -0,0 +1,479 @@
+use super::{PyDocParam, PyDocRaise, PyDocReturn, PyDocStyle, parse_docstring};
+
+/// Roles which refer to a Python object, rendered as code linking to it
+const OBJECT_ROLES: &[&str] = &[
+    "class", "func", "meth", "attr", "mod", "obj", "data", "exc", "const", "type",
+];
+
+/// Render a docstring as Markdown without links
+pub fn render_docstring_markdown(doc: &str) -> String {
+    render_docstring(doc, &|_| None)
+}
+
+/// Render a docstring as Markdown: the summary and the description, then
+/// the documented parameters, return values and exceptions as lists.
+/// Doctest examples become Python code, and a cross reference such as
+/// ``:class:`Path` `` links to what `resolve_link` returns for its target.
+pub fn render_docstring(doc: &str, resolve_link: &dyn Fn(&str) -> Option<String>) -> String {
+    let doc = parse_docstring(doc);
+    let renderer = Renderer { resolve_link };
+    let mut blocks = Vec::new();
+    if !doc.summary.is_empty() {
+        blocks.push(renderer.inline(&doc.summary));
+    }
+    if !doc.description.is_empty() {
+        blocks.push(renderer.blocks(&doc.description));
+    }
+    renderer.push_params("Parameters", &doc.params, &mut blocks);
+    renderer.push_params("Attributes", &doc.attributes, &mut blocks);
+    renderer.push_returns("Returns", &doc.returns, &mut blocks);
+    renderer.push_returns("Yields", &doc.yields, &mut blocks);
+    renderer.push_raises(&doc.raises, &mut blocks);
+    // without sections the examples are part of the description
+    if matches!(doc.style, PyDocStyle::Google | PyDocStyle::Numpy) && !doc.examples.is_empty() {
+        let examples: Vec<String> = doc
+            .examples
+            .iter()
+            .map(|example| renderer.blocks(example))
+            .collect();
+        blocks.push(format!("**Examples**\n\n{}", examples.join("\n\n")));
+    }
+    blocks.join("\n\n")
+}
+
+struct Renderer<'a> {
+    resolve_link: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl Renderer<'_> {
+    fn push_params(&self, title: &str, params: &[PyDocParam], blocks: &mut Vec<String>) {
+        if params.is_empty() {
+            return;
+        }
+        let items: Vec<String> = params
+            .iter()
+            .map(|param| {
+                let mut item = format!("- {}", code(&param.name));
+                if let Some(type_name) = &param.type_name {
+                    item.push_str(&format!(" ({})", code(type_name)));
+                }
+                self.push_description(&mut item, &param.description);
+                item
+            })
+            .collect();
+        blocks.push(format!("**{}**\n\n{}", title, items.join("\n")));
+    }
+
+    fn push_returns(&self, title: &str, values: &[PyDocReturn], blocks: &mut Vec<String>) {
+        if values.is_empty() {
+            return;
+        }
+        let items: Vec<String> = values
+            .iter()
+            .map(|value| {
+                let mut item = match (&value.name, &value.type_name) {
+                    (Some(name), Some(type_name)) => {
+                        format!("- {} ({})", code(name), code(type_name))
+                    }
+                    (Some(name), None) | (None, Some(name)) => format!("- {}", code(name)),
+                    // a description alone
+                    (None, None) => {
+                        return format!("- {}", self.inline(&join_paragraph(&value.description)));
+                    }
+                };
+                self.push_description(&mut item, &value.description);
+                item
+            })
+            .collect();
+        blocks.push(format!("**{}**\n\n{}", title, items.join("\n")));
+    }
+
+    fn push_raises(&self, raises: &[PyDocRaise], blocks: &mut Vec<String>) {
+        if raises.is_empty() {
+            return;
+        }
+        let items: Vec<String> = raises
+            .iter()
+            .map(|raise| {
+                let mut item = format!("- {}", self.reference(&raise.exception, "exc"));
+                self.push_description(&mut item, &raise.description);
+                item
+            })
+            .collect();
+        blocks.push(format!("**Raises**\n\n{}", items.join("\n")));
+    }
+
+    /// A list item is a single paragraph, the lines of the description are
+    /// joined
+    fn push_description(&self, item: &mut String, description: &str) {
+        let text = join_paragraph(description);
+        if !text.is_empty() {
+            item.push_str(" — ");
+            item.push_str(&self.inline(&text));
+        }
+    }
+
+    /// Render text made of paragraphs, doctest examples, literal blocks and
+    /// directives. The line breaks and indentation of paragraphs are kept.
+    fn blocks(&self, text: &str) -> String {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut result: Vec<String> = Vec::new();
+        // whether the last line is text which continues on the next one
+        let mut in_paragraph = false;
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+
+            if trimmed.is_empty() {
+                result.push(String::new());
+                in_paragraph = false;
+                i += 1;
+                continue;
+            }
+
+            if trimmed.starts_with(">>>") {
+                let start = i;
+                while i < lines.len() && !lines[i].trim().is_empty() {
+                    i += 1;
+                }
+                push_block(&mut result, &render_doctest(&lines[start..i]));
+                in_paragraph = false;
+                continue;
+            }
+
+            if let Some(directive) = trimmed
+                .strip_prefix(".. ")
+                .or((trimmed == "..").then_some(""))
+            {
+                i += 1;
+                let body = indented_block(&lines, &mut i, indent);
+                let rendered = self.directive(directive.trim(), &body);
+                if !rendered.is_empty() {
+                    push_block(&mut result, &rendered);
+                }
+                in_paragraph = false;
+                continue;
+            }
+
+            // a title underlined like NumPy sections
+            if let Some(underline) = lines.get(i + 1)
+                && is_underline(underline.trim())
+                && indent == 0
+            {
+                push_block(&mut result, &format!("**{}**", self.inline(trimmed)));
+                in_paragraph = false;
+                i += 2;
+                continue;
+            }
+
+            // a literal block introduced by a trailing `::`
+            if let Some(text) = trimmed.strip_suffix("::") {
+                let text = match text.strip_suffix(' ') {
+                    Some(text) => text.trim_end().to_string(),
+                    None if text.is_empty() => String::new(),
+                    None => format!("{}:", text),
+                };
+                if !text.is_empty() {
+                    self.push_text(&mut result, indent, &text, in_paragraph);
+                }
+                i += 1;
+                let body = indented_block(&lines, &mut i, indent);
+                push_block(&mut result, &format!("```\n{}\n```", body));
+                in_paragraph = false;
+                continue;
+            }
+
+            self.push_text(&mut result, indent, trimmed, in_paragraph);
+            in_paragraph = true;
+            i += 1;
+        }
+
+        // blocks add blank lines around them, keep one
+        let mut text: Vec<String> = Vec::new();
+        for line in result {
+            if line.is_empty() && text.last().is_none_or(|last| last.is_empty()) {
+                continue;
+            }
+            text.push(line);
+        }
+        text.join("\n").trim_end().to_string()
+    }
+
+    fn push_text(&self, result: &mut Vec<String>, indent: usize, text: &str, in_paragraph: bool) {
+        if in_paragraph && let Some(last) = result.last_mut() {
+            // keep the line break of the docstring
+            last.push_str("  ");
+        }
+        let escaped = match text.strip_prefix('#') {
+            Some(rest) => format!("\\#{}", self.inline(rest)),
+            None => self.inline(text),
+        };
+        result.push(format!("{}{}", "&nbsp;".repeat(indent), escaped));
+    }
+
+    /// `.. note::` and the other directives, comments render as nothing
+    fn directive(&self, directive: &str, body: &str) -> String {
+        let Some((name, argument)) = directive.split_once("::") else {
+            return String::new();
+        };
+        let name = name.trim();
+        let argument = argument.trim();
+        match name {
+            "code-block" | "code" | "sourcecode" => {
+                let language = if argument.is_empty() {
+                    "python"
+                } else {
+                    argument
+                };
+                format!("```{}\n{}\n```", language, body)
+            }
+            "doctest" => render_doctest(&body.lines().collect::<Vec<_>>()),
+            "rubric" => format!("**{}**", self.inline(argument)),
+            "math" => format!("```\n{}\n```", body),
+            _ => match admonition_title(name, argument) {
+                Some(title) => {
+                    let mut quote = vec![format!("> **{}**", title)];
+                    let content = self.blocks(body);
+                    if !content.is_empty() {
+                        quote.push(">".to_string());
+                        quote.extend(content.lines().map(|line| {
+                            if line.is_empty() {
+                                ">".to_string()
+                            } else {
+                                format!("> {}", line)
+                            }
+                        }));
+                    }
+                    quote.join("\n")
+                }
+                None => self.blocks(body),
+            },
+        }
+    }
+
+    /// Inline reST: ``` ``code`` ```, roles, interpreted text, links and
+    /// emphasis. Anything else Markdown would read is escaped.
+    fn inline(&self, text: &str) -> String {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("``")
+                && let Some(end) = after.find("``")
+            {
+                result.push_str(&code(&after[..end]));
+                rest = &after[end + 2..];
+                continue;
+            }
+            if c == ':'
+                && let Some((role, content, len)) = parse_role(rest)
+            {
+                result.push_str(&self.reference(content, role));
+                rest = &rest[len..];
+                continue;
+            }
+            if let Some(after) = rest.strip_prefix('`')
+                && let Some(end) = after.find('`')
+            {
+                let content = &after[..end];
+                let after = &after[end + 1..];
+                // `text <url>`_ is a link, `text`_ a reference to one
+                if let Some(after) = after.strip_prefix('_') {
+                    rest = after.strip_prefix('_').unwrap_or(after);
+                    match split_target(content) {
+                        Some((title, url)) => {
+                            result.push_str(&format!("[{}]({})", escape(title), url))
+                        }
+                        None => result.push_str(&escape(content)),
+                    }
+                } else {
+                    result.push_str(&code(content));
+                    rest = after;
+                }
+                continue;
+            }
+            if c == '*'
+                && let Some((emphasis, len)) = parse_emphasis(rest)
+            {
+                result.push_str(&emphasis);
+                rest = &rest[len..];
+                continue;
+            }
+            result.push_str(&escape(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+        result
+    }
+
+    /// A cross reference as code, linked when its target is found
+    fn reference(&self, content: &str, role: &str) -> String {
+        let role = role.strip_prefix("py:").unwrap_or(role);
+        let (title, target) = split_target(content).unwrap_or((content, content));
+        if !OBJECT_ROLES.contains(&role) {
+            return match role {
+                "math" => code(content),
+                // `:ref:`, `:doc:` and the like name a page of the docs
+                _ => escape(title),
+            };
+        }
+        let target = target.trim_start_matches(['~', '!', '.']);
+        // `~pkg.Path` shows only `Path`
+        let mut title = if title == content && content.starts_with('~') {
+            target.rsplit('.').next().unwrap_or(target).to_string()
+        } else {
+            title.trim_start_matches('!').to_string()
+        };
+        if matches!(role, "func" | "meth") && !title.ends_with(')') {
+            title.push_str("()");
+        }
+        match (self.resolve_link)(target) {
+            Some(link) => format!("[{}]({})", code(&title), link),
+            None => code(&title),
+        }
+    }
+}
+
+fn admonition_title(name: &str, argument: &str) -> Option<String> {
+    let title = match name {
+        "note" => "Note",
+        "warning" => "Warning",
+        "tip" => "Tip",
+        "hint" => "Hint",
+        "important" => "Important",
+        "attention" => "Attention",
+        "caution" => "Caution",
+        "danger" => "Danger",
+        "error" => "Error",
+        "seealso" => "See also",
+        "todo" => "Todo",
+        "deprecated" => return Some(format!("Deprecated since version {}", argument)),
+        "versionadded" => return Some(format!("New in version {}", argument)),
+        "versionchanged" => return Some(format!("Changed in version {}", argument)),
+        _ => return None,
+    };
+    Some(title.to_string())
+}
+
+/// Doctest examples as Python, the prompts are dropped so that the code is
+/// highlighted and the expected output becomes comments
+fn render_doctest(lines: &[&str]) -> String {
+    let mut code = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        match line
+            .strip_prefix(">>>")
+            .or_else(|| line.strip_prefix("..."))
+        {
+            Some(source) => code.push(source.strip_prefix(' ').unwrap_or(source).to_string()),
+            None => code.push(format!("# {}", line)),
+        }
+    }
+    format!("```python\n{}\n```", code.join("\n"))
+}
+
+/// The lines indented deeper than `indent` from `i` on, without their
+/// common indentation
+fn indented_block(lines: &[&str], i: &mut usize, indent: usize) -> String {
+    let start = *i;
+    while *i < lines.len()
+        && (lines[*i].trim().is_empty() || lines[*i].len() - lines[*i].trim_start().len() > indent)
+    {
+        *i += 1;
+    }
+    let block = &lines[start..*i];
+    let block_indent = block
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let block: Vec<&str> = block
+        .iter()
+        .map(|line| line.get(block_indent..).unwrap_or("").trim_end())
+        .collect();
+    block.join("\n").trim_matches('\n').to_string()
+}
+
+fn join_paragraph(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.join(" ")
+}
+
+fn push_block(result: &mut Vec<String>, block: &str) {
+    result.push(String::new());
+    result.push(block.to_string());
+    result.push(String::new());
+}
+
+fn is_underline(text: &str) -> bool {
+    text.len() >= 3 && (text.chars().all(|c| c == '-') || text.chars().all(|c| c == '='))
+}
+
+/// `:role:`content`` or `:py:role:`content``, with the length it takes
+fn parse_role(text: &str) -> Option<(&str, &str, usize)> {
+    let tick = text.find(":`")?;
+    let role = &text[1..tick];
+    let is_role = !role.is_empty()
+        && role
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '-' | '_'));
+    if !is_role {
+        return None;
+    }
+    let content_start = tick + 2;
+    let end = text[content_start..].find('`')?;
+    Some((
+        role,
+        &text[content_start..content_start + end],
+        content_start + end + 1,
+    ))
+}
+
+/// `title <target>`
+fn split_target(content: &str) -> Option<(&str, &str)> {
+    let inner = content.strip_suffix('>')?;
+    let open = inner.rfind(" <")?;
+    Some((inner[..open].trim(), &inner[open + 2..]))
+}
+
+/// `*emphasis*` and `**strong**`, which Markdown shares with reST
+fn parse_emphasis(text: &str) -> Option<(String, usize)> {
+    let marker = if text.starts_with("**") { "**" } else { "*" };
+    let inner_start = marker.len();
+    let end = text[inner_start..].find(marker)? + inner_start;
+    let inner = &text[inner_start..end];
+    let is_emphasis = !inner.is_empty()
+        && !inner.starts_with([' ', '*'])
+        && !inner.ends_with(' ')
+        && !text[end + marker.len()..].starts_with(|c: char| c.is_alphanumeric());
+    is_emphasis.then(|| {
+        (
+            format!("{}{}{}", marker, escape(inner), marker),
+            end + marker.len(),
+        )
+    })
+}
+
+fn code(text: &str) -> String {
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '`' | '<' | '>' | '[' | ']' | '\\') {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::{PyEnvironment, PyEnvironmentKind};
+
+/// Virtual environment directories inside a project, in the order they are tried
+const IN_PROJECT_VENVS: &[&str] = &[".venv", "venv", "env", ".env"];
+
+/// The environment the project rooted at `root` uses. `venv` is the
+/// configured one, relative to `root`. Otherwise the first of these is used:
+/// the activated environment, an environment inside the project, the
+/// interpreter PDM recorded, the Poetry environment of the project, and the
+/// project's `__pypackages__`.
+pub fn discover_environment(root: &Path, venv: Option<&str>) -> Option<PyEnvironment> {
+    let environment = match venv {
+        Some(venv) => {
+            let prefix = root.join(venv);
+            let environment = PyEnvironment::load(&prefix, PyEnvironmentKind::Venv);
+            if environment.is_none() {
+                log::warn!("No environment at {}", prefix.display());
+            }
+            environment
+        }
+        None => find_activated()
+            .or_else(|| find_in_project(root))
+            .or_else(|| find_pdm(root))
+            .or_else(|| find_poetry(root))
+            .or_else(|| PyEnvironment::load_pypackages(root)),
+    };
+    if let Some(environment) = &environment {
+        log::info!(
+            "Using {:?} environment at {}",
+            environment.kind,
+            environment.prefix.display()
+        );
+    }
+    environment
+}
+
+fn find_activated() -> Option<PyEnvironment> {
+    let venv = std::env::var_os("VIRTUAL_ENV")
+        .and_then(|prefix| PyEnvironment::load(Path::new(&prefix), PyEnvironmentKind::Venv));
+    venv.or_else(|| {
+        // `base` is active in every conda shell, it is not a project environment
+        if std::env::var("CONDA_DEFAULT_ENV").is_ok_and(|name| name == "base") {
+            return None;
+        }
+        let prefix = std::env::var_os("CONDA_PREFIX")?;
+        PyEnvironment::load(Path::new(&prefix), PyEnvironmentKind::Conda)
+    })
+}
+
+fn find_in_project(root: &Path) -> Option<PyEnvironment> {
+    // uv may be told to use another directory than `.venv`
+    let uv_environment = std::env::var_os("UV_PROJECT_ENVIRONMENT").map(|path| root.join(path));
+    uv_environment
+        .into_iter()
+        .chain(IN_PROJECT_VENVS.iter().map(|name| root.join(name)))
+        .find_map(|prefix| PyEnvironment::load(&prefix, PyEnvironmentKind::Venv))
+}
+
+/// PDM writes the interpreter it uses to `.pdm-python`
+fn find_pdm(root: &Path) -> Option<PyEnvironment> {
+    let interpreter = std::fs::read_to_string(root.join(".pdm-python")).ok()?;
+    let interpreter = PathBuf::from(interpreter.trim());
+    // `<prefix>/bin/python` or `<prefix>/Scripts/python.exe`
+    let prefix = interpreter.parent()?.parent()?;
+    PyEnvironment::load(prefix, PyEnvironmentKind::Pdm)
+}
+
+/// Poetry keeps environments outside the project, named after it as
+/// `<name>-<hash>-py<version>`. The hash cannot be recomputed without
+/// hashing the project path, so the newest environment of the name is used.
+fn find_poetry(root: &Path) -> Option<PyEnvironment> {
+    let name = poetry_project_name(root)?;
+    let prefix = format!("{}-", sanitize_poetry_name(&name));
+    let virtualenvs = poetry_virtualenvs_dir()?;
+    std::fs::read_dir(virtualenvs)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .max_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok())
+        .and_then(|entry| PyEnvironment::load(&entry.path(), PyEnvironmentKind::Poetry))
+}
+
+fn poetry_project_name(root: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(root.join("pyproject.toml")).ok()?;
+    let value: Value = toml_edit::de::from_str(&text).ok()?;
+    let poetry_name = value
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(|poetry| poetry.get("name"));
+    let name = poetry_name.or_else(|| value.get("project")?.get("name"))?;
+    name.as_str().map(str::to_string)
+}
+
+/// The name part of a Poetry environment, as Poetry derives it from the project name
+fn sanitize_poetry_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|ch| match ch {
+            ' ' | '$' | '`' | '!' | '*' | '@' | '"' | '\\' | '\r' | '\n' | '\t' => '_',
+            ch => ch,
+        })
+        .flat_map(char::to_lowercase)
+        .collect();
+    sanitized.chars().take(42).collect()
+}
+
+fn poetry_virtualenvs_dir() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("POETRY_VIRTUALENVS_PATH") {
+        return Some(PathBuf::from(path));
+    }
+    let cache_dir = match std::env::var_os("POETRY_CACHE_DIR") {
+        Some(path) => PathBuf::from(path),
+        None if cfg!(windows) => dirs::cache_dir()?.join("pypoetry").join("Cache"),
+        None => dirs::cache_dir()?.join("pypoetry"),
+    };
+    Some(cache_dir.join("virtualenvs"))
+}
@@ -0,0 +1,118 @@
+mod locate;
+mod pyvenv_cfg;
+mod site_packages;
+
+use std::path::{Path, PathBuf};
+
+pub use locate::discover_environment;
+use python_parser::PyVersionNumber;
+pub use pyvenv_cfg::PyVenvCfg;
+
+/// The tool which created an environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyEnvironmentKind {
+    Venv,
+    Conda,
+    Poetry,
+    Pdm,
+    Uv,
+    /// A PEP 582 `__pypackages__` directory of the project
+    PyPackages,
+}
+
+/// A Python environment, found by reading the filesystem only. Nothing is
+/// executed, so the interpreter may not even run on this machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyEnvironment {
+    pub kind: PyEnvironmentKind,
+    /// The `sys.prefix` of the environment
+    pub prefix: PathBuf,
+    pub interpreter: Option<PathBuf>,
+    pub version: Option<PyVersionNumber>,
+    pub site_packages: Vec<PathBuf>,
+    /// Where imports are resolved from: the site-packages directories, then
+    /// the directories their `.pth` files add
+    pub search_paths: Vec<PathBuf>,
+}
+
+impl PyEnvironment {
+    /// Read the environment at `prefix`, `None` when it is not one
+    pub fn load(prefix: &Path, kind: PyEnvironmentKind) -> Option<PyEnvironment> {
+        let cfg = PyVenvCfg::load(prefix);
+        let is_conda = prefix.join("conda-meta").is_dir();
+        if cfg.is_none() && !is_conda {
+            return None;
+        }
+        let kind = match kind {
+            PyEnvironmentKind::Venv if is_conda => PyEnvironmentKind::Conda,
+            PyEnvironmentKind::Venv if cfg.as_ref().is_some_and(|cfg| cfg.is_uv()) => {
+                PyEnvironmentKind::Uv
+            }
+            kind => kind,
+        };
+
+        let site_packages = site_packages::find_site_packages(prefix);
+        let version = cfg
+            .as_ref()
+            .and_then(|cfg| cfg.get_version())
+            .or_else(|| site_packages::version_from_lib_dir(prefix))
+            .or_else(|| conda_python_version(prefix));
+        Some(PyEnvironment {
+            kind,
+            prefix: prefix.to_path_buf(),
+            interpreter: find_interpreter(prefix),
+            version,
+            search_paths: site_packages::collect_search_paths(&site_packages),
+            site_packages,
+        })
+    }
+
+    /// The packages directory of a PEP 582 project, as used by older PDM versions
+    pub fn load_pypackages(root: &Path) -> Option<PyEnvironment> {
+        let pypackages = root.join("__pypackages__");
+        let (version, lib) = std::fs::read_dir(&pypackages)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let version = PyVersionNumber::from_str(name.to_str()?)?;
+                let lib = entry.path().join("lib");
+                (version.major == 3 && lib.is_dir()).then_some((version, lib))
+            })
+            .max_by_key(|(version, _)| *version)?;
+        let site_packages = vec![lib];
+        Some(PyEnvironment {
+            kind: PyEnvironmentKind::PyPackages,
+            prefix: pypackages,
+            interpreter: None,
+            version: Some(version),
+            search_paths: site_packages::collect_search_paths(&site_packages),
+            site_packages,
+        })
+    }
+}
+
+fn find_interpreter(prefix: &Path) -> Option<PathBuf> {
+    [
+        "bin/python3",
+        "bin/python",
+        "Scripts/python.exe",
+        "python.exe",
+    ]
+    .iter()
+    .map(|name| prefix.join(name))
+    .find(|path| path.exists())
+}
+
+/// Conda records each installed package in `conda-meta`, such as
+/// `python-3.11.4-h955ad1f_0.json`
+fn conda_python_version(prefix: &Path) -> Option<PyVersionNumber> {
+    std::fs::read_dir(prefix.join("conda-meta"))
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let name = entry.file_name();
+            let version = name.to_str()?.strip_prefix("python-")?.split('-').next()?;
+            PyVersionNumber::from_str(version).filter(|version| version.major == 3)
+        })
+}
@@ -0,0 +1,50 @@
+use std::{collections::HashMap, path::Path};
+
+use python_parser::PyVersionNumber;
+
+/// The `pyvenv.cfg` at the root of a virtual environment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyVenvCfg {
+    values: HashMap<String, String>,
+}
+
+impl PyVenvCfg {
+    pub fn load(prefix: &Path) -> Option<PyVenvCfg> {
+        let text = std::fs::read_to_string(prefix.join("pyvenv.cfg")).ok()?;
+        Some(PyVenvCfg::parse(&text))
+    }
+
+    pub fn parse(text: &str) -> PyVenvCfg {
+        let values = text
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                Some((key.trim().to_lowercase(), value.trim().to_string()))
+            })
+            .collect();
+        PyVenvCfg { values }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// The interpreter version, which `venv` writes as `version` and
+    /// `virtualenv` and uv as `version_info`, such as `3.12.1.final.0`
+    pub fn get_version(&self) -> Option<PyVersionNumber> {
+        let version = self.get("version").or_else(|| self.get("version_info"))?;
+        let mut parts = version.split('.').take(3);
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts
+            .next()
+            .and_then(|patch| patch.parse().ok())
+            .unwrap_or(0);
+        (major == 3).then(|| PyVersionNumber::new(major, minor, patch))
+    }
+
+    /// Whether uv created the environment, it records its own version
+    pub fn is_uv(&self) -> bool {
+        self.get("uv").is_some()
+    }
+}
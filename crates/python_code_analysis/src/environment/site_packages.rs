@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use python_parser::PyVersionNumber;
+
+/// The site-packages directories of the environment at `prefix`, newest
+/// Python version first
+pub fn find_site_packages(prefix: &Path) -> Vec<PathBuf> {
+    let mut site_packages: Vec<PathBuf> = Vec::new();
+    // Windows keeps one directory for every version
+    let windows = prefix.join("Lib").join("site-packages");
+    if windows.is_dir() {
+        site_packages.push(windows);
+    }
+    for lib in ["lib", "lib64"] {
+        for (_, dir) in python_lib_dirs(&prefix.join(lib)) {
+            let path = dir.join("site-packages");
+            // `lib64` is usually a link to `lib`
+            let is_known = site_packages.iter().any(|known| same_path(known, &path));
+            if path.is_dir() && !is_known {
+                site_packages.push(path);
+            }
+        }
+    }
+    site_packages
+}
+
+/// The version of the `lib/python3.X` directory of the environment
+pub fn version_from_lib_dir(prefix: &Path) -> Option<PyVersionNumber> {
+    python_lib_dirs(&prefix.join("lib"))
+        .into_iter()
+        .next()
+        .map(|(version, _)| version)
+}
+
+/// The `python3.X` directories in `lib`, newest first
+fn python_lib_dirs(lib: &Path) -> Vec<(PyVersionNumber, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(lib) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let version = name.to_str()?.strip_prefix("python")?;
+            // free-threaded builds use `python3.13t`
+            let version = PyVersionNumber::from_str(version.trim_end_matches('t'))?;
+            (version.major == 3 && entry.path().is_dir()).then(|| (version, entry.path()))
+        })
+        .collect();
+    dirs.sort_by(|(a, _), (b, _)| b.cmp(a));
+    dirs
+}
+
+/// The site-packages directories followed by the directories which their
+/// `.pth` files add. Lines of a `.pth` file which run code are not run,
+/// except that the package mapping of a setuptools editable install is read.
+pub fn collect_search_paths(site_packages: &[PathBuf]) -> Vec<PathBuf> {
+    let mut search_paths = site_packages.to_vec();
+    for dir in site_packages {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        // Python reads `.pth` files in alphabetical order
+        let mut pth_files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "pth"))
+            .collect();
+        pth_files.sort();
+        for pth_file in pth_files {
+            let Ok(text) = std::fs::read_to_string(&pth_file) else {
+                continue;
+            };
+            for path in read_pth_file(dir, &text) {
+                if path.is_dir() && !search_paths.iter().any(|known| same_path(known, &path)) {
+                    search_paths.push(path);
+                }
+            }
+        }
+    }
+    search_paths
+}
+
+fn read_pth_file(site_packages: &Path, text: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("import ") || line.starts_with("import\t") {
+            paths.extend(editable_finder_paths(site_packages, line));
+            continue;
+        }
+        paths.push(site_packages.join(line));
+    }
+    paths
+}
+
+/// A setuptools editable install runs
+/// `import __editable___pkg_1_0_finder; __editable___pkg_1_0_finder.install()`,
+/// and the finder module maps each package to its source in `MAPPING`
+fn editable_finder_paths(site_packages: &Path, line: &str) -> Vec<PathBuf> {
+    let Some(module) = line
+        .strip_prefix("import")
+        .and_then(|rest| rest.split(';').next())
+        .map(str::trim)
+        .filter(|module| module.starts_with("__editable__"))
+    else {
+        return Vec::new();
+    };
+    let Ok(text) = std::fs::read_to_string(site_packages.join(format!("{}.py", module))) else {
+        return Vec::new();
+    };
+    let Some(mapping) = text
+        .lines()
+        .find(|line| line.starts_with("MAPPING"))
+        .and_then(|line| line.split_once('='))
+        .map(|(_, mapping)| mapping)
+    else {
+        return Vec::new();
+    };
+    let strings = python_string_literals(mapping);
+    // the values are the package directories, imports resolve from their parents
+    strings
+        .chunks_exact(2)
+        .filter_map(|pair| Path::new(&pair[1]).parent().map(Path::to_path_buf))
+        .collect()
+}
+
+/// The contents of the quoted strings in `text`, as the `repr` of a dict
+/// writes them
+fn python_string_literals(text: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\'' && ch != '"' {
+            continue;
+        }
+        let quote = ch;
+        let mut literal = String::new();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        literal.push(escaped);
+                    }
+                }
+                ch if ch == quote => break,
+                ch => literal.push(ch),
+            }
+        }
+        literals.push(literal);
+    }
+    literals
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
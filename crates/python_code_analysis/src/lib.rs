@@ -0,0 +1,203 @@
+mod compilation;
+mod config;
+mod db_index;
+mod diagnostic;
+mod docstring;
+mod environment;
+mod notebook;
+mod query;
+mod semantic;
+mod vfs;
+mod workspace;
+
+use std::sync::Arc;
+
+pub use compilation::*;
+pub use config::*;
+pub use db_index::*;
+pub use diagnostic::*;
+pub use docstring::*;
+pub use environment::*;
+use lsp_types::{Diagnostic, Uri};
+pub use notebook::*;
+use python_parser::{ParserConfig, PyLanguageLevel};
+pub use query::*;
+pub use semantic::*;
+pub use vfs::*;
+pub use workspace::*;
+
+#[derive(Debug, Default)]
+pub struct PyAnalysis {
+    pub compilation: PyCompilation,
+    lint_registry: LintRegistry,
+}
+
+impl PyAnalysis {
+    pub fn new() -> Self {
+        PyAnalysis {
+            compilation: PyCompilation::new(),
+            lint_registry: LintRegistry::default(),
+        }
+    }
+
+    pub fn update_file_by_uri(&mut self, uri: &Uri, text: Option<String>) -> Option<FileId> {
+        let file_id = self
+            .compilation
+            .get_db_mut()
+            .get_vfs_mut()
+            .set_file_content(uri, text);
+        self.compilation.update_index();
+        Some(file_id)
+    }
+
+    /// Load many files at once, they are parsed in parallel
+    pub fn update_files_by_uri(&mut self, files: Vec<(Uri, Option<PyFileContent>)>) -> Vec<FileId> {
+        let file_ids = self
+            .compilation
+            .get_db_mut()
+            .get_vfs_mut()
+            .set_files_content(files);
+        self.compilation.update_index();
+        self.prefetch_decl_trees(&file_ids);
+        file_ids
+    }
+
+    /// Derive the declarations of the files on as many threads as there are
+    /// cores, so that the queries asking for them later find them memoized
+    fn prefetch_decl_trees(&self, file_ids: &[FileId]) {
+        let db = self.compilation.get_db();
+        let threads = std::thread::available_parallelism()
+            .map(|it| it.get())
+            .unwrap_or(1);
+        let chunk_size = file_ids.len().div_ceil(threads).max(1);
+        std::thread::scope(|scope| {
+            for chunk in file_ids.chunks(chunk_size) {
+                scope.spawn(move || {
+                    for file_id in chunk {
+                        if file_decl_tree(db, *file_id).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Open a notebook or update its cells
+    pub fn update_notebook_by_uri(&mut self, uri: &Uri, notebook: PyNotebook) -> Option<FileId> {
+        let file_id = self
+            .compilation
+            .get_db_mut()
+            .get_vfs_mut()
+            .set_notebook(uri, notebook);
+        self.compilation.update_index();
+        Some(file_id)
+    }
+
+    /// Forget the cells of a closed notebook, its file is updated or
+    /// removed like the file of any closed document
+    pub fn remove_notebook_by_uri(&mut self, uri: &Uri) -> Option<PyNotebook> {
+        self.compilation
+            .get_db_mut()
+            .get_vfs_mut()
+            .remove_notebook(uri)
+    }
+
+    pub fn get_notebook(&self, file_id: FileId) -> Option<&PyNotebook> {
+        self.compilation.get_db().get_vfs().get_notebook(&file_id)
+    }
+
+    /// The notebook file a cell document belongs to and the index of the cell
+    pub fn find_notebook_cell(&self, cell_uri: &Uri) -> Option<(FileId, usize)> {
+        self.compilation
+            .get_db()
+            .get_vfs()
+            .find_notebook_cell(cell_uri)
+    }
+
+    pub fn remove_file_by_uri(&mut self, uri: &Uri) -> Option<FileId> {
+        let file_id = self.compilation.get_db_mut().get_vfs_mut().remove_file(uri);
+        // a new revision either way, it ends the cancellation of the queries
+        self.compilation.update_index();
+        file_id
+    }
+
+    /// Apply new settings. Files are parsed again when the Python version
+    /// changes and they are asked for, their trees depend on it.
+    pub fn update_config(&mut self, config: Arc<PyConfig>) {
+        self.compilation.get_db_mut().set_config(config);
+        self.update_language_level();
+    }
+
+    pub fn get_config(&self) -> Arc<PyConfig> {
+        self.compilation.get_db().get_config()
+    }
+
+    /// Use the environment imports are resolved from, its Python version is
+    /// the default of `python_version`
+    pub fn set_environment(&mut self, environment: Option<PyEnvironment>) {
+        self.compilation
+            .get_db_mut()
+            .set_environment(environment.map(Arc::new));
+        self.update_language_level();
+    }
+
+    pub fn get_environment(&self) -> Option<Arc<PyEnvironment>> {
+        self.compilation.get_db().get_environment()
+    }
+
+    fn update_language_level(&mut self) {
+        let config = self.get_config();
+        let level = match (&config.python_version, self.get_environment()) {
+            (None, Some(environment)) => environment
+                .version
+                .map(PyLanguageLevel::Py3)
+                .unwrap_or_default(),
+            _ => config.get_language_level(),
+        };
+        let db = self.compilation.get_db_mut();
+        if db.get_vfs().get_parser_config().level == level {
+            return;
+        }
+        db.get_vfs_mut().set_parser_config(ParserConfig::new(level));
+        let queries = db.get_query_db_mut();
+        queries.new_revision();
+        queries.set_changed(QueryKey::ParserConfig);
+    }
+
+    pub fn get_file_id(&self, uri: &Uri) -> Option<FileId> {
+        self.compilation.get_db().get_vfs().get_file_id(uri)
+    }
+
+    /// The diagnostics of a file, memoized until the file or the settings
+    /// change. `None` when the file is not loaded or a change cancelled them.
+    pub fn diagnose_file(&self, file_id: FileId) -> Option<Vec<Diagnostic>> {
+        file_diagnostics(self.compilation.get_db(), &self.lint_registry, file_id)
+            .ok()
+            .flatten()
+            .map(|diagnostics| diagnostics.as_ref().clone())
+    }
+
+    /// The rules run on every file, with the metadata of their codes
+    pub fn get_lint_registry(&self) -> &LintRegistry {
+        &self.lint_registry
+    }
+
+    /// Register a rule which runs after the builtin ones
+    pub fn register_lint_rule(&mut self, rule: Box<dyn LintRule>) {
+        self.lint_registry.register(rule);
+        let queries = self.compilation.get_db_mut().get_query_db_mut();
+        queries.new_revision();
+        queries.set_changed(QueryKey::LintRules);
+    }
+
+    /// Makes the queries running on other threads give up, call it before
+    /// waiting to change the analysis
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.compilation.get_db().get_query_db().cancel_handle()
+    }
+
+    pub fn get_semantic_model(&self, file_id: FileId) -> Option<SemanticModel<'_>> {
+        self.compilation.get_semantic_model(file_id)
+    }
+}
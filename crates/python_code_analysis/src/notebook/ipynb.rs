@@ -0,0 +1,61 @@
+use std::str::FromStr;
+
+use lsp_types::Uri;
+use serde::Deserialize;
+
+use super::{PyCellKind, PyNotebook, PyNotebookCell};
+
+/// The parts of the nbformat 4 JSON the analysis reads
+#[derive(Deserialize)]
+struct IpynbFile {
+    cells: Vec<IpynbCell>,
+}
+
+#[derive(Deserialize)]
+struct IpynbCell {
+    cell_type: String,
+    #[serde(default)]
+    source: IpynbSource,
+}
+
+/// The source of a cell is a string or a list of lines which keep their
+/// line breaks
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IpynbSource {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for IpynbSource {
+    fn default() -> Self {
+        IpynbSource::Lines(Vec::new())
+    }
+}
+
+impl PyNotebook {
+    /// Read an `.ipynb` file, its cells are addressed as the fragments
+    /// `#cell-<index>` of `uri`
+    pub fn from_ipynb(uri: &Uri, json: &str) -> Result<Self, String> {
+        let file: IpynbFile = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let mut cells = Vec::with_capacity(file.cells.len());
+        for (index, cell) in file.cells.into_iter().enumerate() {
+            let cell_uri = Uri::from_str(&format!("{}#cell-{}", uri.as_str(), index))
+                .map_err(|err| err.to_string())?;
+            let text = match cell.source {
+                IpynbSource::Text(text) => text,
+                IpynbSource::Lines(lines) => lines.concat(),
+            };
+            let kind = match cell.cell_type.as_str() {
+                "code" => PyCellKind::Code,
+                _ => PyCellKind::Markup,
+            };
+            cells.push(PyNotebookCell {
+                uri: cell_uri,
+                kind,
+                text,
+            });
+        }
+        Ok(PyNotebook::new(cells))
+    }
+}
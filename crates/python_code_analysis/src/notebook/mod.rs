@@ -0,0 +1,145 @@
+mod ipynb;
+
+use lsp_types::{Position, Range, Uri};
+
+/// Only code cells are analyzed, markdown and raw cells are markup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyCellKind {
+    Code,
+    Markup,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyNotebookCell {
+    /// The document of the cell, positions in the cell are relative to it
+    pub uri: Uri,
+    pub kind: PyCellKind,
+    pub text: String,
+}
+
+/// A notebook analyzed as one document: its code cells one after another,
+/// so that the names a cell binds are seen by the cells below it. The
+/// document is parsed with IPython magics, a line of a cell is a line of
+/// the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyNotebook {
+    cells: Vec<PyNotebookCell>,
+    /// The first line of each cell in the document and how many lines it
+    /// takes, a markup cell takes none
+    cell_lines: Vec<(u32, u32)>,
+    source: String,
+}
+
+impl PyNotebook {
+    pub fn new(cells: Vec<PyNotebookCell>) -> Self {
+        let mut source = String::new();
+        let mut cell_lines = Vec::with_capacity(cells.len());
+        let mut line = 0;
+        for cell in &cells {
+            if cell.kind != PyCellKind::Code {
+                cell_lines.push((line, 0));
+                continue;
+            }
+            let mut code = if is_cell_magic(&cell.text) {
+                comment_out(&cell.text)
+            } else {
+                cell.text.clone()
+            };
+            if !code.ends_with('\n') {
+                code.push('\n');
+            }
+            let count = code.matches('\n').count() as u32;
+            cell_lines.push((line, count));
+            source.push_str(&code);
+            line += count;
+        }
+        PyNotebook {
+            cells,
+            cell_lines,
+            source,
+        }
+    }
+
+    pub fn get_cells(&self) -> &[PyNotebookCell] {
+        &self.cells
+    }
+
+    /// The text analyzed for the notebook
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    /// The index of the cell whose document is `uri`
+    pub fn find_cell(&self, uri: &Uri) -> Option<usize> {
+        self.cells
+            .iter()
+            .position(|cell| cell.uri.as_str() == uri.as_str())
+    }
+
+    /// A position in a code cell as a position in the document
+    pub fn to_source_position(&self, cell: usize, position: Position) -> Option<Position> {
+        let (start, count) = *self.cell_lines.get(cell)?;
+        if position.line >= count {
+            return None;
+        }
+        Some(Position::new(start + position.line, position.character))
+    }
+
+    pub fn to_source_range(&self, cell: usize, range: Range) -> Option<Range> {
+        Some(Range::new(
+            self.to_source_position(cell, range.start)?,
+            self.to_source_position(cell, range.end)?,
+        ))
+    }
+
+    /// The cell a line of the document belongs to, with the line in it. The
+    /// line after the document is the end of its last code cell.
+    pub fn to_cell_line(&self, line: u32) -> Option<(usize, u32)> {
+        if let Some(cell) = self
+            .cell_lines
+            .iter()
+            .position(|(start, count)| (*start..start + count).contains(&line))
+        {
+            return Some((cell, line - self.cell_lines[cell].0));
+        }
+        let cell = self.cell_lines.iter().rposition(|(_, count)| *count > 0)?;
+        let (start, count) = self.cell_lines[cell];
+        (line == start + count).then_some((cell, count))
+    }
+
+    /// A range of the document as a range of the cell it starts in, a range
+    /// which runs past the cell ends with it
+    pub fn to_cell_range(&self, range: Range) -> Option<(usize, Range)> {
+        let (cell, start_line) = self.to_cell_line(range.start.line)?;
+        let cell_end = self.cell_end(cell);
+        let start = Position::new(start_line, range.start.character).min(cell_end);
+        let end = match self.to_cell_line(range.end.line) {
+            Some((end_cell, end_line)) if end_cell == cell => {
+                Position::new(end_line, range.end.character).min(cell_end)
+            }
+            _ => cell_end,
+        };
+        Some((cell, Range::new(start, end)))
+    }
+
+    /// The position after the last character of a cell
+    fn cell_end(&self, cell: usize) -> Position {
+        let text = &self.cells[cell].text;
+        let line = text.matches('\n').count() as u32;
+        let last_line = text.rsplit('\n').next().unwrap_or_default();
+        Position::new(line, last_line.chars().count() as u32)
+    }
+}
+
+/// A cell magic such as `%%bash` takes its whole cell, which is not Python
+fn is_cell_magic(cell: &str) -> bool {
+    cell.trim_start().starts_with("%%")
+}
+
+/// The lines of a cell as comments. A cell magic in the document would take
+/// the cells after it too, as it takes the rest of an IPython script.
+fn comment_out(cell: &str) -> String {
+    cell.split_inclusive('\n')
+        .map(|line| format!("#{}", line))
+        .collect()
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use super::{Cancelled, QueryKey, file_syntax_tree};
+use crate::{
+    FileId,
+    compilation::analyze_decl,
+    db_index::{DbIndex, PyDeclTree},
+};
+
+/// The scopes and declarations of a file, `None` when the file is not loaded
+pub fn file_decl_tree(db: &DbIndex, file_id: FileId) -> Result<Option<Arc<PyDeclTree>>, Cancelled> {
+    let queries = db.get_query_db();
+    queries.fetch(
+        db,
+        QueryKey::DeclTree(file_id),
+        &queries.decl_tree,
+        file_id,
+        || {
+            let Some(tree) = file_syntax_tree(db, file_id)? else {
+                return Ok(None);
+            };
+            let decl_tree = analyze_decl(file_id, &tree.get_module_node());
+            Ok(Some(Arc::new(decl_tree)))
+        },
+    )
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use lsp_types::Diagnostic;
+
+use super::{Cancelled, QueryKey};
+use crate::{FileId, LintRegistry, SemanticModel, db_index::DbIndex, diagnose_file};
+
+/// The diagnostics of a file, `None` when the file is not loaded. They are
+/// computed again only after something they read changed: the file, the
+/// diagnostic settings, or the exports of the other files an import fix was
+/// looked up in.
+pub fn file_diagnostics(
+    db: &DbIndex,
+    registry: &LintRegistry,
+    file_id: FileId,
+) -> Result<Option<Arc<Vec<Diagnostic>>>, Cancelled> {
+    let queries = db.get_query_db();
+    queries.fetch(
+        db,
+        QueryKey::Diagnostics(file_id),
+        &queries.diagnostics,
+        file_id,
+        || {
+            // the tree and the declarations are read by making the model
+            queries.read(QueryKey::DiagnosticConfig)?;
+            queries.read(QueryKey::LintRules)?;
+            let Some(semantic_model) = SemanticModel::new(file_id, db) else {
+                queries.unwind_if_cancelled()?;
+                return Ok(None);
+            };
+            let diagnostics = diagnose_file(&semantic_model, registry);
+            // a change may have come in while the rules ran
+            queries.unwind_if_cancelled()?;
+            Ok(Some(Arc::new(diagnostics)))
+        },
+    )
+}
@@ -0,0 +1,56 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use super::{QueryKey, Revision};
+use crate::FileId;
+
+/// A derived value and the queries it was computed from
+#[derive(Debug, Clone)]
+pub(super) struct Memo<V> {
+    pub value: V,
+    /// The revision the value last became different from the one before
+    pub changed_at: Revision,
+    /// The last revision the value was known to be up to date at
+    pub verified_at: Revision,
+    pub deps: Vec<QueryKey>,
+}
+
+/// The memoized values of one query, by file. The lock is only held to copy
+/// a memo in or out, never while a value is computed.
+#[derive(Debug)]
+pub(super) struct MemoTable<V> {
+    memos: Mutex<HashMap<FileId, Memo<V>>>,
+}
+
+impl<V: Clone> MemoTable<V> {
+    pub fn new() -> Self {
+        MemoTable {
+            memos: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, file_id: FileId) -> Option<Memo<V>> {
+        self.memos.lock().unwrap().get(&file_id).cloned()
+    }
+
+    pub fn get_changed_at(&self, file_id: FileId) -> Option<Revision> {
+        self.memos
+            .lock()
+            .unwrap()
+            .get(&file_id)
+            .map(|memo| memo.changed_at)
+    }
+
+    pub fn insert(&self, file_id: FileId, memo: Memo<V>) {
+        self.memos.lock().unwrap().insert(file_id, memo);
+    }
+
+    pub fn mark_verified(&self, file_id: FileId, revision: Revision) {
+        if let Some(memo) = self.memos.lock().unwrap().get_mut(&file_id) {
+            memo.verified_at = revision;
+        }
+    }
+
+    pub fn remove(&self, file_id: FileId) {
+        self.memos.lock().unwrap().remove(&file_id);
+    }
+}
@@ -0,0 +1,324 @@
+mod decl_tree;
+mod diagnostics;
+mod memo;
+mod module_exports;
+mod module_types;
+mod parse;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+pub use decl_tree::file_decl_tree;
+pub use diagnostics::file_diagnostics;
+use lsp_types::Diagnostic;
+use memo::{Memo, MemoTable};
+pub use module_exports::{PyModuleExport, module_exports};
+pub use module_types::module_types;
+pub use parse::{file_line_index, file_syntax_tree};
+use python_parser::{LineIndex, PySyntaxTree};
+
+use crate::{FileId, PyDeclTree, PyType, db_index::DbIndex};
+
+/// A point in the history of the inputs, every change advances it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Revision(u64);
+
+/// What a query reads. Inputs are set from outside, the other queries are
+/// derived from them on demand and memoized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKey {
+    /// The text of a file with the problems of decoding it
+    FileText(FileId),
+    /// Which files are loaded
+    Files,
+    /// The Python version files are parsed for
+    ParserConfig,
+    /// The `diagnostics` section of the config
+    DiagnosticConfig,
+    LintRules,
+    Parse(FileId),
+    LineIndex(FileId),
+    DeclTree(FileId),
+    ModuleExports(FileId),
+    ModuleTypes(FileId),
+    Diagnostics(FileId),
+}
+
+/// A query gave up because a change is waiting to be applied, its result
+/// would be out of date anyway
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Makes the queries running on other threads give up, so that a change
+/// does not wait for them. It is reset when the change is applied.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    flag: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Acquire)
+    }
+
+    fn reset(&self) {
+        self.flag.store(false, Ordering::Release);
+    }
+
+    /// Run `f` with the queries it runs on this thread also giving up when
+    /// `self` is cancelled, so that a single request can be stopped
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        let outer = SCOPED_CANCEL_HANDLE.replace(Some(self.clone()));
+        let _restore = RestoreScopedHandle(outer);
+        f()
+    }
+}
+
+/// Puts back the handle of the enclosing `CancelHandle::run`, even when `f`
+/// unwinds
+struct RestoreScopedHandle(Option<CancelHandle>);
+
+impl Drop for RestoreScopedHandle {
+    fn drop(&mut self) {
+        SCOPED_CANCEL_HANDLE.set(self.0.take());
+    }
+}
+
+thread_local! {
+    /// The handle of the request running on this thread, see `CancelHandle::run`
+    static SCOPED_CANCEL_HANDLE: RefCell<Option<CancelHandle>> = const { RefCell::new(None) };
+
+    /// The keys read by each query being computed on this thread, the
+    /// innermost last
+    static ACTIVE_QUERIES: RefCell<Vec<Vec<QueryKey>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Memoizes the derived analysis of each file together with the keys it
+/// read, so that a change only recomputes what depends on it. A memo is
+/// reused when none of its keys changed since it was last verified. A value
+/// which is recomputed but comes out equal keeps its old revision, and the
+/// queries depending on it are not recomputed either.
+#[derive(Debug)]
+pub struct QueryDb {
+    revision: Revision,
+    /// When each input last changed
+    changed_at: HashMap<QueryKey, Revision>,
+    cancel_handle: CancelHandle,
+    parse: MemoTable<Option<Arc<PySyntaxTree>>>,
+    line_index: MemoTable<Option<Arc<LineIndex>>>,
+    decl_tree: MemoTable<Option<Arc<PyDeclTree>>>,
+    module_exports: MemoTable<Arc<Vec<PyModuleExport>>>,
+    module_types: MemoTable<Arc<Vec<(String, PyType)>>>,
+    diagnostics: MemoTable<Option<Arc<Vec<Diagnostic>>>>,
+}
+
+impl Default for QueryDb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryDb {
+    pub fn new() -> Self {
+        QueryDb {
+            revision: Revision::default(),
+            changed_at: HashMap::new(),
+            cancel_handle: CancelHandle::default(),
+            parse: MemoTable::new(),
+            line_index: MemoTable::new(),
+            decl_tree: MemoTable::new(),
+            module_exports: MemoTable::new(),
+            module_types: MemoTable::new(),
+            diagnostics: MemoTable::new(),
+        }
+    }
+
+    pub fn get_revision(&self) -> Revision {
+        self.revision
+    }
+
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// Start applying a change, the memos are verified again when asked for
+    pub fn new_revision(&mut self) {
+        self.revision.0 += 1;
+        self.cancel_handle.reset();
+    }
+
+    /// Record that an input changed in the current revision
+    pub fn set_changed(&mut self, key: QueryKey) {
+        self.changed_at.insert(key, self.revision);
+    }
+
+    /// Drop the memos of a file which is gone
+    pub fn remove_file(&mut self, file_id: FileId) {
+        self.parse.remove(file_id);
+        self.line_index.remove(file_id);
+        self.decl_tree.remove(file_id);
+        self.module_exports.remove(file_id);
+        self.module_types.remove(file_id);
+        self.diagnostics.remove(file_id);
+    }
+
+    /// The revision the value of `key` last changed at, `None` when it was
+    /// never set or computed
+    pub fn get_changed_at(&self, key: QueryKey) -> Option<Revision> {
+        match key {
+            QueryKey::Parse(file_id) => self.parse.get_changed_at(file_id),
+            QueryKey::LineIndex(file_id) => self.line_index.get_changed_at(file_id),
+            QueryKey::DeclTree(file_id) => self.decl_tree.get_changed_at(file_id),
+            QueryKey::ModuleExports(file_id) => self.module_exports.get_changed_at(file_id),
+            QueryKey::ModuleTypes(file_id) => self.module_types.get_changed_at(file_id),
+            QueryKey::Diagnostics(file_id) => self.diagnostics.get_changed_at(file_id),
+            _ => self.changed_at.get(&key).copied(),
+        }
+    }
+
+    pub fn unwind_if_cancelled(&self) -> Result<(), Cancelled> {
+        let scoped_cancelled = SCOPED_CANCEL_HANDLE
+            .with_borrow(|handle| handle.as_ref().is_some_and(CancelHandle::is_cancelled));
+        if self.cancel_handle.is_cancelled() || scoped_cancelled {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Record that the query being computed reads an input
+    pub(crate) fn read(&self, key: QueryKey) -> Result<(), Cancelled> {
+        self.unwind_if_cancelled()?;
+        record_read(key);
+        Ok(())
+    }
+
+    /// The memoized value of `key` when it is still valid, or else the value
+    /// `compute` returns. The keys `compute` reads become the dependencies
+    /// of the new memo.
+    fn fetch<V, F>(
+        &self,
+        db: &DbIndex,
+        key: QueryKey,
+        table: &MemoTable<V>,
+        file_id: FileId,
+        compute: F,
+    ) -> Result<V, Cancelled>
+    where
+        V: Clone + PartialEq,
+        F: FnOnce() -> Result<V, Cancelled>,
+    {
+        self.unwind_if_cancelled()?;
+        record_read(key);
+        let old = table.get(file_id);
+        if let Some(memo) = &old {
+            if memo.verified_at == self.revision {
+                return Ok(memo.value.clone());
+            }
+            if !self.any_changed_since(db, &memo.deps, memo.verified_at)? {
+                table.mark_verified(file_id, self.revision);
+                return Ok(memo.value.clone());
+            }
+        }
+
+        let active = ActiveQuery::start();
+        let value = compute();
+        let deps = active.finish();
+        let value = value?;
+        let changed_at = match old {
+            Some(memo) if memo.value == value => memo.changed_at,
+            _ => self.revision,
+        };
+        table.insert(
+            file_id,
+            Memo {
+                value: value.clone(),
+                changed_at,
+                verified_at: self.revision,
+                deps,
+            },
+        );
+        Ok(value)
+    }
+
+    fn any_changed_since(
+        &self,
+        db: &DbIndex,
+        deps: &[QueryKey],
+        revision: Revision,
+    ) -> Result<bool, Cancelled> {
+        for dep in deps {
+            if self.refresh(db, *dep)? > revision {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Bring `key` up to date and return the revision it last changed at
+    fn refresh(&self, db: &DbIndex, key: QueryKey) -> Result<Revision, Cancelled> {
+        let active = ActiveQuery::start();
+        let refreshed = match key {
+            QueryKey::Parse(file_id) => file_syntax_tree(db, file_id).map(|_| ()),
+            QueryKey::LineIndex(file_id) => file_line_index(db, file_id).map(|_| ()),
+            QueryKey::DeclTree(file_id) => file_decl_tree(db, file_id).map(|_| ()),
+            QueryKey::ModuleExports(file_id) => module_exports(db, file_id).map(|_| ()),
+            QueryKey::ModuleTypes(file_id) => module_types(db, file_id).map(|_| ()),
+            // computing diagnostics takes the lint rules, no query reads them
+            QueryKey::Diagnostics(_) => return Ok(self.revision),
+            _ => Ok(()),
+        };
+        // verifying a dependency is not a read of the query being verified
+        active.finish();
+        refreshed?;
+        Ok(self.get_changed_at(key).unwrap_or_default())
+    }
+}
+
+fn record_read(key: QueryKey) {
+    ACTIVE_QUERIES.with_borrow_mut(|stack| {
+        if let Some(reads) = stack.last_mut()
+            && !reads.contains(&key)
+        {
+            reads.push(key);
+        }
+    });
+}
+
+/// Collects the keys read while a query is computed. The frame is popped
+/// even when the computation unwinds.
+struct ActiveQuery {
+    finished: bool,
+}
+
+impl ActiveQuery {
+    fn start() -> Self {
+        ACTIVE_QUERIES.with_borrow_mut(|stack| stack.push(Vec::new()));
+        ActiveQuery { finished: false }
+    }
+
+    fn finish(mut self) -> Vec<QueryKey> {
+        self.finished = true;
+        ACTIVE_QUERIES
+            .with_borrow_mut(|stack| stack.pop())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for ActiveQuery {
+    fn drop(&mut self) {
+        if !self.finished {
+            ACTIVE_QUERIES.with_borrow_mut(|stack| stack.pop());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use python_parser::PySyntaxTree;
+
+use super::{Cancelled, QueryKey, file_decl_tree, file_syntax_tree};
+use crate::{
+    FileId,
+    db_index::{DbIndex, PyDeclKind, PyDeclTree},
+    diagnostic::collect_dunder_all,
+};
+
+/// A name bound at the top level of a module which other modules see
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyModuleExport {
+    pub name: String,
+    pub kind: PyDeclKind,
+}
+
+/// The names `from module import *` binds, in the order they are first
+/// bound: the ones listed in `__all__` when the module assigns it, or else
+/// the ones which do not start with an underscore. Edits inside function
+/// bodies leave them unchanged.
+pub fn module_exports(
+    db: &DbIndex,
+    file_id: FileId,
+) -> Result<Arc<Vec<PyModuleExport>>, Cancelled> {
+    let queries = db.get_query_db();
+    queries.fetch(
+        db,
+        QueryKey::ModuleExports(file_id),
+        &queries.module_exports,
+        file_id,
+        || {
+            let (Some(tree), Some(decl_tree)) =
+                (file_syntax_tree(db, file_id)?, file_decl_tree(db, file_id)?)
+            else {
+                return Ok(Arc::default());
+            };
+            Ok(Arc::new(collect_exports(&tree, &decl_tree)))
+        },
+    )
+}
+
+fn collect_exports(tree: &PySyntaxTree, decl_tree: &PyDeclTree) -> Vec<PyModuleExport> {
+    let Some(root_scope) = decl_tree.get_root_scope() else {
+        return Vec::new();
+    };
+    let root_decls: Vec<_> = root_scope
+        .get_decls()
+        .iter()
+        .filter_map(|decl_id| decl_tree.get_decl(*decl_id))
+        .collect();
+    let dunder_all = root_decls
+        .iter()
+        .any(|decl| decl.get_name() == "__all__")
+        .then(|| collect_dunder_all(&tree.get_red_root()));
+
+    let mut exports: Vec<PyModuleExport> = Vec::new();
+    for decl in root_decls {
+        let name = decl.get_name();
+        let is_exported = match &dunder_all {
+            Some(names) => names.contains(name),
+            None => !name.starts_with('_'),
+        };
+        if !is_exported {
+            continue;
+        }
+        // the last binding of a name decides its kind
+        match exports.iter_mut().find(|export| export.name == name) {
+            Some(export) => export.kind = decl.get_kind().clone(),
+            None => exports.push(PyModuleExport {
+                name: name.to_string(),
+                kind: decl.get_kind().clone(),
+            }),
+        }
+    }
+    exports
+}
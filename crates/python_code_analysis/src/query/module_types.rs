@@ -0,0 +1,82 @@
+use std::{cell::RefCell, sync::Arc};
+
+use super::{Cancelled, QueryKey, module_exports};
+use crate::{FileId, PyType, SemanticModel, db_index::DbIndex};
+
+thread_local! {
+    /// The modules whose types are being inferred on this thread
+    static IN_PROGRESS: RefCell<Vec<FileId>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The inferred type of each export of a module, what an import of it sees.
+/// Within an import cycle, the names imported from a module whose types are
+/// still being inferred are unknown.
+pub fn module_types(
+    db: &DbIndex,
+    file_id: FileId,
+) -> Result<Arc<Vec<(String, PyType)>>, Cancelled> {
+    if IN_PROGRESS.with_borrow(|files| files.contains(&file_id)) {
+        return Ok(Arc::default());
+    }
+    let queries = db.get_query_db();
+    queries.fetch(
+        db,
+        QueryKey::ModuleTypes(file_id),
+        &queries.module_types,
+        file_id,
+        || {
+            let _guard = InProgress::start(file_id);
+            let exports = module_exports(db, file_id)?;
+            let Some(semantic_model) = SemanticModel::new(file_id, db) else {
+                queries.unwind_if_cancelled()?;
+                return Ok(Arc::default());
+            };
+            let decl_tree = semantic_model.get_decl_tree();
+            let Some(root_scope) = decl_tree.get_root_scope() else {
+                return Ok(Arc::default());
+            };
+            let mut types = Vec::with_capacity(exports.len());
+            for export in exports.iter() {
+                queries.unwind_if_cancelled()?;
+                let ty = decl_tree
+                    .get_scope_decls_by_name(root_scope.get_id(), &export.name)
+                    .last()
+                    .map(|decl| semantic_model.infer_decl_type(decl))
+                    .unwrap_or(PyType::Unknown);
+                types.push((export.name.clone(), detach(&semantic_model, ty)));
+            }
+            Ok(Arc::new(types))
+        },
+    )
+}
+
+/// A function type which does not point into the module, the importing one
+/// sees its return type instead of its node
+fn detach(semantic_model: &SemanticModel, ty: PyType) -> PyType {
+    match ty {
+        PyType::Function(signature) => {
+            let mut signature = (*signature).clone();
+            signature.return_type = Some(semantic_model.infer_call_return(&signature));
+            signature.syntax_id = None;
+            PyType::Function(Arc::new(signature))
+        }
+        ty => ty,
+    }
+}
+
+/// Marks a module as being inferred until it is dropped, even when the
+/// inference unwinds
+struct InProgress;
+
+impl InProgress {
+    fn start(file_id: FileId) -> Self {
+        IN_PROGRESS.with_borrow_mut(|files| files.push(file_id));
+        InProgress
+    }
+}
+
+impl Drop for InProgress {
+    fn drop(&mut self) {
+        IN_PROGRESS.with_borrow_mut(|files| files.pop());
+    }
+}
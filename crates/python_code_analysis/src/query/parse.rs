@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use python_parser::{LineIndex, PyParser, PySyntaxTree};
+
+use super::{Cancelled, QueryKey};
+use crate::{FileId, db_index::DbIndex};
+
+/// The tree of a file, `None` when the file is not loaded. The problems of
+/// decoding the file are the first errors of the tree.
+pub fn file_syntax_tree(
+    db: &DbIndex,
+    file_id: FileId,
+) -> Result<Option<Arc<PySyntaxTree>>, Cancelled> {
+    let queries = db.get_query_db();
+    queries.fetch(
+        db,
+        QueryKey::Parse(file_id),
+        &queries.parse,
+        file_id,
+        || {
+            queries.read(QueryKey::FileText(file_id))?;
+            queries.read(QueryKey::ParserConfig)?;
+            let vfs = db.get_vfs();
+            let (Some(text), Some(config)) = (
+                vfs.get_file_content(&file_id),
+                vfs.get_file_parser_config(&file_id),
+            ) else {
+                return Ok(None);
+            };
+            let decode_errors = vfs.get_decode_errors(&file_id).to_vec();
+            let tree = PyParser::parse_with_errors(text, config, None, decode_errors);
+            Ok(Some(Arc::new(tree)))
+        },
+    )
+}
+
+/// Where the lines of a file start, `None` when the file is not loaded
+pub fn file_line_index(db: &DbIndex, file_id: FileId) -> Result<Option<Arc<LineIndex>>, Cancelled> {
+    let queries = db.get_query_db();
+    queries.fetch(
+        db,
+        QueryKey::LineIndex(file_id),
+        &queries.line_index,
+        file_id,
+        || {
+            queries.read(QueryKey::FileText(file_id))?;
+            Ok(db
+                .get_vfs()
+                .get_file_content(&file_id)
+                .map(|text| Arc::new(LineIndex::parse(text))))
+        },
+    )
+}
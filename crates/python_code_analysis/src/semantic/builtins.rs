@@ -0,0 +1,236 @@
+use super::PyType;
+
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "abs",
+    "aiter",
+    "all",
+    "anext",
+    "any",
+    "ascii",
+    "bin",
+    "breakpoint",
+    "callable",
+    "chr",
+    "compile",
+    "copyright",
+    "credits",
+    "delattr",
+    "dir",
+    "divmod",
+    "eval",
+    "exec",
+    "exit",
+    "format",
+    "getattr",
+    "globals",
+    "hasattr",
+    "hash",
+    "help",
+    "hex",
+    "id",
+    "input",
+    "isinstance",
+    "issubclass",
+    "iter",
+    "len",
+    "license",
+    "locals",
+    "max",
+    "min",
+    "next",
+    "oct",
+    "open",
+    "ord",
+    "pow",
+    "print",
+    "quit",
+    "repr",
+    "round",
+    "setattr",
+    "sorted",
+    "sum",
+    "vars",
+    "__import__",
+];
+
+const BUILTIN_CLASSES: &[&str] = &[
+    "bool",
+    "bytearray",
+    "bytes",
+    "classmethod",
+    "complex",
+    "dict",
+    "enumerate",
+    "filter",
+    "float",
+    "frozenset",
+    "int",
+    "list",
+    "map",
+    "memoryview",
+    "object",
+    "property",
+    "range",
+    "reversed",
+    "set",
+    "slice",
+    "staticmethod",
+    "str",
+    "super",
+    "tuple",
+    "type",
+    "zip",
+];
+
+const BUILTIN_CONSTANTS: &[&str] = &[
+    "True",
+    "False",
+    "None",
+    "Ellipsis",
+    "NotImplemented",
+    "__name__",
+    "__file__",
+    "__doc__",
+    "__package__",
+    "__spec__",
+    "__loader__",
+    "__builtins__",
+    "__debug__",
+];
+
+const BUILTIN_EXCEPTIONS: &[&str] = &[
+    "BaseException",
+    "BaseExceptionGroup",
+    "Exception",
+    "ExceptionGroup",
+    "ArithmeticError",
+    "AssertionError",
+    "AttributeError",
+    "BlockingIOError",
+    "BrokenPipeError",
+    "BufferError",
+    "ChildProcessError",
+    "ConnectionAbortedError",
+    "ConnectionError",
+    "ConnectionRefusedError",
+    "ConnectionResetError",
+    "EOFError",
+    "EnvironmentError",
+    "FileExistsError",
+    "FileNotFoundError",
+    "FloatingPointError",
+    "GeneratorExit",
+    "IOError",
+    "ImportError",
+    "IndentationError",
+    "IndexError",
+    "InterruptedError",
+    "IsADirectoryError",
+    "KeyError",
+    "KeyboardInterrupt",
+    "LookupError",
+    "MemoryError",
+    "ModuleNotFoundError",
+    "NameError",
+    "NotADirectoryError",
+    "NotImplementedError",
+    "OSError",
+    "OverflowError",
+    "PermissionError",
+    "ProcessLookupError",
+    "RecursionError",
+    "ReferenceError",
+    "RuntimeError",
+    "StopAsyncIteration",
+    "StopIteration",
+    "SyntaxError",
+    "SystemError",
+    "SystemExit",
+    "TabError",
+    "TimeoutError",
+    "TypeError",
+    "UnboundLocalError",
+    "UnicodeDecodeError",
+    "UnicodeEncodeError",
+    "UnicodeError",
+    "UnicodeTranslateError",
+    "ValueError",
+    "ZeroDivisionError",
+    "Warning",
+    "BytesWarning",
+    "DeprecationWarning",
+    "EncodingWarning",
+    "FutureWarning",
+    "ImportWarning",
+    "PendingDeprecationWarning",
+    "ResourceWarning",
+    "RuntimeWarning",
+    "SyntaxWarning",
+    "UnicodeWarning",
+    "UserWarning",
+];
+
+pub fn is_builtin_name(name: &str) -> bool {
+    BUILTIN_FUNCTIONS.contains(&name)
+        || BUILTIN_CLASSES.contains(&name)
+        || BUILTIN_CONSTANTS.contains(&name)
+        || BUILTIN_EXCEPTIONS.contains(&name)
+}
+
+pub fn is_builtin_function(name: &str) -> bool {
+    BUILTIN_FUNCTIONS.contains(&name)
+}
+
+pub fn is_builtin_class(name: &str) -> bool {
+    BUILTIN_CLASSES.contains(&name) || BUILTIN_EXCEPTIONS.contains(&name)
+}
+
+/// The type a builtin name refers to when it is not shadowed
+pub fn builtin_name_type(name: &str) -> PyType {
+    match name {
+        "True" | "False" | "__debug__" => PyType::Bool,
+        "None" => PyType::None,
+        "Ellipsis" => PyType::Ellipsis,
+        "__name__" | "__file__" | "__package__" => PyType::Str,
+        "__doc__" => PyType::union(vec![PyType::Str, PyType::None]),
+        _ if is_builtin_class(name) => PyType::Class(name.to_string()),
+        _ => PyType::Unknown,
+    }
+}
+
+/// The return type of calling the builtin function `name`
+pub fn builtin_call_type(name: &str, args: &[PyType]) -> PyType {
+    let first = args.first().cloned().unwrap_or(PyType::Unknown);
+    match name {
+        "len" | "hash" | "id" | "ord" => PyType::Int,
+        "repr" | "ascii" | "bin" | "hex" | "oct" | "chr" | "format" | "input" => PyType::Str,
+        "callable" | "isinstance" | "issubclass" | "hasattr" | "all" | "any" => PyType::Bool,
+        "print" | "setattr" | "delattr" | "exec" | "breakpoint" | "help" => PyType::None,
+        "abs" => match first {
+            PyType::Bool => PyType::Int,
+            PyType::Complex => PyType::Float,
+            ty => ty,
+        },
+        "round" if args.len() <= 1 => PyType::Int,
+        "round" => first,
+        "sorted" => match first {
+            PyType::List(element) | PyType::Set(element) => PyType::List(element),
+            PyType::Str => PyType::List(Box::new(PyType::Str)),
+            _ => PyType::List(Box::new(PyType::Unknown)),
+        },
+        "divmod" => PyType::Tuple(vec![first.clone(), first]),
+        "dir" => PyType::List(Box::new(PyType::Str)),
+        "globals" | "locals" | "vars" => PyType::Dict(Box::new(PyType::Str), Box::new(PyType::Any)),
+        "min" | "max" if args.len() == 1 => match first {
+            PyType::List(element) | PyType::Set(element) => *element,
+            PyType::Str => PyType::Str,
+            _ => PyType::Unknown,
+        },
+        "min" | "max" => PyType::union(args.to_vec()),
+        "sum" => match first {
+            PyType::List(element) | PyType::Set(element) if element.is_numeric() => *element,
+            _ => PyType::Int,
+        },
+        _ => PyType::Unknown,
+    }
+}
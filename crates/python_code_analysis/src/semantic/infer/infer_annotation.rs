@@ -0,0 +1,124 @@
+use python_parser::{PyAstNode, PyExpr, PyLiteralToken};
+
+use crate::semantic::{PyType, SemanticModel};
+
+/// Evaluate a type annotation expression into a type
+pub fn infer_annotation(model: &SemanticModel, expr: &PyExpr) -> PyType {
+    match expr {
+        PyExpr::NameExpr(name_expr) => {
+            let Some(name) = name_expr.get_name_token() else {
+                return PyType::Unknown;
+            };
+            let name = name.get_name_text();
+            if let Some(decl) = model.find_decl(name, name_expr.get_position())
+                && decl.is_class()
+            {
+                return PyType::Instance(name.to_string());
+            }
+            infer_annotation_name(name).unwrap_or_else(|| PyType::Annotation(name.to_string()))
+        }
+        PyExpr::AttributeExpr(attribute) => match attribute.get_attr() {
+            Some(attr) if is_typing_module(attribute.get_value()) => {
+                infer_annotation_name(&attr).unwrap_or(PyType::Annotation(attr))
+            }
+            _ => PyType::Annotation(expr.get_text()),
+        },
+        PyExpr::LiteralExpr(literal) => match literal.get_literal() {
+            Some(PyLiteralToken::None(_)) => PyType::None,
+            // forward reference, kept as written without the quotes
+            Some(PyLiteralToken::String(string)) => PyType::Annotation(string.get_value()),
+            _ => PyType::Annotation(expr.get_text()),
+        },
+        PyExpr::BinaryExpr(binary) if binary.get_op().as_deref() == Some("|") => {
+            let left = binary.get_left().map(|it| infer_annotation(model, &it));
+            let right = binary.get_right().map(|it| infer_annotation(model, &it));
+            PyType::union(left.into_iter().chain(right).collect())
+        }
+        PyExpr::ParenExpr(paren) => match paren.child::<PyExpr>() {
+            Some(inner) => infer_annotation(model, &inner),
+            None => PyType::Annotation(expr.get_text()),
+        },
+        PyExpr::SubscriptExpr(subscript) => {
+            let Some(value) = subscript.get_value() else {
+                return PyType::Unknown;
+            };
+            let name = match &value {
+                PyExpr::NameExpr(name_expr) => name_expr
+                    .get_name_token()
+                    .map(|name| name.get_name_text().to_string()),
+                PyExpr::AttributeExpr(attribute) if is_typing_module(attribute.get_value()) => {
+                    attribute.get_attr()
+                }
+                _ => None,
+            };
+            let args: Vec<PyExpr> = match subscript.get_slice() {
+                Some(PyExpr::TupleExpr(tuple)) => tuple.get_exprs().collect(),
+                Some(slice) => vec![slice],
+                None => Vec::new(),
+            };
+            let arg = |index: usize| {
+                args.get(index)
+                    .map(|it| infer_annotation(model, it))
+                    .unwrap_or(PyType::Unknown)
+            };
+            match name.as_deref() {
+                Some("list" | "List" | "Sequence" | "MutableSequence") => {
+                    PyType::List(Box::new(arg(0)))
+                }
+                Some("set" | "Set" | "frozenset" | "FrozenSet" | "AbstractSet") => {
+                    PyType::Set(Box::new(arg(0)))
+                }
+                Some("dict" | "Dict" | "Mapping" | "MutableMapping") => {
+                    let key = arg(0);
+                    PyType::Dict(Box::new(key), Box::new(arg(1)))
+                }
+                Some("tuple" | "Tuple") if !has_ellipsis(&args) => {
+                    PyType::Tuple(args.iter().map(|it| infer_annotation(model, it)).collect())
+                }
+                Some("type" | "Type") => match arg(0) {
+                    PyType::Instance(name) => PyType::Class(name),
+                    _ => PyType::Annotation(expr.get_text()),
+                },
+                Some("Optional") => PyType::union(vec![arg(0), PyType::None]),
+                Some("Union") => {
+                    PyType::union(args.iter().map(|it| infer_annotation(model, it)).collect())
+                }
+                _ => PyType::Annotation(expr.get_text()),
+            }
+        }
+        _ => PyType::Annotation(expr.get_text()),
+    }
+}
+
+fn infer_annotation_name(name: &str) -> Option<PyType> {
+    if let Some(ty) = PyType::from_builtin_name(name) {
+        return Some(ty);
+    }
+    let ty = match name {
+        "list" | "List" => PyType::List(Box::new(PyType::Unknown)),
+        "set" | "Set" => PyType::Set(Box::new(PyType::Unknown)),
+        "dict" | "Dict" => PyType::Dict(Box::new(PyType::Unknown), Box::new(PyType::Unknown)),
+        _ => return None,
+    };
+    Some(ty)
+}
+
+fn is_typing_module(value: Option<PyExpr>) -> bool {
+    match value {
+        Some(PyExpr::NameExpr(name_expr)) => name_expr.get_name_token().is_some_and(|name| {
+            matches!(
+                name.get_name_text(),
+                "typing" | "t" | "typing_extensions" | "collections"
+            )
+        }),
+        Some(PyExpr::AttributeExpr(attribute)) => attribute.get_text() == "collections.abc",
+        _ => false,
+    }
+}
+
+fn has_ellipsis(args: &[PyExpr]) -> bool {
+    args.iter().any(|arg| {
+        matches!(arg, PyExpr::LiteralExpr(literal)
+            if matches!(literal.get_literal(), Some(PyLiteralToken::Ellipsis(_))))
+    })
+}
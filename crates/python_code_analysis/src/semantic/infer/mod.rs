@@ -0,0 +1,670 @@
+mod infer_annotation;
+
+use std::sync::Arc;
+
+pub use infer_annotation::infer_annotation;
+use python_parser::{
+    PyAstNode, PyAstToken, PyClassDef, PyExpr, PyFuncDef, PyLiteralToken, PyParameter,
+    PySyntaxElement, PySyntaxId, PySyntaxKind, PySyntaxNode, PyTokenKind,
+};
+use rowan::{TextRange, TextSize};
+
+use super::{
+    PySignature, PyType, SemanticModel,
+    builtins::{builtin_call_type, builtin_name_type, is_builtin_function},
+    resolve_module,
+};
+use crate::{
+    db_index::{DeclId, PyDecl, PyDeclKind, PyScopeKind},
+    module_types,
+};
+
+const MAX_INFER_DEPTH: usize = 64;
+
+/// Carries the recursion guards of a single inference request
+pub(crate) struct InferContext<'a, 'b> {
+    model: &'b SemanticModel<'a>,
+    depth: usize,
+    visiting_decls: Vec<DeclId>,
+    visiting_funcs: Vec<PySyntaxId>,
+}
+
+impl<'a, 'b> InferContext<'a, 'b> {
+    pub fn new(model: &'b SemanticModel<'a>) -> Self {
+        InferContext {
+            model,
+            depth: 0,
+            visiting_decls: Vec::new(),
+            visiting_funcs: Vec::new(),
+        }
+    }
+
+    pub fn infer_expr(&mut self, expr: &PyExpr) -> PyType {
+        if self.depth >= MAX_INFER_DEPTH {
+            return PyType::Unknown;
+        }
+        self.depth += 1;
+        let ty = self.infer_expr_inner(expr);
+        self.depth -= 1;
+        ty
+    }
+
+    pub fn infer_decl(&mut self, decl: &PyDecl) -> PyType {
+        if self.visiting_decls.contains(&decl.get_id()) || self.depth >= MAX_INFER_DEPTH {
+            return PyType::Unknown;
+        }
+        self.visiting_decls.push(decl.get_id());
+        self.depth += 1;
+        let ty = self.infer_decl_inner(decl);
+        self.depth -= 1;
+        self.visiting_decls.pop();
+        ty
+    }
+
+    /// The declared return type, or the union of every `return` in the body
+    pub fn infer_return_type(&mut self, func: &PyFuncDef) -> PyType {
+        let syntax_id = func.get_syntax_id();
+        if self.visiting_funcs.contains(&syntax_id) {
+            return PyType::Unknown;
+        }
+        let annotation = func
+            .get_return_annotation()
+            .or_else(|| func.get_type_comment()?.get_return_type());
+        if let Some(annotation) = annotation {
+            return infer_annotation(self.model, &annotation);
+        }
+
+        self.visiting_funcs.push(syntax_id);
+        let mut types = Vec::new();
+        let mut falls_through = true;
+        if let Some(body) = func.get_body() {
+            for node in function_returns(body.syntax()) {
+                let value = node.children().find_map(PyExpr::cast);
+                types.push(match value {
+                    Some(value) => self.infer_expr(&value),
+                    None => PyType::None,
+                });
+            }
+            // a trailing return means the function can not fall off the end
+            falls_through = body
+                .syntax()
+                .children()
+                .filter(|child| child.kind() != PySyntaxKind::Docstring.into())
+                .last()
+                .is_none_or(|last| last.kind() != PySyntaxKind::ReturnStmt.into());
+        }
+        if falls_through {
+            types.push(PyType::None);
+        }
+        self.visiting_funcs.pop();
+        PyType::union(types)
+    }
+
+    fn infer_expr_inner(&mut self, expr: &PyExpr) -> PyType {
+        match expr {
+            PyExpr::LiteralExpr(literal) => match literal.get_literal() {
+                Some(token) => infer_literal(&token),
+                None => PyType::Unknown,
+            },
+            PyExpr::NameExpr(name_expr) => {
+                let Some(name) = name_expr.get_name_token() else {
+                    return PyType::Unknown;
+                };
+                self.infer_name(name.get_name_text(), name_expr.get_position())
+            }
+            PyExpr::ParenExpr(paren) => match paren.child::<PyExpr>() {
+                Some(inner) => self.infer_expr(&inner),
+                None => PyType::Tuple(Vec::new()),
+            },
+            PyExpr::TupleExpr(tuple) => {
+                PyType::Tuple(tuple.get_exprs().map(|it| self.infer_expr(&it)).collect())
+            }
+            PyExpr::ListExpr(list) => {
+                let element = self.infer_elements(list.get_exprs());
+                PyType::List(Box::new(element))
+            }
+            PyExpr::SetExpr(set) => {
+                let element = self.infer_elements(set.get_exprs());
+                PyType::Set(Box::new(element))
+            }
+            PyExpr::DictExpr(dict) => {
+                let mut keys = Vec::new();
+                let mut values = Vec::new();
+                for (key, value) in dict.get_key_values() {
+                    if let Some(key) = key {
+                        keys.push(self.infer_expr(&key));
+                    }
+                    if let Some(value) = value {
+                        values.push(self.infer_expr(&value));
+                    }
+                }
+                PyType::Dict(
+                    Box::new(PyType::union(keys)),
+                    Box::new(PyType::union(values)),
+                )
+            }
+            PyExpr::ListCompExpr(comp) => {
+                let element = comp.get_element().map(|it| self.infer_expr(&it));
+                PyType::List(Box::new(element.unwrap_or(PyType::Unknown)))
+            }
+            PyExpr::SetCompExpr(comp) => {
+                let element = comp.get_element().map(|it| self.infer_expr(&it));
+                PyType::Set(Box::new(element.unwrap_or(PyType::Unknown)))
+            }
+            PyExpr::DictCompExpr(comp) => {
+                let key = comp.get_key().map(|it| self.infer_expr(&it));
+                let value = comp.get_value().map(|it| self.infer_expr(&it));
+                PyType::Dict(
+                    Box::new(key.unwrap_or(PyType::Unknown)),
+                    Box::new(value.unwrap_or(PyType::Unknown)),
+                )
+            }
+            PyExpr::GeneratorExpr(generator) => {
+                let element = generator
+                    .get_expr()
+                    .map(|it| self.infer_expr(&it))
+                    .unwrap_or(PyType::Unknown);
+                PyType::Annotation(format!(
+                    "Generator[{}, None, None]",
+                    super::humanize_type(&element)
+                ))
+            }
+            PyExpr::BinaryExpr(binary) => {
+                let op = binary.get_op().unwrap_or_default();
+                let left = binary.get_left().map(|it| self.infer_expr(&it));
+                let right = binary.get_right().map(|it| self.infer_expr(&it));
+                infer_binary(
+                    &op,
+                    left.unwrap_or(PyType::Unknown),
+                    right.unwrap_or(PyType::Unknown),
+                )
+            }
+            PyExpr::BoolOpExpr(bool_op) => {
+                let types = bool_op
+                    .get_values()
+                    .map(|it| self.infer_expr(&it))
+                    .collect();
+                PyType::union(types)
+            }
+            PyExpr::CompareExpr(_) => PyType::Bool,
+            PyExpr::UnaryExpr(unary) => {
+                let op = unary.get_op().unwrap_or_default();
+                if op == "not" {
+                    return PyType::Bool;
+                }
+                match unary.get_operand().map(|it| self.infer_expr(&it)) {
+                    Some(PyType::Bool) => PyType::Int,
+                    Some(ty) if ty.is_numeric() => ty,
+                    _ => PyType::Unknown,
+                }
+            }
+            PyExpr::CallExpr(call) => {
+                let Some(func) = call.get_func() else {
+                    return PyType::Unknown;
+                };
+                if let PyExpr::NameExpr(name_expr) = &func
+                    && let Some(name) = name_expr.get_name_token()
+                    && is_builtin_function(name.get_name_text())
+                    && self
+                        .model
+                        .find_decl(name.get_name_text(), name_expr.get_position())
+                        .is_none()
+                {
+                    let args: Vec<PyType> =
+                        call.get_args().map(|it| self.infer_expr(&it)).collect();
+                    return builtin_call_type(name.get_name_text(), &args);
+                }
+
+                let callee = self.infer_expr(&func);
+                self.infer_call(&callee)
+            }
+            PyExpr::MethodCallExpr(_) => PyType::Unknown,
+            PyExpr::AwaitExpr(await_expr) => {
+                match await_expr.get_value().map(|it| self.infer_expr(&it)) {
+                    Some(PyType::Coroutine(ret)) => *ret,
+                    _ => PyType::Unknown,
+                }
+            }
+            PyExpr::AttributeExpr(attribute) => {
+                let (Some(value), Some(attr)) = (attribute.get_value(), attribute.get_attr())
+                else {
+                    return PyType::Unknown;
+                };
+                let owner = self.infer_expr(&value);
+                self.infer_member(&owner, &attr)
+            }
+            PyExpr::SubscriptExpr(subscript) => {
+                let value = subscript.get_value().map(|it| self.infer_expr(&it));
+                let Some(value) = value else {
+                    return PyType::Unknown;
+                };
+                let slice = subscript.get_slice();
+                infer_subscript(value, slice.as_ref())
+            }
+            PyExpr::IfExpr(if_expr) => {
+                let body = if_expr.get_body().map(|it| self.infer_expr(&it));
+                let orelse = if_expr.get_orelse().map(|it| self.infer_expr(&it));
+                PyType::union(body.into_iter().chain(orelse).collect())
+            }
+            PyExpr::ConditionalExpr(conditional) => {
+                let body = conditional.get_body().map(|it| self.infer_expr(&it));
+                let orelse = conditional.get_orelse().map(|it| self.infer_expr(&it));
+                PyType::union(body.into_iter().chain(orelse).collect())
+            }
+            PyExpr::LambdaExpr(lambda) => {
+                PyType::Function(Arc::new(PySignature::from_lambda(lambda)))
+            }
+            PyExpr::AssignExpr(assign) => match assign.get_value() {
+                Some(value) => self.infer_expr(&value),
+                None => PyType::Unknown,
+            },
+            PyExpr::FStringExpr(_) => PyType::Str,
+            PyExpr::TStringExpr(_) => PyType::Instance("Template".to_string()),
+            PyExpr::SliceExpr(_)
+            | PyExpr::YieldExpr(_)
+            | PyExpr::YieldFromExpr(_)
+            | PyExpr::StarredExpr(_)
+            | PyExpr::DoubleStarredExpr(_)
+            | PyExpr::MagicExpr(_) => PyType::Unknown,
+        }
+    }
+
+    fn infer_elements(&mut self, exprs: impl Iterator<Item = PyExpr>) -> PyType {
+        let types = exprs.map(|it| self.infer_expr(&it)).collect();
+        PyType::union(types)
+    }
+
+    fn infer_name(&mut self, name: &str, position: TextSize) -> PyType {
+        match self.model.find_decl(name, position) {
+            Some(decl) => self.infer_decl(decl),
+            None => builtin_name_type(name),
+        }
+    }
+
+    fn infer_call(&mut self, callee: &PyType) -> PyType {
+        match callee {
+            PyType::Function(signature) => self.infer_signature_return(signature),
+            PyType::Class(name) => match PyType::from_builtin_name(name) {
+                Some(ty) => ty,
+                None => match name.as_str() {
+                    "list" => PyType::List(Box::new(PyType::Unknown)),
+                    "set" => PyType::Set(Box::new(PyType::Unknown)),
+                    "dict" => PyType::Dict(Box::new(PyType::Unknown), Box::new(PyType::Unknown)),
+                    "tuple" => PyType::Tuple(Vec::new()),
+                    _ => PyType::Instance(name.clone()),
+                },
+            },
+            _ => PyType::Unknown,
+        }
+    }
+
+    pub fn infer_signature_return(&mut self, signature: &PySignature) -> PyType {
+        if let Some(return_type) = &signature.return_type {
+            return return_type.clone();
+        }
+        let Some(node) = signature
+            .syntax_id
+            .and_then(|syntax_id| self.model.get_node(syntax_id))
+        else {
+            return PyType::Unknown;
+        };
+        if let Some(func) = PyFuncDef::cast(node.clone()) {
+            let ret = self.infer_return_type(&func);
+            if func.is_async() {
+                return PyType::Coroutine(Box::new(ret));
+            }
+            return ret;
+        }
+        if let Some(class) = PyClassDef::cast(node.clone()) {
+            return match class.get_name() {
+                Some(name) => PyType::Instance(name.get_name_text().to_string()),
+                None => PyType::Unknown,
+            };
+        }
+        if let Some(lambda) = python_parser::PyLambdaExpr::cast(node)
+            && let Some(body) = lambda.get_body()
+        {
+            return self.infer_expr(&body);
+        }
+        PyType::Unknown
+    }
+
+    fn infer_member(&mut self, owner: &PyType, attr: &str) -> PyType {
+        let (class_name, bound) = match owner {
+            PyType::Instance(name) => (name, true),
+            PyType::Class(name) => (name, false),
+            _ => return PyType::Unknown,
+        };
+        let Some(decl) = self.model.find_class_member(class_name, attr) else {
+            return PyType::Unknown;
+        };
+        match self.infer_decl(decl) {
+            PyType::Function(signature) if bound && signature.is_property() => {
+                self.infer_signature_return(&signature)
+            }
+            // a classmethod gets the class whether reached through it or an instance
+            PyType::Function(signature) if bound || signature.is_classmethod() => {
+                let mut signature = (*signature).clone();
+                signature.bind_self();
+                PyType::Function(Arc::new(signature))
+            }
+            ty => ty,
+        }
+    }
+
+    fn infer_decl_inner(&mut self, decl: &PyDecl) -> PyType {
+        let node = decl
+            .get_syntax_id()
+            .and_then(|syntax_id| self.model.get_node(syntax_id));
+        match decl.get_kind() {
+            PyDeclKind::Function => match node.and_then(PyFuncDef::cast) {
+                Some(func) => PyType::Function(Arc::new(PySignature::from_func_def(&func))),
+                None => PyType::Unknown,
+            },
+            PyDeclKind::Class => PyType::Class(decl.get_name().to_string()),
+            PyDeclKind::Import { module } => {
+                // `import a.b` binds the package `a`
+                let prefix = format!("{}.", decl.get_name());
+                if module.starts_with(&prefix) {
+                    PyType::Module(decl.get_name().to_string())
+                } else {
+                    PyType::Module(module.clone())
+                }
+            }
+            PyDeclKind::ImportFrom { module, name } => {
+                let db = self.model.get_db();
+                resolve_module(db, self.model.get_file_id(), module)
+                    .and_then(|file_id| module_types(db, file_id).ok())
+                    .and_then(|types| {
+                        types
+                            .iter()
+                            .find(|(export, _)| export == name)
+                            .map(|(_, ty)| ty.clone())
+                    })
+                    .unwrap_or(PyType::Unknown)
+            }
+            PyDeclKind::Parameter => match node.and_then(PyParameter::cast) {
+                Some(param) => self.infer_param(decl, &param),
+                None => match self.infer_value(decl) {
+                    Some(ty) => ty,
+                    None => PyType::Unknown,
+                },
+            },
+            PyDeclKind::Variable => {
+                if let Some(annotation) = self.get_expr(decl.get_annotation()) {
+                    return infer_annotation(self.model, &annotation);
+                }
+                if let Some(ty) = self.infer_value(decl) {
+                    return ty;
+                }
+                match node {
+                    Some(node) => match loop_iter_for_target(&node, decl.get_range()) {
+                        Some(iter) => {
+                            let iter_type = self.infer_expr(&iter);
+                            iter_element_type(&iter_type)
+                        }
+                        None => PyType::Unknown,
+                    },
+                    None => PyType::Unknown,
+                }
+            }
+        }
+    }
+
+    fn infer_value(&mut self, decl: &PyDecl) -> Option<PyType> {
+        let value = self.get_expr(decl.get_value())?;
+        Some(self.infer_expr(&value))
+    }
+
+    fn infer_param(&mut self, decl: &PyDecl, param: &PyParameter) -> PyType {
+        let annotation = param
+            .get_annotation()
+            .or_else(|| param.get_type_comment_annotation());
+        let declared = match annotation {
+            Some(annotation) => Some(infer_annotation(self.model, &annotation)),
+            None => None,
+        };
+
+        if param.is_vararg() {
+            let element = declared.unwrap_or(PyType::Unknown);
+            return PyType::Annotation(format!("tuple[{}, ...]", super::humanize_type(&element)));
+        }
+        if param.is_kwarg() {
+            let value = declared.unwrap_or(PyType::Unknown);
+            return PyType::Dict(Box::new(PyType::Str), Box::new(value));
+        }
+        if let Some(declared) = declared {
+            return declared;
+        }
+
+        if let Some(ty) = self.method_receiver_type(decl, param) {
+            return ty;
+        }
+        self.infer_value(decl).unwrap_or(PyType::Unknown)
+    }
+
+    /// `self` and `cls`: the first parameter of a method defined in a class body
+    fn method_receiver_type(&self, decl: &PyDecl, param: &PyParameter) -> Option<PyType> {
+        let params = param.get_parent::<python_parser::PyArguments>()?;
+        if params.get_args().next()?.get_syntax_id() != param.get_syntax_id() {
+            return None;
+        }
+        let func = params.get_parent::<PyFuncDef>()?;
+        let decl_tree = self.model.get_decl_tree();
+        let func_scope = decl_tree.get_scope(decl.get_scope())?;
+        let class_scope = decl_tree.get_scope(func_scope.get_parent()?)?;
+        if class_scope.get_kind() != PyScopeKind::Class {
+            return None;
+        }
+        let class = func
+            .syntax()
+            .ancestors()
+            .find_map(PyClassDef::cast)?
+            .get_name()?
+            .get_name_text()
+            .to_string();
+
+        let signature = PySignature::from_func_def(&func);
+        if signature.is_staticmethod() {
+            None
+        } else if signature.is_classmethod()
+            || signature.name == "__new__"
+            || signature.name == "__init_subclass__"
+        {
+            Some(PyType::Class(class))
+        } else {
+            Some(PyType::Instance(class))
+        }
+    }
+
+    fn get_expr(&self, syntax_id: Option<PySyntaxId>) -> Option<PyExpr> {
+        self.model.get_node(syntax_id?).and_then(PyExpr::cast)
+    }
+}
+
+pub fn infer_literal(token: &PyLiteralToken) -> PyType {
+    match token {
+        PyLiteralToken::String(string) => match string.get_token_kind() {
+            PyTokenKind::TkBytesString => PyType::Bytes,
+            _ => PyType::Str,
+        },
+        PyLiteralToken::Number(number) => match number.get_token_kind() {
+            PyTokenKind::TkFloat => PyType::Float,
+            PyTokenKind::TkComplex => PyType::Complex,
+            _ => PyType::Int,
+        },
+        PyLiteralToken::Bool(_) => PyType::Bool,
+        PyLiteralToken::None(_) => PyType::None,
+        PyLiteralToken::Ellipsis(_) => PyType::Ellipsis,
+    }
+}
+
+fn infer_binary(op: &str, left: PyType, right: PyType) -> PyType {
+    match op {
+        "<" | ">" | "<=" | ">=" | "==" | "!=" | "in" | "not" | "is" => PyType::Bool,
+        "and" | "or" => PyType::union(vec![left, right]),
+        _ if left.is_numeric() && right.is_numeric() => {
+            let rank = |ty: &PyType| match ty {
+                PyType::Complex => 3,
+                PyType::Float => 2,
+                _ => 1,
+            };
+            let widest = if rank(&left) >= rank(&right) {
+                left
+            } else {
+                right
+            };
+            match (op, widest) {
+                ("/", PyType::Int | PyType::Bool) => PyType::Float,
+                (_, PyType::Bool) => PyType::Int,
+                (_, ty) => ty,
+            }
+        }
+        "+" => match (left, right) {
+            (PyType::Str, PyType::Str) => PyType::Str,
+            (PyType::Bytes, PyType::Bytes) => PyType::Bytes,
+            (PyType::List(a), PyType::List(b)) => {
+                PyType::List(Box::new(PyType::union(vec![*a, *b])))
+            }
+            (PyType::Tuple(mut a), PyType::Tuple(b)) => {
+                a.extend(b);
+                PyType::Tuple(a)
+            }
+            _ => PyType::Unknown,
+        },
+        "*" => match (left, right) {
+            (ty @ (PyType::Str | PyType::Bytes | PyType::List(_)), PyType::Int)
+            | (PyType::Int, ty @ (PyType::Str | PyType::Bytes | PyType::List(_))) => ty,
+            _ => PyType::Unknown,
+        },
+        "%" => match left {
+            PyType::Str => PyType::Str,
+            PyType::Bytes => PyType::Bytes,
+            _ => PyType::Unknown,
+        },
+        "|" | "&" | "-" | "^" => match (left, right) {
+            (PyType::Set(a), PyType::Set(b)) => PyType::Set(Box::new(PyType::union(vec![*a, *b]))),
+            (PyType::Dict(k, v), PyType::Dict(_, _)) if op == "|" => PyType::Dict(k, v),
+            _ => PyType::Unknown,
+        },
+        _ => PyType::Unknown,
+    }
+}
+
+fn infer_subscript(value: PyType, slice: Option<&PyExpr>) -> PyType {
+    let is_slice = matches!(slice, Some(PyExpr::SliceExpr(_)));
+    match value {
+        PyType::Str | PyType::Bytes => value,
+        PyType::List(_) if is_slice => value,
+        PyType::List(element) => *element,
+        PyType::Dict(_, value) => *value,
+        PyType::Tuple(_) if is_slice => PyType::Unknown,
+        PyType::Tuple(elements) => {
+            let index = match slice {
+                Some(PyExpr::LiteralExpr(literal)) => {
+                    literal.get_literal().and_then(|token| match token {
+                        PyLiteralToken::Number(number) => number.get_int_value().as_i64(),
+                        _ => None,
+                    })
+                }
+                Some(PyExpr::UnaryExpr(unary)) if unary.get_op().as_deref() == Some("-") => {
+                    match unary.get_operand() {
+                        Some(PyExpr::LiteralExpr(literal)) => {
+                            literal.get_literal().and_then(|token| match token {
+                                PyLiteralToken::Number(number) => {
+                                    number.get_int_value().as_i64().map(|it| -it)
+                                }
+                                _ => None,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match index {
+                Some(index) => {
+                    let len = elements.len() as i64;
+                    let index = if index < 0 { len + index } else { index };
+                    if (0..len).contains(&index) {
+                        elements[index as usize].clone()
+                    } else {
+                        PyType::Unknown
+                    }
+                }
+                None => PyType::union(elements),
+            }
+        }
+        _ => PyType::Unknown,
+    }
+}
+
+/// The type produced by iterating over a value of type `ty`
+pub fn iter_element_type(ty: &PyType) -> PyType {
+    match ty {
+        PyType::List(element) | PyType::Set(element) => (**element).clone(),
+        PyType::Dict(key, _) => (**key).clone(),
+        PyType::Str => PyType::Str,
+        PyType::Bytes => PyType::Int,
+        PyType::Tuple(elements) => PyType::union(elements.clone()),
+        PyType::Instance(name) if name == "range" => PyType::Int,
+        _ => PyType::Unknown,
+    }
+}
+
+/// The iterable of a `for` loop or comprehension clause whose only target is
+/// the name at `target_range`
+fn loop_iter_for_target(node: &PySyntaxNode, target_range: TextRange) -> Option<PyExpr> {
+    let mut names = 0;
+    let mut found = false;
+    let mut after_in = false;
+    for element in node.children_with_tokens() {
+        match element {
+            PySyntaxElement::Token(token) => match token.kind().into() {
+                PyTokenKind::TkFor => {
+                    names = 0;
+                    found = false;
+                }
+                PyTokenKind::TkName if !after_in => {
+                    names += 1;
+                    found |= token.text_range() == target_range;
+                }
+                PyTokenKind::TkIn if found => after_in = true,
+                _ => {}
+            },
+            PySyntaxElement::Node(child) => {
+                if after_in {
+                    return if names == 1 {
+                        PyExpr::cast(child)
+                    } else {
+                        None
+                    };
+                }
+                if found {
+                    return None;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Every `return` statement of a function body, skipping nested scopes
+fn function_returns(body: &PySyntaxNode) -> Vec<PySyntaxNode> {
+    let mut result = Vec::new();
+    let mut stack = vec![body.clone()];
+    while let Some(node) = stack.pop() {
+        for child in node.children() {
+            match child.kind().into() {
+                PySyntaxKind::ReturnStmt => result.push(child),
+                PySyntaxKind::FuncDef
+                | PySyntaxKind::AsyncFuncDef
+                | PySyntaxKind::ClassDef
+                | PySyntaxKind::LambdaExpr => {}
+                _ => stack.push(child),
+            }
+        }
+    }
+    result.sort_by_key(|node| node.text_range().start());
+    result
+}
@@ -0,0 +1,277 @@
+mod builtins;
+mod infer;
+mod resolve_module;
+mod signature;
+mod r#type;
+
+pub use builtins::{builtin_name_type, is_builtin_class, is_builtin_function, is_builtin_name};
+use infer::InferContext;
+pub use infer::{infer_literal, iter_element_type};
+pub(crate) use resolve_module::{module_name, resolve_module};
+use std::sync::Arc;
+
+use python_parser::{
+    PyAstNode, PyAstToken, PyClassDef, PyDocstring, PyExpr, PyFuncDef, PyKeyword, PyModule,
+    PyNameExpr, PySyntaxId, PySyntaxNode, PySyntaxTree,
+};
+use rowan::TextSize;
+pub use signature::{PyParamKind, PySignature, PySignatureParam};
+pub use r#type::{PyType, humanize_type};
+
+use crate::{
+    FileId, PyDocExample,
+    db_index::{DbIndex, PyDecl, PyDeclTree, PyScopeKind, ScopeId},
+    extract_doc_examples,
+    query::{file_decl_tree, file_line_index, file_syntax_tree},
+    vfs::PyDocument,
+};
+
+/// A per file view over the index used by the language features
+pub struct SemanticModel<'a> {
+    file_id: FileId,
+    db: &'a DbIndex,
+    document: PyDocument<'a>,
+    tree: Arc<PySyntaxTree>,
+    root: PyModule,
+    decl_tree: Arc<PyDeclTree>,
+}
+
+impl<'a> SemanticModel<'a> {
+    /// `None` when the file is not loaded or a change cancelled the queries
+    /// the model is made of
+    pub fn new(file_id: FileId, db: &'a DbIndex) -> Option<Self> {
+        let text = db.get_vfs().get_file_content(&file_id)?;
+        let line_index = file_line_index(db, file_id).ok()??;
+        let tree = file_syntax_tree(db, file_id).ok()??;
+        let decl_tree = file_decl_tree(db, file_id).ok()??;
+        Some(SemanticModel {
+            file_id,
+            db,
+            document: PyDocument::new(file_id, text, line_index),
+            root: tree.get_module_node(),
+            tree,
+            decl_tree,
+        })
+    }
+
+    pub fn get_file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    pub fn get_db(&self) -> &'a DbIndex {
+        self.db
+    }
+
+    pub fn get_document(&self) -> &PyDocument<'a> {
+        &self.document
+    }
+
+    pub fn get_syntax_tree(&self) -> &PySyntaxTree {
+        &self.tree
+    }
+
+    pub fn get_root(&self) -> &PyModule {
+        &self.root
+    }
+
+    pub fn get_decl_tree(&self) -> &PyDeclTree {
+        &self.decl_tree
+    }
+
+    pub fn get_node(&self, syntax_id: PySyntaxId) -> Option<PySyntaxNode> {
+        syntax_id.to_node_from_root(self.root.syntax())
+    }
+
+    /// The declaration `name` refers to at `position`
+    pub fn find_decl(&self, name: &str, position: TextSize) -> Option<&PyDecl> {
+        self.decl_tree.find_decl(name, position)
+    }
+
+    /// A member declared in the body of the module level class `class_name`
+    pub fn find_class_member(&self, class_name: &str, member: &str) -> Option<&PyDecl> {
+        let class = self.find_class_def(class_name)?;
+        let body = class.get_body()?;
+        let scope = self.decl_tree.find_scope(body.get_position())?;
+        if scope.get_kind() != PyScopeKind::Class {
+            return None;
+        }
+        self.decl_tree
+            .get_scope_decls_by_name(scope.get_id(), member)
+            .last()
+            .copied()
+    }
+
+    /// The declaration a docstring cross reference such as `Greeter.greet`
+    /// names, looked up from `position` and then in the module
+    pub fn find_doc_reference(&self, target: &str, position: TextSize) -> Option<&PyDecl> {
+        let parts: Vec<&str> = target.split('.').collect();
+        let name = parts.last()?;
+        if parts.len() >= 2
+            && let Some(member) = self.find_class_member(parts[parts.len() - 2], name)
+        {
+            return Some(member);
+        }
+        self.find_decl(name, position).or_else(|| {
+            let root_scope = self.decl_tree.get_root_scope()?;
+            self.decl_tree
+                .get_scope_decls_by_name(root_scope.get_id(), name)
+                .last()
+                .copied()
+        })
+    }
+
+    /// The docstring example whose code contains `offset`, for the features
+    /// which work inside examples as they do in the file
+    pub fn find_doc_example(&self, offset: TextSize) -> Option<PyDocExample> {
+        let docstring = self
+            .root
+            .descendants::<PyDocstring>()
+            .find(|docstring| docstring.syntax().text_range().contains(offset))?;
+        let config = self.db.get_vfs().get_parser_config();
+        extract_doc_examples(&docstring, config)
+            .into_iter()
+            .find(|example| example.to_example_offset(offset).is_some())
+    }
+
+    /// Whether any name expression in the file resolves to `decl`
+    pub fn is_decl_referenced(&self, decl: &PyDecl) -> bool {
+        self.root
+            .descendants::<PyNameExpr>()
+            .filter(|name_expr| !is_keyword_name(name_expr))
+            .filter_map(|name_expr| name_expr.get_name_token())
+            .filter(|name| name.get_name_text() == decl.get_name())
+            .filter(|name| name.get_range() != decl.get_range())
+            .any(|name| {
+                self.find_decl(decl.get_name(), name.get_position())
+                    .is_some_and(|found| found.get_id() == decl.get_id())
+            })
+    }
+
+    pub fn find_class_def(&self, class_name: &str) -> Option<PyClassDef> {
+        let root_scope = self.decl_tree.get_root_scope()?;
+        let decl = self
+            .decl_tree
+            .get_scope_decls_by_name(root_scope.get_id(), class_name)
+            .into_iter()
+            .rev()
+            .find(|decl| decl.is_class())?;
+        self.get_node(decl.get_syntax_id()?)
+            .and_then(PyClassDef::cast)
+    }
+
+    pub fn infer_expr(&self, expr: &PyExpr) -> PyType {
+        InferContext::new(self).infer_expr(expr)
+    }
+
+    pub fn infer_decl_type(&self, decl: &PyDecl) -> PyType {
+        InferContext::new(self).infer_decl(decl)
+    }
+
+    pub fn infer_annotation(&self, expr: &PyExpr) -> PyType {
+        infer::infer_annotation(self, expr)
+    }
+
+    pub fn infer_return_type(&self, func: &PyFuncDef) -> PyType {
+        InferContext::new(self).infer_return_type(func)
+    }
+
+    /// The type produced by calling something with `signature`
+    pub fn infer_call_return(&self, signature: &PySignature) -> PyType {
+        InferContext::new(self).infer_signature_return(signature)
+    }
+
+    /// Every signature a call of `callee` may bind to, the `@overload`
+    /// variants in declaration order when there are any
+    pub fn infer_call_signatures(&self, callee: &PyExpr) -> Vec<PySignature> {
+        match self.infer_expr(callee) {
+            PyType::Function(signature) => self.expand_overloads(&signature),
+            PyType::Class(class_name) => {
+                let Some(class) = self.find_class_def(&class_name) else {
+                    return Vec::new();
+                };
+                let signature = PySignature::from_class_def(&class);
+                let overloads: Vec<PySignature> = self
+                    .find_overloads_in_class(&class, "__init__")
+                    .into_iter()
+                    .map(|mut init| {
+                        init.bind_self();
+                        init.name = signature.name.clone();
+                        init.return_annotation = None;
+                        init
+                    })
+                    .collect();
+                if overloads.is_empty() {
+                    vec![signature]
+                } else {
+                    overloads
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn expand_overloads(&self, signature: &PySignature) -> Vec<PySignature> {
+        let Some(func) = signature
+            .syntax_id
+            .and_then(|syntax_id| self.get_node(syntax_id))
+            .and_then(PyFuncDef::cast)
+        else {
+            return vec![signature.clone()];
+        };
+        let Some(decl) = func
+            .get_name()
+            .and_then(|name| self.decl_tree.find_decl_by_range(name.get_range()))
+        else {
+            return vec![signature.clone()];
+        };
+
+        let overloads = self.find_overloads(decl.get_scope(), decl.get_name());
+        if overloads.is_empty() {
+            return vec![signature.clone()];
+        }
+        // the callee was reached through an instance, bind every variant the same way
+        let bound = signature.params.len() < PySignature::from_func_def(&func).params.len();
+        overloads
+            .into_iter()
+            .map(|mut overload| {
+                if bound {
+                    overload.bind_self();
+                }
+                overload
+            })
+            .collect()
+    }
+
+    fn find_overloads_in_class(&self, class: &PyClassDef, name: &str) -> Vec<PySignature> {
+        let Some(scope) = class
+            .get_body()
+            .and_then(|body| self.decl_tree.find_scope(body.get_position()))
+            .filter(|scope| scope.get_kind() == PyScopeKind::Class)
+        else {
+            return Vec::new();
+        };
+        self.find_overloads(scope.get_id(), name)
+    }
+
+    fn find_overloads(&self, scope: ScopeId, name: &str) -> Vec<PySignature> {
+        self.decl_tree
+            .get_scope_decls_by_name(scope, name)
+            .into_iter()
+            .filter(|decl| decl.is_function())
+            .filter_map(|decl| self.get_node(decl.get_syntax_id()?))
+            .filter_map(PyFuncDef::cast)
+            .map(|func| PySignature::from_func_def(&func))
+            .filter(|signature| signature.is_overload())
+            .collect()
+    }
+}
+
+/// The name of a keyword argument, `name` in `f(name=value)`
+pub fn is_keyword_name(name_expr: &PyNameExpr) -> bool {
+    name_expr.get_parent::<PyKeyword>().is_some_and(|keyword| {
+        keyword
+            .syntax()
+            .first_child()
+            .is_some_and(|first| &first == name_expr.syntax())
+    })
+}
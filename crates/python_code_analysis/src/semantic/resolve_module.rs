@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use lsp_types::Uri;
+
+use crate::{FileId, QueryKey, db_index::DbIndex, is_python_file, uri_to_file_path};
+
+/// The workspace file a `from module import ...` in `file_id` imports. A
+/// relative module such as `.utils` is looked up in the package of the
+/// importing file.
+pub(crate) fn resolve_module(db: &DbIndex, file_id: FileId, module: &str) -> Option<FileId> {
+    // the module a name resolves to changes when a file is added or removed
+    db.get_query_db().read(QueryKey::Files).ok()?;
+    let source_roots = db.get_config().workspace.get_source_roots();
+    let vfs = db.get_vfs();
+    let relative = module.trim_start_matches('.');
+    let dots = module.len() - relative.len();
+    let target = if dots == 0 {
+        module.to_string()
+    } else {
+        let uri = vfs.get_uri(&file_id)?;
+        let mut package: Vec<String> = module_name(&uri, &source_roots)?
+            .split('.')
+            .map(str::to_string)
+            .collect();
+        // `.` is the package of a module, and the package an `__init__.py` is
+        let is_package = uri_to_file_path(&uri)?.file_stem()? == "__init__";
+        let up = if is_package { dots - 1 } else { dots };
+        package.truncate(package.len().checked_sub(up)?);
+        if !relative.is_empty() {
+            package.push(relative.to_string());
+        }
+        package.join(".")
+    };
+
+    let mut file_ids = vfs.get_all_file_ids();
+    file_ids.sort();
+    file_ids.into_iter().find(|it| {
+        *it != file_id
+            && vfs
+                .get_uri(it)
+                .and_then(|uri| module_name(&uri, &source_roots))
+                .is_some_and(|name| name == target)
+    })
+}
+
+/// The dotted module path a file is imported by, relative to the innermost
+/// source root it is in. Without a known root only the file name is used.
+pub(crate) fn module_name(uri: &Uri, source_roots: &[PathBuf]) -> Option<String> {
+    let path = uri_to_file_path(uri)?;
+    // a notebook is not imported
+    if !is_python_file(&path) {
+        return None;
+    }
+    let relative = if source_roots.is_empty() {
+        let depth = if path.file_stem()? == "__init__" {
+            2
+        } else {
+            1
+        };
+        let skip = path.components().count().saturating_sub(depth);
+        path.components().skip(skip).collect::<PathBuf>()
+    } else {
+        source_roots
+            .iter()
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .min_by_key(|relative| relative.components().count())?
+            .to_path_buf()
+    };
+
+    let mut parts = relative
+        .parent()?
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    let stem = relative.file_stem()?.to_str()?;
+    if stem != "__init__" {
+        parts.push(stem);
+    }
+    // the `__init__.py` of a source root itself has no module name
+    if parts.is_empty() || !parts.iter().all(|part| is_identifier(part)) {
+        return None;
+    }
+    Some(parts.join("."))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
@@ -0,0 +1,266 @@
+use python_parser::{PyArguments, PyAstNode, PyClassDef, PyFuncDef, PyLambdaExpr, PySyntaxId};
+
+use super::PyType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyParamKind {
+    PositionalOnly,
+    PositionalOrKeyword,
+    VarPositional,
+    KeywordOnly,
+    VarKeyword,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PySignatureParam {
+    pub name: String,
+    pub kind: PyParamKind,
+    pub annotation: Option<String>,
+    pub default: Option<String>,
+}
+
+impl PySignatureParam {
+    pub fn has_default(&self) -> bool {
+        self.default.is_some()
+    }
+
+    /// `b: int = 1`, `c=2`, `*args`, `**kwargs: Any`
+    pub fn to_param_string(&self) -> String {
+        let mut text = match self.kind {
+            PyParamKind::VarPositional => format!("*{}", self.name),
+            PyParamKind::VarKeyword => format!("**{}", self.name),
+            _ => self.name.clone(),
+        };
+        if let Some(annotation) = &self.annotation {
+            text.push_str(": ");
+            text.push_str(annotation);
+        }
+        if let Some(default) = &self.default {
+            if self.annotation.is_some() {
+                text.push_str(" = ");
+            } else {
+                text.push('=');
+            }
+            text.push_str(default);
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PySignature {
+    pub name: String,
+    pub params: Vec<PySignatureParam>,
+    pub return_annotation: Option<String>,
+    pub is_async: bool,
+    // decorator expressions without the leading '@'
+    pub decorators: Vec<String>,
+    pub docstring: Option<String>,
+    pub syntax_id: Option<PySyntaxId>,
+    /// The return type of a signature imported from another module, which
+    /// its syntax id does not point into
+    pub return_type: Option<PyType>,
+}
+
+impl PySignature {
+    pub fn from_func_def(func: &PyFuncDef) -> PySignature {
+        PySignature {
+            name: func
+                .get_name()
+                .map(|name| name.get_name_text().to_string())
+                .unwrap_or_default(),
+            params: func
+                .get_parameters()
+                .map(|params| collect_params(&params))
+                .unwrap_or_default(),
+            return_annotation: func
+                .get_return_annotation()
+                .or_else(|| func.get_type_comment()?.get_return_type())
+                .map(|it| it.get_text()),
+            is_async: func.is_async(),
+            decorators: func
+                .get_decorators()
+                .filter_map(|decorator| decorator.get_name())
+                .map(|expr| expr.get_text())
+                .collect(),
+            docstring: func.get_docstring().and_then(|doc| doc.get_content()),
+            syntax_id: Some(func.get_syntax_id()),
+            return_type: None,
+        }
+    }
+
+    pub fn from_lambda(lambda: &PyLambdaExpr) -> PySignature {
+        PySignature {
+            name: "<lambda>".to_string(),
+            params: lambda
+                .get_parameters()
+                .map(|params| collect_params(&params))
+                .unwrap_or_default(),
+            return_annotation: None,
+            is_async: false,
+            decorators: Vec::new(),
+            docstring: None,
+            syntax_id: Some(lambda.get_syntax_id()),
+            return_type: None,
+        }
+    }
+
+    /// The constructor signature of a class, taken from `__init__` without `self`
+    pub fn from_class_def(class: &PyClassDef) -> PySignature {
+        let init = class.get_body().and_then(|body| {
+            body.get_statements()
+                .filter_map(|stat| find_func_def(stat.syntax()))
+                .find(|func| {
+                    func.get_name()
+                        .is_some_and(|name| name.get_name_text() == "__init__")
+                })
+        });
+        let params = match &init {
+            Some(init) => {
+                let mut signature = PySignature::from_func_def(init);
+                signature.bind_self();
+                signature.params
+            }
+            None => Vec::new(),
+        };
+        PySignature {
+            name: class
+                .get_name()
+                .map(|name| name.get_name_text().to_string())
+                .unwrap_or_default(),
+            params,
+            return_annotation: None,
+            is_async: false,
+            decorators: class
+                .get_decorators()
+                .filter_map(|decorator| decorator.get_name())
+                .map(|expr| expr.get_text())
+                .collect(),
+            docstring: class.get_docstring().and_then(|doc| doc.get_content()),
+            syntax_id: Some(class.get_syntax_id()),
+            return_type: None,
+        }
+    }
+
+    /// Drop the first parameter, as for a method accessed through an instance
+    /// or a classmethod accessed through either
+    pub fn bind_self(&mut self) {
+        if self
+            .params
+            .first()
+            .is_some_and(|param| param.kind != PyParamKind::VarPositional)
+            && !self.is_staticmethod()
+        {
+            self.params.remove(0);
+        }
+    }
+
+    pub fn is_overload(&self) -> bool {
+        self.decorators
+            .iter()
+            .any(|it| it == "overload" || it == "typing.overload")
+    }
+
+    pub fn is_staticmethod(&self) -> bool {
+        self.decorators.iter().any(|it| it == "staticmethod")
+    }
+
+    pub fn is_classmethod(&self) -> bool {
+        self.decorators.iter().any(|it| it == "classmethod")
+    }
+
+    pub fn is_property(&self) -> bool {
+        self.decorators
+            .iter()
+            .any(|it| it == "property" || it.ends_with(".setter") || it.ends_with(".getter"))
+    }
+
+    /// Parameters rendered in order with the `/` and `*` markers
+    pub fn get_param_strings(&self) -> Vec<String> {
+        let mut result = Vec::new();
+        let has_vararg = self
+            .params
+            .iter()
+            .any(|param| param.kind == PyParamKind::VarPositional);
+        let mut star_emitted = has_vararg;
+        for (i, param) in self.params.iter().enumerate() {
+            if param.kind == PyParamKind::KeywordOnly && !star_emitted {
+                result.push("*".to_string());
+                star_emitted = true;
+            }
+            result.push(param.to_param_string());
+            let next_is_posonly = self
+                .params
+                .get(i + 1)
+                .is_some_and(|next| next.kind == PyParamKind::PositionalOnly);
+            if param.kind == PyParamKind::PositionalOnly && !next_is_posonly {
+                result.push("/".to_string());
+            }
+        }
+        result
+    }
+
+    pub fn get_params_string(&self) -> String {
+        self.get_param_strings().join(", ")
+    }
+
+    /// `(a, b: int = 1) -> int`
+    pub fn to_callable_string(&self) -> String {
+        match &self.return_annotation {
+            Some(ret) => format!("({}) -> {}", self.get_params_string(), ret),
+            None => format!("({})", self.get_params_string()),
+        }
+    }
+}
+
+fn find_func_def(node: &python_parser::PySyntaxNode) -> Option<PyFuncDef> {
+    match PyFuncDef::cast(node.clone()) {
+        Some(func) => Some(func),
+        None => node.children().find_map(PyFuncDef::cast),
+    }
+}
+
+fn collect_params(params: &PyArguments) -> Vec<PySignatureParam> {
+    let posonly: Vec<_> = params
+        .get_posonlyargs()
+        .map(|param| param.get_syntax_id())
+        .collect();
+    let kwonly: Vec<_> = params
+        .get_kwonlyargs()
+        .map(|param| param.get_syntax_id())
+        .collect();
+
+    let mut result = Vec::new();
+    for param in params.get_args() {
+        if param.is_star_separator() {
+            continue;
+        }
+        let Some(name) = param.get_name() else {
+            continue;
+        };
+
+        let syntax_id = param.get_syntax_id();
+        let kind = if param.is_vararg() {
+            PyParamKind::VarPositional
+        } else if param.is_kwarg() {
+            PyParamKind::VarKeyword
+        } else if posonly.contains(&syntax_id) {
+            PyParamKind::PositionalOnly
+        } else if kwonly.contains(&syntax_id) {
+            PyParamKind::KeywordOnly
+        } else {
+            PyParamKind::PositionalOrKeyword
+        };
+
+        result.push(PySignatureParam {
+            name: name.get_name_text().to_string(),
+            kind,
+            annotation: param
+                .get_annotation()
+                .or_else(|| param.get_type_comment_annotation())
+                .map(|it| it.get_text()),
+            default: param.get_default_value().map(|it| it.get_text()),
+        });
+    }
+    result
+}
@@ -0,0 +1,40 @@
+use super::PyType;
+
+/// Render a type the way it would be written in a Python annotation
+pub fn humanize_type(ty: &PyType) -> String {
+    match ty {
+        PyType::Unknown => "Unknown".to_string(),
+        PyType::Any => "Any".to_string(),
+        PyType::None => "None".to_string(),
+        PyType::Bool => "bool".to_string(),
+        PyType::Int => "int".to_string(),
+        PyType::Float => "float".to_string(),
+        PyType::Complex => "complex".to_string(),
+        PyType::Str => "str".to_string(),
+        PyType::Bytes => "bytes".to_string(),
+        PyType::Ellipsis => "EllipsisType".to_string(),
+        PyType::List(element) => format!("list[{}]", humanize_type(element)),
+        PyType::Set(element) => format!("set[{}]", humanize_type(element)),
+        PyType::Dict(key, value) => {
+            format!("dict[{}, {}]", humanize_type(key), humanize_type(value))
+        }
+        PyType::Tuple(elements) => {
+            if elements.is_empty() {
+                "tuple[()]".to_string()
+            } else {
+                let elements: Vec<String> = elements.iter().map(humanize_type).collect();
+                format!("tuple[{}]", elements.join(", "))
+            }
+        }
+        PyType::Instance(name) => name.clone(),
+        PyType::Class(name) => format!("type[{}]", name),
+        PyType::Function(signature) => signature.to_callable_string(),
+        PyType::Coroutine(ret) => format!("CoroutineType[Any, Any, {}]", humanize_type(ret)),
+        PyType::Module(name) => format!("Module(\"{}\")", name),
+        PyType::Union(members) => {
+            let members: Vec<String> = members.iter().map(humanize_type).collect();
+            members.join(" | ")
+        }
+        PyType::Annotation(text) => text.clone(),
+    }
+}
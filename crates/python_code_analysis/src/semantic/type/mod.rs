@@ -0,0 +1,93 @@
+mod humanize_type;
+
+use std::sync::Arc;
+
+pub use humanize_type::humanize_type;
+
+use super::PySignature;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyType {
+    Unknown,
+    Any,
+    None,
+    Bool,
+    Int,
+    Float,
+    Complex,
+    Str,
+    Bytes,
+    Ellipsis,
+    List(Box<PyType>),
+    Set(Box<PyType>),
+    Dict(Box<PyType>, Box<PyType>),
+    Tuple(Vec<PyType>),
+    // an instance of the named class
+    Instance(String),
+    // the class object itself, `type[Name]`
+    Class(String),
+    Function(Arc<PySignature>),
+    // the awaitable returned by calling an async function
+    Coroutine(Box<PyType>),
+    Module(String),
+    Union(Vec<PyType>),
+    // an annotation we can not resolve further, kept verbatim
+    Annotation(String),
+}
+
+impl PyType {
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, PyType::Unknown)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            PyType::Bool | PyType::Int | PyType::Float | PyType::Complex
+        )
+    }
+
+    /// Build a union, flattening nested unions and dropping duplicates
+    pub fn union(types: Vec<PyType>) -> PyType {
+        let mut result: Vec<PyType> = Vec::new();
+        for ty in types {
+            let members = match ty {
+                PyType::Union(members) => members,
+                ty => vec![ty],
+            };
+            for member in members {
+                if !result.contains(&member) {
+                    result.push(member);
+                }
+            }
+        }
+
+        // Unknown members add no information once something is known
+        if result.len() > 1 {
+            result.retain(|ty| !ty.is_unknown());
+        }
+
+        match result.len() {
+            0 => PyType::Unknown,
+            1 => result.pop().unwrap(),
+            _ => PyType::Union(result),
+        }
+    }
+
+    /// The builtin type named `name` when it maps onto a primitive type
+    pub fn from_builtin_name(name: &str) -> Option<PyType> {
+        let ty = match name {
+            "bool" => PyType::Bool,
+            "int" => PyType::Int,
+            "float" => PyType::Float,
+            "complex" => PyType::Complex,
+            "str" => PyType::Str,
+            "bytes" => PyType::Bytes,
+            "None" => PyType::None,
+            "Any" => PyType::Any,
+            "object" => PyType::Instance("object".to_string()),
+            _ => return None,
+        };
+        Some(ty)
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use lsp_types::{Position, Range};
+use python_parser::LineIndex;
+use rowan::{TextRange, TextSize};
+
+use super::FileId;
+
+#[derive(Debug, Clone)]
+pub struct PyDocument<'a> {
+    file_id: FileId,
+    text: &'a str,
+    line_index: Arc<LineIndex>,
+}
+
+impl<'a> PyDocument<'a> {
+    pub fn new(file_id: FileId, text: &'a str, line_index: Arc<LineIndex>) -> Self {
+        PyDocument {
+            file_id,
+            text,
+            line_index,
+        }
+    }
+
+    pub fn get_file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    pub fn get_text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn get_text_slice(&self, range: TextRange) -> &'a str {
+        &self.text[range.start().into()..range.end().into()]
+    }
+
+    pub fn get_line_count(&self) -> usize {
+        self.line_index.line_count()
+    }
+
+    // get line base 0
+    pub fn get_line(&self, offset: TextSize) -> Option<usize> {
+        self.line_index.get_line(offset)
+    }
+
+    // get line and col base 0
+    pub fn get_line_col(&self, offset: TextSize) -> Option<(usize, usize)> {
+        self.line_index.get_line_col(offset, self.text)
+    }
+
+    pub fn get_offset(&self, line: usize, col: usize) -> Option<TextSize> {
+        let offset = self.line_index.get_offset(line, col, self.text)?;
+        // clients may send a column past the end of the text
+        Some(offset.min(TextSize::of(self.text)))
+    }
+
+    pub fn get_line_range(&self, line: usize) -> Option<TextRange> {
+        let start = self.line_index.get_line_offset(line)?;
+        let end = match self.line_index.get_line_offset(line + 1) {
+            Some(next_line_start) => next_line_start,
+            None => TextSize::from(self.text.len() as u32),
+        };
+        Some(TextRange::new(start, end))
+    }
+
+    pub fn to_lsp_position(&self, offset: TextSize) -> Option<Position> {
+        let (line, col) = self.get_line_col(offset)?;
+        Some(Position {
+            line: line as u32,
+            character: col as u32,
+        })
+    }
+
+    pub fn to_lsp_range(&self, range: TextRange) -> Option<Range> {
+        Some(Range {
+            start: self.to_lsp_position(range.start())?,
+            end: self.to_lsp_position(range.end())?,
+        })
+    }
+
+    pub fn to_rowan_range(&self, range: Range) -> Option<TextRange> {
+        let start = self.get_offset(range.start.line as usize, range.start.character as usize)?;
+        let end = self.get_offset(range.end.line as usize, range.end.character as usize)?;
+        Some(TextRange::new(start, end))
+    }
+}
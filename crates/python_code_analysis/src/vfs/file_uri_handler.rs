@@ -0,0 +1,18 @@
+use std::{path::PathBuf, str::FromStr};
+
+use lsp_types::Uri;
+use url::Url;
+
+pub fn file_path_to_uri(path: &PathBuf) -> Option<Uri> {
+    let url = Url::from_file_path(path).ok()?;
+    Uri::from_str(url.as_str()).ok()
+}
+
+pub fn uri_to_file_path(uri: &Uri) -> Option<PathBuf> {
+    let url = Url::parse(uri.as_str()).ok()?;
+    if url.scheme() != "file" {
+        return None;
+    }
+
+    url.to_file_path().ok()
+}
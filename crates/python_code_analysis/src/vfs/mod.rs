@@ -0,0 +1,280 @@
+mod document;
+mod file_uri_handler;
+
+use std::collections::HashMap;
+
+pub use document::PyDocument;
+pub use file_uri_handler::{file_path_to_uri, uri_to_file_path};
+use lsp_types::Uri;
+use python_parser::{DecodedSource, ParserConfig, PyParseError};
+
+use crate::PyNotebook;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId {
+    pub id: u32,
+}
+
+impl FileId {
+    pub fn new(id: u32) -> Self {
+        FileId { id }
+    }
+
+    // only for test and virtual files
+    pub const VIRTUAL: FileId = FileId { id: u32::MAX };
+}
+
+#[derive(Debug)]
+pub struct Vfs {
+    file_id_map: HashMap<String, u32>,
+    file_uri_map: HashMap<u32, Uri>,
+    file_data: Vec<Option<String>>,
+    /// The problems of decoding the files loaded from their bytes
+    decode_errors_map: HashMap<FileId, Vec<PyParseError>>,
+    /// The open notebooks, their cells make up the content of the file
+    notebook_map: HashMap<FileId, PyNotebook>,
+    parser_config: ParserConfig,
+    changes: HashMap<FileId, VfsFileChange>,
+}
+
+/// The content a file is set to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PyFileContent {
+    /// The text of an open document or the code cells of a notebook
+    Text(String),
+    /// The bytes of a file on disk, decoded as its BOM and coding declaration
+    /// say. The problems of decoding them are errors of its tree.
+    Bytes(Vec<u8>),
+}
+
+impl From<String> for PyFileContent {
+    fn from(text: String) -> Self {
+        PyFileContent::Text(text)
+    }
+}
+
+/// What setting the content of a file changed. Loading a file from disk
+/// again often changes nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VfsFileChange {
+    /// The text or the problems of decoding it
+    pub text: bool,
+    /// The file was added or removed
+    pub loaded: bool,
+}
+
+impl VfsFileChange {
+    pub fn is_changed(&self) -> bool {
+        self.text || self.loaded
+    }
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Vfs {
+            file_id_map: HashMap::new(),
+            file_uri_map: HashMap::new(),
+            file_data: Vec::new(),
+            decode_errors_map: HashMap::new(),
+            notebook_map: HashMap::new(),
+            parser_config: ParserConfig::default(),
+            changes: HashMap::new(),
+        }
+    }
+
+    pub fn file_id(&mut self, uri: &Uri) -> FileId {
+        if let Some(&id) = self.file_id_map.get(uri.as_str()) {
+            return FileId { id };
+        }
+
+        let id = self.file_data.len() as u32;
+        self.file_id_map.insert(uri.as_str().to_string(), id);
+        self.file_uri_map.insert(id, uri.clone());
+        self.file_data.push(None);
+        FileId { id }
+    }
+
+    pub fn get_file_id(&self, uri: &Uri) -> Option<FileId> {
+        self.file_id_map.get(uri.as_str()).map(|&id| FileId { id })
+    }
+
+    pub fn get_uri(&self, id: &FileId) -> Option<Uri> {
+        self.file_uri_map.get(&id.id).cloned()
+    }
+
+    pub fn set_file_content(&mut self, uri: &Uri, data: Option<String>) -> FileId {
+        let fid = self.file_id(uri);
+        log::debug!("file_id: {:?}, uri: {}", fid, uri.as_str());
+        self.store_file(fid, data.map(|text| (text, Vec::new())));
+        fid
+    }
+
+    /// Set the content of many files at once, the bytes of a file are
+    /// decoded as its BOM and coding declaration say
+    pub fn set_files_content(&mut self, files: Vec<(Uri, Option<PyFileContent>)>) -> Vec<FileId> {
+        let mut file_ids = Vec::with_capacity(files.len());
+        for (uri, content) in files {
+            let fid = self.file_id(&uri);
+            self.store_file(fid, content.map(decode_content));
+            file_ids.push(fid);
+        }
+        file_ids
+    }
+
+    fn store_file(&mut self, fid: FileId, content: Option<(String, Vec<PyParseError>)>) {
+        let old_text = self.file_data[fid.id as usize].as_ref();
+        let change = VfsFileChange {
+            text: old_text != content.as_ref().map(|(text, _)| text)
+                || content
+                    .as_ref()
+                    .is_some_and(|(_, errors)| self.get_decode_errors(&fid) != errors.as_slice()),
+            loaded: old_text.is_some() != content.is_some(),
+        };
+        match content {
+            Some((text, decode_errors)) => {
+                if decode_errors.is_empty() {
+                    self.decode_errors_map.remove(&fid);
+                } else {
+                    self.decode_errors_map.insert(fid, decode_errors);
+                }
+                self.file_data[fid.id as usize] = Some(text);
+            }
+            None => {
+                self.decode_errors_map.remove(&fid);
+                self.file_data[fid.id as usize] = None;
+            }
+        }
+        self.record_change(fid, change);
+    }
+
+    fn record_change(&mut self, fid: FileId, change: VfsFileChange) {
+        let recorded = self.changes.entry(fid).or_default();
+        recorded.text |= change.text;
+        recorded.loaded |= change.loaded;
+    }
+
+    /// What changed in each file since the last call
+    pub fn take_changes(&mut self) -> Vec<(FileId, VfsFileChange)> {
+        let mut changes: Vec<_> = self
+            .changes
+            .drain()
+            .filter(|(_, change)| change.is_changed())
+            .collect();
+        changes.sort_by_key(|(fid, _)| *fid);
+        changes
+    }
+
+    /// Set the cells of a notebook, the file of the notebook holds the
+    /// source of its code cells
+    pub fn set_notebook(&mut self, uri: &Uri, notebook: PyNotebook) -> FileId {
+        let fid = self.set_file_content(uri, Some(notebook.get_source().to_string()));
+        self.notebook_map.insert(fid, notebook);
+        fid
+    }
+
+    /// Forget the cells of a notebook, its file is left as it is
+    pub fn remove_notebook(&mut self, uri: &Uri) -> Option<PyNotebook> {
+        let fid = self.get_file_id(uri)?;
+        self.notebook_map.remove(&fid)
+    }
+
+    pub fn get_notebook(&self, id: &FileId) -> Option<&PyNotebook> {
+        self.notebook_map.get(id)
+    }
+
+    /// The notebook file a cell document belongs to and the index of the cell
+    pub fn find_notebook_cell(&self, cell_uri: &Uri) -> Option<(FileId, usize)> {
+        self.notebook_map
+            .iter()
+            .find_map(|(fid, notebook)| Some((*fid, notebook.find_cell(cell_uri)?)))
+    }
+
+    pub fn remove_file(&mut self, uri: &Uri) -> Option<FileId> {
+        let fid = self.get_file_id(uri)?;
+        self.store_file(fid, None);
+        Some(fid)
+    }
+
+    pub fn get_file_content(&self, id: &FileId) -> Option<&String> {
+        self.file_data.get(id.id as usize)?.as_ref()
+    }
+
+    pub fn is_loaded(&self, id: &FileId) -> bool {
+        self.get_file_content(id).is_some()
+    }
+
+    /// Whether the file already has the text the content decodes to
+    pub fn has_content(&self, id: &FileId, content: &PyFileContent) -> bool {
+        let Some(text) = self.get_file_content(id) else {
+            return false;
+        };
+        match content {
+            PyFileContent::Text(new_text) => {
+                text == new_text && self.get_decode_errors(id).is_empty()
+            }
+            PyFileContent::Bytes(bytes) => {
+                let source = DecodedSource::decode(bytes);
+                *text == source.text && self.get_decode_errors(id) == source.errors.as_slice()
+            }
+        }
+    }
+
+    /// The problems of decoding a file loaded from its bytes
+    pub fn get_decode_errors(&self, id: &FileId) -> &[PyParseError] {
+        self.decode_errors_map
+            .get(id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn get_all_file_ids(&self) -> Vec<FileId> {
+        self.file_data
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| text.is_some())
+            .map(|(id, _)| FileId { id: id as u32 })
+            .collect()
+    }
+
+    pub fn get_parser_config(&self) -> ParserConfig {
+        self.parser_config
+    }
+
+    pub fn set_parser_config(&mut self, config: ParserConfig) {
+        self.parser_config = config;
+    }
+
+    /// The config a loaded file is parsed with
+    pub fn get_file_parser_config(&self, id: &FileId) -> Option<ParserConfig> {
+        self.get_file_content(id)?;
+        let uri = self.file_uri_map.get(&id.id)?;
+        Some(file_parser_config(self.parser_config, uri))
+    }
+}
+
+fn decode_content(content: PyFileContent) -> (String, Vec<PyParseError>) {
+    match content {
+        PyFileContent::Text(text) => (text, Vec::new()),
+        PyFileContent::Bytes(bytes) => {
+            let source = DecodedSource::decode(&bytes);
+            (source.text, source.errors)
+        }
+    }
+}
+
+/// IPython scripts and the cells of notebooks are parsed with their magics
+/// and shell escapes
+fn file_parser_config(config: ParserConfig, uri: &Uri) -> ParserConfig {
+    let path = uri.path();
+    if path.as_str().ends_with(".ipy") || path.as_str().ends_with(".ipynb") {
+        config.with_ipython_magics(true)
+    } else {
+        config
+    }
+}
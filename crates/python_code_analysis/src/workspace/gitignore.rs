@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use wax::{Glob, Pattern};
+
+/// The rules of one `.gitignore` file, they match paths relative to the
+/// directory holding it
+#[derive(Debug)]
+pub struct GitIgnore {
+    base: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+#[derive(Debug)]
+struct IgnoreRule {
+    glob: Glob<'static>,
+    /// `!pattern` includes again what an earlier rule ignored
+    negated: bool,
+    /// `pattern/` only matches directories
+    dir_only: bool,
+}
+
+impl GitIgnore {
+    /// The `.gitignore` of `dir`, `None` when there is none
+    pub fn load(dir: &Path) -> Option<GitIgnore> {
+        let text = std::fs::read_to_string(dir.join(".gitignore")).ok()?;
+        Some(GitIgnore::parse(dir, &text))
+    }
+
+    pub fn parse(base: &Path, text: &str) -> GitIgnore {
+        let rules = text.lines().filter_map(parse_rule).collect();
+        GitIgnore {
+            base: base.to_path_buf(),
+            rules,
+        }
+    }
+
+    /// `Some(true)` when the path is ignored, `Some(false)` when a negated
+    /// rule includes it again, and `None` when no rule matches it. The last
+    /// matching rule decides, as in git.
+    pub fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| (is_dir || !rule.dir_only) && rule.glob.is_match(relative))
+            .map(|rule| !rule.negated)
+    }
+}
+
+fn parse_rule(line: &str) -> Option<IgnoreRule> {
+    // trailing spaces are ignored unless escaped
+    let mut pattern = line.trim_end_matches('\r');
+    if !pattern.ends_with("\\ ") {
+        pattern = pattern.trim_end();
+    }
+    if pattern.is_empty() || pattern.starts_with('#') {
+        return None;
+    }
+    let negated = pattern.starts_with('!');
+    if negated {
+        pattern = &pattern[1..];
+    }
+    let dir_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    // a pattern with a slash is relative to the `.gitignore`, one without
+    // matches a name at any depth
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+    let expression = if anchored {
+        to_wax_expression(pattern)
+    } else {
+        format!("**/{}", to_wax_expression(pattern))
+    };
+    match Glob::new(&expression) {
+        Ok(glob) => Some(IgnoreRule {
+            glob: glob.into_owned(),
+            negated,
+            dir_only,
+        }),
+        Err(err) => {
+            log::debug!("Skip .gitignore pattern `{}`: {}", line, err);
+            None
+        }
+    }
+}
+
+/// Escape the characters which wax reads as syntax but gitignore does not
+fn to_wax_expression(pattern: &str) -> String {
+    let mut expression = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    push_literal(&mut expression, escaped);
+                }
+            }
+            '{' | '}' | '<' | '>' | '(' | ')' | ',' | ':' | '$' => {
+                expression.push('\\');
+                expression.push(ch);
+            }
+            _ => expression.push(ch),
+        }
+    }
+    expression
+}
+
+fn push_literal(expression: &mut String, ch: char) {
+    if !ch.is_alphanumeric() && ch != ' ' {
+        expression.push('\\');
+    }
+    expression.push(ch);
+}
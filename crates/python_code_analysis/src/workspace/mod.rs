@@ -0,0 +1,188 @@
+mod gitignore;
+
+use std::path::{Path, PathBuf};
+
+pub use gitignore::GitIgnore;
+use wax::{Glob, Pattern};
+
+use crate::{PyFileContent, PyNotebook, file_path_to_uri};
+
+/// Directories which hold generated or third party code
+const SKIPPED_DIRS: &[&str] = &["__pycache__", "node_modules", "site-packages", "venv"];
+
+/// Decides which files under a root belong to the workspace: the Python
+/// files which are not in a hidden or third party directory, not ignored by
+/// a `.gitignore`, not excluded and, when there are include globs, included.
+/// Globs match paths relative to the root.
+#[derive(Debug)]
+pub struct WorkspaceFilter {
+    root: PathBuf,
+    include: Vec<Glob<'static>>,
+    exclude: Vec<Glob<'static>>,
+}
+
+impl WorkspaceFilter {
+    pub fn new(root: &Path, include: &[String], exclude: &[String]) -> Result<Self, String> {
+        Ok(WorkspaceFilter {
+            root: root.to_path_buf(),
+            include: compile_globs(include)?,
+            exclude: compile_globs(exclude)?,
+        })
+    }
+
+    pub fn get_root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The workspace files, sorted by path
+    pub fn collect_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut ignores = Vec::new();
+        self.walk(&self.root, &mut ignores, &mut files);
+        files
+    }
+
+    fn walk(&self, dir: &Path, ignores: &mut Vec<GitIgnore>, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let gitignore = GitIgnore::load(dir);
+        let has_gitignore = gitignore.is_some();
+        ignores.extend(gitignore);
+
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            // links to directories are not followed, they may form a cycle
+            if file_type.is_dir() {
+                if !self.is_excluded(&path, true, ignores) {
+                    self.walk(&path, ignores, files);
+                }
+            } else if (is_python_file(&path) || is_ipython_file(&path) || is_notebook_file(&path))
+                && path.is_file()
+                && !self.is_excluded(&path, false, ignores)
+                && self.is_included(&path)
+            {
+                files.push(path);
+            }
+        }
+
+        if has_gitignore {
+            ignores.pop();
+        }
+    }
+
+    /// Whether `path` is a workspace file, for a single file which changed.
+    /// The `.gitignore` files of its directories are read again.
+    pub fn is_workspace_file(&self, path: &Path) -> bool {
+        if !is_python_file(path) && !is_ipython_file(path) && !is_notebook_file(path) {
+            return false;
+        }
+        let Some(ignores) = self.load_parent_ignores(path) else {
+            return false;
+        };
+        !self.is_excluded(path, false, &ignores) && self.is_included(path)
+    }
+
+    /// Whether the files in `dir` may be workspace files
+    pub fn is_workspace_dir(&self, dir: &Path) -> bool {
+        if dir == self.root {
+            return true;
+        }
+        let Some(ignores) = self.load_parent_ignores(dir) else {
+            return false;
+        };
+        !self.is_excluded(dir, true, &ignores)
+    }
+
+    /// The `.gitignore` files which apply to `path`, `None` when it is not
+    /// under the root or one of its directories is excluded
+    fn load_parent_ignores(&self, path: &Path) -> Option<Vec<GitIgnore>> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut components: Vec<_> = relative.components().collect();
+        components.pop()?;
+        let mut ignores: Vec<GitIgnore> = GitIgnore::load(&self.root).into_iter().collect();
+        let mut dir = self.root.clone();
+        for component in components {
+            dir.push(component);
+            if self.is_excluded(&dir, true, &ignores) {
+                return None;
+            }
+            ignores.extend(GitIgnore::load(&dir));
+        }
+        Some(ignores)
+    }
+
+    fn is_excluded(&self, path: &Path, is_dir: bool, ignores: &[GitIgnore]) -> bool {
+        if is_dir {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+                return true;
+            }
+        }
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if self.exclude.iter().any(|glob| glob.is_match(relative)) {
+            return true;
+        }
+        // a nested `.gitignore` takes precedence over its parents
+        ignores
+            .iter()
+            .rev()
+            .find_map(|gitignore| gitignore.matched(path, is_dir))
+            .unwrap_or(false)
+    }
+
+    fn is_included(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        self.include.is_empty() || self.include.iter().any(|glob| glob.is_match(relative))
+    }
+}
+
+pub fn is_python_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "py" || extension == "pyi")
+}
+
+/// An IPython script, parsed with its magics and shell escapes
+pub fn is_ipython_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "ipy")
+}
+
+/// A Jupyter notebook, analyzed as the code of its cells
+pub fn is_notebook_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "ipynb")
+}
+
+/// The content a workspace file is analyzed as, the code cells of a notebook
+/// one after another. A source file is left as bytes, it is decoded when it
+/// is parsed.
+pub fn read_source_file(path: &Path) -> Result<PyFileContent, String> {
+    if !is_notebook_file(path) {
+        let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+        return Ok(PyFileContent::Bytes(bytes));
+    }
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let uri =
+        file_path_to_uri(&path.to_path_buf()).ok_or_else(|| "invalid file path".to_string())?;
+    let notebook = PyNotebook::from_ipynb(&uri, &text)?;
+    Ok(PyFileContent::Text(notebook.get_source().to_string()))
+}
+
+fn compile_globs(patterns: &[String]) -> Result<Vec<Glob<'static>>, String> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Glob::new(pattern)
+                .map(Glob::into_owned)
+                .map_err(|err| format!("invalid glob `{}`: {}", pattern, err))
+        })
+        .collect()
+}
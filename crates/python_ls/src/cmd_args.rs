@@ -0,0 +1,50 @@
+use clap::{Parser, ValueEnum};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "A language server for Python")]
+pub struct CmdArgs {
+    /// Communication method
+    #[arg(long, short, default_value = "stdio")]
+    pub communication: Communication,
+
+    /// IP address to listen on, only used with tcp
+    #[arg(long, default_value = "127.0.0.1")]
+    pub ip: String,
+
+    /// Port number, only used with tcp
+    #[arg(long, default_value_t = 5007)]
+    pub port: u16,
+
+    /// Directory of the log file, logs are written to stderr when not set
+    #[arg(long)]
+    pub log_path: Option<String>,
+
+    /// Logging level
+    #[arg(long, default_value = "info")]
+    pub log_level: LogLevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Communication {
+    Stdio,
+    Tcp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl From<LogLevel> for log::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+        }
+    }
+}
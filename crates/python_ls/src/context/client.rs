@@ -0,0 +1,106 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicI32, Ordering},
+    },
+    time::Duration,
+};
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    ConfigurationParams, PublishDiagnosticsParams,
+    notification::{Notification as _, PublishDiagnostics},
+    request::{Request as _, WorkspaceConfiguration},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::sync::oneshot;
+
+/// How long a request to the client waits for its response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends server initiated messages to the client
+pub struct ClientProxy {
+    conn: Connection,
+    id_counter: AtomicI32,
+    pending_requests: Mutex<HashMap<RequestId, oneshot::Sender<Response>>>,
+}
+
+impl ClientProxy {
+    pub fn new(conn: Connection) -> Self {
+        ClientProxy {
+            conn,
+            id_counter: AtomicI32::new(0),
+            pending_requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn send_notification(&self, method: &str, params: impl Serialize) {
+        let notification = Notification::new(method.to_string(), params);
+        if let Err(err) = self.conn.sender.send(Message::Notification(notification)) {
+            log::error!("Failed to send notification: {:?}", err);
+        }
+    }
+
+    /// Send a request and wait for the client's response. The response is
+    /// routed back by `on_response`, so this must not be awaited on the main
+    /// loop.
+    pub async fn send_request(&self, method: &str, params: impl Serialize) -> Option<Response> {
+        let id = RequestId::from(self.id_counter.fetch_add(1, Ordering::Relaxed));
+        let (sender, receiver) = oneshot::channel();
+        self.pending_requests
+            .lock()
+            .unwrap()
+            .insert(id.clone(), sender);
+
+        let request = Request::new(id.clone(), method.to_string(), params);
+        if let Err(err) = self.conn.sender.send(Message::Request(request)) {
+            log::error!("Failed to send request: {:?}", err);
+            self.pending_requests.lock().unwrap().remove(&id);
+            return None;
+        }
+        match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(response)) => Some(response),
+            _ => {
+                log::warn!("No response to {}", method);
+                self.pending_requests.lock().unwrap().remove(&id);
+                None
+            }
+        }
+    }
+
+    /// Hand a response to the request waiting for it, false when none is
+    pub fn on_response(&self, response: Response) -> bool {
+        let Some(sender) = self.pending_requests.lock().unwrap().remove(&response.id) else {
+            return false;
+        };
+        // the request may have timed out in the meantime
+        let _ = sender.send(response);
+        true
+    }
+
+    /// The settings of each requested item, `None` when the client failed to answer
+    pub async fn get_configuration<C: DeserializeOwned>(
+        &self,
+        params: ConfigurationParams,
+    ) -> Option<Vec<C>> {
+        let response = self
+            .send_request(WorkspaceConfiguration::METHOD, params)
+            .await?;
+        if let Some(err) = response.error {
+            log::error!("Failed to get configuration: {}", err.message);
+            return None;
+        }
+        match serde_json::from_value(response.result?) {
+            Ok(configs) => Some(configs),
+            Err(err) => {
+                log::error!("Invalid configuration: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn publish_diagnostics(&self, params: PublishDiagnosticsParams) {
+        self.send_notification(PublishDiagnostics::METHOD, params);
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use lsp_types::{ConfigurationItem, ConfigurationParams, InitializeParams, Uri};
+use python_code_analysis::{PyConfig, load_config_file, merge_config_value, uri_to_file_path};
+use serde_json::Value;
+
+use super::ClientProxy;
+
+/// The settings section of the client's workspace settings
+const CONFIG_SECTION: &str = "python_ls";
+
+/// Where the settings come from, from the lowest precedence to the highest:
+/// the config files of the workspace root, the initialization options, and
+/// the `python_ls` section of the client's workspace settings
+pub struct ConfigManager {
+    root_uri: Option<Uri>,
+    initialization_options: Option<Value>,
+    /// Whether the client answers `workspace/configuration`
+    supports_configuration: bool,
+    /// Settings the client pushed with `workspace/didChangeConfiguration`
+    pushed_settings: Mutex<Option<Value>>,
+}
+
+impl ConfigManager {
+    pub fn new(params: &InitializeParams) -> Self {
+        #[allow(deprecated)]
+        let root_uri = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| folder.uri.clone())
+            .or_else(|| params.root_uri.clone());
+        let supports_configuration = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.configuration)
+            .unwrap_or(false);
+        ConfigManager {
+            root_uri,
+            initialization_options: params.initialization_options.clone(),
+            supports_configuration,
+            pushed_settings: Mutex::new(None),
+        }
+    }
+
+    pub fn get_root_path(&self) -> Option<PathBuf> {
+        self.root_uri.as_ref().and_then(uri_to_file_path)
+    }
+
+    pub fn supports_configuration(&self) -> bool {
+        self.supports_configuration
+    }
+
+    /// Keep the settings of a `workspace/didChangeConfiguration` which
+    /// carries them, clients which answer `workspace/configuration` are asked instead
+    pub fn set_pushed_settings(&self, settings: Value) {
+        if let Some(settings) = settings.get(CONFIG_SECTION) {
+            *self.pushed_settings.lock().unwrap() = Some(settings.clone());
+        }
+    }
+
+    /// The settings which are known without asking the client
+    pub fn load_local_config(&self) -> Option<PyConfig> {
+        parse_config(self.load_local_value(), self.get_root_path().as_deref())
+    }
+
+    /// The settings of every source
+    pub async fn load_config(&self, client: &ClientProxy) -> Option<PyConfig> {
+        let mut value = self.load_local_value();
+        let client_settings = if self.supports_configuration {
+            let params = ConfigurationParams {
+                items: vec![ConfigurationItem {
+                    scope_uri: self.root_uri.clone(),
+                    section: Some(CONFIG_SECTION.to_string()),
+                }],
+            };
+            client
+                .get_configuration::<Value>(params)
+                .await
+                .and_then(|configs| configs.into_iter().next())
+        } else {
+            self.pushed_settings.lock().unwrap().clone()
+        };
+        // clients answer `null` for a section the user never set
+        if let Some(settings) = client_settings.filter(|it| !it.is_null()) {
+            merge_optional(&mut value, settings);
+        }
+        parse_config(value, self.get_root_path().as_deref())
+    }
+
+    fn load_local_value(&self) -> Option<Value> {
+        let mut value = self
+            .get_root_path()
+            .and_then(|root| load_config_file(&root));
+        if let Some(options) = self.initialization_options.clone() {
+            merge_optional(&mut value, options);
+        }
+        value
+    }
+}
+
+fn merge_optional(base: &mut Option<Value>, overlay: Value) {
+    match base {
+        Some(base) => merge_config_value(base, overlay),
+        None => *base = Some(overlay),
+    }
+}
+
+/// `None` when the settings are invalid, the default ones when there are
+/// none. Their paths and globs are relative to `root`.
+fn parse_config(value: Option<Value>, root: Option<&Path>) -> Option<PyConfig> {
+    let config = match value {
+        Some(value) => serde_json::from_value::<PyConfig>(value).map_err(|err| err.to_string()),
+        None => Ok(PyConfig::default()),
+    };
+    let config = config.and_then(|mut config| {
+        config.set_root(root)?;
+        Ok(config)
+    });
+    match config {
+        Ok(config) => Some(config),
+        Err(err) => {
+            log::error!("Invalid config: {}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,122 @@
+mod client;
+mod config_manager;
+mod progress;
+mod snapshot;
+mod workspace_manager;
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+pub use client::ClientProxy;
+pub use config_manager::ConfigManager;
+use lsp_server::{Connection, ErrorCode, Message, RequestId, Response};
+pub use progress::ProgressTask;
+use python_code_analysis::{CancelHandle, PyAnalysis};
+pub use snapshot::ServerContextSnapshot;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+pub use workspace_manager::WorkspaceManager;
+
+pub struct ServerContext {
+    conn: Connection,
+    client: Arc<ClientProxy>,
+    analysis: Arc<RwLock<PyAnalysis>>,
+    cancel_handle: CancelHandle,
+    config_manager: Arc<ConfigManager>,
+    workspace_manager: Arc<WorkspaceManager>,
+    cancellations: Arc<Mutex<HashMap<RequestId, CancellationToken>>>,
+}
+
+impl ServerContext {
+    pub fn new(
+        conn: Connection,
+        config_manager: ConfigManager,
+        workspace_manager: WorkspaceManager,
+    ) -> Self {
+        let client = ClientProxy::new(Connection {
+            sender: conn.sender.clone(),
+            receiver: conn.receiver.clone(),
+        });
+        let analysis = PyAnalysis::new();
+        ServerContext {
+            conn,
+            client: Arc::new(client),
+            cancel_handle: analysis.cancel_handle(),
+            analysis: Arc::new(RwLock::new(analysis)),
+            config_manager: Arc::new(config_manager),
+            workspace_manager: Arc::new(workspace_manager),
+            cancellations: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn snapshot(&self) -> ServerContextSnapshot {
+        ServerContextSnapshot {
+            analysis: self.analysis.clone(),
+            cancel_handle: self.cancel_handle.clone(),
+            client: self.client.clone(),
+            config_manager: self.config_manager.clone(),
+            workspace_manager: self.workspace_manager.clone(),
+        }
+    }
+
+    pub fn send(&self, response: Response) {
+        if let Err(err) = self.conn.sender.send(Message::Response(response)) {
+            log::error!("Failed to send response: {:?}", err);
+        }
+    }
+
+    /// Run a request on the runtime. A request which is cancelled before it
+    /// finishes is stopped and answered with `RequestCancelled`.
+    pub fn task<F, Fut>(&self, req_id: RequestId, exec: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = Option<Response>> + Send + 'static,
+    {
+        let cancel_token = CancellationToken::new();
+        self.cancellations
+            .lock()
+            .unwrap()
+            .insert(req_id.clone(), cancel_token.clone());
+
+        let sender = self.conn.sender.clone();
+        let cancellations = self.cancellations.clone();
+        tokio::spawn(async move {
+            let response = tokio::select! {
+                response = exec(cancel_token.clone()) => response,
+                _ = cancel_token.cancelled() => None,
+            };
+            cancellations.lock().unwrap().remove(&req_id);
+            let response = if cancel_token.is_cancelled() {
+                Some(Response::new_err(
+                    req_id,
+                    ErrorCode::RequestCanceled as i32,
+                    "request cancelled".to_string(),
+                ))
+            } else {
+                response
+            };
+            if let Some(response) = response
+                && let Err(err) = sender.send(Message::Response(response))
+            {
+                log::error!("Failed to send response: {:?}", err);
+            }
+        });
+    }
+
+    /// Cancel a request which is still running, for `$/cancelRequest`
+    pub fn cancel(&self, req_id: RequestId) {
+        if let Some(token) = self.cancellations.lock().unwrap().get(&req_id) {
+            token.cancel();
+        }
+    }
+
+    pub fn close(&self) {
+        for (_, token) in self.cancellations.lock().unwrap().drain() {
+            token.cancel();
+        }
+        self.workspace_manager.close();
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicI32, Ordering},
+};
+
+use lsp_types::{
+    NumberOrString, ProgressParams, ProgressParamsValue, WorkDoneProgress, WorkDoneProgressBegin,
+    WorkDoneProgressCreateParams, WorkDoneProgressEnd, WorkDoneProgressReport,
+    notification::{Notification as _, Progress},
+    request::{Request as _, WorkDoneProgressCreate},
+};
+
+use super::ClientProxy;
+
+static TOKEN_COUNTER: AtomicI32 = AtomicI32::new(0);
+
+/// A long running task shown by the client through `$/progress`
+pub struct ProgressTask {
+    client: Arc<ClientProxy>,
+    token: NumberOrString,
+}
+
+impl ProgressTask {
+    /// Ask the client to show a progress, `None` when it refuses or does not
+    /// support them
+    pub async fn create(client: Arc<ClientProxy>, title: &str) -> Option<ProgressTask> {
+        let token = NumberOrString::String(format!(
+            "python_ls/{}",
+            TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let params = WorkDoneProgressCreateParams {
+            token: token.clone(),
+        };
+        let response = client
+            .send_request(WorkDoneProgressCreate::METHOD, params)
+            .await?;
+        if let Some(err) = response.error {
+            log::warn!("Failed to create progress: {}", err.message);
+            return None;
+        }
+        let task = ProgressTask { client, token };
+        task.send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: title.to_string(),
+            cancellable: Some(false),
+            message: None,
+            percentage: Some(0),
+        }));
+        Some(task)
+    }
+
+    pub fn report(&self, message: String, percentage: u32) {
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: Some(false),
+            message: Some(message),
+            percentage: Some(percentage.min(100)),
+        }));
+    }
+
+    pub fn finish(self, message: String) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd {
+            message: Some(message),
+        }));
+    }
+
+    fn send(&self, progress: WorkDoneProgress) {
+        self.client.send_notification(
+            Progress::METHOD,
+            ProgressParams {
+                token: self.token.clone(),
+                value: ProgressParamsValue::WorkDone(progress),
+            },
+        );
+    }
+}
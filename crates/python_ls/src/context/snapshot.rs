@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use python_code_analysis::{CancelHandle, PyAnalysis};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use super::{ClientProxy, ConfigManager, WorkspaceManager};
+
+#[derive(Clone)]
+pub struct ServerContextSnapshot {
+    pub analysis: Arc<RwLock<PyAnalysis>>,
+    /// Stops the queries holding the analysis, before an edit is applied
+    pub cancel_handle: CancelHandle,
+    pub client: Arc<ClientProxy>,
+    pub config_manager: Arc<ConfigManager>,
+    pub workspace_manager: Arc<WorkspaceManager>,
+}
+
+impl ServerContextSnapshot {
+    /// Run `f` on the blocking thread pool against the analysis as it is
+    /// now, so that long requests do not hold up the runtime. Edits wait
+    /// until `f` returns, and cancel the queries it runs meanwhile, as does
+    /// cancelling the request. `None` when the request was cancelled either
+    /// way, the result may lack what the cancelled queries would have added.
+    /// `cancel_token` is then cancelled, so that the request is answered
+    /// with `RequestCancelled`.
+    pub async fn with_analysis<F, R>(&self, cancel_token: &CancellationToken, f: F) -> Option<R>
+    where
+        F: FnOnce(&PyAnalysis) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let analysis = tokio::select! {
+            analysis = self.analysis.clone().read_owned() => analysis,
+            _ = cancel_token.cancelled() => return None,
+        };
+        let request_handle = CancelHandle::default();
+        let watcher = tokio::spawn({
+            let cancel_token = cancel_token.clone();
+            let request_handle = request_handle.clone();
+            async move {
+                cancel_token.cancelled().await;
+                request_handle.cancel();
+            }
+        });
+        let edit_handle = self.cancel_handle.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            if request_handle.is_cancelled() {
+                return None;
+            }
+            let result = request_handle.run(|| f(&analysis));
+            // checked while the analysis is still held, no edit was applied
+            if request_handle.is_cancelled() || edit_handle.is_cancelled() {
+                None
+            } else {
+                Some(result)
+            }
+        })
+        .await;
+        watcher.abort();
+        match result {
+            Ok(Some(result)) => Some(result),
+            Ok(None) => {
+                cancel_token.cancel();
+                None
+            }
+            Err(err) => {
+                log::error!("Analysis task failed: {}", err);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use lsp_types::{InitializeParams, Uri};
+use notify::RecommendedWatcher;
+use python_code_analysis::WorkspaceFilter;
+use tokio_util::sync::CancellationToken;
+
+/// A document the client has open
+struct OpenFile {
+    uri: Uri,
+    /// The version of the last edit the client sent
+    version: i32,
+    /// Cancels the diagnostics which wait for the edits to settle
+    pending_diagnostics: Option<CancellationToken>,
+}
+
+/// The files of the workspace: the documents the client has open, whose
+/// text comes from the client, and the files on disk which are indexed
+pub struct WorkspaceManager {
+    /// Whether the client shows `$/progress`
+    supports_work_done_progress: bool,
+    /// The open documents by their uri text
+    open_files: Mutex<HashMap<String, OpenFile>>,
+    /// The filter of the last indexing, `None` before the first one
+    filter: Mutex<Option<Arc<WorkspaceFilter>>>,
+    /// Watches the workspace root until it is dropped
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl WorkspaceManager {
+    pub fn new(params: &InitializeParams) -> Self {
+        let supports_work_done_progress = params
+            .capabilities
+            .window
+            .as_ref()
+            .and_then(|window| window.work_done_progress)
+            .unwrap_or(false);
+        WorkspaceManager {
+            supports_work_done_progress,
+            open_files: Mutex::new(HashMap::new()),
+            filter: Mutex::new(None),
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub fn supports_work_done_progress(&self) -> bool {
+        self.supports_work_done_progress
+    }
+
+    pub fn open_file(&self, uri: &Uri, version: i32) {
+        let file = OpenFile {
+            uri: uri.clone(),
+            version,
+            pending_diagnostics: None,
+        };
+        if let Some(old) = self
+            .open_files
+            .lock()
+            .unwrap()
+            .insert(uri.as_str().to_string(), file)
+        {
+            old.cancel_diagnostics();
+        }
+    }
+
+    pub fn close_file(&self, uri: &Uri) {
+        if let Some(file) = self.open_files.lock().unwrap().remove(uri.as_str()) {
+            file.cancel_diagnostics();
+        }
+    }
+
+    pub fn set_version(&self, uri: &Uri, version: i32) {
+        if let Some(file) = self.open_files.lock().unwrap().get_mut(uri.as_str()) {
+            file.version = version;
+        }
+    }
+
+    /// The version of an open document, `None` when it is not open
+    pub fn get_version(&self, uri: &Uri) -> Option<i32> {
+        self.open_files
+            .lock()
+            .unwrap()
+            .get(uri.as_str())
+            .map(|file| file.version)
+    }
+
+    /// Cancel the diagnostics of a document which are waiting and return the
+    /// token of the next ones, `None` when the document is not open
+    pub fn debounce_diagnostics(&self, uri: &Uri) -> Option<CancellationToken> {
+        let mut open_files = self.open_files.lock().unwrap();
+        let file = open_files.get_mut(uri.as_str())?;
+        file.cancel_diagnostics();
+        let token = CancellationToken::new();
+        file.pending_diagnostics = Some(token.clone());
+        Some(token)
+    }
+
+    pub fn get_open_files(&self) -> Vec<Uri> {
+        self.open_files
+            .lock()
+            .unwrap()
+            .values()
+            .map(|file| file.uri.clone())
+            .collect()
+    }
+
+    pub fn get_filter(&self) -> Option<Arc<WorkspaceFilter>> {
+        self.filter.lock().unwrap().clone()
+    }
+
+    pub fn set_filter(&self, filter: Arc<WorkspaceFilter>) {
+        *self.filter.lock().unwrap() = Some(filter);
+    }
+
+    pub fn set_watcher(&self, watcher: RecommendedWatcher) {
+        *self.watcher.lock().unwrap() = Some(watcher);
+    }
+
+    /// Stop watching the workspace
+    pub fn close(&self) {
+        self.watcher.lock().unwrap().take();
+        for file in self.open_files.lock().unwrap().values() {
+            file.cancel_diagnostics();
+        }
+    }
+}
+
+impl OpenFile {
+    fn cancel_diagnostics(&self) {
+        if let Some(token) = &self.pending_diagnostics {
+            token.cancel();
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use lsp_types::{CodeActionKind, TextEdit};
+use python_parser::{
+    PyAstNode, PyBinaryExpr, PyCallExpr, PyExpr, PyKeyword, PyLiteralExpr, PySyntaxNode,
+    PyTokenKind,
+};
+
+use crate::handlers::code_actions::CodeActionBuilder;
+
+/// Rewrite `"..." % args` and `"...".format(args)` under the cursor as an f-string
+pub fn convert_to_fstring(builder: &mut CodeActionBuilder) {
+    let level = builder
+        .get_semantic_model()
+        .get_db()
+        .get_vfs()
+        .get_parser_config()
+        .level;
+    if !level.support_f_string() {
+        return;
+    }
+    let Some(token) = builder.get_token() else {
+        return;
+    };
+    let Some((node, new_text)) = token.parent_ancestors().find_map(|node| {
+        let new_text = if let Some(binary) = PyBinaryExpr::cast(node.clone()) {
+            convert_percent_format(&binary)
+        } else if let Some(call) = PyCallExpr::cast(node.clone()) {
+            convert_format_call(&call)
+        } else {
+            None
+        }?;
+        Some((node, new_text))
+    }) else {
+        return;
+    };
+    let Some(range) = builder.get_document().to_lsp_range(node.text_range()) else {
+        return;
+    };
+    builder.add_edits(
+        "Convert to f-string".to_string(),
+        CodeActionKind::REFACTOR_REWRITE,
+        vec![TextEdit { range, new_text }],
+        None,
+    );
+}
+
+/// A plain string literal split into its quotes and body
+struct StringParts {
+    quote: String,
+    body: String,
+}
+
+impl StringParts {
+    fn from_literal(literal: &PyLiteralExpr) -> Option<StringParts> {
+        let mut tokens = literal.syntax().children_with_tokens();
+        let token = tokens.next()?.into_token()?;
+        // implicit concatenation or anything but a plain string
+        if tokens.next().is_some() || token.kind() != PyTokenKind::TkString.into() {
+            return None;
+        }
+        let text = token.text();
+        let text = text.strip_prefix(['u', 'U']).unwrap_or(text);
+        let quote = ["\"\"\"", "'''", "\"", "'"]
+            .into_iter()
+            .find(|quote| text.starts_with(quote))?;
+        let body = text.strip_prefix(quote)?.strip_suffix(quote)?;
+        Some(StringParts {
+            quote: quote.to_string(),
+            body: body.to_string(),
+        })
+    }
+
+    fn to_fstring(&self, body: &str) -> String {
+        format!("f{}{}{}", self.quote, body, self.quote)
+    }
+}
+
+fn convert_percent_format(binary: &PyBinaryExpr) -> Option<String> {
+    if binary.get_op()? != "%" {
+        return None;
+    }
+    let PyExpr::LiteralExpr(literal) = binary.get_left()? else {
+        return None;
+    };
+    let parts = StringParts::from_literal(&literal)?;
+    let args: Vec<PyExpr> = match binary.get_right()? {
+        PyExpr::ParenExpr(paren) => match paren.child::<PyExpr>()? {
+            PyExpr::TupleExpr(tuple) => tuple.get_exprs().collect(),
+            // `"%s" % (x)` is not a tuple, x may be one at runtime
+            _ => return None,
+        },
+        PyExpr::TupleExpr(tuple) => tuple.get_exprs().collect(),
+        // other values may be tuples at runtime, only literals are certain
+        expr @ PyExpr::LiteralExpr(_) => vec![expr],
+        _ => return None,
+    };
+    let mut args = args.iter();
+
+    let mut body = String::new();
+    let mut chars = parts.body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => body.push_str("{{"),
+            '}' => body.push_str("}}"),
+            '%' => {
+                if chars.peek() == Some(&'%') {
+                    chars.next();
+                    body.push('%');
+                    continue;
+                }
+                let (conversion, spec) = parse_percent_spec(&mut chars)?;
+                let arg = args.next()?;
+                // `%d` truncates a float where `:d` raises
+                if "dixXo".contains(conversion) && is_float_literal(arg) {
+                    return None;
+                }
+                let arg = expr_text(arg.syntax(), false)?;
+                body.push_str(&format!("{{{}{}}}", arg, spec));
+            }
+            _ => body.push(c),
+        }
+    }
+    if args.next().is_some() {
+        return None;
+    }
+    Some(parts.to_fstring(&body))
+}
+
+/// Translate the printf style specifier after a `%` into the conversion and
+/// format spec of a replacement field, along with the conversion character
+fn parse_percent_spec(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<(char, String)> {
+    let mut flags = String::new();
+    while let Some(c) = chars.peek().copied().filter(|c| "-+ #0".contains(*c)) {
+        flags.push(c);
+        chars.next();
+    }
+    let mut width = String::new();
+    while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+        width.push(c);
+        chars.next();
+    }
+    let mut precision = String::new();
+    if chars.peek() == Some(&'.') {
+        chars.next();
+        precision.push('.');
+        while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+            precision.push(c);
+            chars.next();
+        }
+    }
+    // length modifiers have no effect in Python
+    while chars.peek().is_some_and(|c| "hlL".contains(*c)) {
+        chars.next();
+    }
+    // `%(name)s`, `%*d` and `%c` have no direct f-string form
+    let conversion = chars.next()?;
+    if !"sradifFeEgGxXo".contains(conversion) {
+        return None;
+    }
+
+    let is_text = matches!(conversion, 's' | 'r' | 'a');
+    let mut spec = String::new();
+    if flags.contains('-') {
+        spec.push('<');
+    } else if is_text && !width.is_empty() {
+        // `%5s` pads on the left, a str replacement field pads on the right
+        spec.push('>');
+    }
+    if flags.contains('+') {
+        spec.push('+');
+    } else if flags.contains(' ') {
+        spec.push(' ');
+    }
+    if flags.contains('#') {
+        spec.push('#');
+    }
+    if flags.contains('0') && !flags.contains('-') && !is_text {
+        spec.push('0');
+    }
+    spec.push_str(&width);
+    spec.push_str(&precision);
+    // the type is kept even without other options, `{x}` would format a
+    // float or a str where `%d` converts or raises
+    let type_char = match conversion {
+        's' | 'r' | 'a' => String::new(),
+        'i' => "d".to_string(),
+        c => c.to_string(),
+    };
+    spec.push_str(&type_char);
+
+    let conversion_text = match conversion {
+        'r' => "!r",
+        'a' => "!a",
+        's' if !spec.is_empty() => "!s",
+        _ => "",
+    };
+    if spec.is_empty() {
+        Some((conversion, conversion_text.to_string()))
+    } else {
+        Some((conversion, format!("{}:{}", conversion_text, spec)))
+    }
+}
+
+fn is_float_literal(expr: &PyExpr) -> bool {
+    let PyExpr::LiteralExpr(literal) = expr else {
+        return false;
+    };
+    literal
+        .syntax()
+        .first_token()
+        .is_some_and(|token| token.kind() == PyTokenKind::TkFloat.into())
+}
+
+fn convert_format_call(call: &PyCallExpr) -> Option<String> {
+    let PyExpr::AttributeExpr(attribute) = call.get_func()? else {
+        return None;
+    };
+    if attribute.get_attr()? != "format" {
+        return None;
+    }
+    let PyExpr::LiteralExpr(literal) = attribute.get_value()? else {
+        return None;
+    };
+    let parts = StringParts::from_literal(&literal)?;
+
+    let mut positional = Vec::new();
+    let mut keywords = Vec::new();
+    for child in call.get_args_list()?.syntax().children() {
+        if let Some(expr) = PyExpr::cast(child.clone()) {
+            if matches!(expr, PyExpr::StarredExpr(_) | PyExpr::DoubleStarredExpr(_)) {
+                return None;
+            }
+            positional.push(expr);
+        } else if let Some(keyword) = PyKeyword::cast(child) {
+            let name = keyword.syntax().first_child().and_then(PyExpr::cast)?;
+            let value = keyword
+                .syntax()
+                .children()
+                .filter_map(PyExpr::cast)
+                .nth(1)?;
+            keywords.push((name.syntax().text().to_string(), value));
+        } else {
+            return None;
+        }
+    }
+
+    let mut uses = vec![0; positional.len() + keywords.len()];
+    let mut auto_index = Some(0);
+    let mut manual = false;
+    let mut body = String::new();
+    let mut chars = parts.body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                body.push_str("{{");
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                body.push_str("}}");
+            }
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        // nested replacement fields in the format spec
+                        '{' => return None,
+                        c => field.push(c),
+                    }
+                }
+                let name_end = field.find(['.', '[', '!', ':']).unwrap_or(field.len());
+                let (name, rest) = field.split_at(name_end);
+                let index = if name.is_empty() {
+                    let index = auto_index?;
+                    auto_index = Some(index + 1);
+                    index
+                } else if let Ok(index) = name.parse::<usize>() {
+                    manual = true;
+                    index
+                } else {
+                    positional.len() + keywords.iter().position(|(key, _)| key == name)?
+                };
+                let arg = match positional.get(index) {
+                    Some(expr) => expr,
+                    None => &keywords.get(index - positional.len())?.1,
+                };
+                uses[index] += 1;
+                // a repeated field would evaluate the argument more than once
+                if uses[index] > 1 && !matches!(arg, PyExpr::NameExpr(_)) {
+                    return None;
+                }
+                let (suffix, conversion) = match rest.find(['!', ':']) {
+                    Some(at) => rest.split_at(at),
+                    None => (rest, ""),
+                };
+                let suffix = convert_field_suffix(suffix, &parts.quote)?;
+                // `{0.real}` of `format(1 + 2)` is `(1 + 2).real`
+                let force_parens = !suffix.is_empty() && !matches!(arg, PyExpr::NameExpr(_));
+                let text = expr_text(arg.syntax(), force_parens)?;
+                body.push_str(&format!("{{{}{}{}}}", text, suffix, conversion));
+            }
+            // a single `}` is an error in a format string
+            '}' => return None,
+            _ => body.push(c),
+        }
+    }
+    // mixing automatic and manual numbering is an error, unused arguments
+    // would no longer be evaluated
+    if (manual && auto_index != Some(0)) || uses.contains(&0) {
+        return None;
+    }
+    Some(parts.to_fstring(&body))
+}
+
+/// The attribute and index accesses after a field name as Python source.
+/// An index which is not all digits is a str key, `{0[name]}` is `d['name']`
+fn convert_field_suffix(suffix: &str, quote: &str) -> Option<String> {
+    // the key is quoted with the quote the f-string does not use
+    let key_quote = if quote.contains('\'') { '"' } else { '\'' };
+    let mut result = String::new();
+    let mut rest = suffix;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let (key, after) = after.split_once(']')?;
+            if key.is_empty() {
+                return None;
+            }
+            if key.bytes().all(|b| b.is_ascii_digit()) {
+                result.push_str(&format!("[{}]", key));
+            } else if key.contains([key_quote, '\\']) {
+                return None;
+            } else {
+                result.push_str(&format!("[{}{}{}]", key_quote, key, key_quote));
+            }
+            rest = after;
+        } else {
+            let end = rest[1..].find(['.', '[']).map_or(rest.len(), |at| at + 1);
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    Some(result)
+}
+
+/// The source of an argument as it can appear inside a replacement field
+fn expr_text(node: &PySyntaxNode, force_parens: bool) -> Option<String> {
+    let text = node.text().to_string();
+    // quotes, backslashes and comments are not allowed in the expressions
+    // of an f-string before Python 3.12
+    if text.contains(['\'', '"', '\\', '\n', '#']) {
+        return None;
+    }
+    if force_parens || text.contains([':', '!', '{', '}']) {
+        return Some(format!("({})", text));
+    }
+    Some(text)
+}
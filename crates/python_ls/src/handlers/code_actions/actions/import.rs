@@ -0,0 +1,64 @@
+use lsp_types::CodeActionKind;
+use python_code_analysis::{PyDeclKind, add_import_fixes, is_builtin_name, remove_import_fix};
+use python_parser::{PyAstNode, PyAstToken, PyNameExpr, PySyntaxId, PySyntaxKind};
+
+use crate::handlers::code_actions::CodeActionBuilder;
+
+/// Import an unresolved name under the cursor
+pub fn add_missing_import(builder: &mut CodeActionBuilder) {
+    let Some(name_expr) = builder
+        .get_token()
+        .and_then(|token| token.parent())
+        .and_then(PyNameExpr::cast)
+    else {
+        return;
+    };
+    let Some(name) = name_expr.get_name_token() else {
+        return;
+    };
+    let semantic_model = builder.get_semantic_model();
+    let name_text = name.get_name_text();
+    if is_builtin_name(name_text)
+        || semantic_model
+            .find_decl(name_text, name.get_position())
+            .is_some()
+    {
+        return;
+    }
+    for fix in add_import_fixes(semantic_model, name_text) {
+        builder.add_workspace_edit(fix.title, CodeActionKind::QUICKFIX, fix.edit, None, false);
+    }
+}
+
+/// Remove the names of the import statement under the cursor which are never used
+pub fn remove_unused_import(builder: &mut CodeActionBuilder) {
+    let Some(stmt) = builder.get_token().and_then(|token| {
+        token.parent_ancestors().find(|node| {
+            matches!(
+                node.kind().into(),
+                PySyntaxKind::ImportStmt | PySyntaxKind::ImportFromStmt
+            )
+        })
+    }) else {
+        return;
+    };
+    let semantic_model = builder.get_semantic_model();
+    let syntax_id = PySyntaxId::from_node(&stmt);
+    let unused: Vec<_> = semantic_model
+        .get_decl_tree()
+        .get_decls()
+        .iter()
+        .filter(|decl| decl.get_syntax_id() == Some(syntax_id))
+        // `from __future__ import ...` changes how the module compiles
+        .filter(|decl| {
+            !matches!(decl.get_kind(), PyDeclKind::ImportFrom { module, .. } if module == "__future__")
+        })
+        .filter(|decl| !semantic_model.is_decl_referenced(decl))
+        .collect();
+    if unused.is_empty() {
+        return;
+    }
+    if let Some(fix) = remove_import_fix(semantic_model, &stmt, &unused) {
+        builder.add_workspace_edit(fix.title, CodeActionKind::QUICKFIX, fix.edit, None, false);
+    }
+}
@@ -0,0 +1,54 @@
+mod fstring;
+mod import;
+mod suppress;
+mod wrap_try;
+
+use lsp_types::{CodeActionKind, Diagnostic};
+use python_code_analysis::DiagnosticData;
+
+use super::CodeActionBuilder;
+
+/// Offers actions for one diagnostic the client sent with the request
+type DiagnosticAction = fn(&mut CodeActionBuilder, &Diagnostic);
+/// Offers actions for the requested range, whatever its diagnostics are
+type RangeAction = fn(&mut CodeActionBuilder);
+
+const DIAGNOSTIC_ACTIONS: &[DiagnosticAction] = &[
+    attached_fixes,
+    suppress::add_noqa,
+    suppress::add_type_ignore,
+];
+
+const RANGE_ACTIONS: &[RangeAction] = &[
+    import::add_missing_import,
+    import::remove_unused_import,
+    wrap_try::wrap_in_try_except,
+    fstring::convert_to_fstring,
+];
+
+pub fn build_actions(builder: &mut CodeActionBuilder, diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        for action in DIAGNOSTIC_ACTIONS {
+            action(builder, diagnostic);
+        }
+    }
+    for action in RANGE_ACTIONS {
+        action(builder);
+    }
+}
+
+/// The fixes computed with the diagnostic and stored in its data
+fn attached_fixes(builder: &mut CodeActionBuilder, diagnostic: &Diagnostic) {
+    let Some(data) = DiagnosticData::from_diagnostic(diagnostic) else {
+        return;
+    };
+    for (i, fix) in data.fixes.into_iter().enumerate() {
+        builder.add_workspace_edit(
+            fix.title,
+            CodeActionKind::QUICKFIX,
+            fix.edit,
+            Some(diagnostic),
+            i == 0,
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use lsp_types::{CodeActionKind, Diagnostic, NumberOrString, Range, TextEdit};
+use python_parser::{PyAstNode, PySyntaxToken, PyTokenKind};
+use rowan::TextSize;
+
+use crate::handlers::code_actions::CodeActionBuilder;
+
+/// `# noqa: CODE` at the end of the line the diagnostic starts on
+pub fn add_noqa(builder: &mut CodeActionBuilder, diagnostic: &Diagnostic) {
+    let Some(code) = suppressible_code(builder, diagnostic) else {
+        return;
+    };
+    let line = diagnostic.range.start.line as usize;
+    let edit = match find_line_comment(builder, line) {
+        // a bare `# noqa` already suppresses everything on the line
+        Some(comment) if comment.text().contains("noqa") => {
+            extend_code_list(builder, &comment, "noqa:", None, code)
+        }
+        _ => append_comment_edit(builder, line, format!("# noqa: {}", code)),
+    };
+    if let Some(edit) = edit {
+        builder.add_edits(
+            format!("Suppress `{}` with `# noqa`", code),
+            CodeActionKind::QUICKFIX,
+            vec![edit],
+            Some(diagnostic),
+        );
+    }
+}
+
+/// `# type: ignore[CODE]` at the end of the line the diagnostic starts on
+pub fn add_type_ignore(builder: &mut CodeActionBuilder, diagnostic: &Diagnostic) {
+    let Some(code) = suppressible_code(builder, diagnostic) else {
+        return;
+    };
+    let line = diagnostic.range.start.line as usize;
+    let edit = match find_line_comment(builder, line) {
+        Some(comment) if comment.text().contains("type: ignore") => {
+            extend_code_list(builder, &comment, "type: ignore[", Some(']'), code)
+        }
+        _ => append_comment_edit(builder, line, format!("# type: ignore[{}]", code)),
+    };
+    if let Some(edit) = edit {
+        builder.add_edits(
+            format!("Suppress `{}` with `# type: ignore`", code),
+            CodeActionKind::QUICKFIX,
+            vec![edit],
+            Some(diagnostic),
+        );
+    }
+}
+
+/// The code of a diagnostic this server published, syntax errors can not be suppressed
+fn suppressible_code<'a>(
+    builder: &CodeActionBuilder,
+    diagnostic: &'a Diagnostic,
+) -> Option<&'a str> {
+    let Some(NumberOrString::String(code)) = &diagnostic.code else {
+        return None;
+    };
+    builder
+        .get_lint_registry()
+        .find_suppressible(code)
+        .map(|_| code.as_str())
+}
+
+/// Add `code` to the code list which follows `marker` in an existing
+/// suppression comment. `None` when the comment suppresses it already.
+fn extend_code_list(
+    builder: &CodeActionBuilder,
+    comment: &PySyntaxToken,
+    marker: &str,
+    terminator: Option<char>,
+    code: &str,
+) -> Option<TextEdit> {
+    let text = comment.text();
+    let start = text.find(marker)? + marker.len();
+    let end = match terminator {
+        Some(terminator) => start + text[start..].find(terminator)?,
+        None => start + text[start..].trim_end().len(),
+    };
+    if text[start..end].split(',').any(|it| it.trim() == code) {
+        return None;
+    }
+    let offset = comment.text_range().start() + TextSize::from(end as u32);
+    let position = builder.get_document().to_lsp_position(offset)?;
+    Some(TextEdit {
+        range: Range::new(position, position),
+        new_text: format!(", {}", code),
+    })
+}
+
+/// Insert `comment` at the end of `line`, after an existing comment when there is one
+fn append_comment_edit(
+    builder: &CodeActionBuilder,
+    line: usize,
+    comment: String,
+) -> Option<TextEdit> {
+    let document = builder.get_document();
+    let line_range = document.get_line_range(line)?;
+    let content = document.get_text_slice(line_range).trim_end();
+    if content.is_empty() {
+        return None;
+    }
+    let offset = line_range.start() + TextSize::from(content.len() as u32);
+    let position = document.to_lsp_position(offset)?;
+    let separator = match find_line_comment(builder, line) {
+        Some(_) => " ",
+        None => "  ",
+    };
+    Some(TextEdit {
+        range: Range::new(position, position),
+        new_text: format!("{}{}", separator, comment),
+    })
+}
+
+fn find_line_comment(builder: &CodeActionBuilder, line: usize) -> Option<PySyntaxToken> {
+    let line_range = builder.get_document().get_line_range(line)?;
+    builder
+        .get_semantic_model()
+        .get_root()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| token.kind() == PyTokenKind::TkComment.into())
+        .find(|token| line_range.contains(token.text_range().start()))
+}
@@ -0,0 +1,134 @@
+use lsp_types::{CodeActionKind, TextEdit};
+use python_parser::{PySyntaxKind, PySyntaxNode, PyTokenKind};
+use rowan::{TextRange, TextSize};
+
+use crate::handlers::code_actions::CodeActionBuilder;
+
+/// Wrap the statements covered by the range in `try: ... except Exception: raise`
+pub fn wrap_in_try_except(builder: &mut CodeActionBuilder) {
+    let Some(stmts) = selected_statements(builder) else {
+        return;
+    };
+    let (Some(first), Some(last)) = (stmts.first(), stmts.last()) else {
+        return;
+    };
+    // re-indenting would change the content of a multi-line string
+    let has_multiline_string = stmts.iter().any(|stmt| {
+        stmt.descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .any(|token| is_string_token(token.kind().into()) && token.text().contains('\n'))
+    });
+    if has_multiline_string {
+        return;
+    }
+
+    let document = builder.get_document();
+    let start = first.text_range().start();
+    let Some(line_start) = document
+        .get_line(start)
+        .and_then(|line| document.get_line_range(line))
+        .map(|range| range.start())
+    else {
+        return;
+    };
+    let indent = document.get_text_slice(TextRange::new(line_start, start));
+    if !indent.chars().all(|c| c == ' ' || c == '\t') {
+        return;
+    }
+    let unit = if indent.contains('\t') { "\t" } else { "    " };
+
+    // the comment after the last statement stays with it, a `# noqa` there
+    // suppresses the errors of that statement
+    let last_end = last.text_range().end();
+    let Some(line_end) = document
+        .get_line(last_end)
+        .and_then(|line| document.get_line_range(line))
+        .map(|range| range.end())
+    else {
+        return;
+    };
+    let rest = document.get_text_slice(TextRange::new(last_end, line_end));
+    let rest = rest.trim_end_matches(['\n', '\r']);
+    let trimmed = rest.trim_start();
+    if !trimmed.is_empty() && !trimmed.starts_with('#') {
+        return;
+    }
+
+    let range = TextRange::new(line_start, last_end + TextSize::of(rest));
+    let body = document
+        .get_text_slice(range)
+        .split('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", unit, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let new_text = format!(
+        "{indent}try:\n{body}\n{indent}except Exception:\n{indent}{unit}raise",
+        indent = indent,
+        body = body,
+        unit = unit
+    );
+    let Some(range) = document.to_lsp_range(range) else {
+        return;
+    };
+    builder.add_edits(
+        "Wrap in `try`/`except`".to_string(),
+        CodeActionKind::REFACTOR_REWRITE,
+        vec![TextEdit { range, new_text }],
+        None,
+    );
+}
+
+/// The statements of one block which the range starts and ends in
+fn selected_statements(builder: &CodeActionBuilder) -> Option<Vec<PySyntaxNode>> {
+    let first = enclosing_statement(builder, builder.get_range().start())?;
+    let range = builder.get_range();
+    let last = if range.is_empty() {
+        first.clone()
+    } else {
+        enclosing_statement(builder, range.end() - TextSize::from(1))?
+    };
+    let suite = first.parent()?;
+    if last.parent().as_ref() != Some(&suite) {
+        return None;
+    }
+    let stmts: Vec<PySyntaxNode> = suite
+        .children()
+        .skip_while(|stmt| stmt != &first)
+        .take_while(|stmt| stmt.text_range().start() <= last.text_range().start())
+        .filter(|stmt| !stmt.text_range().is_empty())
+        .collect();
+    (!stmts.is_empty()).then_some(stmts)
+}
+
+/// The innermost statement which contains `offset`, `None` between statements
+fn enclosing_statement(builder: &CodeActionBuilder, offset: TextSize) -> Option<PySyntaxNode> {
+    let token = builder.token_at(offset)?;
+    let mut node = token.parent()?;
+    if node.kind() == PySyntaxKind::Suite.into() {
+        return None;
+    }
+    loop {
+        let parent = node.parent()?;
+        if parent.kind() == PySyntaxKind::Suite.into() {
+            return Some(node);
+        }
+        node = parent;
+    }
+}
+
+fn is_string_token(kind: PyTokenKind) -> bool {
+    matches!(
+        kind,
+        PyTokenKind::TkString
+            | PyTokenKind::TkBytesString
+            | PyTokenKind::TkRawString
+            | PyTokenKind::TkRawBytesString
+            | PyTokenKind::TkFString
+    )
+}
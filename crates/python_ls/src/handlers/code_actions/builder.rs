@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionResponse, Diagnostic, Range,
+    TextEdit, Uri, WorkspaceEdit,
+};
+use python_code_analysis::{LintRegistry, PyDocument, SemanticModel};
+use python_parser::{PyAstNode, PySyntaxToken, PyTokenKind};
+use rowan::{TextRange, TextSize, TokenAtOffset};
+
+pub struct CodeActionBuilder<'a> {
+    semantic_model: &'a SemanticModel<'a>,
+    lint_registry: &'a LintRegistry,
+    uri: Uri,
+    range: TextRange,
+    actions: Vec<CodeAction>,
+}
+
+impl<'a> CodeActionBuilder<'a> {
+    pub fn new(
+        semantic_model: &'a SemanticModel<'a>,
+        lint_registry: &'a LintRegistry,
+        range: Range,
+    ) -> Option<Self> {
+        let uri = semantic_model
+            .get_db()
+            .get_vfs()
+            .get_uri(&semantic_model.get_file_id())?;
+        let range = semantic_model.get_document().to_rowan_range(range)?;
+        Some(CodeActionBuilder {
+            semantic_model,
+            lint_registry,
+            uri,
+            range,
+            actions: Vec::new(),
+        })
+    }
+
+    pub fn get_semantic_model(&self) -> &'a SemanticModel<'a> {
+        self.semantic_model
+    }
+
+    /// The rules whose codes the diagnostics of the file are reported under
+    pub fn get_lint_registry(&self) -> &'a LintRegistry {
+        self.lint_registry
+    }
+
+    pub fn get_document(&self) -> &PyDocument<'a> {
+        self.semantic_model.get_document()
+    }
+
+    /// The range the client asked actions for
+    pub fn get_range(&self) -> TextRange {
+        self.range
+    }
+
+    /// The token at the start of the range
+    pub fn get_token(&self) -> Option<PySyntaxToken> {
+        self.token_at(self.range.start())
+    }
+
+    /// The token at `offset`, between two tokens the one which is not layout
+    pub fn token_at(&self, offset: TextSize) -> Option<PySyntaxToken> {
+        let root = self.semantic_model.get_root().syntax();
+        match root.token_at_offset(offset) {
+            TokenAtOffset::Single(token) => Some(token),
+            TokenAtOffset::Between(left, right) => {
+                if is_layout_token(&right) {
+                    Some(left)
+                } else {
+                    Some(right)
+                }
+            }
+            TokenAtOffset::None => None,
+        }
+    }
+
+    pub fn add_edits(
+        &mut self,
+        title: String,
+        kind: CodeActionKind,
+        edits: Vec<TextEdit>,
+        diagnostic: Option<&Diagnostic>,
+    ) {
+        let edit = WorkspaceEdit {
+            changes: Some(HashMap::from([(self.uri.clone(), edits)])),
+            ..Default::default()
+        };
+        self.add_workspace_edit(title, kind, edit, diagnostic, false);
+    }
+
+    /// Actions are keyed by title, a fix attached to a diagnostic and the
+    /// same fix offered for the range only show up once
+    pub fn add_workspace_edit(
+        &mut self,
+        title: String,
+        kind: CodeActionKind,
+        edit: WorkspaceEdit,
+        diagnostic: Option<&Diagnostic>,
+        is_preferred: bool,
+    ) {
+        if self.actions.iter().any(|action| action.title == title) {
+            return;
+        }
+        self.actions.push(CodeAction {
+            title,
+            kind: Some(kind),
+            diagnostics: diagnostic.map(|it| vec![it.clone()]),
+            edit: Some(edit),
+            is_preferred: is_preferred.then_some(true),
+            ..Default::default()
+        });
+    }
+
+    pub fn finish(self, only: Option<&[CodeActionKind]>) -> CodeActionResponse {
+        self.actions
+            .into_iter()
+            .filter(|action| {
+                let Some(only) = only else {
+                    return true;
+                };
+                let kind = action.kind.as_ref().map(|it| it.as_str()).unwrap_or("");
+                only.iter()
+                    .any(|it| kind == it.as_str() || kind.starts_with(&format!("{}.", it.as_str())))
+            })
+            .map(CodeActionOrCommand::CodeAction)
+            .collect()
+    }
+}
+
+fn is_layout_token(token: &PySyntaxToken) -> bool {
+    matches!(
+        token.kind().into(),
+        PyTokenKind::TkWhitespace
+            | PyTokenKind::TkNewline
+            | PyTokenKind::TkIndent
+            | PyTokenKind::TkDedent
+    )
+}
@@ -0,0 +1,67 @@
+mod actions;
+mod builder;
+
+use builder::CodeActionBuilder;
+use lsp_types::{
+    ClientCapabilities, CodeActionContext, CodeActionKind, CodeActionOptions, CodeActionOrCommand,
+    CodeActionParams, CodeActionProviderCapability, CodeActionResponse, Range, ServerCapabilities,
+};
+use python_code_analysis::{FileId, PyAnalysis};
+use tokio_util::sync::CancellationToken;
+
+use crate::{context::ServerContextSnapshot, handlers::notebook_document::DocumentMapping};
+
+pub async fn on_code_action_handler(
+    context: ServerContextSnapshot,
+    params: CodeActionParams,
+    cancel_token: CancellationToken,
+) -> Option<CodeActionResponse> {
+    let uri = params.text_document.uri;
+    context
+        .with_analysis(&cancel_token, move |analysis| {
+            let mapping = DocumentMapping::new(analysis, &uri)?;
+            let mut context = params.context;
+            for diagnostic in &mut context.diagnostics {
+                diagnostic.range = mapping.to_file_range(diagnostic.range)?;
+            }
+            let range = mapping.to_file_range(params.range)?;
+            let mut actions = code_action(analysis, mapping.file_id, range, &context)?;
+            for action in &mut actions {
+                let CodeActionOrCommand::CodeAction(action) = action else {
+                    continue;
+                };
+                if let Some(edit) = &mut action.edit {
+                    mapping.to_document_edit(edit);
+                }
+                for diagnostic in action.diagnostics.iter_mut().flatten() {
+                    diagnostic.range = mapping.to_document_range(diagnostic.range)?;
+                }
+            }
+            Some(actions)
+        })
+        .await?
+}
+
+pub fn code_action(
+    analysis: &PyAnalysis,
+    file_id: FileId,
+    range: Range,
+    context: &CodeActionContext,
+) -> Option<CodeActionResponse> {
+    let semantic_model = analysis.get_semantic_model(file_id)?;
+    let mut builder = CodeActionBuilder::new(&semantic_model, analysis.get_lint_registry(), range)?;
+    actions::build_actions(&mut builder, &context.diagnostics);
+    Some(builder.finish(context.only.as_deref()))
+}
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.code_action_provider =
+        Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(vec![
+                CodeActionKind::QUICKFIX,
+                CodeActionKind::REFACTOR_REWRITE,
+            ]),
+            resolve_provider: Some(false),
+            ..Default::default()
+        }));
+}
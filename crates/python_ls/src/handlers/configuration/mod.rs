@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use lsp_types::DidChangeConfigurationParams;
+use python_code_analysis::{PyConfig, PyEnvironment, discover_environment};
+
+use crate::{
+    context::{ConfigManager, ServerContextSnapshot},
+    handlers::{diagnostic::publish_open_files_diagnostics, workspace::index_workspace},
+};
+
+pub async fn on_did_change_configuration(
+    context: ServerContextSnapshot,
+    params: DidChangeConfigurationParams,
+) -> Option<()> {
+    context.config_manager.set_pushed_settings(params.settings);
+    // asking the client for its settings waits on the main loop
+    tokio::spawn(reload_config(context));
+    Some(())
+}
+
+/// Load the settings of every source and find the environment again, and
+/// publish the diagnostics of the open files when either changed. The
+/// workspace is indexed again when its files are configured differently.
+pub async fn reload_config(context: ServerContextSnapshot) {
+    let Some(config) = context.config_manager.load_config(&context.client).await else {
+        return;
+    };
+    let environment = find_environment(&context.config_manager, &config);
+    let mut analysis = context.analysis.write().await;
+    if *analysis.get_config() == config
+        && analysis.get_environment().as_deref() == environment.as_ref()
+    {
+        return;
+    }
+    log::info!("Config changed");
+    let workspace_changed = analysis.get_config().workspace != config.workspace;
+    analysis.set_environment(environment);
+    analysis.update_config(Arc::new(config));
+    publish_open_files_diagnostics(
+        &analysis,
+        &context.client,
+        &context.workspace_manager.get_open_files(),
+    );
+    drop(analysis);
+
+    if workspace_changed {
+        index_workspace(context).await;
+    }
+}
+
+/// The environment of the workspace root, the configured one if any
+pub fn find_environment(
+    config_manager: &ConfigManager,
+    config: &PyConfig,
+) -> Option<PyEnvironment> {
+    let root = config_manager.get_root_path()?;
+    discover_environment(&root, config.workspace.venv.as_deref())
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use lsp_types::{Diagnostic, PublishDiagnosticsParams, Uri};
+use python_code_analysis::{FileId, PyAnalysis, PyCellKind};
+
+use crate::context::{ClientProxy, ServerContextSnapshot};
+
+/// How long the diagnostics of an edited document wait for the next edit
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Push the diagnostics of `uri` to the client, nothing is published for
+/// files the analysis does not know
+pub fn publish_diagnostics(
+    analysis: &PyAnalysis,
+    client: &ClientProxy,
+    uri: &Uri,
+    version: Option<i32>,
+) {
+    let Some(file_id) = analysis.get_file_id(uri) else {
+        return;
+    };
+    let Some(diagnostics) = analysis.diagnose_file(file_id) else {
+        return;
+    };
+    send_diagnostics(analysis, client, file_id, uri.clone(), diagnostics, version);
+}
+
+/// Publish the diagnostics of an edited document once the edits settle.
+/// They are dropped when another edit of the document comes first, so no
+/// diagnostics of an outdated version reach the client.
+pub fn schedule_diagnostics(context: ServerContextSnapshot, uri: Uri, version: i32) {
+    let Some(token) = context.workspace_manager.debounce_diagnostics(&uri) else {
+        return;
+    };
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = tokio::time::sleep(DEBOUNCE) => {}
+        }
+        let client = context.client.clone();
+        let workspace_manager = context.workspace_manager.clone();
+        let edit_token = token.clone();
+        context
+            .with_analysis(&token, move |analysis| {
+                let Some(file_id) = analysis.get_file_id(&uri) else {
+                    return;
+                };
+                let Some(diagnostics) = analysis.diagnose_file(file_id) else {
+                    return;
+                };
+                // an edit may have come in while they were computed
+                if edit_token.is_cancelled() || workspace_manager.get_version(&uri) != Some(version)
+                {
+                    return;
+                }
+                send_diagnostics(analysis, &client, file_id, uri, diagnostics, Some(version));
+            })
+            .await;
+    });
+}
+
+/// Send the diagnostics of a file. The diagnostics of a notebook go to the
+/// cells they are in, each code cell gets its own list, the cell versions
+/// are not tracked.
+fn send_diagnostics(
+    analysis: &PyAnalysis,
+    client: &ClientProxy,
+    file_id: FileId,
+    uri: Uri,
+    diagnostics: Vec<Diagnostic>,
+    version: Option<i32>,
+) {
+    let Some(notebook) = analysis.get_notebook(file_id) else {
+        client.publish_diagnostics(PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        });
+        return;
+    };
+    let mut cell_diagnostics: Vec<Vec<Diagnostic>> = vec![Vec::new(); notebook.get_cells().len()];
+    for mut diagnostic in diagnostics {
+        let Some((cell, range)) = notebook.to_cell_range(diagnostic.range) else {
+            continue;
+        };
+        diagnostic.range = range;
+        cell_diagnostics[cell].push(diagnostic);
+    }
+    for (cell, diagnostics) in notebook.get_cells().iter().zip(cell_diagnostics) {
+        if cell.kind != PyCellKind::Code {
+            continue;
+        }
+        client.publish_diagnostics(PublishDiagnosticsParams {
+            uri: cell.uri.clone(),
+            diagnostics,
+            version: None,
+        });
+    }
+}
+
+/// Publish the diagnostics of the open documents again, after a change
+/// which they may depend on
+pub fn publish_open_files_diagnostics(
+    analysis: &PyAnalysis,
+    client: &ClientProxy,
+    open_files: &[Uri],
+) {
+    for uri in open_files {
+        publish_diagnostics(analysis, client, uri, None);
+    }
+}
+
+/// Clear the diagnostics of a closed file
+pub fn clear_diagnostics(client: &ClientProxy, uri: &Uri) {
+    client.publish_diagnostics(PublishDiagnosticsParams {
+        uri: uri.clone(),
+        diagnostics: Vec::new(),
+        version: None,
+    });
+}
@@ -0,0 +1,279 @@
+use lsp_types::{FoldingRange, FoldingRangeKind};
+use python_code_analysis::PyDocument;
+use python_parser::{PyAstNode, PyModule, PySyntaxKind, PySyntaxNode, PySyntaxToken, PyTokenKind};
+use rowan::{NodeOrToken, TextRange, TextSize};
+
+pub struct FoldingRangeBuilder<'a> {
+    document: &'a PyDocument<'a>,
+    ranges: Vec<FoldingRange>,
+    // (open bracket kind, line)
+    brackets: Vec<(PyTokenKind, usize)>,
+    regions: Vec<usize>,
+    // (first line, last line) of the comment block being collected
+    comment_block: Option<(usize, usize)>,
+}
+
+impl<'a> FoldingRangeBuilder<'a> {
+    pub fn new(document: &'a PyDocument<'a>) -> Self {
+        FoldingRangeBuilder {
+            document,
+            ranges: Vec::new(),
+            brackets: Vec::new(),
+            regions: Vec::new(),
+            comment_block: None,
+        }
+    }
+
+    pub fn build(&mut self, root: &PyModule) {
+        for element in root.syntax().descendants_with_tokens() {
+            match element {
+                NodeOrToken::Node(node) => self.visit_node(&node),
+                NodeOrToken::Token(token) => self.visit_token(&token),
+            }
+        }
+        self.flush_comment_block();
+    }
+
+    pub fn finish(mut self) -> Vec<FoldingRange> {
+        self.ranges
+            .sort_by_key(|range| (range.start_line, std::cmp::Reverse(range.end_line)));
+        self.ranges
+            .dedup_by(|a, b| a.start_line == b.start_line && a.end_line == b.end_line);
+        self.ranges
+    }
+
+    fn visit_node(&mut self, node: &PySyntaxNode) {
+        match node.kind().into() {
+            PySyntaxKind::Suite => {
+                self.fold_imports(node);
+                if let Some(header) = node
+                    .parent()
+                    .filter(|it| it.kind() != PySyntaxKind::Module.into())
+                {
+                    self.fold_suite(&header, node);
+                }
+            }
+            PySyntaxKind::Docstring => {
+                self.fold_node(node, Some(FoldingRangeKind::Comment));
+            }
+            PySyntaxKind::FStringExpr | PySyntaxKind::TStringExpr => {
+                self.fold_node(node, None);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_token(&mut self, token: &PySyntaxToken) {
+        let kind: PyTokenKind = token.kind().into();
+        if kind == PyTokenKind::TkComment {
+            self.visit_comment(token);
+            return;
+        }
+        if !is_layout_token(kind) {
+            self.flush_comment_block();
+        }
+
+        match kind {
+            PyTokenKind::TkLeftParen | PyTokenKind::TkLeftBracket | PyTokenKind::TkLeftBrace => {
+                if let Some(line) = self.get_line(token.text_range().start()) {
+                    self.brackets.push((kind, line));
+                }
+            }
+            PyTokenKind::TkRightParen | PyTokenKind::TkRightBracket | PyTokenKind::TkRightBrace => {
+                self.fold_bracket(token, kind)
+            }
+            PyTokenKind::TkString
+            | PyTokenKind::TkBytesString
+            | PyTokenKind::TkRawBytesString
+            | PyTokenKind::TkRawString
+                if token
+                    .parent()
+                    .is_none_or(|parent| parent.kind() != PySyntaxKind::Docstring.into()) =>
+            {
+                let range = token.text_range();
+                self.add_range(range.start(), range.end(), None);
+            }
+            _ => {}
+        }
+    }
+
+    /// From the header line of a compound statement to the last line of its body
+    fn fold_suite(&mut self, header: &PySyntaxNode, suite: &PySyntaxNode) {
+        // the keyword of the header, so that decorators stay visible
+        let Some(start) = header
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|token| !is_layout_token(token.kind().into()))
+            .map(|token| token.text_range().start())
+        else {
+            return;
+        };
+        if let Some(end) = content_end(suite) {
+            self.add_range(start, end, None);
+        }
+    }
+
+    /// Runs of consecutive import statements
+    fn fold_imports(&mut self, suite: &PySyntaxNode) {
+        let mut run: Option<(TextSize, TextSize)> = None;
+        for child in suite.children() {
+            let is_import = matches!(
+                child.kind().into(),
+                PySyntaxKind::ImportStmt | PySyntaxKind::ImportFromStmt
+            );
+            match (&mut run, is_import) {
+                (Some((_, end)), true) => *end = child.text_range().end(),
+                (None, true) => run = Some((child.text_range().start(), child.text_range().end())),
+                (Some((start, end)), false) => {
+                    let (start, end) = (*start, *end);
+                    self.add_range(start, end, Some(FoldingRangeKind::Imports));
+                    run = None;
+                }
+                (None, false) => {}
+            }
+        }
+        if let Some((start, end)) = run {
+            self.add_range(start, end, Some(FoldingRangeKind::Imports));
+        }
+    }
+
+    fn fold_node(&mut self, node: &PySyntaxNode, kind: Option<FoldingRangeKind>) {
+        let range = node.text_range();
+        self.add_range(range.start(), range.end(), kind);
+    }
+
+    fn fold_bracket(&mut self, token: &PySyntaxToken, kind: PyTokenKind) {
+        let open = match kind {
+            PyTokenKind::TkRightParen => PyTokenKind::TkLeftParen,
+            PyTokenKind::TkRightBracket => PyTokenKind::TkLeftBracket,
+            _ => PyTokenKind::TkLeftBrace,
+        };
+        // 括号不匹配时忽略这个右括号
+        if self.brackets.last().is_none_or(|(kind, _)| *kind != open) {
+            return;
+        }
+        let Some((_, start_line)) = self.brackets.pop() else {
+            return;
+        };
+        let Some(close_line) = self.get_line(token.text_range().start()) else {
+            return;
+        };
+        // keep a closing bracket which starts its own line visible
+        let end_line = if self.is_line_start(token) {
+            close_line.saturating_sub(1)
+        } else {
+            close_line
+        };
+        self.push(start_line, end_line, None);
+    }
+
+    fn visit_comment(&mut self, token: &PySyntaxToken) {
+        let Some(line) = self.get_line(token.text_range().start()) else {
+            return;
+        };
+        let text = token.text().trim_start_matches('#').trim_start();
+        if is_marker(text, "region") {
+            self.flush_comment_block();
+            self.regions.push(line);
+            return;
+        }
+        if is_marker(text, "endregion") {
+            self.flush_comment_block();
+            if let Some(start_line) = self.regions.pop() {
+                self.push(start_line, line, Some(FoldingRangeKind::Region));
+            }
+            return;
+        }
+
+        // only comments on their own line form a block
+        if !self.is_line_start(token) {
+            self.flush_comment_block();
+            return;
+        }
+        match &mut self.comment_block {
+            Some((_, last)) if *last + 1 == line => *last = line,
+            _ => {
+                self.flush_comment_block();
+                self.comment_block = Some((line, line));
+            }
+        }
+    }
+
+    fn flush_comment_block(&mut self) {
+        if let Some((start_line, end_line)) = self.comment_block.take() {
+            self.push(start_line, end_line, Some(FoldingRangeKind::Comment));
+        }
+    }
+
+    fn is_line_start(&self, token: &PySyntaxToken) -> bool {
+        let start = token.text_range().start();
+        let Some(line_range) = self
+            .get_line(start)
+            .and_then(|line| self.document.get_line_range(line))
+        else {
+            return false;
+        };
+        let before = TextRange::new(line_range.start(), start);
+        self.document
+            .get_text_slice(before)
+            .chars()
+            .all(|c| c == ' ' || c == '\t' || c == '\x0c')
+    }
+
+    fn add_range(&mut self, start: TextSize, end: TextSize, kind: Option<FoldingRangeKind>) {
+        // an end right after a newline belongs to the previous line
+        let end = if end > start {
+            end - TextSize::from(1)
+        } else {
+            end
+        };
+        let (Some(start_line), Some(end_line)) = (self.get_line(start), self.get_line(end)) else {
+            return;
+        };
+        self.push(start_line, end_line, kind);
+    }
+
+    fn push(&mut self, start_line: usize, end_line: usize, kind: Option<FoldingRangeKind>) {
+        if end_line <= start_line {
+            return;
+        }
+        self.ranges.push(FoldingRange {
+            start_line: start_line as u32,
+            start_character: None,
+            end_line: end_line as u32,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        });
+    }
+
+    fn get_line(&self, offset: TextSize) -> Option<usize> {
+        self.document.get_line(offset)
+    }
+}
+
+/// `region` in `# region setup` but not in `# regional settings`
+fn is_marker(text: &str, marker: &str) -> bool {
+    text.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+fn is_layout_token(kind: PyTokenKind) -> bool {
+    matches!(
+        kind,
+        PyTokenKind::TkWhitespace
+            | PyTokenKind::TkNewline
+            | PyTokenKind::TkIndent
+            | PyTokenKind::TkDedent
+            | PyTokenKind::TkComment
+    )
+}
+
+/// The end of the last token in `node` which is not layout or a comment
+fn content_end(node: &PySyntaxNode) -> Option<TextSize> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| !is_layout_token(token.kind().into()))
+        .last()
+        .map(|token| token.text_range().end())
+}
@@ -0,0 +1,50 @@
+mod builder;
+
+use builder::FoldingRangeBuilder;
+use lsp_types::{
+    ClientCapabilities, FoldingRange, FoldingRangeParams, FoldingRangeProviderCapability, Position,
+    ServerCapabilities,
+};
+use python_code_analysis::{FileId, PyAnalysis};
+use tokio_util::sync::CancellationToken;
+
+use crate::{context::ServerContextSnapshot, handlers::notebook_document::DocumentMapping};
+
+pub async fn on_folding_range_handler(
+    context: ServerContextSnapshot,
+    params: FoldingRangeParams,
+    cancel_token: CancellationToken,
+) -> Option<Vec<FoldingRange>> {
+    let uri = params.text_document.uri;
+    context
+        .with_analysis(&cancel_token, move |analysis| {
+            let mapping = DocumentMapping::new(analysis, &uri)?;
+            let ranges = folding_range(analysis, mapping.file_id)?;
+            // the folds of a cell are the ones of the notebook inside it
+            let ranges = ranges
+                .into_iter()
+                .filter_map(|mut fold| {
+                    fold.start_line = mapping
+                        .to_document_position(Position::new(fold.start_line, 0))?
+                        .line;
+                    fold.end_line = mapping
+                        .to_document_position(Position::new(fold.end_line, 0))?
+                        .line;
+                    Some(fold)
+                })
+                .collect();
+            Some(ranges)
+        })
+        .await?
+}
+
+pub fn folding_range(analysis: &PyAnalysis, file_id: FileId) -> Option<Vec<FoldingRange>> {
+    let semantic_model = analysis.get_semantic_model(file_id)?;
+    let mut builder = FoldingRangeBuilder::new(semantic_model.get_document());
+    builder.build(semantic_model.get_root());
+    Some(builder.finish())
+}
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.folding_range_provider = Some(FoldingRangeProviderCapability::Simple(true));
+}
@@ -0,0 +1,260 @@
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+use python_code_analysis::{
+    PyDecl, PyDeclKind, PyScopeKind, PySignature, PyType, SemanticModel, builtin_name_type,
+    humanize_type, is_builtin_class, is_builtin_function, is_builtin_name,
+};
+use python_parser::{
+    PyAstNode, PyAstToken, PyAttributeExpr, PyCallExpr, PyClassDef, PyExpr, PyFuncDef, PyKeyword,
+    PySyntaxKind, PySyntaxToken, PyTokenKind,
+};
+
+use super::{hover_number::hover_number, render_signature_docstring};
+
+pub fn build_hover(semantic_model: &SemanticModel, token: PySyntaxToken) -> Option<Hover> {
+    let content = match token.kind().into() {
+        PyTokenKind::TkName => build_name_hover(semantic_model, &token)?,
+        PyTokenKind::TkInt | PyTokenKind::TkFloat | PyTokenKind::TkComplex => hover_number(&token)?,
+        _ => return None,
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: content,
+        }),
+        range: semantic_model
+            .get_document()
+            .to_lsp_range(token.text_range()),
+    })
+}
+
+fn build_name_hover(semantic_model: &SemanticModel, token: &PySyntaxToken) -> Option<String> {
+    let parent = token.parent()?;
+    match parent.kind().into() {
+        PySyntaxKind::FuncDef => {
+            let func = PyFuncDef::cast(parent)?;
+            return Some(hover_func_def(semantic_model, &func, false));
+        }
+        PySyntaxKind::ClassDef => {
+            let class = PyClassDef::cast(parent)?;
+            return Some(hover_class_def(semantic_model, &class));
+        }
+        PySyntaxKind::AttributeExpr => {
+            let attribute = PyAttributeExpr::cast(parent)?;
+            return hover_attribute(semantic_model, &attribute);
+        }
+        PySyntaxKind::NameExpr => {
+            if let Some(keyword) = parent.parent().and_then(PyKeyword::cast)
+                && keyword
+                    .get_name()
+                    .is_some_and(|name| name.syntax() == token)
+            {
+                return hover_keyword_argument(semantic_model, &keyword);
+            }
+        }
+        _ => {}
+    }
+
+    let name = token.text();
+    let decl_tree = semantic_model.get_decl_tree();
+    let decl = decl_tree
+        .find_decl_by_range(token.text_range())
+        .or_else(|| semantic_model.find_decl(name, token.text_range().start()));
+    match decl {
+        Some(decl) => hover_decl(semantic_model, decl),
+        None if parent.kind() == PySyntaxKind::NameExpr.into() && is_builtin_name(name) => {
+            Some(hover_builtin(name))
+        }
+        None => None,
+    }
+}
+
+fn hover_decl(semantic_model: &SemanticModel, decl: &PyDecl) -> Option<String> {
+    let node = decl
+        .get_syntax_id()
+        .and_then(|syntax_id| semantic_model.get_node(syntax_id));
+    let name = decl.get_name();
+    let text = match decl.get_kind() {
+        PyDeclKind::Function => {
+            let func = PyFuncDef::cast(node?)?;
+            return Some(hover_func_def(semantic_model, &func, false));
+        }
+        PyDeclKind::Class => {
+            let class = PyClassDef::cast(node?)?;
+            return Some(hover_class_def(semantic_model, &class));
+        }
+        PyDeclKind::Parameter => {
+            let ty = semantic_model.infer_decl_type(decl);
+            typed_name("parameter", name, &ty)
+        }
+        PyDeclKind::Variable => {
+            let ty = semantic_model.infer_decl_type(decl);
+            typed_name(variable_kind(semantic_model, decl), name, &ty)
+        }
+        PyDeclKind::Import { .. } => format!("(module) {}", name),
+        PyDeclKind::ImportFrom { module, name } => {
+            let alias = decl.get_name();
+            let text = if alias == name {
+                format!("from {} import {}", module, name)
+            } else {
+                format!("from {} import {} as {}", module, name, alias)
+            };
+            // the type the name has in the module it is imported from
+            typed_name("import", &text, &semantic_model.infer_decl_type(decl))
+        }
+    };
+    Some(code_block(&text))
+}
+
+fn variable_kind(semantic_model: &SemanticModel, decl: &PyDecl) -> &'static str {
+    let name = decl.get_name();
+    let is_constant = name.chars().any(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if is_constant {
+        return "constant";
+    }
+
+    let in_class = semantic_model
+        .get_decl_tree()
+        .get_scope(decl.get_scope())
+        .is_some_and(|scope| scope.get_kind() == PyScopeKind::Class);
+    if in_class {
+        "class variable"
+    } else {
+        "variable"
+    }
+}
+
+fn typed_name(kind: &str, name: &str, ty: &PyType) -> String {
+    if ty.is_unknown() {
+        format!("({}) {}", kind, name)
+    } else {
+        format!("({}) {}: {}", kind, name, humanize_type(ty))
+    }
+}
+
+fn hover_func_def(semantic_model: &SemanticModel, func: &PyFuncDef, bound: bool) -> String {
+    let mut signature = PySignature::from_func_def(func);
+    if bound || signature.is_classmethod() {
+        signature.bind_self();
+    }
+    let return_type = match &signature.return_annotation {
+        Some(annotation) => annotation.clone(),
+        None => humanize_type(&semantic_model.infer_return_type(func)),
+    };
+
+    let is_method = func
+        .get_name()
+        .and_then(|name| {
+            semantic_model
+                .get_decl_tree()
+                .find_decl_by_range(name.get_range())
+        })
+        .and_then(|decl| semantic_model.get_decl_tree().get_scope(decl.get_scope()))
+        .is_some_and(|scope| scope.get_kind() == PyScopeKind::Class);
+
+    let mut lines: Vec<String> = signature
+        .decorators
+        .iter()
+        .map(|decorator| format!("@{}", decorator))
+        .collect();
+    if is_method && signature.is_property() {
+        lines.push(format!("(property) {}: {}", signature.name, return_type));
+    } else {
+        lines.push(format!(
+            "({}) {}def {}({}) -> {}",
+            if is_method { "method" } else { "function" },
+            if signature.is_async { "async " } else { "" },
+            signature.name,
+            signature.get_params_string(),
+            return_type
+        ));
+    }
+
+    with_docstring(
+        code_block(&lines.join("\n")),
+        render_signature_docstring(semantic_model, &signature),
+    )
+}
+
+fn hover_class_def(semantic_model: &SemanticModel, class: &PyClassDef) -> String {
+    let signature = PySignature::from_class_def(class);
+    let mut lines: Vec<String> = signature
+        .decorators
+        .iter()
+        .map(|decorator| format!("@{}", decorator))
+        .collect();
+    let arguments = class
+        .get_arguments()
+        .map(|arguments| arguments.get_text())
+        .unwrap_or_default();
+    lines.push(format!("(class) class {}{}", signature.name, arguments));
+
+    with_docstring(
+        code_block(&lines.join("\n")),
+        render_signature_docstring(semantic_model, &signature),
+    )
+}
+
+fn hover_attribute(semantic_model: &SemanticModel, attribute: &PyAttributeExpr) -> Option<String> {
+    let name = attribute.get_attr()?;
+    let owner = semantic_model.infer_expr(&attribute.get_value()?);
+    let class_name = match &owner {
+        PyType::Instance(class_name) | PyType::Class(class_name) => class_name,
+        _ => return None,
+    };
+
+    if let Some(decl) = semantic_model.find_class_member(class_name, &name)
+        && let Some(func) = decl
+            .get_syntax_id()
+            .and_then(|syntax_id| semantic_model.get_node(syntax_id))
+            .and_then(PyFuncDef::cast)
+    {
+        let bound = matches!(owner, PyType::Instance(_));
+        return Some(hover_func_def(semantic_model, &func, bound));
+    }
+
+    let ty = semantic_model.infer_expr(&PyExpr::AttributeExpr(attribute.clone()));
+    if ty.is_unknown() {
+        return None;
+    }
+    Some(code_block(&typed_name("attribute", &name, &ty)))
+}
+
+fn hover_keyword_argument(semantic_model: &SemanticModel, keyword: &PyKeyword) -> Option<String> {
+    let name = keyword.get_name()?.get_name_text().to_string();
+    let call = keyword.syntax().ancestors().find_map(PyCallExpr::cast)?;
+    let PyType::Function(signature) = semantic_model.infer_expr(&call.get_func()?) else {
+        return None;
+    };
+    let param = signature.params.iter().find(|param| param.name == name)?;
+    Some(code_block(&format!(
+        "(parameter) {}",
+        param.to_param_string()
+    )))
+}
+
+fn hover_builtin(name: &str) -> String {
+    let text = if is_builtin_function(name) {
+        format!("(function) {}", name)
+    } else if is_builtin_class(name) {
+        format!("(class) {}", name)
+    } else {
+        typed_name("constant", name, &builtin_name_type(name))
+    };
+    code_block(&text)
+}
+
+fn code_block(text: &str) -> String {
+    format!("```python\n{}\n```", text)
+}
+
+fn with_docstring(mut content: String, docstring: Option<String>) -> String {
+    if let Some(docstring) = docstring {
+        content.push_str("\n\n---\n\n");
+        content.push_str(&docstring);
+    }
+    content
+}
@@ -0,0 +1,74 @@
+use python_parser::{FloatOrLarge, PySyntaxToken, PyTokenKind, float_token_value, int_token_value};
+
+/// The literal's type and its value as Python would print it
+pub fn hover_number(token: &PySyntaxToken) -> Option<String> {
+    let (type_name, value) = match token.kind().into() {
+        PyTokenKind::TkInt => ("int", int_token_value(token).ok()?.to_string()),
+        PyTokenKind::TkFloat => {
+            let value = match float_token_value(token).ok()? {
+                FloatOrLarge::Float(value) => format_float_repr(value),
+                FloatOrLarge::Infinity => "inf".to_string(),
+                FloatOrLarge::NegativeInfinity => "-inf".to_string(),
+                FloatOrLarge::NaN => "nan".to_string(),
+            };
+            ("float", value)
+        }
+        PyTokenKind::TkComplex => {
+            let text = token.text().replace('_', "");
+            let imag = text.trim_end_matches(['j', 'J']).parse::<f64>().ok()?;
+            let value = format_float_repr(imag);
+            let value = value.strip_suffix(".0").unwrap_or(&value).to_string();
+            ("complex", format!("{}j", value))
+        }
+        _ => return None,
+    };
+
+    Some(format!(
+        "```python\n(literal) {}: {}\n```\n\n---\n\nValue: `{}`",
+        token.text(),
+        type_name,
+        value
+    ))
+}
+
+/// Format a float like `repr(float)`: the shortest round trip digits, in
+/// scientific notation when the exponent is below -4 or at least 16
+pub fn format_float_repr(value: f64) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+
+    // `{:e}` gives the shortest round trip digits, e.g. `1.2345e3`
+    let sci = format!("{:e}", value);
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+
+    if !(-4..16).contains(&exponent) {
+        let mut result = format!("{}{}", sign, &digits[..1]);
+        if digits.len() > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        let exp_sign = if exponent < 0 { '-' } else { '+' };
+        return format!("{}e{}{:02}", result, exp_sign, exponent.abs());
+    }
+
+    let point = exponent + 1;
+    let body = if point <= 0 {
+        format!("0.{}{}", "0".repeat((-point) as usize), digits)
+    } else if point as usize >= digits.len() {
+        format!("{}{}.0", digits, "0".repeat(point as usize - digits.len()))
+    } else {
+        let (int_part, frac_part) = digits.split_at(point as usize);
+        format!("{}.{}", int_part, frac_part)
+    };
+    format!("{}{}", sign, body)
+}
@@ -0,0 +1,95 @@
+mod build_hover;
+mod hover_number;
+
+use build_hover::build_hover;
+use lsp_types::{
+    ClientCapabilities, Hover, HoverParams, HoverProviderCapability, Position, ServerCapabilities,
+};
+use python_code_analysis::{FileId, PyAnalysis, PySignature, SemanticModel, render_docstring};
+use python_parser::{PyAstNode, PySyntaxToken, PyTokenKind};
+use rowan::TokenAtOffset;
+use tokio_util::sync::CancellationToken;
+
+use crate::{context::ServerContextSnapshot, handlers::notebook_document::DocumentMapping};
+
+pub async fn on_hover_handler(
+    context: ServerContextSnapshot,
+    params: HoverParams,
+    cancel_token: CancellationToken,
+) -> Option<Hover> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+    context
+        .with_analysis(&cancel_token, move |analysis| {
+            let mapping = DocumentMapping::new(analysis, &uri)?;
+            let mut result = hover(
+                analysis,
+                mapping.file_id,
+                mapping.to_file_position(position)?,
+            )?;
+            result.range = result
+                .range
+                .and_then(|range| mapping.to_document_range(range));
+            Some(result)
+        })
+        .await?
+}
+
+pub fn hover(analysis: &PyAnalysis, file_id: FileId, position: Position) -> Option<Hover> {
+    let semantic_model = analysis.get_semantic_model(file_id)?;
+    let document = semantic_model.get_document();
+    let offset = document.get_offset(position.line as usize, position.character as usize)?;
+    let root = semantic_model.get_root();
+    let token = match root.syntax().token_at_offset(offset) {
+        TokenAtOffset::Single(token) => token,
+        TokenAtOffset::Between(left, right) => {
+            if is_hover_token(&right) {
+                right
+            } else {
+                left
+            }
+        }
+        TokenAtOffset::None => return None,
+    };
+
+    build_hover(&semantic_model, token)
+}
+
+/// The docstring of `signature` as Markdown, its cross references link to
+/// the definitions they name in the file
+pub fn render_signature_docstring(
+    semantic_model: &SemanticModel,
+    signature: &PySignature,
+) -> Option<String> {
+    let docstring = signature.docstring.as_deref()?;
+    let position = signature
+        .syntax_id
+        .and_then(|syntax_id| semantic_model.get_node(syntax_id))
+        .map(|node| node.text_range().start())
+        .unwrap_or_default();
+    let uri = semantic_model
+        .get_db()
+        .get_vfs()
+        .get_uri(&semantic_model.get_file_id());
+    let resolve_link = |target: &str| {
+        let uri = uri.as_ref()?;
+        let decl = semantic_model.find_doc_reference(target, position)?;
+        let line = semantic_model
+            .get_document()
+            .get_line(decl.get_position())?;
+        Some(format!("{}#L{}", uri.as_str(), line + 1))
+    };
+    let rendered = render_docstring(docstring, &resolve_link);
+    (!rendered.is_empty()).then_some(rendered)
+}
+
+fn is_hover_token(token: &PySyntaxToken) -> bool {
+    matches!(
+        token.kind().into(),
+        PyTokenKind::TkName | PyTokenKind::TkInt | PyTokenKind::TkFloat | PyTokenKind::TkComplex
+    )
+}
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.hover_provider = Some(HoverProviderCapability::Simple(true));
+}
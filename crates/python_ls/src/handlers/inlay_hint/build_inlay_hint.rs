@@ -0,0 +1,247 @@
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, TextEdit};
+use python_code_analysis::{
+    PyDecl, PyDeclKind, PyHintConfig, PyParamKind, PyScopeKind, PyType, SemanticModel,
+    humanize_type,
+};
+use python_parser::{
+    PyAssignStmt, PyAstNode, PyCallExpr, PyExpr, PyFuncDef, PySyntaxKind, PyUnaryExpr,
+};
+use rowan::{TextRange, TextSize};
+
+/// All hints of the file inside `range`, with the text edits which turn them
+/// into real code already filled in
+pub fn build_inlay_hints(
+    semantic_model: &SemanticModel,
+    config: &PyHintConfig,
+    range: TextRange,
+) -> Option<Vec<InlayHint>> {
+    let mut builder = InlayHintBuilder {
+        semantic_model,
+        range,
+        hints: Vec::new(),
+    };
+    if !config.enable {
+        return Some(builder.hints);
+    }
+
+    if config.variable_types || config.target_types {
+        for decl in semantic_model.get_decl_tree().get_decls() {
+            builder.build_decl_hint(config, decl);
+        }
+    }
+    let root = semantic_model.get_root();
+    if config.return_types {
+        for func in root.descendants::<PyFuncDef>() {
+            builder.build_return_hint(&func);
+        }
+    }
+    if config.parameter_names {
+        for call in root.descendants::<PyCallExpr>() {
+            builder.build_param_name_hints(&call);
+        }
+    }
+
+    let mut hints = builder.hints;
+    hints.sort_by_key(|hint| (hint.position.line, hint.position.character));
+    Some(hints)
+}
+
+struct InlayHintBuilder<'a, 'b> {
+    semantic_model: &'b SemanticModel<'a>,
+    range: TextRange,
+    hints: Vec<InlayHint>,
+}
+
+impl InlayHintBuilder<'_, '_> {
+    fn build_decl_hint(&mut self, config: &PyHintConfig, decl: &PyDecl) {
+        if *decl.get_kind() != PyDeclKind::Variable || decl.get_annotation().is_some() {
+            return;
+        }
+        let decl_tree = self.semantic_model.get_decl_tree();
+        if decl_tree
+            .get_scope(decl.get_scope())
+            .is_some_and(|scope| scope.get_kind() == PyScopeKind::Comprehension)
+        {
+            return;
+        }
+        let Some(node) = decl
+            .get_syntax_id()
+            .and_then(|syntax_id| self.semantic_model.get_node(syntax_id))
+        else {
+            return;
+        };
+
+        let assign = match node.kind().into() {
+            PySyntaxKind::AssignStmt if config.variable_types => PyAssignStmt::cast(node),
+            PySyntaxKind::ForStmt | PySyntaxKind::WithStmt if config.target_types => None,
+            _ => return,
+        };
+
+        let ty = self.semantic_model.infer_decl_type(decl);
+        if !is_hint_type(&ty) || self.is_obvious_value(decl, &ty) {
+            return;
+        }
+        let type_text = humanize_type(&ty);
+
+        // 只有单一目标的首次赋值才能直接写成注解
+        let name_range = decl.get_range();
+        let annotatable = assign.is_some_and(|assign| {
+            let exprs: Vec<PyExpr> = assign.get_targets().collect();
+            exprs.len() == 2
+                && matches!(&exprs[0], PyExpr::NameExpr(name) if name.get_range() == name_range)
+        }) && decl_tree
+            .get_scope_decls_by_name(decl.get_scope(), decl.get_name())
+            .first()
+            .is_some_and(|first| first.get_id() == decl.get_id())
+            && is_annotation_text(&type_text);
+
+        let edit = annotatable.then(|| format!(": {}", type_text));
+        self.push(
+            name_range.end(),
+            format!(": {}", type_text),
+            InlayHintKind::TYPE,
+            false,
+            edit,
+        );
+    }
+
+    fn build_return_hint(&mut self, func: &PyFuncDef) {
+        if func.get_return_annotation().is_some() || func.get_type_comment().is_some() {
+            return;
+        }
+        let Some(params) = func.get_parameters() else {
+            return;
+        };
+        let ty = self.semantic_model.infer_return_type(func);
+        if !is_hint_type(&ty) {
+            return;
+        }
+        let type_text = humanize_type(&ty);
+        let edit = is_annotation_text(&type_text).then(|| format!(" -> {}", type_text));
+        self.push(
+            params.get_range().end(),
+            format!("-> {}", type_text),
+            InlayHintKind::TYPE,
+            true,
+            edit,
+        );
+    }
+
+    fn build_param_name_hints(&mut self, call: &PyCallExpr) {
+        let Some(func) = call.get_func() else {
+            return;
+        };
+        // 多个重载时无法确定参数名
+        let signatures = self.semantic_model.infer_call_signatures(&func);
+        let [signature] = signatures.as_slice() else {
+            return;
+        };
+        let Some(args_list) = call.get_args_list() else {
+            return;
+        };
+
+        let mut positional = signature.params.iter().filter(|param| {
+            matches!(
+                param.kind,
+                PyParamKind::PositionalOnly | PyParamKind::PositionalOrKeyword
+            )
+        });
+        for node in args_list.syntax().children() {
+            let Some(arg) = PyExpr::cast(node) else {
+                // 关键字参数之后不再有位置参数
+                return;
+            };
+            if matches!(arg, PyExpr::StarredExpr(_) | PyExpr::DoubleStarredExpr(_)) {
+                return;
+            }
+            let Some(param) = positional.next() else {
+                return;
+            };
+            if param.kind == PyParamKind::PositionalOnly
+                || param.name.starts_with('_')
+                || !is_literal_arg(&arg)
+            {
+                continue;
+            }
+            let label = format!("{}=", param.name);
+            self.push(
+                arg.get_position(),
+                label.clone(),
+                InlayHintKind::PARAMETER,
+                false,
+                Some(label),
+            );
+        }
+    }
+
+    /// `x = Foo()` already says what `x` is
+    fn is_obvious_value(&self, decl: &PyDecl, ty: &PyType) -> bool {
+        let Some(PyExpr::CallExpr(call)) = decl
+            .get_value()
+            .and_then(|syntax_id| self.semantic_model.get_node(syntax_id))
+            .and_then(PyExpr::cast)
+        else {
+            return false;
+        };
+        match (call.get_func(), ty) {
+            (Some(func), PyType::Instance(name)) => func.get_text() == *name,
+            _ => false,
+        }
+    }
+
+    fn push(
+        &mut self,
+        offset: TextSize,
+        label: String,
+        kind: InlayHintKind,
+        padding_left: bool,
+        edit: Option<String>,
+    ) {
+        if !self.range.contains_inclusive(offset) {
+            return;
+        }
+        let Some(position) = self.semantic_model.get_document().to_lsp_position(offset) else {
+            return;
+        };
+        self.hints.push(InlayHint {
+            position,
+            label: InlayHintLabel::String(label),
+            kind: Some(kind),
+            text_edits: edit.map(|new_text| {
+                vec![TextEdit {
+                    range: lsp_types::Range::new(position, position),
+                    new_text,
+                }]
+            }),
+            tooltip: None,
+            padding_left: Some(padding_left),
+            padding_right: None,
+            data: None,
+        });
+    }
+}
+
+fn is_hint_type(ty: &PyType) -> bool {
+    !matches!(
+        ty,
+        PyType::Unknown | PyType::Any | PyType::Function(_) | PyType::Module(_)
+    )
+}
+
+/// Whether the rendered type can be written into the source as it is
+fn is_annotation_text(type_text: &str) -> bool {
+    !type_text.contains("Unknown") && !type_text.contains("CoroutineType")
+}
+
+fn is_literal_arg(arg: &PyExpr) -> bool {
+    match arg {
+        PyExpr::LiteralExpr(_) | PyExpr::FStringExpr(_) => true,
+        PyExpr::UnaryExpr(unary) => is_negative_number(unary),
+        _ => false,
+    }
+}
+
+fn is_negative_number(unary: &PyUnaryExpr) -> bool {
+    unary.get_op().as_deref() == Some("-")
+        && matches!(unary.get_operand(), Some(PyExpr::LiteralExpr(_)))
+}
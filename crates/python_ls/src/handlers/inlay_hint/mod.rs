@@ -0,0 +1,163 @@
+mod build_inlay_hint;
+
+use build_inlay_hint::build_inlay_hints;
+use lsp_types::{
+    ClientCapabilities, InlayHint, InlayHintLabel, InlayHintOptions, InlayHintParams,
+    InlayHintServerCapabilities, OneOf, Position, Range, ServerCapabilities, Uri,
+};
+use python_code_analysis::{FileId, PyAnalysis};
+use python_parser::PyAstNode;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::{context::ServerContextSnapshot, handlers::notebook_document::DocumentMapping};
+
+/// What `inlayHint/resolve` needs to find the hint again
+#[derive(Debug, Serialize, Deserialize)]
+struct InlayHintResolveData {
+    uri: Uri,
+    position: Position,
+}
+
+pub async fn on_inlay_hint_handler(
+    context: ServerContextSnapshot,
+    params: InlayHintParams,
+    cancel_token: CancellationToken,
+) -> Option<Vec<InlayHint>> {
+    let uri = params.text_document.uri;
+    let range = params.range;
+    let hints = context
+        .with_analysis(&cancel_token, {
+            let uri = uri.clone();
+            move |analysis| {
+                let mapping = DocumentMapping::new(analysis, &uri)?;
+                // the line after a cell is past it, such a range takes the
+                // whole file and the hints outside the cell are left out
+                let range = mapping
+                    .to_file_range(range)
+                    .unwrap_or(Range::new(Position::new(0, 0), Position::new(u32::MAX, 0)));
+                let hints = inlay_hint(analysis, mapping.file_id, range)?;
+                let hints = hints
+                    .into_iter()
+                    .filter_map(|hint| to_document_inlay_hint(&mapping, hint))
+                    .collect::<Vec<_>>();
+                Some(hints)
+            }
+        })
+        .await??;
+    // 编辑在 resolve 时才返回
+    let hints = hints
+        .into_iter()
+        .map(|mut hint| {
+            if hint.text_edits.take().is_some() {
+                let data = InlayHintResolveData {
+                    uri: uri.clone(),
+                    position: hint.position,
+                };
+                hint.data = serde_json::to_value(data).ok();
+            }
+            hint
+        })
+        .collect();
+    Some(hints)
+}
+
+pub async fn on_resolve_inlay_hint(
+    context: ServerContextSnapshot,
+    hint: InlayHint,
+    cancel_token: CancellationToken,
+) -> InlayHint {
+    let Some(data) = hint
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value::<InlayHintResolveData>(data).ok())
+    else {
+        return hint;
+    };
+    let fallback = hint.clone();
+    context
+        .with_analysis(&cancel_token, move |analysis| {
+            match DocumentMapping::new(analysis, &data.uri) {
+                Some(mapping) => resolve_document_inlay_hint(analysis, &mapping, hint),
+                None => hint,
+            }
+        })
+        .await
+        .unwrap_or(fallback)
+}
+
+pub fn inlay_hint(analysis: &PyAnalysis, file_id: FileId, range: Range) -> Option<Vec<InlayHint>> {
+    let semantic_model = analysis.get_semantic_model(file_id)?;
+    let range = semantic_model
+        .get_document()
+        .to_rowan_range(range)
+        .unwrap_or_else(|| semantic_model.get_root().get_range());
+    let config = analysis.get_config();
+    build_inlay_hints(&semantic_model, &config.hint, range)
+}
+
+/// Fill in the text edits of a hint returned without them
+pub fn resolve_inlay_hint(
+    analysis: &PyAnalysis,
+    file_id: FileId,
+    mut hint: InlayHint,
+) -> InlayHint {
+    let range = Range::new(hint.position, hint.position);
+    let resolved = inlay_hint(analysis, file_id, range)
+        .into_iter()
+        .flatten()
+        .find(|it| {
+            it.position == hint.position
+                && it.kind == hint.kind
+                && label_text(&it.label) == label_text(&hint.label)
+        });
+    if let Some(resolved) = resolved {
+        hint.text_edits = resolved.text_edits;
+    }
+    hint
+}
+
+/// A hint of the file in the document, `None` when it is in another cell
+/// of the notebook
+pub fn to_document_inlay_hint(mapping: &DocumentMapping, mut hint: InlayHint) -> Option<InlayHint> {
+    hint.position = mapping.to_document_position(hint.position)?;
+    if let Some(edits) = &mut hint.text_edits {
+        for edit in edits.iter_mut() {
+            edit.range = mapping.to_document_range(edit.range)?;
+        }
+    }
+    Some(hint)
+}
+
+/// Resolve a hint of the document through the file it is analyzed in
+pub fn resolve_document_inlay_hint(
+    analysis: &PyAnalysis,
+    mapping: &DocumentMapping,
+    hint: InlayHint,
+) -> InlayHint {
+    let Some(position) = mapping.to_file_position(hint.position) else {
+        return hint;
+    };
+    let file_hint = InlayHint {
+        position,
+        ..hint.clone()
+    };
+    let resolved = resolve_inlay_hint(analysis, mapping.file_id, file_hint);
+    to_document_inlay_hint(mapping, resolved).unwrap_or(hint)
+}
+
+fn label_text(label: &InlayHintLabel) -> String {
+    match label {
+        InlayHintLabel::String(label) => label.clone(),
+        InlayHintLabel::LabelParts(parts) => parts.iter().map(|part| part.value.as_str()).collect(),
+    }
+}
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.inlay_hint_provider = Some(OneOf::Right(
+        InlayHintServerCapabilities::Options(InlayHintOptions {
+            resolve_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        }),
+    ));
+}
@@ -0,0 +1,39 @@
+mod code_actions;
+mod configuration;
+mod diagnostic;
+mod folding_range;
+mod hover;
+mod inlay_hint;
+mod notebook_document;
+mod notification_handler;
+mod request_handler;
+mod response_handler;
+mod selection_range;
+mod signature_help;
+mod text_document;
+mod workspace;
+
+#[cfg(test)]
+mod test;
+#[cfg(test)]
+mod test_lib;
+
+pub use configuration::{find_environment, reload_config};
+use lsp_types::{ClientCapabilities, ServerCapabilities};
+pub use notification_handler::on_notification_handler;
+pub use request_handler::on_request_handler;
+pub use response_handler::on_response_handler;
+pub use workspace::watch_workspace;
+
+pub fn server_capabilities(client_capabilities: &ClientCapabilities) -> ServerCapabilities {
+    let mut server_capabilities = ServerCapabilities::default();
+    text_document::register_capabilities(&mut server_capabilities, client_capabilities);
+    notebook_document::register_capabilities(&mut server_capabilities, client_capabilities);
+    hover::register_capabilities(&mut server_capabilities, client_capabilities);
+    signature_help::register_capabilities(&mut server_capabilities, client_capabilities);
+    folding_range::register_capabilities(&mut server_capabilities, client_capabilities);
+    selection_range::register_capabilities(&mut server_capabilities, client_capabilities);
+    inlay_hint::register_capabilities(&mut server_capabilities, client_capabilities);
+    code_actions::register_capabilities(&mut server_capabilities, client_capabilities);
+    server_capabilities
+}
@@ -0,0 +1,85 @@
+use lsp_types::{Position, Range, TextEdit, Uri, WorkspaceEdit};
+use python_code_analysis::{FileId, PyAnalysis, PyNotebook};
+
+/// Where the positions of a document are in the file it is analyzed in. A
+/// notebook cell is a part of the file of its notebook, any other document
+/// is a file of its own.
+pub struct DocumentMapping<'a> {
+    pub file_id: FileId,
+    cell: Option<(&'a PyNotebook, usize)>,
+    /// The uri of the notebook file, for a cell
+    file_uri: Option<Uri>,
+}
+
+impl<'a> DocumentMapping<'a> {
+    pub fn new(analysis: &'a PyAnalysis, uri: &Uri) -> Option<Self> {
+        if let Some(file_id) = analysis.get_file_id(uri) {
+            return Some(DocumentMapping {
+                file_id,
+                cell: None,
+                file_uri: None,
+            });
+        }
+        let (file_id, cell) = analysis.find_notebook_cell(uri)?;
+        let notebook = analysis.get_notebook(file_id)?;
+        Some(DocumentMapping {
+            file_id,
+            cell: Some((notebook, cell)),
+            file_uri: analysis.compilation.get_db().get_vfs().get_uri(&file_id),
+        })
+    }
+
+    pub fn to_file_position(&self, position: Position) -> Option<Position> {
+        match self.cell {
+            Some((notebook, cell)) => notebook.to_source_position(cell, position),
+            None => Some(position),
+        }
+    }
+
+    pub fn to_file_range(&self, range: Range) -> Option<Range> {
+        match self.cell {
+            Some((notebook, cell)) => notebook.to_source_range(cell, range),
+            None => Some(range),
+        }
+    }
+
+    /// A range of the file as a range of the document, `None` when it is in
+    /// another cell of the notebook
+    pub fn to_document_range(&self, range: Range) -> Option<Range> {
+        match self.cell {
+            Some((notebook, cell)) => notebook
+                .to_cell_range(range)
+                .filter(|(range_cell, _)| *range_cell == cell)
+                .map(|(_, range)| range),
+            None => Some(range),
+        }
+    }
+
+    pub fn to_document_position(&self, position: Position) -> Option<Position> {
+        self.to_document_range(Range::new(position, position))
+            .map(|range| range.start)
+    }
+
+    /// Move the edits of the notebook file to the cells they are in, an edit
+    /// which runs past its cell is cut at the end of the cell
+    pub fn to_document_edit(&self, edit: &mut WorkspaceEdit) {
+        let (Some((notebook, _)), Some(file_uri)) = (self.cell, &self.file_uri) else {
+            return;
+        };
+        let Some(changes) = &mut edit.changes else {
+            return;
+        };
+        let Some(edits) = changes.remove(file_uri) else {
+            return;
+        };
+        for edit in edits {
+            let Some((cell, range)) = notebook.to_cell_range(edit.range) else {
+                continue;
+            };
+            changes
+                .entry(notebook.get_cells()[cell].uri.clone())
+                .or_default()
+                .push(TextEdit::new(range, edit.new_text));
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod document_mapping;
+mod notebook_document_handler;
+
+pub use document_mapping::DocumentMapping;
+use lsp_types::{
+    ClientCapabilities, Notebook, NotebookCellSelector, NotebookDocumentSyncOptions,
+    NotebookSelector, OneOf, ServerCapabilities,
+};
+pub use notebook_document_handler::*;
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.notebook_document_sync = Some(OneOf::Left(NotebookDocumentSyncOptions {
+        notebook_selector: vec![NotebookSelector::ByNotebook {
+            notebook: Notebook::String("jupyter-notebook".to_string()),
+            cells: Some(vec![NotebookCellSelector {
+                language: "python".to_string(),
+            }]),
+        }],
+        save: Some(false),
+    }));
+}
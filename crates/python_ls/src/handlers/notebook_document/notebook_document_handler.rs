@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use lsp_types::{
+    DidChangeNotebookDocumentParams, DidCloseNotebookDocumentParams, DidOpenNotebookDocumentParams,
+    DidSaveNotebookDocumentParams, NotebookCell, NotebookCellKind, NotebookDocumentCellChange,
+    TextDocumentItem,
+};
+use python_code_analysis::{PyCellKind, PyNotebook, PyNotebookCell};
+
+use crate::{
+    context::ServerContextSnapshot,
+    handlers::{
+        diagnostic::{clear_diagnostics, publish_diagnostics, schedule_diagnostics},
+        text_document::read_workspace_file,
+    },
+};
+
+pub async fn on_did_open_notebook_document(
+    context: ServerContextSnapshot,
+    params: DidOpenNotebookDocumentParams,
+) -> Option<()> {
+    let notebook = params.notebook_document;
+    let texts = cell_texts(params.cell_text_documents);
+    let cells = notebook
+        .cells
+        .iter()
+        .map(|cell| new_cell(cell, &texts))
+        .collect();
+    context
+        .workspace_manager
+        .open_file(&notebook.uri, notebook.version);
+    context.cancel_handle.cancel();
+    let mut analysis = context.analysis.write().await;
+    analysis.update_notebook_by_uri(&notebook.uri, PyNotebook::new(cells));
+    publish_diagnostics(
+        &analysis,
+        &context.client,
+        &notebook.uri,
+        Some(notebook.version),
+    );
+    Some(())
+}
+
+pub async fn on_did_change_notebook_document(
+    context: ServerContextSnapshot,
+    params: DidChangeNotebookDocumentParams,
+) -> Option<()> {
+    let uri = params.notebook_document.uri;
+    let version = params.notebook_document.version;
+    context.workspace_manager.set_version(&uri, version);
+    context.cancel_handle.cancel();
+    let mut analysis = context.analysis.write().await;
+    let file_id = analysis.get_file_id(&uri)?;
+    let mut cells = analysis.get_notebook(file_id)?.get_cells().to_vec();
+    if let Some(change) = params.change.cells {
+        // the diagnostics of the removed cells go away with them
+        let closed = change
+            .structure
+            .as_ref()
+            .and_then(|structure| structure.did_close.as_ref());
+        for document in closed.into_iter().flatten() {
+            clear_diagnostics(&context.client, &document.uri);
+        }
+        apply_cell_change(&mut cells, change);
+    }
+    analysis.update_notebook_by_uri(&uri, PyNotebook::new(cells));
+    drop(analysis);
+    schedule_diagnostics(context, uri, version);
+    Some(())
+}
+
+pub async fn on_did_save_notebook_document(
+    _: ServerContextSnapshot,
+    _: DidSaveNotebookDocumentParams,
+) -> Option<()> {
+    Some(())
+}
+
+pub async fn on_did_close_notebook_document(
+    context: ServerContextSnapshot,
+    params: DidCloseNotebookDocumentParams,
+) -> Option<()> {
+    let uri = params.notebook_document.uri;
+    context.workspace_manager.close_file(&uri);
+    let disk_content = read_workspace_file(&context, &uri);
+    context.cancel_handle.cancel();
+    let mut analysis = context.analysis.write().await;
+    analysis.remove_notebook_by_uri(&uri);
+    match disk_content {
+        Some(content) => {
+            analysis.update_files_by_uri(vec![(uri.clone(), Some(content))]);
+        }
+        None => {
+            analysis.remove_file_by_uri(&uri);
+        }
+    }
+    for document in &params.cell_text_documents {
+        clear_diagnostics(&context.client, &document.uri);
+    }
+    Some(())
+}
+
+/// Apply the changes of the cells in the order of the protocol: the cells
+/// which are added or removed, their kinds, then their text
+fn apply_cell_change(cells: &mut Vec<PyNotebookCell>, change: NotebookDocumentCellChange) {
+    if let Some(structure) = change.structure {
+        let texts = cell_texts(structure.did_open.unwrap_or_default());
+        let start = (structure.array.start as usize).min(cells.len());
+        let end = (start + structure.array.delete_count as usize).min(cells.len());
+        let added = structure.array.cells.unwrap_or_default();
+        cells.splice(start..end, added.iter().map(|cell| new_cell(cell, &texts)));
+    }
+    for data in change.data.into_iter().flatten() {
+        if let Some(cell) = find_cell(cells, &data.document) {
+            cell.kind = cell_kind(&data.kind);
+        }
+    }
+    for content in change.text_content.into_iter().flatten() {
+        // full sync, the last change holds the whole cell
+        let Some(last) = content.changes.into_iter().last() else {
+            continue;
+        };
+        if let Some(cell) = find_cell(cells, &content.document.uri) {
+            cell.text = last.text;
+        }
+    }
+}
+
+fn find_cell<'a>(
+    cells: &'a mut [PyNotebookCell],
+    uri: &lsp_types::Uri,
+) -> Option<&'a mut PyNotebookCell> {
+    cells
+        .iter_mut()
+        .find(|cell| cell.uri.as_str() == uri.as_str())
+}
+
+fn new_cell(cell: &NotebookCell, texts: &HashMap<String, String>) -> PyNotebookCell {
+    PyNotebookCell {
+        uri: cell.document.clone(),
+        kind: cell_kind(&cell.kind),
+        text: texts
+            .get(cell.document.as_str())
+            .cloned()
+            .unwrap_or_default(),
+    }
+}
+
+/// The text of the cell documents by their uri text
+fn cell_texts(documents: Vec<TextDocumentItem>) -> HashMap<String, String> {
+    documents
+        .into_iter()
+        .map(|document| (document.uri.as_str().to_string(), document.text))
+        .collect()
+}
+
+fn cell_kind(kind: &NotebookCellKind) -> PyCellKind {
+    match kind {
+        NotebookCellKind::Code => PyCellKind::Code,
+        NotebookCellKind::Markup => PyCellKind::Markup,
+    }
+}
@@ -0,0 +1,127 @@
+use std::{error::Error, future::Future};
+
+use lsp_server::{Notification, RequestId};
+use lsp_types::{
+    CancelParams, NumberOrString,
+    notification::{
+        Cancel, DidChangeConfiguration, DidChangeNotebookDocument, DidChangeTextDocument,
+        DidCloseNotebookDocument, DidCloseTextDocument, DidOpenNotebookDocument,
+        DidOpenTextDocument, DidSaveNotebookDocument, DidSaveTextDocument,
+    },
+};
+use serde::de::DeserializeOwned;
+
+use super::configuration::on_did_change_configuration;
+use super::notebook_document::{
+    on_did_change_notebook_document, on_did_close_notebook_document, on_did_open_notebook_document,
+    on_did_save_notebook_document,
+};
+use super::text_document::{
+    on_did_change_text_document, on_did_close_document, on_did_open_text_document,
+    on_did_save_text_document,
+};
+use crate::context::{ServerContext, ServerContextSnapshot};
+
+pub async fn on_notification_handler(
+    notification: Notification,
+    server_context: &mut ServerContext,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    NotificationDispatcher::new(notification, server_context)
+        .on_context::<Cancel, _>(on_cancel_request)
+        .on_sync::<DidOpenTextDocument, _, _>(on_did_open_text_document)
+        .await
+        .on_sync::<DidChangeTextDocument, _, _>(on_did_change_text_document)
+        .await
+        .on_sync::<DidSaveTextDocument, _, _>(on_did_save_text_document)
+        .await
+        .on_sync::<DidCloseTextDocument, _, _>(on_did_close_document)
+        .await
+        .on_sync::<DidOpenNotebookDocument, _, _>(on_did_open_notebook_document)
+        .await
+        .on_sync::<DidChangeNotebookDocument, _, _>(on_did_change_notebook_document)
+        .await
+        .on_sync::<DidSaveNotebookDocument, _, _>(on_did_save_notebook_document)
+        .await
+        .on_sync::<DidCloseNotebookDocument, _, _>(on_did_close_notebook_document)
+        .await
+        .on_sync::<DidChangeConfiguration, _, _>(on_did_change_configuration)
+        .await
+        .finish();
+    Ok(())
+}
+
+pub struct NotificationDispatcher<'a> {
+    notification: Option<Notification>,
+    context: &'a mut ServerContext,
+}
+
+impl<'a> NotificationDispatcher<'a> {
+    pub fn new(notification: Notification, context: &'a mut ServerContext) -> Self {
+        NotificationDispatcher {
+            notification: Some(notification),
+            context,
+        }
+    }
+
+    /// Handle the notification in the main loop with the server context
+    /// itself, for what a snapshot can not do
+    pub fn on_context<N, F>(&mut self, handler: F) -> &mut Self
+    where
+        N: lsp_types::notification::Notification,
+        N::Params: DeserializeOwned,
+        F: FnOnce(&mut ServerContext, N::Params),
+    {
+        let Some(notification) = self
+            .notification
+            .take_if(|notification| notification.method == N::METHOD)
+        else {
+            return self;
+        };
+
+        match serde_json::from_value::<N::Params>(notification.params) {
+            Ok(params) => handler(self.context, params),
+            Err(err) => log::error!("Invalid params for {}: {}", N::METHOD, err),
+        }
+        self
+    }
+
+    /// Handle the notification in the main loop, keeping the client's order
+    pub async fn on_sync<N, F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        N: lsp_types::notification::Notification,
+        N::Params: DeserializeOwned,
+        F: FnOnce(ServerContextSnapshot, N::Params) -> Fut,
+        Fut: Future<Output = Option<()>>,
+    {
+        let Some(notification) = self
+            .notification
+            .take_if(|notification| notification.method == N::METHOD)
+        else {
+            return self;
+        };
+
+        match serde_json::from_value::<N::Params>(notification.params) {
+            Ok(params) => {
+                handler(self.context.snapshot(), params).await;
+            }
+            Err(err) => log::error!("Invalid params for {}: {}", N::METHOD, err),
+        }
+        self
+    }
+
+    pub fn finish(&mut self) {
+        if let Some(notification) = self.notification.take()
+            && !notification.method.starts_with("$/")
+        {
+            log::warn!("Unhandled notification: {}", notification.method);
+        }
+    }
+}
+
+fn on_cancel_request(context: &mut ServerContext, params: CancelParams) {
+    let req_id = match params.id {
+        NumberOrString::Number(id) => RequestId::from(id),
+        NumberOrString::String(id) => RequestId::from(id),
+    };
+    context.cancel(req_id);
+}
@@ -0,0 +1,98 @@
+use std::{error::Error, future::Future};
+
+use lsp_server::{ErrorCode, Request, RequestId, Response};
+use lsp_types::request::{
+    CodeActionRequest, FoldingRangeRequest, HoverRequest, InlayHintRequest,
+    InlayHintResolveRequest, SelectionRangeRequest, SignatureHelpRequest,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    code_actions::on_code_action_handler,
+    folding_range::on_folding_range_handler,
+    hover::on_hover_handler,
+    inlay_hint::{on_inlay_hint_handler, on_resolve_inlay_hint},
+    selection_range::on_selection_range_handler,
+    signature_help::on_signature_help_handler,
+};
+use crate::context::{ServerContext, ServerContextSnapshot};
+
+pub async fn on_request_handler(
+    req: Request,
+    server_context: &mut ServerContext,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    RequestDispatcher::new(req, server_context)
+        .on_parallel::<HoverRequest, _, _>(on_hover_handler)
+        .on_parallel::<SignatureHelpRequest, _, _>(on_signature_help_handler)
+        .on_parallel::<FoldingRangeRequest, _, _>(on_folding_range_handler)
+        .on_parallel::<SelectionRangeRequest, _, _>(on_selection_range_handler)
+        .on_parallel::<InlayHintRequest, _, _>(on_inlay_hint_handler)
+        .on_parallel::<InlayHintResolveRequest, _, _>(on_resolve_inlay_hint)
+        .on_parallel::<CodeActionRequest, _, _>(on_code_action_handler)
+        .finish();
+    Ok(())
+}
+
+pub struct RequestDispatcher<'a> {
+    req: Option<Request>,
+    context: &'a mut ServerContext,
+}
+
+impl<'a> RequestDispatcher<'a> {
+    pub fn new(req: Request, context: &'a mut ServerContext) -> Self {
+        RequestDispatcher {
+            req: Some(req),
+            context,
+        }
+    }
+
+    /// Run the handler on a snapshot of the server state, off the main loop
+    pub fn on_parallel<R, F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + Send + 'static,
+        R::Result: Serialize + 'static,
+        F: FnOnce(ServerContextSnapshot, R::Params, CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = R::Result> + Send + 'static,
+    {
+        let Some(req) = self.req.take_if(|req| req.method == R::METHOD) else {
+            return self;
+        };
+
+        let id = req.id.clone();
+        let params = match serde_json::from_value::<R::Params>(req.params) {
+            Ok(params) => params,
+            Err(err) => {
+                self.context.send(Response::new_err(
+                    id,
+                    ErrorCode::InvalidParams as i32,
+                    err.to_string(),
+                ));
+                return self;
+            }
+        };
+
+        let snapshot = self.context.snapshot();
+        self.context
+            .task(id.clone(), move |cancel_token| async move {
+                let result = handler(snapshot, params, cancel_token).await;
+                Some(Response::new_ok(id, result))
+            });
+        self
+    }
+
+    pub fn finish(&mut self) {
+        if let Some(req) = self.req.take() {
+            log::warn!("Unhandled request: {}", req.method);
+            self.context.send(method_not_found(
+                req.id,
+                format!("unhandled request: {}", req.method),
+            ));
+        }
+    }
+}
+
+fn method_not_found(id: RequestId, message: String) -> Response {
+    Response::new_err(id, ErrorCode::MethodNotFound as i32, message)
+}
@@ -0,0 +1,16 @@
+use std::error::Error;
+
+use lsp_server::Response;
+
+use crate::context::ServerContext;
+
+pub async fn on_response_handler(
+    response: Response,
+    server_context: &mut ServerContext,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let id = response.id.clone();
+    if !server_context.snapshot().client.on_response(response) {
+        log::debug!("Unhandled response: {:?}", id);
+    }
+    Ok(())
+}
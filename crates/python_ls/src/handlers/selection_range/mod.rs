@@ -0,0 +1,153 @@
+use lsp_types::{
+    ClientCapabilities, Position, Range, SelectionRange, SelectionRangeParams,
+    SelectionRangeProviderCapability, ServerCapabilities,
+};
+use python_code_analysis::{FileId, PyAnalysis, PyDocument};
+use python_parser::{PyAstNode, PySyntaxKind, PySyntaxNode, PySyntaxToken, PyTokenKind};
+use rowan::{TextRange, TokenAtOffset};
+use tokio_util::sync::CancellationToken;
+
+use crate::{context::ServerContextSnapshot, handlers::notebook_document::DocumentMapping};
+
+pub async fn on_selection_range_handler(
+    context: ServerContextSnapshot,
+    params: SelectionRangeParams,
+    cancel_token: CancellationToken,
+) -> Option<Vec<SelectionRange>> {
+    let uri = params.text_document.uri;
+    context
+        .with_analysis(&cancel_token, move |analysis| {
+            let mapping = DocumentMapping::new(analysis, &uri)?;
+            let positions = params
+                .positions
+                .iter()
+                .map(|position| mapping.to_file_position(*position))
+                .collect::<Option<Vec<_>>>()?;
+            let ranges = selection_range(analysis, mapping.file_id, &positions)?;
+            let ranges = ranges
+                .into_iter()
+                .zip(params.positions)
+                .map(|(selection, position)| {
+                    to_document_selection_range(&mapping, selection).unwrap_or(SelectionRange {
+                        range: Range::new(position, position),
+                        parent: None,
+                    })
+                })
+                .collect();
+            Some(ranges)
+        })
+        .await?
+}
+
+/// The chain of selection ranges of the file in the document. In a cell the
+/// chain stops at the ranges which reach into the cells before it, and the
+/// ones which run past it are cut at its end.
+pub fn to_document_selection_range(
+    mapping: &DocumentMapping,
+    selection: SelectionRange,
+) -> Option<SelectionRange> {
+    let range = mapping.to_document_range(selection.range)?;
+    let mut parent = selection
+        .parent
+        .and_then(|parent| to_document_selection_range(mapping, *parent));
+    // two ranges cut at the end of the cell are the same
+    while let Some(outer) = parent.take_if(|outer| outer.range == range) {
+        parent = outer.parent.map(|it| *it);
+    }
+    Some(SelectionRange {
+        range,
+        parent: parent.map(Box::new),
+    })
+}
+
+pub fn selection_range(
+    analysis: &PyAnalysis,
+    file_id: FileId,
+    positions: &[Position],
+) -> Option<Vec<SelectionRange>> {
+    let semantic_model = analysis.get_semantic_model(file_id)?;
+    let document = semantic_model.get_document();
+    let root = semantic_model.get_root().syntax();
+    let result = positions
+        .iter()
+        .map(|position| {
+            build_selection_range(document, root, *position).unwrap_or(SelectionRange {
+                range: Range::new(*position, *position),
+                parent: None,
+            })
+        })
+        .collect();
+    Some(result)
+}
+
+/// The chain from the token under the cursor out through its ancestors
+fn build_selection_range(
+    document: &PyDocument,
+    root: &PySyntaxNode,
+    position: Position,
+) -> Option<SelectionRange> {
+    let offset = document.get_offset(position.line as usize, position.character as usize)?;
+    let token = match root.token_at_offset(offset) {
+        TokenAtOffset::Single(token) => token,
+        TokenAtOffset::Between(left, right) => {
+            if is_layout_token(&left) {
+                right
+            } else {
+                left
+            }
+        }
+        TokenAtOffset::None => return None,
+    };
+
+    let mut ranges = vec![token.text_range()];
+    for node in token.parent_ancestors() {
+        let Some(range) = content_range(&node) else {
+            continue;
+        };
+        if ranges.last().is_some_and(|last| last.contains_range(range)) {
+            continue;
+        }
+        ranges.push(range);
+    }
+
+    let mut result: Option<SelectionRange> = None;
+    for range in ranges.into_iter().rev() {
+        result = Some(SelectionRange {
+            range: document.to_lsp_range(range)?,
+            parent: result.map(Box::new),
+        });
+    }
+    result
+}
+
+/// The node range without the newlines and indentation that surround a suite
+fn content_range(node: &PySyntaxNode) -> Option<TextRange> {
+    if node.kind() != PySyntaxKind::Suite.into() {
+        return Some(node.text_range());
+    }
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| !is_layout_token(token));
+    let first = tokens.next()?;
+    let last = tokens.last().unwrap_or_else(|| first.clone());
+    Some(TextRange::new(
+        first.text_range().start(),
+        last.text_range().end(),
+    ))
+}
+
+fn is_layout_token(token: &PySyntaxToken) -> bool {
+    matches!(
+        token.kind().into(),
+        PyTokenKind::TkWhitespace
+            | PyTokenKind::TkNewline
+            | PyTokenKind::TkIndent
+            | PyTokenKind::TkDedent
+    )
+}
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.selection_range_provider =
+        Some(SelectionRangeProviderCapability::Simple(true));
+}
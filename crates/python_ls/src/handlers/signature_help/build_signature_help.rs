@@ -0,0 +1,252 @@
+use crate::handlers::hover::render_signature_docstring;
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp,
+    SignatureInformation,
+};
+use python_code_analysis::{
+    PyParamKind, PySignature, PySignatureParam, SemanticModel, humanize_type,
+};
+use python_parser::{PyAstNode, PyCallExpr, PyExpr, PyFuncDef, PyKeyword, PyTokenKind};
+use rowan::TextSize;
+
+/// An argument of the call as far as parameter binding is concerned
+#[derive(Debug, Clone, PartialEq)]
+enum CallArg {
+    Positional,
+    // `*iterable`
+    Unpacked,
+    // `**mapping`
+    DoubleUnpacked,
+    Keyword(String),
+}
+
+pub fn build_signature_help(
+    semantic_model: &SemanticModel,
+    call: &PyCallExpr,
+    offset: TextSize,
+) -> Option<SignatureHelp> {
+    let signatures = semantic_model.infer_call_signatures(&call.get_func()?);
+    if signatures.is_empty() {
+        return None;
+    }
+
+    let args_list = call.get_args_list()?;
+    let args: Vec<CallArg> = args_list
+        .syntax()
+        .children()
+        .filter_map(|node| {
+            if let Some(keyword) = PyKeyword::cast(node.clone()) {
+                let name = keyword.get_name()?.get_name_text().to_string();
+                return Some(CallArg::Keyword(name));
+            }
+            Some(match PyExpr::cast(node)? {
+                PyExpr::StarredExpr(_) => CallArg::Unpacked,
+                PyExpr::DoubleStarredExpr(_) => CallArg::DoubleUnpacked,
+                _ => CallArg::Positional,
+            })
+        })
+        .collect();
+    let current = args_list
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| {
+            token.kind() == PyTokenKind::TkComma.into() && token.text_range().end() <= offset
+        })
+        .count();
+
+    let active_signature = signatures
+        .iter()
+        .position(|signature| accepts(signature, &args, current))
+        .unwrap_or(0);
+    let signatures = signatures
+        .iter()
+        .map(|signature| {
+            // 客户端把超出范围的下标当作 0, 没有可高亮的参数时不给出下标
+            let active_parameter =
+                active_parameter(signature, &args, current).map(|index| index as u32);
+            build_signature_information(semantic_model, signature, active_parameter)
+        })
+        .collect::<Vec<_>>();
+
+    Some(SignatureHelp {
+        active_parameter: signatures[active_signature].active_parameter,
+        active_signature: Some(active_signature as u32),
+        signatures,
+    })
+}
+
+fn build_signature_information(
+    semantic_model: &SemanticModel,
+    signature: &PySignature,
+    active_parameter: Option<u32>,
+) -> SignatureInformation {
+    let mut label = format!("{}(", signature.name);
+    let mut parameters = Vec::new();
+    let mut params = signature.params.iter();
+    for (i, text) in signature.get_param_strings().into_iter().enumerate() {
+        if i > 0 {
+            label.push_str(", ");
+        }
+        // `/` and `*` only mark the parameter kinds
+        if text != "/" && text != "*" {
+            let start = utf16_len(&label);
+            let end = start + utf16_len(&text);
+            parameters.push(ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, end]),
+                documentation: params.next().and_then(|param| {
+                    let annotation = param.annotation.as_ref()?;
+                    Some(Documentation::String(annotation.clone()))
+                }),
+            });
+        }
+        label.push_str(&text);
+    }
+    label.push(')');
+
+    let return_type = match &signature.return_annotation {
+        Some(annotation) => Some(annotation.clone()),
+        None => signature
+            .syntax_id
+            .and_then(|syntax_id| semantic_model.get_node(syntax_id))
+            .and_then(PyFuncDef::cast)
+            .map(|func| humanize_type(&semantic_model.infer_return_type(&func))),
+    };
+    if let Some(return_type) = return_type {
+        label.push_str(" -> ");
+        label.push_str(&return_type);
+    }
+
+    let documentation = render_signature_docstring(semantic_model, signature).map(|value| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        })
+    });
+
+    SignatureInformation {
+        label,
+        documentation,
+        parameters: Some(parameters),
+        active_parameter,
+    }
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// The parameter the argument at `current` binds to, following Python's
+/// binding rules: positional arguments fill the positional parameters in
+/// order, keywords bind by name and anything left over goes to `*args` or
+/// `**kwargs`
+fn active_parameter(signature: &PySignature, args: &[CallArg], current: usize) -> Option<usize> {
+    let params = &signature.params;
+    let find_kind = |kind: PyParamKind| params.iter().position(|param| param.kind == kind);
+
+    let before = &args[..current.min(args.len())];
+    let positional = before
+        .iter()
+        .filter(|arg| matches!(arg, CallArg::Positional | CallArg::Unpacked))
+        .count();
+    let given: Vec<&str> = before
+        .iter()
+        .filter_map(|arg| match arg {
+            CallArg::Keyword(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    let after_keyword = before
+        .iter()
+        .any(|arg| matches!(arg, CallArg::Keyword(_) | CallArg::DoubleUnpacked));
+
+    match args.get(current) {
+        Some(CallArg::Keyword(name)) => params
+            .iter()
+            .position(|param| {
+                param.name == *name
+                    && matches!(
+                        param.kind,
+                        PyParamKind::PositionalOrKeyword | PyParamKind::KeywordOnly
+                    )
+            })
+            .or_else(|| find_kind(PyParamKind::VarKeyword)),
+        Some(CallArg::DoubleUnpacked) => find_kind(PyParamKind::VarKeyword),
+        Some(CallArg::Unpacked) => {
+            find_kind(PyParamKind::VarPositional).or_else(|| nth_positional(params, positional))
+        }
+        // 关键字参数之后只能继续传关键字参数, 提示下一个还没有给出的参数
+        None if after_keyword => params
+            .iter()
+            .enumerate()
+            .filter(|(_, param)| {
+                matches!(
+                    param.kind,
+                    PyParamKind::PositionalOrKeyword | PyParamKind::KeywordOnly
+                )
+            })
+            .skip_while(|(i, _)| *i < positional)
+            .find(|(_, param)| !given.contains(&param.name.as_str()))
+            .map(|(i, _)| i)
+            .or_else(|| find_kind(PyParamKind::VarKeyword)),
+        Some(CallArg::Positional) | None => {
+            nth_positional(params, positional).or_else(|| find_kind(PyParamKind::VarPositional))
+        }
+    }
+}
+
+fn nth_positional(params: &[PySignatureParam], n: usize) -> Option<usize> {
+    params
+        .iter()
+        .enumerate()
+        .filter(|(_, param)| {
+            matches!(
+                param.kind,
+                PyParamKind::PositionalOnly | PyParamKind::PositionalOrKeyword
+            )
+        })
+        .nth(n)
+        .map(|(i, _)| i)
+}
+
+/// Whether the arguments typed so far can bind to `signature`
+fn accepts(signature: &PySignature, args: &[CallArg], current: usize) -> bool {
+    let params = &signature.params;
+    let has_kind = |kind: PyParamKind| params.iter().any(|param| param.kind == kind);
+
+    let positional = args
+        .iter()
+        .filter(|arg| matches!(arg, CallArg::Positional))
+        .count();
+    let capacity = positional_capacity(params);
+    if positional > capacity && !has_kind(PyParamKind::VarPositional) {
+        return false;
+    }
+
+    let keywords_ok = args.iter().all(|arg| match arg {
+        CallArg::Keyword(name) => {
+            params.iter().any(|param| {
+                param.name == *name
+                    && matches!(
+                        param.kind,
+                        PyParamKind::PositionalOrKeyword | PyParamKind::KeywordOnly
+                    )
+            }) || has_kind(PyParamKind::VarKeyword)
+        }
+        _ => true,
+    });
+    // an empty slot being typed must still have a parameter to go to
+    keywords_ok && (current < args.len() || active_parameter(signature, args, current).is_some())
+}
+
+fn positional_capacity(params: &[PySignatureParam]) -> usize {
+    params
+        .iter()
+        .filter(|param| {
+            matches!(
+                param.kind,
+                PyParamKind::PositionalOnly | PyParamKind::PositionalOrKeyword
+            )
+        })
+        .count()
+}
@@ -0,0 +1,94 @@
+mod build_signature_help;
+
+use build_signature_help::build_signature_help;
+use lsp_types::{
+    ClientCapabilities, Position, ServerCapabilities, SignatureHelp, SignatureHelpOptions,
+    SignatureHelpParams,
+};
+use python_code_analysis::{FileId, PyAnalysis};
+use python_parser::{PyAstNode, PyCallArgList, PyCallExpr, PySyntaxNode, PyTokenKind};
+use rowan::{TextSize, TokenAtOffset};
+use tokio_util::sync::CancellationToken;
+
+use crate::{context::ServerContextSnapshot, handlers::notebook_document::DocumentMapping};
+
+pub async fn on_signature_help_handler(
+    context: ServerContextSnapshot,
+    params: SignatureHelpParams,
+    cancel_token: CancellationToken,
+) -> Option<SignatureHelp> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+    context
+        .with_analysis(&cancel_token, move |analysis| {
+            let mapping = DocumentMapping::new(analysis, &uri)?;
+            signature_help(
+                analysis,
+                mapping.file_id,
+                mapping.to_file_position(position)?,
+            )
+        })
+        .await?
+}
+
+pub fn signature_help(
+    analysis: &PyAnalysis,
+    file_id: FileId,
+    position: Position,
+) -> Option<SignatureHelp> {
+    let semantic_model = analysis.get_semantic_model(file_id)?;
+    let document = semantic_model.get_document();
+    let offset = document.get_offset(position.line as usize, position.character as usize)?;
+    let call = find_enclosing_call(semantic_model.get_root().syntax(), offset)?;
+    build_signature_help(&semantic_model, &call, offset)
+}
+
+/// The innermost call whose parentheses contain `offset`
+fn find_enclosing_call(root: &PySyntaxNode, offset: TextSize) -> Option<PyCallExpr> {
+    let mut token = match root.token_at_offset(offset) {
+        TokenAtOffset::Single(token) => token,
+        TokenAtOffset::Between(left, _) => left,
+        TokenAtOffset::None => return None,
+    };
+    // 未闭合的参数列表不包含其后的空白, 从前一个有效 token 开始查找
+    while matches!(
+        token.kind().into(),
+        PyTokenKind::TkWhitespace | PyTokenKind::TkNewline | PyTokenKind::TkComment
+    ) {
+        token = token.prev_token()?;
+    }
+
+    token
+        .parent_ancestors()
+        .filter_map(PyCallArgList::cast)
+        .find(|args| is_inside_parens(args, offset))
+        .and_then(|args| args.get_parent::<PyCallExpr>())
+}
+
+fn is_inside_parens(args: &PyCallArgList, offset: TextSize) -> bool {
+    let mut left = None;
+    let mut right = None;
+    for token in args
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+    {
+        match token.kind().into() {
+            PyTokenKind::TkLeftParen if left.is_none() => left = Some(token.text_range()),
+            PyTokenKind::TkRightParen => right = Some(token.text_range()),
+            _ => {}
+        }
+    }
+    let Some(left) = left else {
+        return false;
+    };
+    offset >= left.end() && right.is_none_or(|right| offset <= right.start())
+}
+
+pub fn register_capabilities(server_capabilities: &mut ServerCapabilities, _: &ClientCapabilities) {
+    server_capabilities.signature_help_provider = Some(SignatureHelpOptions {
+        trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+        retrigger_characters: Some(vec![")".to_string()]),
+        work_done_progress_options: Default::default(),
+    });
+}
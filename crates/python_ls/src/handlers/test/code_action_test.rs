@@ -0,0 +1,271 @@
+#[cfg(test)]
+mod tests {
+    use python_code_analysis::PyConfig;
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualCodeAction};
+
+    fn action(title: &str, result: &str) -> VirtualCodeAction {
+        VirtualCodeAction {
+            title: title.to_string(),
+            result: result.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_add_missing_import() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "\"\"\"Module.\"\"\"\nimport os\n\nx = Pa<??>th(os.sep)\n",
+            vec![action(
+                "Add `from pathlib import Path`",
+                "\"\"\"Module.\"\"\"\nimport os\nfrom pathlib import Path\n\nx = Path(os.sep)\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "x = js<??>on.dumps(1)\n",
+            vec![action(
+                "Add `import json`",
+                "import json\nx = json.dumps(1)\n"
+            )],
+        ));
+        assert!(
+            ws.check_no_code_action("import json\nx = js<??>on.dumps(1)\n", "Add `import json`")
+        );
+    }
+
+    #[test]
+    fn test_add_import_from_workspace() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.def_file(
+            "utils.py",
+            "def helper():\n    pass\n\ndef other():\n    pass\n",
+        );
+        assert!(ws.check_code_action(
+            "from utils import other\n\nhel<??>per()\n",
+            vec![action(
+                "Add `from utils import helper`",
+                "from utils import other, helper\n\nhelper()\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_add_import_from_nested_package() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.set_config(PyConfig::default());
+        ws.def_file("pkg/sub/helpers.py", "def split():\n    pass\n");
+        ws.def_file("pkg/__init__.py", "VERSION = 1\n");
+        assert!(ws.check_code_action(
+            "spl<??>it()\n",
+            vec![action(
+                "Add `from pkg.sub.helpers import split`",
+                "from pkg.sub.helpers import split\nsplit()\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "print(VER<??>SION)\n",
+            vec![action(
+                "Add `from pkg import VERSION`",
+                "from pkg import VERSION\nprint(VERSION)\n",
+            )],
+        ));
+
+        // modules are imported relative to the source roots
+        let mut config = PyConfig::default();
+        config.workspace.source_roots = vec!["src".to_string()];
+        ws.set_config(config);
+        ws.def_file("src/app/models.py", "class User:\n    pass\n");
+        assert!(ws.check_code_action(
+            "Us<??>er()\n",
+            vec![action(
+                "Add `from app.models import User`",
+                "from app.models import User\nUser()\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_remove_unused_import() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "import o<??>s, sys\nprint(sys.argv)\n",
+            vec![action(
+                "Remove unused import `os`",
+                "import sys\nprint(sys.argv)\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "import os\nfrom typing import A<??>ny\nprint(os.sep)\n",
+            vec![action(
+                "Remove unused import `Any`",
+                "import os\nprint(os.sep)\n",
+            )],
+        ));
+        assert!(ws.check_no_code_action(
+            "import o<??>s\nprint(os.sep)\n",
+            "Remove unused import `os`"
+        ));
+    }
+
+    #[test]
+    fn test_remove_unused_import_keeps_comments() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        // only the name goes, the comments of the others stay
+        assert!(ws.check_code_action(
+            "from m import (a,  # first\n    <??>b,\n)\nprint(a)\n",
+            vec![action(
+                "Remove unused import `b`",
+                "from m import (a,  # first\n)\nprint(a)\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "from m import (a,  # first\n    <??>b\n)\nprint(a)\n",
+            vec![action(
+                "Remove unused import `b`",
+                "from m import (a  # first\n)\nprint(a)\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "from m import <??>a, b, c  # noqa: E501\nprint(c)\n",
+            vec![action(
+                "Remove unused imports",
+                "from m import c  # noqa: E501\nprint(c)\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "from m import a, <??>b as c\nprint(a)\n",
+            vec![action(
+                "Remove unused import `c`",
+                "from m import a\nprint(a)\n",
+            )],
+        ));
+        // the comment of a removed statement goes with its line
+        assert!(ws.check_code_action(
+            "import <??>os  # noqa: F401\nimport sys\nprint(sys.argv)\n",
+            vec![action(
+                "Remove unused import `os`",
+                "import sys\nprint(sys.argv)\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_wrap_in_try_except() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "def f(x):\n    y = x\n    pri<??>nt(y)\n",
+            vec![action(
+                "Wrap in `try`/`except`",
+                "def f(x):\n    y = x\n    try:\n        print(y)\n    except Exception:\n        raise\n",
+            )],
+        ));
+        // the trailing comment stays on the statement it belongs to
+        assert!(ws.check_code_action(
+            "imp<??>ort os  # noqa: F401\nx = 1\n",
+            vec![action(
+                "Wrap in `try`/`except`",
+                "try:\n    import os  # noqa: F401\nexcept Exception:\n    raise\nx = 1\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "def f():\n    <??>x = g()  # type: ignore\n    return x\n",
+            vec![action(
+                "Wrap in `try`/`except`",
+                "def f():\n    try:\n        x = g()  # type: ignore\n    except Exception:\n        raise\n    return x\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_convert_percent_format() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "x = \"{%s} %-5s %5.2f %%\" <??>% (name, label, value)\n",
+            vec![action(
+                "Convert to f-string",
+                "x = f\"{{{name}}} {label!s:<5} {value:5.2f} %\"\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "x = '%r and %s' %<??> (a, b.c)\n",
+            vec![action("Convert to f-string", "x = f'{a!r} and {b.c}'\n")],
+        ));
+        // a name on the right may hold a tuple at runtime
+        assert!(ws.check_no_code_action("x = \"%s\" <??>% value\n", "Convert to f-string"));
+        // the conversion type stays, `%d` does not become a plain field
+        assert!(ws.check_code_action(
+            "x = '%d items, %i left' %<??> (n, 2)\n",
+            vec![action(
+                "Convert to f-string",
+                "x = f'{n:d} items, {2:d} left'\n"
+            )],
+        ));
+        assert!(ws.check_no_code_action("x = '%d' <??>% 3.5\n", "Convert to f-string"));
+    }
+
+    #[test]
+    fn test_convert_format_call() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "s = \"{} is {age:>3} {{x}}\".for<??>mat(name, age=n)\n",
+            vec![action(
+                "Convert to f-string",
+                "s = f\"{name} is {n:>3} {{x}}\"\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "s = \"{0.real}{1[0]}\".for<??>mat(a + b, items)\n",
+            vec![action(
+                "Convert to f-string",
+                "s = f\"{(a + b).real}{items[0]}\"\n"
+            )],
+        ));
+        // an index which is not all digits is a str key
+        assert!(ws.check_code_action(
+            "s = '{0[name]} {0[1]} {1.a[-1]}'.for<??>mat(d, x)\n",
+            vec![action(
+                "Convert to f-string",
+                "s = f'{d[\"name\"]} {d[1]} {x.a[\"-1\"]}'\n"
+            )],
+        ));
+        // an unused argument would no longer be evaluated
+        assert!(ws.check_no_code_action("s = \"{}\".for<??>mat(a, b)\n", "Convert to f-string"));
+    }
+
+    #[test]
+    fn test_no_fstring_before_python_36() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.set_python_version(5);
+        assert!(ws.check_no_code_action("s = \"{}\".for<??>mat(a)\n", "Convert to f-string"));
+    }
+
+    #[test]
+    fn test_suppress_diagnostic() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.set_python_version(9);
+        assert!(ws.check_code_action(
+            "T = int <??>| str\n",
+            vec![
+                action(
+                    "Suppress `PY002` with `# noqa`",
+                    "T = int | str  # noqa: PY002\n",
+                ),
+                action(
+                    "Suppress `PY002` with `# type: ignore`",
+                    "T = int | str  # type: ignore[PY002]\n",
+                ),
+            ],
+        ));
+        assert!(ws.check_code_action(
+            "T = int <??>| str  # noqa: E501\n",
+            vec![action(
+                "Suppress `PY002` with `# noqa`",
+                "T = int | str  # noqa: E501, PY002\n",
+            )],
+        ));
+        assert!(ws.check_no_code_action(
+            "T = int <??>| str  # noqa\n",
+            "Suppress `PY002` with `# noqa`"
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use python_code_analysis::{
+        PyConfig, PyDiagnosticSeverity, merge_config_value, parse_config_file,
+        requires_python_version,
+    };
+
+    use crate::handlers::test_lib::ProviderVirtualWorkspace;
+
+    fn parse(file_name: &str, text: &str) -> PyConfig {
+        let value = parse_config_file(file_name, text).unwrap().unwrap();
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_requires_python_version() {
+        assert_eq!(requires_python_version(">=3.9"), Some("3.9".to_string()));
+        assert_eq!(
+            requires_python_version(">=3.8, <4, !=3.9.*"),
+            Some("3.8".to_string())
+        );
+        assert_eq!(requires_python_version(">3.9"), Some("3.10".to_string()));
+        assert_eq!(requires_python_version(">3.9.1"), Some("3.9".to_string()));
+        assert_eq!(
+            requires_python_version("~=3.11.2"),
+            Some("3.11".to_string())
+        );
+        assert_eq!(requires_python_version("<3.12"), None);
+    }
+
+    #[test]
+    fn test_pyproject_config() {
+        let config = parse(
+            "pyproject.toml",
+            r#"
+[project]
+requires-python = ">=3.9"
+
+[tool.python_ls]
+source-roots = ["src"]
+stub-paths = ["typings"]
+
+[tool.python_ls.diagnostics.severity]
+unused-import = "warning"
+
+[tool.python_ls.format]
+line-length = 100
+quote-style = "single"
+"#,
+        );
+        assert_eq!(config.python_version.as_deref(), Some("3.9"));
+        assert_eq!(config.workspace.source_roots, vec!["src"]);
+        assert_eq!(config.workspace.stub_paths, vec!["typings"]);
+        assert_eq!(
+            config.diagnostics.severity.get("unused-import"),
+            Some(&PyDiagnosticSeverity::Warning)
+        );
+        assert_eq!(config.format.line_length, 100);
+        assert_eq!(config.format.indent_width, 4);
+
+        // the setting wins over the version the project declares
+        let config = parse(
+            "pyproject.toml",
+            "[project]\nrequires-python = \">=3.9\"\n\n[tool.python_ls]\npython-version = \"3.12\"\n",
+        );
+        assert_eq!(config.python_version.as_deref(), Some("3.12"));
+    }
+
+    #[test]
+    fn test_setup_cfg_config() {
+        let config = parse(
+            "setup.cfg",
+            r#"
+[metadata]
+name = demo
+
+[tool:python_ls]
+python_version = 3.11
+exclude =
+    build
+    docs/*.py
+
+[tool:python_ls.diagnostics]
+disable = unused-import, PY008
+enable = false
+
+[options]
+python_requires = >=3.8
+"#,
+        );
+        assert_eq!(config.python_version.as_deref(), Some("3.11"));
+        assert_eq!(config.workspace.exclude, vec!["build", "docs/*.py"]);
+        assert_eq!(config.diagnostics.disable, vec!["unused-import", "PY008"]);
+        assert!(!config.diagnostics.enable);
+
+        let config = parse("setup.cfg", "[options]\npython_requires = >=3.10\n");
+        assert_eq!(config.python_version.as_deref(), Some("3.10"));
+        assert_eq!(
+            parse_config_file("setup.cfg", "[metadata]\nname = demo\n"),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_config_precedence() {
+        let mut value = parse_config_file(
+            "pyproject.toml",
+            "[tool.python_ls]\npython-version = \"3.9\"\ninclude = [\"src\"]\n",
+        )
+        .unwrap()
+        .unwrap();
+        let local = parse_config_file(".python_ls.toml", "python-version = \"3.10\"\n")
+            .unwrap()
+            .unwrap();
+        merge_config_value(&mut value, local);
+        merge_config_value(&mut value, serde_json::json!({ "exclude": ["build"] }));
+        let config: PyConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.python_version.as_deref(), Some("3.10"));
+        assert_eq!(config.workspace.include, vec!["src"]);
+        assert_eq!(config.workspace.exclude, vec!["build"]);
+    }
+
+    #[test]
+    fn test_python_version_reparses_files() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.set_python_version(12);
+        let file_id = ws.def("T = int | str\n");
+        assert!(ws.analysis.diagnose_file(file_id).unwrap().is_empty());
+
+        let config = PyConfig {
+            python_version: Some("3.9".to_string()),
+            ..Default::default()
+        };
+        ws.analysis.update_config(Arc::new(config));
+        let diagnostics = ws.analysis.diagnose_file(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            Some(lsp_types::NumberOrString::String("PY002".to_string()))
+        );
+    }
+}
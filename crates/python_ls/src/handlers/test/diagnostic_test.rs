@@ -0,0 +1,455 @@
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use lsp_types::DiagnosticSeverity;
+    use python_code_analysis::{
+        DiagnosticContext, LintRule, LintRuleMeta, PyConfig, parse_config_toml,
+    };
+    use python_parser::{PySyntaxKind, PySyntaxNode};
+
+    use crate::handlers::test_lib::{
+        ProviderVirtualWorkspace, VirtualCodeAction, VirtualDiagnostic,
+    };
+
+    fn diagnostic(code: &str, line: u32) -> VirtualDiagnostic {
+        VirtualDiagnostic {
+            code: code.to_string(),
+            line,
+        }
+    }
+
+    fn action(title: &str, result: &str) -> VirtualCodeAction {
+        VirtualCodeAction {
+            title: title.to_string(),
+            result: result.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_undefined_name() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            r#"
+def f(a):
+    print(a, b)
+    return undefined_call()
+
+class C[T]:
+    def m(self) -> T:
+        return __class__
+
+type Alias = list[int]
+x: Alias = []
+"#,
+            vec![diagnostic("PY004", 2), diagnostic("PY004", 3),],
+        ));
+        // a star import may define anything
+        assert!(ws.check_diagnostics("from os.path import *\nprint(join)\n", vec![]));
+    }
+
+    #[test]
+    fn test_global_binding() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            "def init():\n    global COUNT\n    COUNT = 0\n\ndef read():\n    return COUNT\n",
+            vec![],
+        ));
+    }
+
+    #[test]
+    fn test_unused_import() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            r#"
+from __future__ import annotations
+import os
+import sys
+import json as json
+from typing import TYPE_CHECKING, List, Dict
+
+__all__ = ["Dict"]
+
+if TYPE_CHECKING:
+    from pathlib import Path
+
+def f(p: "Path") -> List[int]:
+    return [sys.maxsize]
+"#,
+            vec![diagnostic("PY005", 2)],
+        ));
+    }
+
+    #[test]
+    fn test_remove_unused_import_fix() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "from os.path import join, spl<??>it\n\njoin('a', 'b')\n",
+            vec![action(
+                "Remove unused import `split`",
+                "from os.path import join\n\njoin('a', 'b')\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_unused_variable() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            r#"
+module_level = 1
+
+def f():
+    unused = 1
+    _ignored = 2
+    a, b = 1, 2
+    used = 3
+    try:
+        pass
+    except ValueError as error:
+        pass
+    return used
+
+def g():
+    hidden = 1
+    return locals()
+"#,
+            vec![diagnostic("PY006", 4), diagnostic("PY006", 10),],
+        ));
+    }
+
+    #[test]
+    fn test_remove_unused_variable_fix() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "def f():\n    un<??>used = 1\n    return 2\n",
+            vec![action(
+                "Remove assignment to unused variable `unused`",
+                "def f():\n    return 2\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "def f():\n    res<??>ult = compute()\n",
+            vec![action(
+                "Remove assignment to unused variable `result`",
+                "def f():\n    compute()\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "def f():\n    try:\n        pass\n    except ValueError as er<??>ror:\n        pass\n",
+            vec![action(
+                "Remove assignment to unused variable `error`",
+                "def f():\n    try:\n        pass\n    except ValueError:\n        pass\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_unused_argument() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let source = r#"
+class C:
+    def m(self, used, unused, _private):
+        return used
+
+    @staticmethod
+    def s(value):
+        pass
+
+    def stub(self, value):
+        ...
+
+def f(x):
+    return 1
+"#;
+        // off by default
+        assert!(ws.check_diagnostics(source, vec![]));
+        ws.enable_diagnostic("unused-argument");
+        assert!(ws.check_diagnostics(
+            source,
+            vec![diagnostic("PY007", 2), diagnostic("PY007", 12),],
+        ));
+    }
+
+    #[test]
+    fn test_none_comparison() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            "def f(x):\n    return x == None or None != x or x is None\n",
+            vec![diagnostic("PY008", 1), diagnostic("PY008", 1)],
+        ));
+        assert!(ws.check_code_action(
+            "def f(x):\n    return x !<??>= None\n",
+            vec![action(
+                "Replace `!=` with `is not`",
+                "def f(x):\n    return x is not None\n",
+            )],
+        ));
+    }
+
+    #[test]
+    fn test_rule_config() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let config: PyConfig = serde_json::from_value(serde_json::json!({
+            "diagnostics": {
+                "disable": ["PY008"],
+                "severity": { "unused-import": "warning" },
+                "overrides": [{
+                    "files": ["tests/**"],
+                    "disable": ["unused-import"],
+                    "enables": ["PY007"],
+                    "severity": { "undefined-name": "information" }
+                }]
+            }
+        }))
+        .unwrap();
+        ws.set_config(config);
+        let source = "import os\n\ndef f(x):\n    return y == None\n";
+
+        let file_id = ws.def_file("src/main.py", source);
+        let diagnostics = ws.analysis.diagnose_file(file_id).unwrap();
+        let mut severities: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.range.start.line, diagnostic.severity))
+            .collect();
+        severities.sort_by_key(|(line, _)| *line);
+        assert_eq!(
+            severities,
+            vec![
+                (0, Some(DiagnosticSeverity::WARNING)),
+                (3, Some(DiagnosticSeverity::ERROR)),
+            ]
+        );
+
+        let file_id = ws.def_file("tests/test_main.py", source);
+        let diagnostics = ws.analysis.diagnose_file(file_id).unwrap();
+        let mut severities: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.range.start.line, diagnostic.severity))
+            .collect();
+        severities.sort_by_key(|(line, _)| *line);
+        assert_eq!(
+            severities,
+            vec![
+                (2, Some(DiagnosticSeverity::HINT)),
+                (3, Some(DiagnosticSeverity::INFORMATION)),
+            ]
+        );
+    }
+
+    /// A rule of a plugin, with a code the builtin rules do not know
+    struct PrintCallRule;
+
+    const PRINT_CALL: LintRuleMeta = LintRuleMeta {
+        code: "EX001",
+        name: "print-call",
+        default_severity: DiagnosticSeverity::WARNING,
+        description: "Calls of `print`",
+        default_enabled: true,
+        tags: &[],
+    };
+
+    impl LintRule for PrintCallRule {
+        fn get_metas(&self) -> &[LintRuleMeta] {
+            &[PRINT_CALL]
+        }
+
+        fn get_node_kinds(&self) -> &'static [PySyntaxKind] {
+            &[PySyntaxKind::CallExpr]
+        }
+
+        fn check_node(&self, context: &mut DiagnosticContext, node: &PySyntaxNode) {
+            if node.text().to_string().starts_with("print(") {
+                let range = node.text_range();
+                context.add_diagnostic(&PRINT_CALL, range, "`print` call".to_string(), Vec::new());
+            }
+        }
+    }
+
+    #[test]
+    fn test_registered_rule() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.analysis.register_lint_rule(Box::new(PrintCallRule));
+        let registry = ws.analysis.get_lint_registry();
+        assert_eq!(registry.find_meta("print-call"), Some(&PRINT_CALL));
+        assert_eq!(registry.find_meta("ex001"), Some(&PRINT_CALL));
+
+        let source = "print(1)
+print(2)  # noqa: EX001
+";
+        let file_id = ws.def_file("src/main.py", source);
+        let diagnostics = ws.analysis.diagnose_file(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 0);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+
+        // the config names it by its code or its name
+        let config: PyConfig = serde_json::from_value(serde_json::json!({
+            "diagnostics": {
+                "severity": { "print-call": "error" },
+                "overrides": [{ "files": ["tests/**"], "disable": ["EX001"] }]
+            }
+        }))
+        .unwrap();
+        ws.set_config(config);
+        let diagnostics = ws.analysis.diagnose_file(file_id).unwrap();
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        let file_id = ws.def_file("tests/test_main.py", "print(1)\n");
+        assert_eq!(ws.analysis.diagnose_file(file_id), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_override_globs_are_relative_to_root() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let mut config: PyConfig = serde_json::from_value(serde_json::json!({
+            "diagnostics": {
+                "overrides": [{ "files": ["tests/**"], "disable": ["unused-import"] }]
+            }
+        }))
+        .unwrap();
+        config
+            .diagnostics
+            .compile_overrides(Some(Path::new("/work/tests/project")))
+            .unwrap();
+        ws.analysis.update_config(Arc::new(config));
+        let source = "import os
+";
+
+        // a `tests` directory above the root does not match
+        let file_id = ws.def_file("work/tests/project/src/main.py", source);
+        assert_eq!(ws.analysis.diagnose_file(file_id).unwrap().len(), 1);
+        let file_id = ws.def_file("work/tests/project/tests/test_main.py", source);
+        assert!(ws.analysis.diagnose_file(file_id).unwrap().is_empty());
+        // nor does one below the root which the glob does not start at
+        let file_id = ws.def_file("work/tests/project/src/tests/test_util.py", source);
+        assert_eq!(ws.analysis.diagnose_file(file_id).unwrap().len(), 1);
+
+        let mut config: PyConfig = serde_json::from_value(serde_json::json!({
+            "diagnostics": { "overrides": [{ "files": ["tests/[a"] }] }
+        }))
+        .unwrap();
+        assert!(config.diagnostics.compile_overrides(None).is_err());
+    }
+
+    #[test]
+    fn test_parse_config_toml() {
+        let pyproject = r#"
+[project]
+name = "demo"
+
+[tool.python_ls.diagnostics]
+disable = ["unused-variable"]
+"#;
+        let value = parse_config_toml("pyproject.toml", pyproject)
+            .unwrap()
+            .unwrap();
+        let config: PyConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.diagnostics.disable, vec!["unused-variable"]);
+        assert_eq!(
+            parse_config_toml("pyproject.toml", "[project]\nname = \"demo\"\n"),
+            Ok(None)
+        );
+        assert!(parse_config_toml(".python_ls.toml", "diagnostics = [").is_err());
+    }
+
+    #[test]
+    fn test_line_suppression() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            r#"
+import os  # noqa: PY005
+import sys  # noqa
+import json  # type: ignore[unused-import]
+import re  # pyright: ignore
+import abc  # noqa: E501
+import io  # type: ignore  # noqa: PY004, PY005
+"#,
+            vec![diagnostic("PY005", 5), diagnostic("PY009", 6)],
+        ));
+    }
+
+    #[test]
+    fn test_type_ignore_codes() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        // the codes of mypy and pyright for the checks of this server
+        assert!(ws.check_diagnostics(
+            r#"
+a = missing  # type: ignore[name-defined]
+b = missing  # type: ignore[PY004]
+c = missing  # pyright: ignore[reportUndefinedVariable]
+for d in missing:  # type: ignore[undefined-name, attr-defined]
+    pass
+e = missing  # type: ignore[attr-defined]
+"#,
+            vec![diagnostic("PY004", 6)],
+        ));
+        // an alias which suppressed nothing may still be needed by its checker
+        assert!(ws.check_diagnostics(
+            "import os  # type: ignore[name-defined, PY004]\n\nos.getcwd()\n",
+            vec![diagnostic("PY009", 0)],
+        ));
+    }
+
+    #[test]
+    fn test_leading_type_ignore() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            "#!/usr/bin/env python\n# type: ignore\n\nimport os\nprint(missing)\n",
+            vec![],
+        ));
+        // after the docstring, or with codes, it only covers its own line
+        assert!(ws.check_diagnostics(
+            "\"\"\"Docs\"\"\"\n# type: ignore\nprint(missing)\n",
+            vec![diagnostic("PY004", 2)],
+        ));
+        assert!(ws.check_diagnostics(
+            "# type: ignore[name-defined]\nprint(missing)\n",
+            vec![diagnostic("PY004", 1)],
+        ));
+    }
+
+    #[test]
+    fn test_file_and_region_suppression() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            "# ruff: noqa: PY005\nimport os\nprint(missing)\n",
+            vec![diagnostic("PY004", 2)],
+        ));
+        assert!(ws.check_diagnostics(
+            r#"
+def f():
+    # python_ls: disable=PY006
+    a = 1
+    # python_ls: enable=PY006
+    b = 2
+    # python_ls: disable
+    c = 3
+"#,
+            vec![diagnostic("PY006", 5)],
+        ));
+        // an own pragma which suppresses nothing is reported even without codes
+        assert!(ws.check_diagnostics(
+            "# python_ls: noqa\nimport os\n\nos.getcwd()\n",
+            vec![diagnostic("PY009", 0)],
+        ));
+    }
+
+    #[test]
+    fn test_remove_unused_suppression_fix() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_code_action(
+            "import os  # noqa: PY004, PY<??>005\n\nos.getcwd()\n",
+            vec![action(
+                "Remove unused suppression of `PY005`",
+                "import os  # noqa: PY004\n\nos.getcwd()\n",
+            )],
+        ));
+        assert!(ws.check_code_action(
+            "import os  # noqa: PY<??>005\n\nos.getcwd()\n",
+            vec![action(
+                "Remove unused suppression of `PY005`",
+                "import os\n\nos.getcwd()\n",
+            )],
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use python_parser::{PyAstNode, PyAstToken, PyNameExpr};
+    use rowan::{TextRange, TextSize};
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualDiagnostic};
+
+    fn diagnostic(line: u32) -> VirtualDiagnostic {
+        VirtualDiagnostic {
+            code: "PY003".to_string(),
+            line,
+        }
+    }
+
+    #[test]
+    fn test_doc_example_diagnostics() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            r#"
+def add(a, b):
+    """Add two numbers.
+
+    >>> if add(1, 2):
+    ...     print("3")
+    3
+    >>> add(1 2)
+    Traceback (most recent call last):
+    SyntaxError: invalid syntax
+
+    ```python
+    def broken(:
+        pass
+    ```
+
+    ```text
+    not python (
+    ```
+    """
+    return a + b
+"#,
+            vec![diagnostic(7), diagnostic(12)],
+        ));
+    }
+
+    #[test]
+    fn test_doc_example_ranges() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let content = r#"def total(items):
+    """Sum the items.
+
+    >>> values = [1, 2]
+    >>> for value in values:
+    ...     total(value)
+    """
+"#;
+        let file_id = ws.def(content);
+        let semantic_model = ws.analysis.get_semantic_model(file_id).unwrap();
+        let offset = TextSize::from(content.find("total(value)").unwrap() as u32);
+        let example = semantic_model.find_doc_example(offset).unwrap();
+        assert_eq!(example.to_example_offset(TextSize::from(0)), None);
+
+        // each name of the example maps to the same text in the file
+        let names: Vec<(String, TextRange)> = example
+            .get_tree()
+            .get_module_node()
+            .descendants::<PyNameExpr>()
+            .filter_map(|name| name.get_name_token())
+            .map(|name| (name.get_name_text().to_string(), name.get_range()))
+            .collect();
+        assert_eq!(names.len(), 4);
+        for (name, range) in names {
+            let host = example.to_host_range(range).unwrap();
+            assert_eq!(&content[host], name);
+            assert_eq!(example.to_example_offset(host.start()), Some(range.start()));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use python_code_analysis::{render_docstring, render_docstring_markdown};
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualHoverResult};
+
+    #[test]
+    fn test_render_sections() {
+        let rendered = render_docstring_markdown(
+            r#"Fetch rows from a table.
+
+    Args:
+        table (Table): The table
+            to read.
+        *keys: The keys to fetch.
+
+    Returns:
+        dict[str, tuple]: The rows.
+
+    Raises:
+        IOError: An error reading the table.
+    "#,
+        );
+        assert_eq!(
+            rendered,
+            "Fetch rows from a table.\n\n\
+             **Parameters**\n\n\
+             - `table` (`Table`) — The table to read.\n\
+             - `*keys` — The keys to fetch.\n\n\
+             **Returns**\n\n\
+             - `dict[str, tuple]` — The rows.\n\n\
+             **Raises**\n\n\
+             - `IOError` — An error reading the table."
+        );
+    }
+
+    #[test]
+    fn test_render_inline_markup() {
+        let resolve_link = |target: &str| match target {
+            "pkg.Table" => Some("file:///pkg.py#L3".to_string()),
+            _ => None,
+        };
+        let rendered = render_docstring(
+            "Read a :class:`~pkg.Table` with :func:`load`, ``rows[0]`` and *some* care_.\n\n\
+             See `the docs <https://example.com>`_ or :ref:`tables`.",
+            &resolve_link,
+        );
+        assert_eq!(
+            rendered,
+            "Read a [`Table`](file:///pkg.py#L3) with `load()`, `rows[0]` and *some* care\\_.\n\n\
+             See [the docs](https://example.com) or tables."
+        );
+    }
+
+    #[test]
+    fn test_render_directives() {
+        let rendered = render_docstring_markdown(
+            r#"Parse a value.
+
+    .. note::
+       Values are stripped.
+
+    .. deprecated:: 2.0
+       Use :func:`parse_all`.
+
+    .. code-block:: json
+
+       {"a": 1}
+
+    .. a comment which is not shown
+
+    Example::
+
+        parse(" 1 ")
+    "#,
+        );
+        assert_eq!(
+            rendered,
+            "Parse a value.\n\n\
+             > **Note**\n>\n> Values are stripped.\n\n\
+             > **Deprecated since version 2.0**\n>\n> Use `parse_all()`.\n\n\
+             ```json\n{\"a\": 1}\n```\n\n\
+             Example:\n\n\
+             ```\nparse(\" 1 \")\n```"
+        );
+    }
+
+    #[test]
+    fn test_render_doctest() {
+        let rendered = render_docstring_markdown(
+            r#"Add numbers.
+
+    >>> for n in range(2):
+    ...     add(n, 1)
+    1
+    2
+    "#,
+        );
+        assert_eq!(
+            rendered,
+            "Add numbers.\n\n```python\nfor n in range(2):\n    add(n, 1)\n# 1\n# 2\n```"
+        );
+    }
+
+    #[test]
+    fn test_hover_docstring_links() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(
+            ws.check_hover(
+                r#"
+class Table:
+    def read(self):
+        pass
+
+def lo<??>ad(name):
+    """Load a :class:`Table`, see :meth:`Table.read` and :func:`missing`."""
+"#,
+                VirtualHoverResult {
+                    value: "```python\n(function) def load(name) -> None\n```\n\n---\n\n\
+                        Load a [`Table`](file:///virtual_1.py#L2), see \
+                        [`Table.read()`](file:///virtual_1.py#L3) and `missing()`."
+                        .to_string(),
+                },
+            )
+        );
+    }
+}
@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use python_code_analysis::{PyDocStyle, parse_docstring};
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualDiagnostic};
+
+    fn diagnostic(line: u32) -> VirtualDiagnostic {
+        VirtualDiagnostic {
+            code: "PY003".to_string(),
+            line,
+        }
+    }
+
+    #[test]
+    fn test_google_docstring() {
+        let doc = parse_docstring(
+            r#"Fetch rows from a table.
+
+    Retrieves the rows of the given keys.
+
+    Args:
+        table (Table): The table to read.
+        keys (Sequence[str], optional): The keys
+            of the rows to fetch.
+        *args: Passed along.
+
+    Returns:
+        dict[str, tuple]: A mapping of keys
+        to rows.
+
+    Raises:
+        IOError: An error reading the table.
+
+    Note:
+        Keys which are missing are skipped.
+
+    Examples:
+        >>> fetch(table, ["a"])
+        {'a': (1,)}
+    "#,
+        );
+        assert_eq!(doc.style, PyDocStyle::Google);
+        assert_eq!(doc.summary, "Fetch rows from a table.");
+        assert_eq!(
+            doc.description,
+            "Retrieves the rows of the given keys.\n\nNote:\n    Keys which are missing are skipped."
+        );
+        let params: Vec<_> = doc
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), param.type_name.as_deref(), param.line))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("table", Some("Table"), 5),
+                ("keys", Some("Sequence[str], optional"), 6),
+                ("*args", None, 8),
+            ]
+        );
+        assert_eq!(doc.params[1].description, "The keys\nof the rows to fetch.");
+        assert_eq!(
+            doc.returns[0].type_name.as_deref(),
+            Some("dict[str, tuple]")
+        );
+        assert_eq!(doc.returns[0].description, "A mapping of keys\nto rows.");
+        assert_eq!(doc.raises[0].exception, "IOError");
+        assert_eq!(doc.examples, vec![">>> fetch(table, [\"a\"])\n{'a': (1,)}"]);
+        assert!(doc.errors.is_empty());
+    }
+
+    #[test]
+    fn test_numpy_docstring() {
+        let doc = parse_docstring(
+            r#"
+    Compute the mean.
+
+    Parameters
+    ----------
+    x, y : array_like
+        The values.
+    axis : int, optional
+
+    Returns
+    -------
+    mean : ndarray
+        The mean of the values.
+
+    Yields
+    ------
+    int
+
+    Raises
+    ------
+    ValueError
+        When the values are empty.
+    "#,
+        );
+        assert_eq!(doc.style, PyDocStyle::Numpy);
+        assert_eq!(doc.summary, "Compute the mean.");
+        assert_eq!(doc.description, "");
+        let params: Vec<_> = doc
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), param.type_name.as_deref()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("x", Some("array_like")),
+                ("y", Some("array_like")),
+                ("axis", Some("int, optional")),
+            ]
+        );
+        assert_eq!(doc.params[0].description, "The values.");
+        assert_eq!(doc.returns[0].name.as_deref(), Some("mean"));
+        assert_eq!(doc.returns[0].type_name.as_deref(), Some("ndarray"));
+        assert_eq!(doc.yields[0].type_name.as_deref(), Some("int"));
+        assert_eq!(doc.raises[0].exception, "ValueError");
+        assert!(doc.errors.is_empty());
+    }
+
+    #[test]
+    fn test_sphinx_and_epydoc_docstring() {
+        let doc = parse_docstring(
+            r#"Send a message.
+
+    :param str recipient: Who gets it.
+    :param body: The text,
+        in plain text.
+    :type body: str
+    :returns: Whether it was sent.
+    :rtype: bool
+    :raises ConnectionError: When the server is down.
+    :ivar retries: How often sending is tried.
+    "#,
+        );
+        assert_eq!(doc.style, PyDocStyle::Sphinx);
+        assert_eq!(doc.summary, "Send a message.");
+        assert_eq!(doc.params[0].type_name.as_deref(), Some("str"));
+        assert_eq!(doc.params[1].type_name.as_deref(), Some("str"));
+        assert_eq!(doc.params[1].description, "The text,\nin plain text.");
+        assert_eq!(doc.returns[0].type_name.as_deref(), Some("bool"));
+        assert_eq!(doc.returns[0].description, "Whether it was sent.");
+        assert_eq!(doc.raises[0].exception, "ConnectionError");
+        assert_eq!(doc.attributes[0].name, "retries");
+
+        let doc = parse_docstring(
+            "Add two numbers.\n\n@param a: The first.\n@type a: int\n@param b: The second.\n@return: The sum.\n@rtype: int\n",
+        );
+        assert_eq!(doc.style, PyDocStyle::Epydoc);
+        assert_eq!(doc.params.len(), 2);
+        assert_eq!(doc.params[0].type_name.as_deref(), Some("int"));
+        assert_eq!(doc.returns[0].type_name.as_deref(), Some("int"));
+    }
+
+    #[test]
+    fn test_plain_docstring() {
+        let doc = parse_docstring(
+            "Return the answer\n    to everything.\n\n    Nothing more.\n\n    >>> answer()\n    42\n    ",
+        );
+        assert_eq!(doc.style, PyDocStyle::Plain);
+        assert_eq!(doc.summary, "Return the answer to everything.");
+        assert_eq!(doc.description, "Nothing more.\n\n>>> answer()\n42");
+        assert_eq!(doc.examples, vec![">>> answer()\n42"]);
+    }
+
+    #[test]
+    fn test_malformed_sections() {
+        let doc = parse_docstring(
+            "Summary.\n\n    Args:\n        not a parameter\n        x: Fine.\n\n    Returns:\n\n    Raises:\n        not an exception: Text.\n    ",
+        );
+        let errors: Vec<_> = doc.errors.iter().map(|error| error.line).collect();
+        assert_eq!(errors, vec![3, 6, 9]);
+        assert_eq!(doc.params.len(), 1);
+
+        let doc =
+            parse_docstring("Summary.\n\n:param: Missing the name.\n:raises: Missing the type.\n");
+        assert_eq!(doc.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_doc_error_diagnostics() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_diagnostics(
+            r#"
+def scale(value, factor, *, clamp=False):
+    """Scale a value.
+
+    Args:
+        value: The value.
+        factor: The factor.
+        offset: Not a parameter.
+    """
+    return value * factor if clamp else value
+
+class Point:
+    """A point.
+
+    :param x: The x coordinate.
+    :param z: Not a parameter.
+    """
+
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def move(self, dx, dy):
+        """Move the point.
+
+        Parameters
+        ----------
+        dx, dy : float
+            The distance.
+        """
+
+def undocumented(a, b):
+    """Only a summary, the parameters are not asked for."""
+"#,
+            vec![diagnostic(1), diagnostic(7), diagnostic(15), diagnostic(18),],
+        ));
+        assert!(ws.check_diagnostics(
+            "def f(a):\n    \"\"\"Summary.\n\n    Args:\n        a: Fine.\n\n    Returns:\n    \"\"\"\n",
+            vec![diagnostic(6)],
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use python_code_analysis::{PyEnvironment, PyEnvironmentKind, discover_environment};
+    use python_parser::PyVersionNumber;
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, TempDir};
+
+    fn site_packages(prefix: &Path) -> PathBuf {
+        prefix.join("lib/python3.11/site-packages")
+    }
+
+    #[test]
+    fn test_venv() {
+        let dir = TempDir::new("venv");
+        dir.write(
+            ".venv/pyvenv.cfg",
+            "home = /usr/bin\ninclude-system-site-packages = false\nversion = 3.11.4\n",
+        );
+        dir.mkdir(".venv/lib/python3.11/site-packages");
+        let source = dir.mkdir("src");
+        dir.write(
+            ".venv/lib/python3.11/site-packages/demo.pth",
+            "# a comment\n../../../../src\nimport sys\nmissing\n",
+        );
+
+        let environment = discover_environment(&dir.0, Some(".venv")).unwrap();
+        assert_eq!(environment.kind, PyEnvironmentKind::Venv);
+        assert_eq!(environment.version, Some(PyVersionNumber::new(3, 11, 4)));
+        let prefix = dir.0.join(".venv");
+        assert_eq!(environment.site_packages, vec![site_packages(&prefix)]);
+        assert_eq!(environment.search_paths.len(), 2);
+        assert_eq!(
+            environment.search_paths[1].canonicalize().unwrap(),
+            source.canonicalize().unwrap()
+        );
+
+        assert!(discover_environment(&dir.0, Some("missing")).is_none());
+    }
+
+    #[test]
+    fn test_uv_editable_install() {
+        let dir = TempDir::new("uv");
+        dir.write(
+            "env/pyvenv.cfg",
+            "home = /opt/python/bin\nimplementation = CPython\nuv = 0.4.18\nversion_info = 3.12.6\n",
+        );
+        let prefix = dir.0.join("env");
+        let project = dir.mkdir("project/src/pkg");
+        let site = site_packages(&prefix);
+        fs::create_dir_all(&site).unwrap();
+        dir.write(
+            "env/lib/python3.11/site-packages/__editable__.pkg-1.0.pth",
+            "import __editable___pkg_1_0_finder; __editable___pkg_1_0_finder.install()\n",
+        );
+        dir.write(
+            "env/lib/python3.11/site-packages/__editable___pkg_1_0_finder.py",
+            &format!(
+                "import sys\nMAPPING: dict[str, str] = {{'pkg': {:?}}}\nNAMESPACES = {{}}\n",
+                project.to_str().unwrap()
+            ),
+        );
+
+        let environment = PyEnvironment::load(&prefix, PyEnvironmentKind::Venv).unwrap();
+        assert_eq!(environment.kind, PyEnvironmentKind::Uv);
+        assert_eq!(environment.version, Some(PyVersionNumber::new(3, 12, 6)));
+        assert_eq!(
+            environment.search_paths,
+            vec![site, project.parent().unwrap().to_path_buf()]
+        );
+    }
+
+    #[test]
+    fn test_conda() {
+        let dir = TempDir::new("conda");
+        dir.write("conda-meta/python-3.10.13-h955ad1f_0.json", "{}");
+        dir.write("conda-meta/numpy-1.26.0-py310_0.json", "{}");
+
+        let environment = PyEnvironment::load(&dir.0, PyEnvironmentKind::Venv).unwrap();
+        assert_eq!(environment.kind, PyEnvironmentKind::Conda);
+        assert_eq!(environment.version, Some(PyVersionNumber::new(3, 10, 13)));
+        assert!(PyEnvironment::load(&dir.0.join("conda-meta"), PyEnvironmentKind::Venv).is_none());
+    }
+
+    #[test]
+    fn test_pypackages() {
+        let dir = TempDir::new("pypackages");
+        dir.mkdir("__pypackages__/3.9/lib");
+        let lib = dir.mkdir("__pypackages__/3.11/lib");
+
+        let environment = PyEnvironment::load_pypackages(&dir.0).unwrap();
+        assert_eq!(environment.version, Some(PyVersionNumber::new(3, 11, 0)));
+        assert_eq!(environment.site_packages, vec![lib]);
+    }
+
+    #[test]
+    fn test_environment_version() {
+        let dir = TempDir::new("level");
+        dir.write("venv/pyvenv.cfg", "version = 3.9.18\n");
+
+        let mut ws = ProviderVirtualWorkspace::new();
+        let file_id = ws.def("T = int | str\n");
+        assert!(ws.analysis.diagnose_file(file_id).unwrap().is_empty());
+
+        ws.analysis
+            .set_environment(discover_environment(&dir.0, Some("venv")));
+        assert_eq!(ws.analysis.diagnose_file(file_id).unwrap().len(), 1);
+
+        // the configured version wins over the environment's
+        ws.set_python_version(12);
+        assert!(ws.analysis.diagnose_file(file_id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use lsp_types::FoldingRangeKind;
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualFoldingRange};
+
+    fn range(
+        start_line: u32,
+        end_line: u32,
+        kind: Option<FoldingRangeKind>,
+    ) -> VirtualFoldingRange {
+        VirtualFoldingRange {
+            start_line,
+            end_line,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_folding_suites_and_docstring() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_folding_range(
+            r#"@decorator
+def f(x):
+    """Summary.
+
+    Details.
+    """
+    if x:
+        return 1
+    else:
+        return 2
+
+class A:
+    pass
+"#,
+            vec![
+                range(1, 9, None),
+                range(2, 5, Some(FoldingRangeKind::Comment)),
+                range(6, 7, None),
+                range(8, 9, None),
+                range(11, 12, None),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_folding_imports_and_comments() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_folding_range(
+            r#"import os
+import sys
+from typing import Any
+
+# first
+# second
+x = 1  # trailing
+# single
+"#,
+            vec![
+                range(0, 2, Some(FoldingRangeKind::Imports)),
+                range(4, 5, Some(FoldingRangeKind::Comment)),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_folding_regions() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_folding_range(
+            r#"# region setup
+x = 1
+#region inner
+y = 2
+# endregion
+# endregion
+"#,
+            vec![
+                range(0, 5, Some(FoldingRangeKind::Region)),
+                range(2, 4, Some(FoldingRangeKind::Region)),
+            ],
+        ));
+        // words which only start with a marker are plain comments
+        assert!(ws.check_folding_range(
+            r#"# regional settings
+x = 1
+# endregions of the map
+y = 2
+"#,
+            vec![],
+        ));
+    }
+
+    #[test]
+    fn test_folding_brackets_and_strings() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_folding_range(
+            r#"x = foo(
+1,
+2,
+)
+s = """a
+b
+c"""
+y = {1: 2,
+3: 4}
+"#,
+            vec![range(0, 2, None), range(4, 6, None), range(7, 8, None),],
+        ));
+    }
+}
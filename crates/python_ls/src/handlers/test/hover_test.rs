@@ -0,0 +1,354 @@
+#[cfg(test)]
+mod tests {
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualHoverResult};
+
+    #[test]
+    fn test_hover_function_signature() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+@cache
+async def fe<??>tch(a, b: int = 1, /, c=2, *args, d, e: str = "x", **kw) -> int:
+    """Fetch things."""
+    return 1
+"#,
+            VirtualHoverResult {
+                value: "```python\n@cache\n(function) async def fetch(a, b: int = 1, /, c=2, *args, d, e: str = \"x\", **kw) -> int\n```\n\n---\n\nFetch things.".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_keyword_only_marker() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+def f(a, *, b):
+    pass
+
+<??>f(1, b=2)
+"#,
+            VirtualHoverResult {
+                value: "```python\n(function) def f(a, *, b) -> None\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_inferred_return_type() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+def add<??>(a: int, b: int):
+    if a:
+        return a + b
+    return 1.5
+"#,
+            VirtualHoverResult {
+                value:
+                    "```python\n(function) def add(a: int, b: int) -> int | float\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_method_and_docstring() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+class Greeter(Base, metaclass=Meta):
+    """Say hello.
+
+    Example::
+
+        Greeter().greet("x")
+    """
+
+    def greet(self, name: str) -> str:
+        """Greet *name*.
+
+        >>> Greeter().greet("bob")
+        'hello bob'
+        """
+        return "hello " + name
+
+g = Greeter()
+g.gr<??>eet("bob")
+"#,
+            VirtualHoverResult {
+                value: "```python\n(method) def greet(name: str) -> str\n```\n\n---\n\nGreet *name*.\n\n```python\nGreeter().greet(\"bob\")\n# 'hello bob'\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_class() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+class Base:
+    pass
+
+@dataclass
+class Po<??>int(Base, metaclass=Meta):
+    """A point."""
+"#,
+            VirtualHoverResult {
+                value: "```python\n@dataclass\n(class) class Point(Base, metaclass=Meta)\n```\n\n---\n\nA point.".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_variable_types() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+items = [1, 2.5]
+<??>items
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) items: list[int | float]\n```".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+class A:
+    pass
+
+def make() -> A:
+    return A()
+
+v<??>alue = make()
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) value: A\n```".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+MAX_<??>SIZE = {"a": (1, "b")}
+"#,
+            VirtualHoverResult {
+                value:
+                    "```python\n(constant) MAX_SIZE: dict[str, tuple[int, str]]\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_parameter() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+def f(x: Optional[int], *args: str, **kwargs):
+    return <??>x
+"#,
+            VirtualHoverResult {
+                value: "```python\n(parameter) x: int | None\n```".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+def f(x: Optional[int], *args: str, **kwargs):
+    return ar<??>gs
+"#,
+            VirtualHoverResult {
+                value: "```python\n(parameter) args: tuple[str, ...]\n```".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+class A:
+    def m(self):
+        return se<??>lf
+"#,
+            VirtualHoverResult {
+                value: "```python\n(parameter) self: A\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_async_call_result() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+async def load() -> bytes:
+    return b""
+
+async def main():
+    da<??>ta = await load()
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) data: bytes\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_import() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+import os.path
+from collections import OrderedDict as OD
+o<??>s
+"#,
+            VirtualHoverResult {
+                value: "```python\n(module) os\n```".to_string(),
+            },
+        ));
+        assert!(
+            ws.check_hover(
+                r#"
+from collections import OrderedDict as OD
+O<??>D
+"#,
+                VirtualHoverResult {
+                    value: "```python\n(import) from collections import OrderedDict as OD\n```"
+                        .to_string(),
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn test_hover_import_from_workspace_module() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.def_file(
+            "shapes.py",
+            "def area(r: float) -> float:\n    return r * r\n\nUNIT = \"cm\"\n",
+        );
+        assert!(
+            ws.check_hover(
+                "from shapes import area, UNIT\nare<??>a(2.0)\n",
+                VirtualHoverResult {
+                    value: "```python\n(import) from shapes import area: (r: float) -> float\n```"
+                        .to_string(),
+                },
+            )
+        );
+        assert!(ws.check_hover(
+            "from shapes import UNIT as U\nprint(U<??>)\n",
+            VirtualHoverResult {
+                value: "```python\n(import) from shapes import UNIT as U: str\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_number_literal() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+x = 0x<??>FF
+"#,
+            VirtualHoverResult {
+                value: "```python\n(literal) 0xFF: int\n```\n\n---\n\nValue: `255`".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+x = 1_0<??>00_000_000_000_000_000_000
+"#,
+            VirtualHoverResult {
+                value: "```python\n(literal) 1_000_000_000_000_000_000_000: int\n```\n\n---\n\nValue: `1000000000000000000000`".to_string(),
+            },
+        ));
+        assert!(
+            ws.check_hover(
+                r#"
+x = 1.5<??>e20
+"#,
+                VirtualHoverResult {
+                    value: "```python\n(literal) 1.5e20: float\n```\n\n---\n\nValue: `1.5e+20`"
+                        .to_string(),
+                },
+            )
+        );
+        assert!(
+            ws.check_hover(
+                r#"
+x = <??>.000012
+"#,
+                VirtualHoverResult {
+                    value: "```python\n(literal) .000012: float\n```\n\n---\n\nValue: `1.2e-05`"
+                        .to_string(),
+                },
+            )
+        );
+        assert!(
+            ws.check_hover(
+                r#"
+x = 1_0<??>00.5
+"#,
+                VirtualHoverResult {
+                    value: "```python\n(literal) 1_000.5: float\n```\n\n---\n\nValue: `1000.5`"
+                        .to_string(),
+                },
+            )
+        );
+        assert!(
+            ws.check_hover(
+                r#"
+x = 1_0<??>00.5e-7
+"#,
+                VirtualHoverResult {
+                    value:
+                        "```python\n(literal) 1_000.5e-7: float\n```\n\n---\n\nValue: `0.00010005`"
+                            .to_string(),
+                },
+            )
+        );
+        assert!(
+            ws.check_hover(
+                r#"
+x = 1<??>e1_0
+"#,
+                VirtualHoverResult {
+                    value:
+                        "```python\n(literal) 1e1_0: float\n```\n\n---\n\nValue: `10000000000.0`"
+                            .to_string(),
+                },
+            )
+        );
+        assert!(ws.check_hover(
+            r#"
+x = 3<??>j
+"#,
+            VirtualHoverResult {
+                value: "```python\n(literal) 3j: complex\n```\n\n---\n\nValue: `3j`".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_builtin() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+n = l<??>en([1])
+"#,
+            VirtualHoverResult {
+                value: "```python\n(function) len\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_keyword_argument() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+def f(a, *, flag: bool = False):
+    pass
+
+f(1, fl<??>ag=True)
+"#,
+            VirtualHoverResult {
+                value: "```python\n(parameter) flag: bool = False\n```".to_string(),
+            },
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use python_code_analysis::PyConfig;
+
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualInlayHint};
+
+    fn hint(label: &str, line: u32, character: u32, edit: Option<&str>) -> VirtualInlayHint {
+        VirtualInlayHint {
+            label: label.to_string(),
+            line,
+            character,
+            edit: edit.map(|it| it.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_inlay_hint_variable_types() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_inlay_hint(
+            r#"x = 1
+x = "a"
+a, b = 1, 2.0
+items = [1, 2]
+y: int = 1
+"#,
+            vec![
+                hint(": int", 0, 1, Some(": int")),
+                hint(": str", 1, 1, None),
+                hint(": int", 2, 1, None),
+                hint(": float", 2, 4, None),
+                hint(": list[int]", 3, 5, Some(": list[int]")),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_inlay_hint_for_imported_names() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        ws.set_config(PyConfig::default());
+        ws.def_file(
+            "pkg/shapes.py",
+            "def area(r: float) -> float:\n    return r * r\n\nSIZES = [1, 2]\n",
+        );
+        ws.def_file(
+            "pkg/report.py",
+            "from .shapes import area, SIZES\n\ntotal = area(2.0)\nsizes = SIZES\n",
+        );
+        assert!(ws.check_inlay_hint(
+            r#"from pkg.shapes import area
+from pkg.report import total
+
+x = area(1.0)
+y = total
+"#,
+            vec![
+                hint(": float", 3, 1, Some(": float")),
+                hint("r=", 3, 9, Some("r=")),
+                hint(": float", 4, 1, Some(": float")),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_inlay_hint_skips_obvious_constructor() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_inlay_hint(
+            r#"class A:
+    pass
+
+a = A()
+"#,
+            vec![],
+        ));
+    }
+
+    #[test]
+    fn test_inlay_hint_for_target_and_return_type() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_inlay_hint(
+            r#"def f(n: int):
+    for i in [1, 2]:
+        pass
+    return "s"
+"#,
+            vec![
+                hint("-> str", 0, 13, Some(" -> str")),
+                hint(": int", 1, 9, None),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_inlay_hint_parameter_names() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_inlay_hint(
+            r#"def f(a, /, count, flag, *rest, key=None) -> None:
+    pass
+
+f(1, 2, -3, 4, key=5)
+f(n, True)
+"#,
+            vec![
+                hint("count=", 3, 5, Some("count=")),
+                hint("flag=", 3, 8, Some("flag=")),
+                hint("count=", 4, 5, Some("count=")),
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_inlay_hint_config() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let mut config = PyConfig::default();
+        config.hint.variable_types = false;
+        config.hint.parameter_names = false;
+        ws.analysis.update_config(Arc::new(config));
+        assert!(ws.check_inlay_hint(
+            r#"x = 1
+
+def f(a):
+    return 1
+
+f(1)
+"#,
+            vec![hint("-> int", 2, 8, Some(" -> int"))],
+        ));
+    }
+}
@@ -0,0 +1,17 @@
+mod code_action_test;
+mod config_test;
+mod diagnostic_test;
+mod docstring_example_test;
+mod docstring_render_test;
+mod docstring_test;
+mod environment_test;
+mod folding_range_test;
+mod hover_test;
+mod inlay_hint_test;
+mod notebook_test;
+mod query_test;
+mod selection_range_test;
+mod server_test;
+mod signature_help_test;
+mod type_comment_test;
+mod workspace_test;
@@ -0,0 +1,368 @@
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use lsp_types::{
+        CodeActionContext, CodeActionOrCommand, HoverContents, InlayHintLabel, NumberOrString,
+        Position, Range, Uri,
+    };
+    use python_code_analysis::{
+        PyCellKind, PyFileContent, PyNotebook, PyNotebookCell, file_syntax_tree, read_source_file,
+    };
+
+    use crate::handlers::{
+        code_actions::code_action,
+        hover::hover,
+        inlay_hint::{inlay_hint, resolve_document_inlay_hint, to_document_inlay_hint},
+        notebook_document::DocumentMapping,
+        selection_range::{selection_range, to_document_selection_range},
+        test_lib::{ProviderVirtualWorkspace, TempDir},
+    };
+
+    fn notebook_uri() -> Uri {
+        Uri::from_str("file:///analysis.ipynb").unwrap()
+    }
+
+    fn cell(index: usize, kind: PyCellKind, text: &str) -> PyNotebookCell {
+        PyNotebookCell {
+            uri: Uri::from_str(&format!("vscode-notebook-cell:/analysis.ipynb#C{}", index))
+                .unwrap(),
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    fn def_notebook(ws: &mut ProviderVirtualWorkspace, cells: Vec<PyNotebookCell>) {
+        ws.analysis
+            .update_notebook_by_uri(&notebook_uri(), PyNotebook::new(cells));
+    }
+
+    /// The codes of the diagnostics of each cell, with the line in the cell
+    fn cell_diagnostics(ws: &ProviderVirtualWorkspace) -> Vec<(usize, String, u32)> {
+        let file_id = ws.analysis.get_file_id(&notebook_uri()).unwrap();
+        let notebook = ws.analysis.get_notebook(file_id).unwrap();
+        let mut result: Vec<_> = ws
+            .analysis
+            .diagnose_file(file_id)
+            .unwrap()
+            .into_iter()
+            .filter_map(|diagnostic| {
+                let (cell, range) = notebook.to_cell_range(diagnostic.range)?;
+                let code = match diagnostic.code {
+                    Some(NumberOrString::String(code)) => code,
+                    _ => String::new(),
+                };
+                Some((cell, code, range.start.line))
+            })
+            .collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn test_magics_in_cells() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        def_notebook(
+            &mut ws,
+            vec![
+                cell(
+                    0,
+                    PyCellKind::Code,
+                    "%matplotlib inline\nimport os\n!pip install numpy\nos.path?\nprint(os.sep)\n",
+                ),
+                cell(
+                    1,
+                    PyCellKind::Code,
+                    "files = !ls -la\nfor f in files:\n    %time print(f)\n",
+                ),
+                // the lines of a string are not magics
+                cell(
+                    2,
+                    PyCellKind::Code,
+                    "text = \"\"\"\n%pwd\n!ls\n\"\"\"\nprint(text)\n",
+                ),
+                cell(3, PyCellKind::Code, "%%bash\necho hi\n"),
+                cell(
+                    4,
+                    PyCellKind::Code,
+                    "x = 7 % 2\ny = x != 1\nprint(missing)\n",
+                ),
+            ],
+        );
+        assert_eq!(cell_diagnostics(&ws), vec![(4, "PY004".to_string(), 2)]);
+
+        let file_id = ws.analysis.get_file_id(&notebook_uri()).unwrap();
+        let notebook = ws.analysis.get_notebook(file_id).unwrap();
+        assert!(
+            notebook.get_source().contains(
+                "text = \"\"\"\n%pwd\n!ls\n\"\"\"\nprint(text)\n#%%bash\n#echo hi\nx = 7"
+            )
+        );
+        let tree = file_syntax_tree(ws.analysis.compilation.get_db(), file_id)
+            .unwrap()
+            .unwrap();
+        assert!(tree.get_errors().is_empty());
+    }
+
+    #[test]
+    fn test_scope_flows_between_cells() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        def_notebook(
+            &mut ws,
+            vec![
+                cell(0, PyCellKind::Code, "import os\nroot = os.getcwd()"),
+                cell(1, PyCellKind::Markup, "# Results\nundefined"),
+                cell(2, PyCellKind::Code, "%time print(root)\nprint(missing)\n"),
+                cell(3, PyCellKind::Code, "!ls\nprint(other)"),
+            ],
+        );
+        assert_eq!(
+            cell_diagnostics(&ws),
+            vec![(2, "PY004".to_string(), 1), (3, "PY004".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_cell_positions() {
+        let notebook = PyNotebook::new(vec![
+            cell(0, PyCellKind::Code, "a = 1\nb = 2"),
+            cell(1, PyCellKind::Markup, "text"),
+            cell(2, PyCellKind::Code, "c = a\n"),
+        ]);
+        assert_eq!(notebook.get_source(), "a = 1\nb = 2\nc = a\n");
+        assert_eq!(
+            notebook.to_source_position(2, Position::new(0, 4)),
+            Some(Position::new(2, 4))
+        );
+        assert_eq!(notebook.to_source_position(1, Position::new(0, 0)), None);
+        assert_eq!(notebook.to_cell_line(1), Some((0, 1)));
+        assert_eq!(notebook.to_cell_line(2), Some((2, 0)));
+        // the end of the document is the end of the last code cell
+        assert_eq!(notebook.to_cell_line(3), Some((2, 1)));
+        // a range which runs into the next cell ends with its own cell
+        assert_eq!(
+            notebook.to_cell_range(Range::new(Position::new(1, 0), Position::new(2, 3))),
+            Some((0, Range::new(Position::new(1, 0), Position::new(1, 5))))
+        );
+    }
+
+    #[test]
+    fn test_from_ipynb() {
+        let json = r##"{
+  "cells": [
+    {"cell_type": "markdown", "metadata": {}, "source": ["# Title\n"]},
+    {"cell_type": "code", "metadata": {}, "outputs": [], "execution_count": 1,
+     "source": ["%load_ext autoreload\n", "x = 1\n", "print(x)"]},
+    {"cell_type": "code", "metadata": {}, "outputs": [], "source": "y = x?"}
+  ],
+  "metadata": {},
+  "nbformat": 4,
+  "nbformat_minor": 5
+}"##;
+        let notebook = PyNotebook::from_ipynb(&notebook_uri(), json).unwrap();
+        assert_eq!(notebook.get_cells().len(), 3);
+        assert_eq!(notebook.get_cells()[0].kind, PyCellKind::Markup);
+        assert_eq!(
+            notebook.get_cells()[1].uri.as_str(),
+            "file:///analysis.ipynb#cell-1"
+        );
+        assert_eq!(
+            notebook.get_source(),
+            "%load_ext autoreload\nx = 1\nprint(x)\ny = x?\n"
+        );
+        assert!(PyNotebook::from_ipynb(&notebook_uri(), "{\"cells\": 1}").is_err());
+    }
+
+    #[test]
+    fn test_read_notebook_file() {
+        let dir = TempDir::new("notebook_read");
+        let path = dir.write(
+            "explore.ipynb",
+            r#"{"cells": [{"cell_type": "code", "source": ["import sys\n", "!echo hi"]}], "nbformat": 4}"#,
+        );
+        assert_eq!(
+            read_source_file(&path).unwrap(),
+            PyFileContent::Text("import sys\n!echo hi\n".to_string())
+        );
+        let path = dir.write("broken.ipynb", "not json");
+        assert!(read_source_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_hover_in_cell() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        def_notebook(
+            &mut ws,
+            vec![
+                cell(
+                    0,
+                    PyCellKind::Code,
+                    "def area(r: float) -> float:\n    return r * r\n",
+                ),
+                cell(1, PyCellKind::Code, "area(2.0)\n"),
+            ],
+        );
+        let cell_uri = ws
+            .analysis
+            .get_notebook(ws.analysis.get_file_id(&notebook_uri()).unwrap())
+            .unwrap()
+            .get_cells()[1]
+            .uri
+            .clone();
+        let mapping = DocumentMapping::new(&ws.analysis, &cell_uri).unwrap();
+        let position = mapping.to_file_position(Position::new(0, 1)).unwrap();
+        assert_eq!(position, Position::new(2, 1));
+        let result = hover(&ws.analysis, mapping.file_id, position).unwrap();
+        let HoverContents::Markup(content) = result.contents else {
+            panic!("expected markup");
+        };
+        assert!(content.value.contains("def area(r: float) -> float"));
+        assert_eq!(
+            result
+                .range
+                .and_then(|range| mapping.to_document_range(range)),
+            Some(Range::new(Position::new(0, 0), Position::new(0, 4)))
+        );
+    }
+
+    #[test]
+    fn test_selection_range_in_cell() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        def_notebook(
+            &mut ws,
+            vec![
+                cell(0, PyCellKind::Code, "x = 1\n"),
+                cell(1, PyCellKind::Code, "y = [x, 2]\nprint(y)\n"),
+            ],
+        );
+        let file_id = ws.analysis.get_file_id(&notebook_uri()).unwrap();
+        let cell_uri = ws.analysis.get_notebook(file_id).unwrap().get_cells()[1]
+            .uri
+            .clone();
+        let mapping = DocumentMapping::new(&ws.analysis, &cell_uri).unwrap();
+        let position = mapping.to_file_position(Position::new(0, 6)).unwrap();
+        let mut result = selection_range(&ws.analysis, file_id, &[position]).unwrap();
+        let selection = to_document_selection_range(&mapping, result.pop().unwrap()).unwrap();
+
+        let mut ranges = Vec::new();
+        let mut current = Some(Box::new(selection));
+        while let Some(selection) = current {
+            ranges.push(selection.range);
+            current = selection.parent;
+        }
+        // the module reaches into the cell before, the chain stops at the
+        // statement
+        assert_eq!(
+            ranges,
+            vec![
+                Range::new(Position::new(0, 5), Position::new(0, 6)),
+                Range::new(Position::new(0, 4), Position::new(0, 10)),
+                Range::new(Position::new(0, 0), Position::new(0, 10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inlay_hint_in_cell() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        def_notebook(
+            &mut ws,
+            vec![
+                cell(0, PyCellKind::Code, "x = 1\n"),
+                cell(1, PyCellKind::Code, "items = [x, 2]\n"),
+            ],
+        );
+        let file_id = ws.analysis.get_file_id(&notebook_uri()).unwrap();
+        let cell_uri = ws.analysis.get_notebook(file_id).unwrap().get_cells()[1]
+            .uri
+            .clone();
+        let mapping = DocumentMapping::new(&ws.analysis, &cell_uri).unwrap();
+        let range = mapping
+            .to_file_range(Range::new(Position::new(0, 0), Position::new(0, 14)))
+            .unwrap();
+        let hints: Vec<_> = inlay_hint(&ws.analysis, file_id, range)
+            .unwrap()
+            .into_iter()
+            .filter_map(|hint| to_document_inlay_hint(&mapping, hint))
+            .collect();
+        assert_eq!(hints.len(), 1);
+        let mut hint = hints[0].clone();
+        assert!(matches!(&hint.label, InlayHintLabel::String(label) if label == ": list[int]"));
+        assert_eq!(hint.position, Position::new(0, 5));
+
+        // the edit is resolved in the cell as well
+        hint.text_edits = None;
+        let hint = resolve_document_inlay_hint(&ws.analysis, &mapping, hint);
+        let edits = hint.text_edits.unwrap();
+        assert_eq!(edits[0].new_text, ": list[int]");
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(0, 5), Position::new(0, 5))
+        );
+    }
+
+    #[test]
+    fn test_code_action_edits_cell() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        def_notebook(
+            &mut ws,
+            vec![
+                cell(0, PyCellKind::Code, "x = 1\n"),
+                cell(1, PyCellKind::Code, "import os\nprint(x)\n"),
+            ],
+        );
+        let file_id = ws.analysis.get_file_id(&notebook_uri()).unwrap();
+        let cell_uri = ws.analysis.get_notebook(file_id).unwrap().get_cells()[1]
+            .uri
+            .clone();
+        let mapping = DocumentMapping::new(&ws.analysis, &cell_uri).unwrap();
+        let range = mapping
+            .to_file_range(Range::new(Position::new(0, 7), Position::new(0, 7)))
+            .unwrap();
+        let diagnostics = ws
+            .analysis
+            .diagnose_file(file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|diagnostic| diagnostic.range.start.line == range.start.line)
+            .collect();
+        let context = CodeActionContext {
+            diagnostics,
+            ..Default::default()
+        };
+        let actions = code_action(&ws.analysis, file_id, range, &context).unwrap();
+        let mut edit = actions
+            .into_iter()
+            .find_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) if action.title.contains("os") => {
+                    action.edit
+                }
+                _ => None,
+            })
+            .unwrap();
+        mapping.to_document_edit(&mut edit);
+        let changes: Vec<_> = edit.changes.unwrap().into_iter().collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, cell_uri);
+        assert_eq!(
+            changes[0].1[0].range,
+            Range::new(Position::new(0, 0), Position::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn test_ipython_script() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let code = "import os\n%cd /tmp\nfiles = !ls\nprint(files)\nos.path?\n";
+        let file_id = ws.def_file("setup.ipy", code);
+        let tree = file_syntax_tree(ws.analysis.compilation.get_db(), file_id)
+            .unwrap()
+            .unwrap();
+        assert!(tree.get_errors().is_empty());
+        let file_id = ws.def_file("setup.py", code);
+        let tree = file_syntax_tree(ws.analysis.compilation.get_db(), file_id)
+            .unwrap()
+            .unwrap();
+        assert!(!tree.get_errors().is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        str::FromStr,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use lsp_server::Connection;
+    use lsp_types::{DiagnosticSeverity, InitializeParams, Uri};
+    use python_code_analysis::{
+        CancelHandle, DiagnosticContext, DiagnosticData, FileId, LintRule, LintRuleMeta,
+        PyDeclKind, PyType, QueryKey, module_exports, module_types,
+    };
+
+    use tokio_util::sync::CancellationToken;
+
+    use crate::{
+        context::{ConfigManager, ServerContext, WorkspaceManager},
+        handlers::test_lib::ProviderVirtualWorkspace,
+    };
+
+    /// Counts how many times the diagnostics of any file are computed
+    struct CountingRule(Arc<AtomicUsize>);
+
+    impl LintRule for CountingRule {
+        fn get_metas(&self) -> &[LintRuleMeta] {
+            &[LintRuleMeta {
+                code: "TEST001",
+                name: "counting",
+                default_severity: DiagnosticSeverity::HINT,
+                description: "Counts the files checked",
+                default_enabled: true,
+                tags: &[],
+            }]
+        }
+
+        fn check_file(&self, _context: &mut DiagnosticContext) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn counting_workspace() -> (ProviderVirtualWorkspace, Arc<AtomicUsize>) {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        ws.analysis
+            .register_lint_rule(Box::new(CountingRule(count.clone())));
+        (ws, count)
+    }
+
+    fn export_names(ws: &mut ProviderVirtualWorkspace, text: &str) -> Vec<String> {
+        let file_id = ws.def(text);
+        module_exports(ws.analysis.compilation.get_db(), file_id)
+            .unwrap()
+            .iter()
+            .map(|export| export.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_diagnostics_are_memoized() {
+        let (mut ws, count) = counting_workspace();
+        let a = ws.def_file("a.py", "x = 1\n");
+        let b = ws.def_file("b.py", "y = 2\n");
+        ws.analysis.diagnose_file(a).unwrap();
+        ws.analysis.diagnose_file(b).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        // nothing changed
+        ws.analysis.diagnose_file(a).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        // an edit of `b` leaves the diagnostics of `a` as they were
+        ws.def_file("b.py", "y = 3\n");
+        ws.analysis.diagnose_file(a).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        ws.analysis.diagnose_file(b).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        // setting the same text again changes nothing
+        ws.def_file("a.py", "x = 1\n");
+        ws.analysis.diagnose_file(a).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        // the diagnostic settings are read by every file
+        ws.enable_diagnostic("doc-error");
+        ws.analysis.diagnose_file(a).unwrap();
+        ws.analysis.diagnose_file(b).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 5);
+
+        // other settings are not
+        ws.set_python_version(12);
+        ws.analysis.diagnose_file(a).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 5);
+    }
+
+    fn fix_titles(ws: &ProviderVirtualWorkspace, file_id: FileId) -> Vec<String> {
+        ws.analysis
+            .diagnose_file(file_id)
+            .unwrap()
+            .iter()
+            .filter_map(DiagnosticData::from_diagnostic)
+            .flat_map(|data| data.fixes)
+            .map(|fix| fix.title)
+            .collect()
+    }
+
+    #[test]
+    fn test_import_fixes_follow_other_files() {
+        let (mut ws, count) = counting_workspace();
+        let a = ws.def_file(
+            "a.py",
+            "helper()
+",
+        );
+        assert!(fix_titles(&ws, a).is_empty());
+
+        // a new file defining the name brings a fix
+        let b = ws.def_file(
+            "b.py",
+            "def helper():
+    return 1
+",
+        );
+        assert_eq!(fix_titles(&ws, a), vec!["Add `from b import helper`"]);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        // an edit which keeps the exports of `b` keeps the diagnostics
+        ws.def_file(
+            "b.py",
+            "def helper():
+    return 2
+",
+        );
+        assert_eq!(fix_titles(&ws, a), vec!["Add `from b import helper`"]);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        // the fix goes away with the definition and with the file
+        ws.def_file(
+            "b.py",
+            "def other():
+    return 2
+",
+        );
+        assert!(fix_titles(&ws, a).is_empty());
+        ws.def_file(
+            "b.py",
+            "def helper():
+    return 1
+",
+        );
+        assert_eq!(fix_titles(&ws, a).len(), 1);
+        let uri = ws
+            .analysis
+            .compilation
+            .get_db()
+            .get_vfs()
+            .get_uri(&b)
+            .unwrap();
+        ws.analysis.remove_file_by_uri(&uri);
+        assert!(fix_titles(&ws, a).is_empty());
+    }
+
+    #[test]
+    fn test_early_cutoff() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let file_id = ws.def_file("m.py", "def f():\n    return 1\n\nVALUE = 1\n");
+        let db = ws.analysis.compilation.get_db();
+        let exports = module_exports(db, file_id).unwrap();
+        let exports_changed_at = db
+            .get_query_db()
+            .get_changed_at(QueryKey::ModuleExports(file_id));
+        let types = module_types(db, file_id).unwrap();
+        assert_eq!(
+            types.as_ref(),
+            &vec![
+                ("f".to_string(), types[0].1.clone()),
+                ("VALUE".to_string(), PyType::Int)
+            ]
+        );
+
+        // an edit of a function body keeps the exports
+        ws.def_file("m.py", "def f():\n    return 2\n\nVALUE = 1\n");
+        let db = ws.analysis.compilation.get_db();
+        let queries = db.get_query_db();
+        assert_eq!(module_exports(db, file_id).unwrap(), exports);
+        assert_eq!(
+            queries.get_changed_at(QueryKey::Parse(file_id)),
+            Some(queries.get_revision())
+        );
+        assert_eq!(
+            queries.get_changed_at(QueryKey::ModuleExports(file_id)),
+            exports_changed_at
+        );
+
+        // a new top level name changes them
+        ws.def_file("m.py", "def f():\n    return 2\n\nVALUE = 1\nOTHER = ''\n");
+        let db = ws.analysis.compilation.get_db();
+        let queries = db.get_query_db();
+        assert_eq!(module_exports(db, file_id).unwrap().len(), 3);
+        assert_eq!(
+            queries.get_changed_at(QueryKey::ModuleExports(file_id)),
+            Some(queries.get_revision())
+        );
+        assert_eq!(
+            module_types(db, file_id).unwrap().last(),
+            Some(&("OTHER".to_string(), PyType::Str))
+        );
+    }
+
+    #[test]
+    fn test_module_types_through_imports() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let a = ws.def_file("a.py", "from b import B\nA = 1\nC = B\n");
+        let b = ws.def_file("b.py", "from a import A\nB = 'b'\nD = A\n");
+        let db = ws.analysis.compilation.get_db();
+        assert_eq!(
+            module_types(db, a).unwrap().as_ref(),
+            &vec![
+                ("B".to_string(), PyType::Str),
+                ("A".to_string(), PyType::Int),
+                ("C".to_string(), PyType::Str),
+            ]
+        );
+        // `a` imports from `b`, which was inferred while `a` was
+        assert_eq!(
+            module_types(db, b).unwrap().last(),
+            Some(&("D".to_string(), PyType::Unknown))
+        );
+
+        // a change in `b` reaches the names `a` imports from it
+        ws.def_file("b.py", "B = 2\n");
+        let db = ws.analysis.compilation.get_db();
+        assert_eq!(
+            module_types(db, a).unwrap().last(),
+            Some(&("C".to_string(), PyType::Int))
+        );
+    }
+
+    #[test]
+    fn test_module_exports() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert_eq!(
+            export_names(
+                &mut ws,
+                "import os\nfrom typing import Any\n_private = 1\ndef f(): pass\nclass C: pass\nf = 2\n"
+            ),
+            vec!["os", "Any", "f", "C"]
+        );
+        assert_eq!(
+            export_names(
+                &mut ws,
+                "__all__ = ['_g', 'h']\ndef _g(): pass\ndef h(): pass\ndef k(): pass\n"
+            ),
+            vec!["_g", "h"]
+        );
+
+        let file_id = ws.def("def f(): pass\nf = 1\n");
+        let exports = module_exports(ws.analysis.compilation.get_db(), file_id).unwrap();
+        assert_eq!(exports.len(), 1);
+        assert_eq!(exports[0].kind, PyDeclKind::Variable);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let (mut ws, count) = counting_workspace();
+        let file_id = ws.def("x = 1\n");
+        let handle = ws.analysis.cancel_handle();
+        handle.cancel();
+        assert!(ws.analysis.diagnose_file(file_id).is_none());
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        // applying the change which cancelled them resumes the queries
+        ws.def("y = 1\n");
+        assert!(!handle.is_cancelled());
+        assert!(ws.analysis.diagnose_file(file_id).is_some());
+
+        // removing a file which was never loaded is a change too
+        handle.cancel();
+        let uri = Uri::from_str("file:///missing.py").unwrap();
+        assert!(ws.analysis.remove_file_by_uri(&uri).is_none());
+        assert!(ws.analysis.diagnose_file(file_id).is_some());
+    }
+
+    #[test]
+    fn test_request_cancellation() {
+        let (mut ws, count) = counting_workspace();
+        let file_id = ws.def("x = 1\n");
+        let request = CancelHandle::default();
+        request.cancel();
+        assert!(request.run(|| ws.analysis.diagnose_file(file_id)).is_none());
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        // the queries of other requests go on
+        assert!(ws.analysis.diagnose_file(file_id).is_some());
+        let other = CancelHandle::default();
+        assert!(other.run(|| ws.analysis.diagnose_file(file_id)).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_with_analysis_cancellation() {
+        let (server, _client) = Connection::memory();
+        let params = InitializeParams::default();
+        let context = ServerContext::new(
+            server,
+            ConfigManager::new(&params),
+            WorkspaceManager::new(&params),
+        )
+        .snapshot();
+        let uri = Uri::from_str("file:///main.py").unwrap();
+        let file_id = context
+            .analysis
+            .write()
+            .await
+            .update_file_by_uri(&uri, Some("x = 1\n".to_string()))
+            .unwrap();
+
+        let token = CancellationToken::new();
+        let diagnostics = context
+            .with_analysis(&token, move |analysis| analysis.diagnose_file(file_id))
+            .await;
+        assert!(diagnostics.flatten().is_some());
+        assert!(!token.is_cancelled());
+
+        // a request cancelled before it runs does not run
+        token.cancel();
+        let ran = context.with_analysis(&token, |_| ()).await;
+        assert!(ran.is_none());
+
+        // an edit coming in cancels the request, its result may be partial
+        let token = CancellationToken::new();
+        let diagnostics = context
+            .with_analysis(&token, move |analysis| {
+                analysis.cancel_handle().cancel();
+                analysis.diagnose_file(file_id)
+            })
+            .await;
+        assert!(diagnostics.is_none());
+        assert!(token.is_cancelled());
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use crate::handlers::test_lib::ProviderVirtualWorkspace;
+
+    #[test]
+    fn test_selection_range_expands_through_call() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_selection_range(
+            r#"def f(obj):
+    x = 1
+    obj.me<??>thod(x)
+"#,
+            vec![
+                "method",
+                "obj.method",
+                "obj.method(x)",
+                "x = 1\n    obj.method(x)",
+                "def f(obj):\n    x = 1\n    obj.method(x)\n",
+            ],
+        ));
+    }
+
+    #[test]
+    fn test_selection_range_argument() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_selection_range(
+            r#"print(a + <??>b)
+"#,
+            vec!["b", "a + b", "(a + b)", "print(a + b)", "print(a + b)\n"],
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use lsp_server::{Connection, ErrorCode, Message, RequestId};
+    use lsp_types::{InitializeParams, Uri};
+
+    use crate::context::{ConfigManager, ServerContext, WorkspaceManager};
+
+    fn server_context() -> (ServerContext, Connection) {
+        let (server, client) = Connection::memory();
+        let params = InitializeParams::default();
+        let context = ServerContext::new(
+            server,
+            ConfigManager::new(&params),
+            WorkspaceManager::new(&params),
+        );
+        (context, client)
+    }
+
+    #[tokio::test]
+    async fn test_request_cancellation() {
+        let (context, client) = server_context();
+        let req_id = RequestId::from(7);
+        context.task(req_id.clone(), |_| std::future::pending());
+        context.cancel(req_id.clone());
+
+        let message = tokio::task::spawn_blocking(move || {
+            client.receiver.recv_timeout(Duration::from_secs(5))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        let Message::Response(response) = message else {
+            panic!("expected a response, got {:?}", message);
+        };
+        assert_eq!(response.id, req_id);
+        assert_eq!(
+            response.error.map(|err| err.code),
+            Some(ErrorCode::RequestCanceled as i32)
+        );
+    }
+
+    #[test]
+    fn test_debounce_diagnostics() {
+        let manager = WorkspaceManager::new(&InitializeParams::default());
+        let uri = Uri::from_str("file:///main.py").unwrap();
+        assert!(manager.debounce_diagnostics(&uri).is_none());
+
+        manager.open_file(&uri, 1);
+        let first = manager.debounce_diagnostics(&uri).unwrap();
+        manager.set_version(&uri, 2);
+        let second = manager.debounce_diagnostics(&uri).unwrap();
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+        assert_eq!(manager.get_version(&uri), Some(2));
+
+        manager.close_file(&uri);
+        assert!(second.is_cancelled());
+        assert_eq!(manager.get_version(&uri), None);
+    }
+}
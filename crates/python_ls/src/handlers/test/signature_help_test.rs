@@ -0,0 +1,271 @@
+#[cfg(test)]
+mod tests {
+    use crate::handlers::test_lib::{ProviderVirtualWorkspace, VirtualSignatureHelpResult};
+
+    #[test]
+    fn test_signature_help_positional() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def add(a: int, b: int = 1) -> int:
+    return a + b
+
+add(1, <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["add(a: int, b: int = 1) -> int".to_string()],
+                active_signature: 0,
+                active_parameter: Some("b: int = 1".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_unclosed_call() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, b):
+    pass
+
+f(<??>
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, b) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("a".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_keyword_argument() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, b, c):
+    pass
+
+f(1, c=<??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, b, c) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("c".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_after_keyword() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, b, c):
+    pass
+
+f(1, b=2, <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, b, c) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("c".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_positional_only_and_keyword_only() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, /, b, *, c):
+    pass
+
+f(1, 2, <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, /, b, *, c) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: None,
+            },
+        ));
+
+        // a positional only parameter can not be given by keyword
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, /, **kwargs):
+    pass
+
+f(1, a=<??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, /, **kwargs) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("**kwargs".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_var_args() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, *args: int, key=None, **kwargs):
+    pass
+
+f(1, 2, 3, <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, *args: int, key=None, **kwargs) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("*args: int".to_string()),
+            },
+        ));
+
+        assert!(ws.check_signature_help(
+            r#"
+def f(a, *args: int, key=None, **kwargs):
+    pass
+
+f(1, other=<??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["f(a, *args: int, key=None, **kwargs) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("**kwargs".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_overloads() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+from typing import overload
+
+@overload
+def get(key: str) -> str: ...
+@overload
+def get(key: str, default: int) -> int: ...
+def get(key, default=None):
+    return default
+
+get("a", <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec![
+                    "get(key: str) -> str".to_string(),
+                    "get(key: str, default: int) -> int".to_string(),
+                ],
+                active_signature: 1,
+                active_parameter: Some("default: int".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_method_and_class() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+class Point:
+    def __init__(self, x: int, y: int):
+        self.x = x
+
+    def move(self, dx: int, dy: int) -> None:
+        pass
+
+p = Point(<??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["Point(x: int, y: int)".to_string()],
+                active_signature: 0,
+                active_parameter: Some("x: int".to_string()),
+            },
+        ));
+
+        assert!(ws.check_signature_help(
+            r#"
+class Point:
+    def move(self, dx: int, dy: int) -> None:
+        pass
+
+Point().move(1, <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["move(dx: int, dy: int) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("dy: int".to_string()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_signature_help_classmethod_and_staticmethod() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        for call in ["A.c(1, <??>)", "A().c(1, <??>)"] {
+            assert!(ws.check_signature_help(
+                &format!(
+                    r#"
+class A:
+    @classmethod
+    def c(cls, x: int, y: int) -> None:
+        pass
+
+{}
+"#,
+                    call
+                ),
+                VirtualSignatureHelpResult {
+                    signatures: vec!["c(x: int, y: int) -> None".to_string()],
+                    active_signature: 0,
+                    active_parameter: Some("y: int".to_string()),
+                },
+            ));
+        }
+        for call in ["A.s(1, <??>)", "A().s(1, <??>)"] {
+            assert!(ws.check_signature_help(
+                &format!(
+                    r#"
+class A:
+    @staticmethod
+    def s(x: int, y: int) -> None:
+        pass
+
+{}
+"#,
+                    call
+                ),
+                VirtualSignatureHelpResult {
+                    signatures: vec!["s(x: int, y: int) -> None".to_string()],
+                    active_signature: 0,
+                    active_parameter: Some("y: int".to_string()),
+                },
+            ));
+        }
+    }
+
+    #[test]
+    fn test_signature_help_nested_call() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_signature_help(
+            r#"
+def outer(a, b):
+    pass
+
+def inner(x):
+    return x
+
+outer(inner(1), <??>)
+"#,
+            VirtualSignatureHelpResult {
+                signatures: vec!["outer(a, b) -> None".to_string()],
+                active_signature: 0,
+                active_parameter: Some("b".to_string()),
+            },
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use python_code_analysis::PyDeclKind;
+    use rowan::TextSize;
+
+    use crate::handlers::test_lib::{
+        ProviderVirtualWorkspace, VirtualDiagnostic, VirtualHoverResult,
+    };
+
+    #[test]
+    fn test_hover_assign_type_comment() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(ws.check_hover(
+            r#"
+items = []  # type: list[int]
+<??>items
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) items: list[int]\n```".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+for name in load():  # type: str
+    <??>name
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) name: str\n```".to_string(),
+            },
+        ));
+        assert!(ws.check_hover(
+            r#"
+with open("a") as f:  # type: bytes
+    <??>f
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) f: bytes\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_hover_signature_type_comment() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        assert!(
+            ws.check_hover(
+                r#"
+class Greeter:
+    def gr<??>eet(self, name, *tags):  # type: (str, *str) -> bool
+        return True
+"#,
+                VirtualHoverResult {
+                    value:
+                        "```python\n(method) def greet(self, name: str, *tags: str) -> bool\n```"
+                            .to_string(),
+                },
+            )
+        );
+        assert!(ws.check_hover(
+            r#"
+def count(text):
+    # type: (str) -> int
+    return len(text)
+
+tot<??>al = count("abc")
+"#,
+            VirtualHoverResult {
+                value: "```python\n(variable) total: int\n```".to_string(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_type_comment_names_resolve() {
+        let mut ws = ProviderVirtualWorkspace::new();
+        let code = "from pathlib import Path\n\nroot = None  # type: Path\n";
+        let file_id = ws.def(code);
+        let semantic_model = ws.analysis.get_semantic_model(file_id).unwrap();
+        let offset = code.rfind("Path").unwrap();
+        let decl = semantic_model
+            .find_decl("Path", TextSize::new(offset as u32))
+            .unwrap();
+        assert!(matches!(decl.get_kind(), PyDeclKind::ImportFrom { .. }));
+
+        // a name only used in a type comment is still used
+        assert!(ws.check_diagnostics(
+            r#"
+from typing import List, Optional
+import os
+
+def first(items):  # type: (List[str]) -> Optional[str]
+    return items[0] if items else None
+"#,
+            vec![VirtualDiagnostic {
+                code: "PY005".to_string(),
+                line: 2,
+            }],
+        ));
+    }
+}
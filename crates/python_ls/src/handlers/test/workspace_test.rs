@@ -0,0 +1,270 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        path::{Path, PathBuf},
+    };
+
+    use lsp_server::Connection;
+    use lsp_types::{InitializeParams, WorkspaceFolder};
+    use python_code_analysis::{PyAnalysis, PyFileContent, WorkspaceFilter, file_path_to_uri};
+
+    use crate::{
+        context::{ConfigManager, ServerContext, WorkspaceManager},
+        handlers::{
+            test_lib::{ProviderVirtualWorkspace, TempDir},
+            workspace::{
+                diff_workspace_files, file_watcher::on_files_changed, index_workspace, read_files,
+            },
+        },
+    };
+
+    fn relative_files(dir: &TempDir, filter: &WorkspaceFilter) -> Vec<String> {
+        filter
+            .collect_files()
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&dir.0)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let dir = TempDir::new("workspace_collect");
+        dir.write(
+            ".gitignore",
+            "# build output\nbuild/\n*.gen.py\n!keep.gen.py\n/top.py\n",
+        );
+        dir.write("main.py", "");
+        dir.write("top.py", "");
+        dir.write("pkg/top.py", "");
+        dir.write("pkg/__init__.pyi", "");
+        dir.write("pkg/a.gen.py", "");
+        dir.write("pkg/keep.gen.py", "");
+        dir.write("pkg/notes.txt", "");
+        dir.write("pkg/.gitignore", "local.py\n");
+        dir.write("pkg/local.py", "");
+        dir.write("local.py", "");
+        dir.write("build/out.py", "");
+        dir.write(".hidden/secret.py", "");
+        dir.write("pkg/__pycache__/cached.py", "");
+        dir.write("tests/test_a.py", "");
+
+        let filter = WorkspaceFilter::new(&dir.0, &[], &[]).unwrap();
+        assert_eq!(
+            relative_files(&dir, &filter),
+            vec![
+                "local.py",
+                "main.py",
+                "pkg/__init__.pyi",
+                "pkg/keep.gen.py",
+                "pkg/top.py",
+                "tests/test_a.py",
+            ]
+        );
+
+        let filter = WorkspaceFilter::new(&dir.0, &["pkg/**".to_string()], &[]).unwrap();
+        assert_eq!(
+            relative_files(&dir, &filter),
+            vec!["pkg/__init__.pyi", "pkg/keep.gen.py", "pkg/top.py"]
+        );
+
+        let filter = WorkspaceFilter::new(&dir.0, &[], &["tests".to_string()]).unwrap();
+        assert_eq!(
+            relative_files(&dir, &filter),
+            vec![
+                "local.py",
+                "main.py",
+                "pkg/__init__.pyi",
+                "pkg/keep.gen.py",
+                "pkg/top.py",
+            ]
+        );
+        assert!(WorkspaceFilter::new(&dir.0, &["[".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_is_workspace_file() {
+        let dir = TempDir::new("workspace_file");
+        dir.write(".gitignore", "generated/\n");
+        dir.write("pkg/.gitignore", "*_pb2.py\n");
+        let filter = WorkspaceFilter::new(&dir.0, &[], &["legacy/**".to_string()]).unwrap();
+
+        assert!(filter.is_workspace_file(&dir.0.join("main.py")));
+        assert!(filter.is_workspace_file(&dir.0.join("pkg/mod.pyi")));
+        assert!(!filter.is_workspace_file(&dir.0.join("pkg/api_pb2.py")));
+        assert!(filter.is_workspace_file(&dir.0.join("api_pb2.py")));
+        assert!(!filter.is_workspace_file(&dir.0.join("generated/mod.py")));
+        assert!(!filter.is_workspace_file(&dir.0.join("legacy/old.py")));
+        assert!(!filter.is_workspace_file(&dir.0.join(".git/hooks/hook.py")));
+        assert!(!filter.is_workspace_file(&dir.0.join("README.md")));
+        assert!(filter.is_workspace_dir(&dir.0.join("pkg")));
+        assert!(!filter.is_workspace_dir(&dir.0.join("pkg/__pycache__")));
+    }
+
+    #[test]
+    fn test_diff_workspace_files() {
+        let dir = TempDir::new("workspace_diff");
+        let same = dir.write("same.py", "x = 1\n");
+        let changed = dir.write("changed.py", "y = 1\n");
+        let opened = dir.write("opened.py", "z = 1\n");
+        let gone = dir.0.join("gone.py");
+        let outside = std::env::temp_dir().join("python_ls_outside.py");
+
+        let mut ws = ProviderVirtualWorkspace::new();
+        let mut load = |path: &std::path::PathBuf, text: &str| {
+            let uri = file_path_to_uri(path).unwrap();
+            ws.analysis.update_file_by_uri(&uri, Some(text.to_string()));
+            uri
+        };
+        load(&same, "x = 1\n");
+        let changed_uri = load(&changed, "y = 0\n");
+        let opened_uri = load(&opened, "z = 2\n");
+        let gone_uri = load(&gone, "w = 1\n");
+        load(&outside, "v = 1\n");
+        let added = dir.write("pkg/added.py", "a = 1\n");
+
+        let filter = WorkspaceFilter::new(&dir.0, &[], &[]).unwrap();
+        let files = read_files(filter.collect_files());
+        let open_files = vec![opened_uri];
+        let mut changes = diff_workspace_files(&ws.analysis, &dir.0, files, &open_files);
+        changes.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        assert_eq!(
+            changes,
+            vec![
+                (changed_uri, Some(PyFileContent::Bytes(b"y = 1\n".to_vec()))),
+                (gone_uri, None),
+                (
+                    file_path_to_uri(&added).unwrap(),
+                    Some(PyFileContent::Bytes(b"a = 1\n".to_vec()))
+                ),
+            ]
+        );
+
+        ws.analysis.update_files_by_uri(changes);
+        let files = read_files(filter.collect_files());
+        assert!(diff_workspace_files(&ws.analysis, &dir.0, files, &open_files).is_empty());
+    }
+
+    #[test]
+    fn test_load_declared_encoding() {
+        let dir = TempDir::new("workspace_encoding");
+        let legacy = dir.0.join("legacy.py");
+        std::fs::write(&legacy, b"# -*- coding: latin-1 -*-\nname = 'Jos\xE9'\n").unwrap();
+        let bom = dir.0.join("bom.py");
+        std::fs::write(&bom, b"\xEF\xBB\xBFx = 1\n").unwrap();
+        let unknown = dir.0.join("unknown.py");
+        std::fs::write(&unknown, b"# coding: klingon\nx = 1\n").unwrap();
+
+        let mut ws = ProviderVirtualWorkspace::new();
+        let files = read_files(vec![legacy.clone(), bom.clone(), unknown.clone()]);
+        let changes = diff_workspace_files(&ws.analysis, &dir.0, files, &[]);
+        ws.analysis.update_files_by_uri(changes);
+        let [legacy_id, bom_id, unknown_id] = [&legacy, &bom, &unknown].map(|path| {
+            ws.analysis
+                .get_file_id(&file_path_to_uri(path).unwrap())
+                .unwrap()
+        });
+        let vfs = ws.analysis.compilation.get_db().get_vfs();
+        assert_eq!(
+            vfs.get_file_content(&legacy_id).unwrap(),
+            "# -*- coding: latin-1 -*-\nname = 'José'\n"
+        );
+        assert_eq!(vfs.get_file_content(&bom_id).unwrap(), "x = 1\n");
+
+        // the problems of the declaration are diagnostics of the file
+        let messages: Vec<_> = ws
+            .analysis
+            .diagnose_file(unknown_id)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+        assert_eq!(messages, vec!["unknown encoding: klingon"]);
+        assert!(ws.analysis.diagnose_file(legacy_id).unwrap().is_empty());
+
+        // they are kept when the files are parsed for another version
+        let mut config = (*ws.analysis.get_config()).clone();
+        config.python_version = Some("3.8".to_string());
+        ws.analysis.update_config(std::sync::Arc::new(config));
+        assert_eq!(ws.analysis.diagnose_file(unknown_id).unwrap().len(), 1);
+
+        // and a file which decodes the same is not loaded again
+        let files = read_files(vec![legacy, bom, unknown]);
+        assert!(diff_workspace_files(&ws.analysis, &dir.0, files, &[]).is_empty());
+    }
+
+    fn server_context(root: &Path) -> (ServerContext, Connection) {
+        let (server, client) = Connection::memory();
+        let params = InitializeParams {
+            workspace_folders: Some(vec![WorkspaceFolder {
+                uri: file_path_to_uri(&root.to_path_buf()).unwrap(),
+                name: "root".to_string(),
+            }]),
+            ..Default::default()
+        };
+        let context = ServerContext::new(
+            server,
+            ConfigManager::new(&params),
+            WorkspaceManager::new(&params),
+        );
+        (context, client)
+    }
+
+    fn loaded_text(analysis: &PyAnalysis, path: &PathBuf) -> Option<String> {
+        let file_id = analysis.get_file_id(&file_path_to_uri(path).unwrap())?;
+        let vfs = analysis.compilation.get_db().get_vfs();
+        vfs.get_file_content(&file_id).cloned()
+    }
+
+    fn messages(analysis: &PyAnalysis, path: &PathBuf) -> Vec<String> {
+        let file_id = analysis
+            .get_file_id(&file_path_to_uri(path).unwrap())
+            .unwrap();
+        analysis
+            .diagnose_file(file_id)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_files_changed_with_gitignore() {
+        let dir = TempDir::new("workspace_watch");
+        let kept = dir.write("kept.py", "x = 1\n");
+        let removed = dir.write("removed.py", "y = 1\n");
+        let ignored = dir.write("gen/ignored.py", "z = 1\n");
+        let (context, _client) = server_context(&dir.0);
+        let context = context.snapshot();
+        index_workspace(context.clone()).await;
+        assert!(loaded_text(&*context.analysis.read().await, &ignored).is_some());
+
+        // a new `.gitignore` rescans the workspace, which also takes the
+        // other changes of the batch
+        let gitignore = dir.write(".gitignore", "gen/\n");
+        std::fs::remove_file(&removed).unwrap();
+        std::fs::write(&kept, "x = 2\n").unwrap();
+        let paths = HashSet::from([gitignore, removed.clone(), kept.clone()]);
+        on_files_changed(&context, &dir.0, paths).await;
+        {
+            let analysis = context.analysis.read().await;
+            assert_eq!(loaded_text(&analysis, &kept).as_deref(), Some("x = 2\n"));
+            assert_eq!(loaded_text(&analysis, &removed), None);
+            assert_eq!(loaded_text(&analysis, &ignored), None);
+        }
+
+        // a changed file keeps the problems of decoding it
+        std::fs::write(&kept, b"# coding: klingon\nx = 3\n").unwrap();
+        on_files_changed(&context, &dir.0, HashSet::from([kept.clone()])).await;
+        let analysis = context.analysis.read().await;
+        assert_eq!(
+            messages(&analysis, &kept),
+            vec!["unknown encoding: klingon"]
+        );
+    }
+}
@@ -0,0 +1,408 @@
+use lsp_types::{
+    CodeActionContext, CodeActionOrCommand, FoldingRangeKind, HoverContents, InlayHintLabel,
+    MarkupContent, NumberOrString, ParameterLabel, Position, Range, Uri,
+};
+use python_code_analysis::{FileId, PyAnalysis, PyConfig};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use super::{
+    code_actions::code_action,
+    folding_range::folding_range,
+    hover::hover,
+    inlay_hint::{inlay_hint, resolve_inlay_hint},
+    selection_range::selection_range,
+    signature_help::signature_help,
+};
+
+/// A workspace of in-memory files for the handler tests. The cursor position
+/// is marked with `<??>` in the source text.
+pub struct ProviderVirtualWorkspace {
+    pub analysis: PyAnalysis,
+    id_counter: u32,
+}
+
+#[derive(Debug)]
+pub struct VirtualHoverResult {
+    pub value: String,
+}
+
+#[derive(Debug)]
+pub struct VirtualSignatureHelpResult {
+    // the label of each signature, in order
+    pub signatures: Vec<String>,
+    pub active_signature: u32,
+    // the highlighted parameter text of the active signature, `None` when nothing is highlighted
+    pub active_parameter: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VirtualFoldingRange {
+    pub start_line: u32,
+    pub end_line: u32,
+    pub kind: Option<FoldingRangeKind>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VirtualInlayHint {
+    pub label: String,
+    pub line: u32,
+    pub character: u32,
+    // the text inserted when the hint is resolved and accepted
+    pub edit: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VirtualCodeAction {
+    pub title: String,
+    // the document text after applying the action
+    pub result: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VirtualDiagnostic {
+    pub code: String,
+    // the zero based line the diagnostic starts on
+    pub line: u32,
+}
+
+impl ProviderVirtualWorkspace {
+    pub fn new() -> Self {
+        ProviderVirtualWorkspace {
+            analysis: PyAnalysis::new(),
+            id_counter: 0,
+        }
+    }
+
+    /// Parse the files as Python 3.`minor`
+    pub fn set_python_version(&mut self, minor: u32) {
+        let mut config = PyConfig::clone(&self.analysis.get_config());
+        config.python_version = Some(format!("3.{}", minor));
+        self.analysis.update_config(Arc::new(config));
+    }
+
+    /// Use the settings of a config file at the root of the virtual files
+    pub fn set_config(&mut self, mut config: PyConfig) {
+        config.set_root(Some(Path::new("/"))).unwrap();
+        self.analysis.update_config(Arc::new(config));
+    }
+
+    /// Turn on a diagnostic which is off by default
+    pub fn enable_diagnostic(&mut self, code: &str) {
+        let mut config = PyConfig::clone(&self.analysis.get_config());
+        config.diagnostics.enables.push(code.to_string());
+        self.analysis.update_config(Arc::new(config));
+    }
+
+    pub fn def_file(&mut self, file_name: &str, content: &str) -> FileId {
+        let uri = Uri::from_str(&format!("file:///{}", file_name)).unwrap();
+        self.analysis
+            .update_file_by_uri(&uri, Some(content.to_string()))
+            .unwrap()
+    }
+
+    pub fn def(&mut self, content: &str) -> FileId {
+        self.id_counter += 1;
+        let file_name = format!("virtual_{}.py", self.id_counter);
+        self.def_file(&file_name, content)
+    }
+
+    /// Define a file containing a `<??>` marker and return the marker position
+    pub fn def_with_cursor(&mut self, block_str: &str) -> Option<(FileId, Position)> {
+        let offset = block_str.find("<??>")?;
+        let content = block_str.replace("<??>", "");
+        let before = &block_str[..offset];
+        let line = before.matches('\n').count() as u32;
+        let character = before
+            .rsplit('\n')
+            .next()
+            .map(|text| text.chars().count())
+            .unwrap_or(0) as u32;
+        let file_id = self.def(&content);
+        Some((file_id, Position { line, character }))
+    }
+
+    pub fn check_hover(&mut self, block_str: &str, expect: VirtualHoverResult) -> bool {
+        let Some((file_id, position)) = self.def_with_cursor(block_str) else {
+            return false;
+        };
+        let Some(result) = hover(&self.analysis, file_id, position) else {
+            dbg!("no hover result");
+            return false;
+        };
+        let HoverContents::Markup(MarkupContent { value, .. }) = result.contents else {
+            return false;
+        };
+        if value != expect.value {
+            dbg!(&value, &expect.value);
+            return false;
+        }
+        true
+    }
+
+    pub fn check_signature_help(
+        &mut self,
+        block_str: &str,
+        expect: VirtualSignatureHelpResult,
+    ) -> bool {
+        let Some((file_id, position)) = self.def_with_cursor(block_str) else {
+            return false;
+        };
+        let Some(result) = signature_help(&self.analysis, file_id, position) else {
+            dbg!("no signature help result");
+            return false;
+        };
+        let labels: Vec<String> = result
+            .signatures
+            .iter()
+            .map(|signature| signature.label.clone())
+            .collect();
+        let active_signature = result.active_signature.unwrap_or(0);
+        let active_parameter =
+            result
+                .signatures
+                .get(active_signature as usize)
+                .and_then(|signature| {
+                    let index = signature.active_parameter? as usize;
+                    let Some(param) = signature.parameters.as_ref()?.get(index) else {
+                        return Some(format!("<out of range {}>", index));
+                    };
+                    let ParameterLabel::LabelOffsets([start, end]) = param.label else {
+                        return None;
+                    };
+                    let label: Vec<u16> = signature.label.encode_utf16().collect();
+                    String::from_utf16(&label[start as usize..end as usize]).ok()
+                });
+        if labels != expect.signatures
+            || active_signature != expect.active_signature
+            || active_parameter != expect.active_parameter
+        {
+            dbg!(&labels, active_signature, &active_parameter, &expect);
+            return false;
+        }
+        true
+    }
+
+    pub fn check_folding_range(
+        &mut self,
+        block_str: &str,
+        expect: Vec<VirtualFoldingRange>,
+    ) -> bool {
+        let file_id = self.def(block_str);
+        let Some(result) = folding_range(&self.analysis, file_id) else {
+            return false;
+        };
+        let result: Vec<VirtualFoldingRange> = result
+            .into_iter()
+            .map(|range| VirtualFoldingRange {
+                start_line: range.start_line,
+                end_line: range.end_line,
+                kind: range.kind,
+            })
+            .collect();
+        if result != expect {
+            dbg!(&result, &expect);
+            return false;
+        }
+        true
+    }
+
+    /// `expect` is the text of each selection range, from the innermost out
+    pub fn check_selection_range(&mut self, block_str: &str, expect: Vec<&str>) -> bool {
+        let Some((file_id, position)) = self.def_with_cursor(block_str) else {
+            return false;
+        };
+        let Some(mut result) = selection_range(&self.analysis, file_id, &[position]) else {
+            return false;
+        };
+        let Some(semantic_model) = self.analysis.get_semantic_model(file_id) else {
+            return false;
+        };
+        let document = semantic_model.get_document();
+
+        let mut texts = Vec::new();
+        let mut current = result.pop().map(Box::new);
+        while let Some(selection) = current {
+            let Some(range) = document.to_rowan_range(selection.range) else {
+                return false;
+            };
+            texts.push(document.get_text_slice(range).to_string());
+            current = selection.parent;
+        }
+        if texts != expect {
+            dbg!(&texts, &expect);
+            return false;
+        }
+        true
+    }
+
+    pub fn check_inlay_hint(&mut self, block_str: &str, expect: Vec<VirtualInlayHint>) -> bool {
+        let file_id = self.def(block_str);
+        let range = Range::new(Position::new(0, 0), Position::new(u32::MAX, 0));
+        let Some(hints) = inlay_hint(&self.analysis, file_id, range) else {
+            return false;
+        };
+        let result: Vec<VirtualInlayHint> = hints
+            .into_iter()
+            .map(|mut hint| {
+                // 与客户端一样, 不带编辑的提示通过 resolve 取得编辑
+                hint.text_edits = None;
+                let hint = resolve_inlay_hint(&self.analysis, file_id, hint);
+                VirtualInlayHint {
+                    label: match hint.label {
+                        InlayHintLabel::String(label) => label,
+                        InlayHintLabel::LabelParts(parts) => {
+                            parts.into_iter().map(|part| part.value).collect()
+                        }
+                    },
+                    line: hint.position.line,
+                    character: hint.position.character,
+                    edit: hint
+                        .text_edits
+                        .and_then(|edits| edits.into_iter().next())
+                        .map(|edit| edit.new_text),
+                }
+            })
+            .collect();
+        if result != expect {
+            dbg!(&result, &expect);
+            return false;
+        }
+        true
+    }
+
+    /// The diagnostics of the file are exactly `expect`, in document order
+    pub fn check_diagnostics(&mut self, block_str: &str, expect: Vec<VirtualDiagnostic>) -> bool {
+        let file_id = self.def(block_str);
+        let Some(diagnostics) = self.analysis.diagnose_file(file_id) else {
+            return false;
+        };
+        let mut result: Vec<VirtualDiagnostic> = diagnostics
+            .into_iter()
+            .map(|diagnostic| VirtualDiagnostic {
+                code: match diagnostic.code {
+                    Some(NumberOrString::String(code)) => code,
+                    Some(NumberOrString::Number(code)) => code.to_string(),
+                    None => String::new(),
+                },
+                line: diagnostic.range.start.line,
+            })
+            .collect();
+        result.sort_by_key(|diagnostic| diagnostic.line);
+        if result != expect {
+            dbg!(&result, &expect);
+            return false;
+        }
+        true
+    }
+
+    /// The code actions at the cursor, with the diagnostics of the cursor
+    /// line sent along as a client does
+    fn code_actions(&mut self, block_str: &str) -> Option<Vec<VirtualCodeAction>> {
+        let (file_id, position) = self.def_with_cursor(block_str)?;
+        let diagnostics = self
+            .analysis
+            .diagnose_file(file_id)?
+            .into_iter()
+            .filter(|diagnostic| {
+                diagnostic.range.start.line <= position.line
+                    && position.line <= diagnostic.range.end.line
+            })
+            .collect();
+        let context = CodeActionContext {
+            diagnostics,
+            ..Default::default()
+        };
+        let range = Range::new(position, position);
+        let actions = code_action(&self.analysis, file_id, range, &context)?;
+        let semantic_model = self.analysis.get_semantic_model(file_id)?;
+        let document = semantic_model.get_document();
+        let mut result = Vec::new();
+        for action in actions {
+            let CodeActionOrCommand::CodeAction(action) = action else {
+                continue;
+            };
+            let mut edits: Vec<_> = action
+                .edit?
+                .changes?
+                .into_values()
+                .flatten()
+                .filter_map(|edit| Some((document.to_rowan_range(edit.range)?, edit.new_text)))
+                .collect();
+            edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start()));
+            let mut text = document.get_text().to_string();
+            for (range, new_text) in edits {
+                text.replace_range(
+                    usize::from(range.start())..usize::from(range.end()),
+                    &new_text,
+                );
+            }
+            result.push(VirtualCodeAction {
+                title: action.title,
+                result: text,
+            });
+        }
+        Some(result)
+    }
+
+    /// Every expected action is offered at the cursor and produces the expected text
+    pub fn check_code_action(&mut self, block_str: &str, expect: Vec<VirtualCodeAction>) -> bool {
+        let Some(actions) = self.code_actions(block_str) else {
+            return false;
+        };
+        for action in &expect {
+            if !actions.contains(action) {
+                dbg!(&actions, action);
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn check_no_code_action(&mut self, block_str: &str, title: &str) -> bool {
+        let Some(actions) = self.code_actions(block_str) else {
+            return false;
+        };
+        if actions.iter().any(|action| action.title == title) {
+            dbg!(&actions, title);
+            return false;
+        }
+        true
+    }
+}
+
+/// A scratch directory for the tests which read the filesystem, it is
+/// removed when dropped
+pub struct TempDir(pub PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("python_ls_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn write(&self, path: &str, content: &str) -> PathBuf {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn mkdir(&self, path: &str) -> PathBuf {
+        let path = self.0.join(path);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,88 @@
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, Uri,
+};
+use python_code_analysis::{PyFileContent, read_source_file, uri_to_file_path};
+
+use crate::{
+    context::ServerContextSnapshot,
+    handlers::diagnostic::{clear_diagnostics, publish_diagnostics, schedule_diagnostics},
+};
+
+pub async fn on_did_open_text_document(
+    context: ServerContextSnapshot,
+    params: DidOpenTextDocumentParams,
+) -> Option<()> {
+    let uri = params.text_document.uri;
+    context
+        .workspace_manager
+        .open_file(&uri, params.text_document.version);
+    context.cancel_handle.cancel();
+    let mut analysis = context.analysis.write().await;
+    analysis.update_file_by_uri(&uri, Some(params.text_document.text));
+    publish_diagnostics(
+        &analysis,
+        &context.client,
+        &uri,
+        Some(params.text_document.version),
+    );
+    Some(())
+}
+
+pub async fn on_did_change_text_document(
+    context: ServerContextSnapshot,
+    params: DidChangeTextDocumentParams,
+) -> Option<()> {
+    // full sync, the last change holds the whole document
+    let text = params.content_changes.into_iter().last()?.text;
+    let uri = params.text_document.uri;
+    let version = params.text_document.version;
+    context.workspace_manager.set_version(&uri, version);
+    context.cancel_handle.cancel();
+    context
+        .analysis
+        .write()
+        .await
+        .update_file_by_uri(&uri, Some(text));
+    // typing sends a burst of changes, only the last one is diagnosed
+    schedule_diagnostics(context, uri, version);
+    Some(())
+}
+
+pub async fn on_did_save_text_document(
+    _: ServerContextSnapshot,
+    _: DidSaveTextDocumentParams,
+) -> Option<()> {
+    Some(())
+}
+
+pub async fn on_did_close_document(
+    context: ServerContextSnapshot,
+    params: DidCloseTextDocumentParams,
+) -> Option<()> {
+    let uri = params.text_document.uri;
+    context.workspace_manager.close_file(&uri);
+    let disk_content = read_workspace_file(&context, &uri);
+    context.cancel_handle.cancel();
+    let mut analysis = context.analysis.write().await;
+    match disk_content {
+        Some(content) => {
+            analysis.update_files_by_uri(vec![(uri.clone(), Some(content))]);
+        }
+        None => {
+            analysis.remove_file_by_uri(&uri);
+        }
+    }
+    clear_diagnostics(&context.client, &uri);
+    Some(())
+}
+
+/// The content of a closed document on disk, a workspace file goes back to it
+pub fn read_workspace_file(context: &ServerContextSnapshot, uri: &Uri) -> Option<PyFileContent> {
+    context
+        .workspace_manager
+        .get_filter()
+        .zip(uri_to_file_path(uri))
+        .filter(|(filter, path)| filter.is_workspace_file(path))
+        .and_then(|(_, path)| read_source_file(&path).ok())
+}
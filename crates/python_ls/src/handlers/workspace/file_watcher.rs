@@ -0,0 +1,128 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use python_code_analysis::CONFIG_FILE_NAMES;
+use tokio::sync::mpsc;
+
+use super::{changed_files, index_workspace, read_files, removed_files};
+use crate::{
+    context::ServerContextSnapshot,
+    handlers::{configuration::reload_config, diagnostic::publish_open_files_diagnostics},
+};
+
+/// How long to wait for more changes before handling them, a checkout or a
+/// code generator changes many files at once
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Index the workspace, then keep the analysis in line with the changes made
+/// on disk outside the editor until the watcher is dropped
+pub async fn watch_workspace(context: ServerContextSnapshot) {
+    let Some(root) = context.config_manager.get_root_path() else {
+        return;
+    };
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        }
+        Ok(_) => {}
+        Err(err) => log::warn!("File watcher error: {}", err),
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+        Ok(watcher)
+    });
+    match watcher {
+        Ok(watcher) => context.workspace_manager.set_watcher(watcher),
+        Err(err) => log::error!("Failed to watch {}: {}", root.display(), err),
+    }
+
+    // the changes made while indexing are handled after it
+    index_workspace(context.clone()).await;
+    while let Some(path) = receiver.recv().await {
+        let mut paths = HashSet::from([path]);
+        while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, receiver.recv()).await {
+            paths.insert(path);
+        }
+        on_files_changed(&context, &root, paths).await;
+    }
+}
+
+/// Bring the analysis in line with a batch of paths changed on disk
+pub async fn on_files_changed(
+    context: &ServerContextSnapshot,
+    root: &Path,
+    paths: HashSet<PathBuf>,
+) {
+    let Some(filter) = context.workspace_manager.get_filter() else {
+        return;
+    };
+    let mut config_changed = false;
+    let mut reindex = false;
+    let mut changed = Vec::new();
+    let mut removed = Vec::new();
+    for path in paths {
+        let Some(parent) = path.parent() else {
+            continue;
+        };
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if parent == root && CONFIG_FILE_NAMES.contains(&name.as_str()) {
+            config_changed = true;
+        } else if !filter.is_workspace_dir(parent) {
+            continue;
+        } else if name == ".gitignore" {
+            reindex = true;
+        } else if path.is_dir() {
+            // the files of a directory which was moved in may not be reported
+            reindex |= filter.is_workspace_dir(&path);
+        } else if path.is_file() {
+            if filter.is_workspace_file(&path) {
+                changed.push(path);
+            }
+        } else {
+            // a removed file or directory
+            removed.push(path);
+        }
+    }
+
+    if config_changed {
+        reload_config(context.clone()).await;
+    }
+    // the scan of the whole workspace also reads the files changed in the
+    // batch, and unloads the removed ones as they are no longer on disk
+    if reindex {
+        index_workspace(context.clone()).await;
+        return;
+    }
+    if changed.is_empty() && removed.is_empty() {
+        return;
+    }
+
+    let files = tokio::task::spawn_blocking(move || read_files(changed))
+        .await
+        .unwrap_or_default();
+    let mut analysis = context.analysis.write().await;
+    let open_files = context.workspace_manager.get_open_files();
+    let mut changes = changed_files(&analysis, files, &open_files);
+    // a removed directory takes its files along
+    changes.extend(removed_files(
+        &analysis,
+        |path| removed.iter().any(|removed| path.starts_with(removed)),
+        &open_files,
+    ));
+    if changes.is_empty() {
+        return;
+    }
+    log::info!("{} files changed on disk", changes.len());
+    analysis.update_files_by_uri(changes);
+    publish_open_files_diagnostics(&analysis, &context.client, &open_files);
+}
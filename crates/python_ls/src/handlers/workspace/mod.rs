@@ -0,0 +1,163 @@
+pub mod file_watcher;
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub use file_watcher::watch_workspace;
+use lsp_types::Uri;
+use python_code_analysis::{
+    PyAnalysis, PyFileContent, WorkspaceFilter, file_path_to_uri, read_source_file,
+    uri_to_file_path,
+};
+
+use crate::{
+    context::{ProgressTask, ServerContextSnapshot},
+    handlers::diagnostic::publish_open_files_diagnostics,
+};
+
+/// How many files are read between two progress reports
+const READ_CHUNK_SIZE: usize = 256;
+
+/// Scan the workspace root and parse the files which are new or changed
+/// since the last scan in parallel, then publish the diagnostics of the
+/// open documents which may depend on them
+pub async fn index_workspace(context: ServerContextSnapshot) {
+    let Some(root) = context.config_manager.get_root_path() else {
+        return;
+    };
+    let config = context.analysis.read().await.get_config();
+    let filter =
+        match WorkspaceFilter::new(&root, &config.workspace.include, &config.workspace.exclude) {
+            Ok(filter) => Arc::new(filter),
+            Err(err) => {
+                log::error!("Invalid workspace config: {}", err);
+                return;
+            }
+        };
+    context.workspace_manager.set_filter(filter.clone());
+
+    let progress = if context.workspace_manager.supports_work_done_progress() {
+        ProgressTask::create(context.client.clone(), "Indexing workspace").await
+    } else {
+        None
+    };
+    let paths = tokio::task::spawn_blocking(move || filter.collect_files())
+        .await
+        .unwrap_or_default();
+    let mut files = Vec::with_capacity(paths.len());
+    for (index, chunk) in paths.chunks(READ_CHUNK_SIZE).enumerate() {
+        if let Some(progress) = &progress {
+            let done = index * READ_CHUNK_SIZE;
+            progress.report(
+                format!("{}/{} files", done, paths.len()),
+                (done * 100 / paths.len()) as u32,
+            );
+        }
+        let chunk = chunk.to_vec();
+        files.extend(
+            tokio::task::spawn_blocking(move || read_files(chunk))
+                .await
+                .unwrap_or_default(),
+        );
+    }
+
+    let mut analysis = context.analysis.write().await;
+    let open_files = context.workspace_manager.get_open_files();
+    let changes = diff_workspace_files(&analysis, &root, files, &open_files);
+    log::info!(
+        "Indexed {} files in {}, {} changed",
+        paths.len(),
+        root.display(),
+        changes.len()
+    );
+    if !changes.is_empty() {
+        analysis.update_files_by_uri(changes);
+        publish_open_files_diagnostics(&analysis, &context.client, &open_files);
+    }
+    if let Some(progress) = progress {
+        progress.finish(format!("{} files", paths.len()));
+    }
+}
+
+/// The content of each file, files which can not be read or are not valid
+/// notebooks are skipped
+pub fn read_files(paths: Vec<PathBuf>) -> Vec<(PathBuf, PyFileContent)> {
+    paths
+        .into_iter()
+        .filter_map(|path| match read_source_file(&path) {
+            Ok(content) => Some((path, content)),
+            Err(err) => {
+                log::warn!("Failed to read {}: {}", path.display(), err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The updates which bring the analysis in line with the workspace files on
+/// disk: the content of the files which are new or changed, and `None` for the
+/// files under `root` which are gone. Open documents are left alone, their
+/// text comes from the client.
+pub fn diff_workspace_files(
+    analysis: &PyAnalysis,
+    root: &Path,
+    files: Vec<(PathBuf, PyFileContent)>,
+    open_files: &[Uri],
+) -> Vec<(Uri, Option<PyFileContent>)> {
+    let on_disk: HashSet<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+    let mut changes = changed_files(analysis, files, open_files);
+    changes.extend(removed_files(
+        analysis,
+        |path| path.starts_with(root) && !on_disk.contains(path),
+        open_files,
+    ));
+    changes
+}
+
+/// The files whose content differs from the analysis
+fn changed_files(
+    analysis: &PyAnalysis,
+    files: Vec<(PathBuf, PyFileContent)>,
+    open_files: &[Uri],
+) -> Vec<(Uri, Option<PyFileContent>)> {
+    let vfs = analysis.compilation.get_db().get_vfs();
+    let open_files = uri_set(open_files);
+    files
+        .into_iter()
+        .filter_map(|(path, content)| {
+            let uri = file_path_to_uri(&path)?;
+            if open_files.contains(uri.as_str()) {
+                return None;
+            }
+            let known = vfs
+                .get_file_id(&uri)
+                .is_some_and(|file_id| vfs.has_content(&file_id, &content));
+            (!known).then_some((uri, Some(content)))
+        })
+        .collect()
+}
+
+/// The loaded files whose path is removed
+fn removed_files(
+    analysis: &PyAnalysis,
+    is_removed: impl Fn(&Path) -> bool,
+    open_files: &[Uri],
+) -> Vec<(Uri, Option<PyFileContent>)> {
+    let vfs = analysis.compilation.get_db().get_vfs();
+    let open_files = uri_set(open_files);
+    vfs.get_all_file_ids()
+        .into_iter()
+        .filter(|file_id| vfs.get_file_content(file_id).is_some())
+        .filter_map(|file_id| vfs.get_uri(&file_id))
+        .filter(|uri| !open_files.contains(uri.as_str()))
+        .filter(|uri| uri_to_file_path(uri).is_some_and(|path| is_removed(&path)))
+        .map(|uri| (uri, None))
+        .collect()
+}
+
+fn uri_set(uris: &[Uri]) -> HashSet<&str> {
+    uris.iter().map(|uri| uri.as_str()).collect()
+}
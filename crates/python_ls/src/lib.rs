@@ -0,0 +1,88 @@
+pub mod cmd_args;
+mod context;
+mod handlers;
+mod logger;
+
+use std::{error::Error, sync::Arc};
+
+use cmd_args::{CmdArgs, Communication};
+use context::{ConfigManager, ServerContext, WorkspaceManager};
+use handlers::{
+    find_environment, on_notification_handler, on_request_handler, on_response_handler,
+    reload_config, server_capabilities, watch_workspace,
+};
+use lsp_server::{Connection, Message};
+use lsp_types::{InitializeParams, InitializeResult, ServerInfo};
+
+pub async fn run_ls(cmd_args: CmdArgs) -> Result<(), Box<dyn Error + Sync + Send>> {
+    logger::init_logger(&cmd_args);
+    let (connection, threads) = match cmd_args.communication {
+        Communication::Stdio => Connection::stdio(),
+        Communication::Tcp => Connection::listen((cmd_args.ip.as_str(), cmd_args.port))?,
+    };
+
+    let (id, params) = connection.initialize_start()?;
+    let initialization_params: InitializeParams = serde_json::from_value(params)?;
+    let initialize_result = InitializeResult {
+        capabilities: server_capabilities(&initialization_params.capabilities),
+        server_info: Some(ServerInfo {
+            name: "python_ls".to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }),
+        offset_encoding: None,
+    };
+    connection.initialize_finish(id, serde_json::to_value(initialize_result)?)?;
+    log::info!("python_ls initialized");
+
+    main_loop(connection, initialization_params).await?;
+    threads.join()?;
+    log::info!("python_ls shutdown");
+    Ok(())
+}
+
+async fn main_loop(
+    connection: Connection,
+    initialization_params: InitializeParams,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let mut server_context = ServerContext::new(
+        Connection {
+            sender: connection.sender.clone(),
+            receiver: connection.receiver.clone(),
+        },
+        ConfigManager::new(&initialization_params),
+        WorkspaceManager::new(&initialization_params),
+    );
+    let snapshot = server_context.snapshot();
+    if let Some(config) = snapshot.config_manager.load_local_config() {
+        let environment = find_environment(&snapshot.config_manager, &config);
+        let mut analysis = snapshot.analysis.write().await;
+        analysis.set_environment(environment);
+        analysis.update_config(Arc::new(config));
+    }
+    tokio::spawn(watch_workspace(snapshot.clone()));
+    if snapshot.config_manager.supports_configuration() {
+        // the client's answer is handled by the loop below
+        tokio::spawn(reload_config(snapshot));
+    }
+
+    for msg in &connection.receiver {
+        match msg {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    server_context.close();
+                    return Ok(());
+                }
+                on_request_handler(request, &mut server_context).await?;
+            }
+            Message::Notification(notification) => {
+                on_notification_handler(notification, &mut server_context).await?;
+            }
+            Message::Response(response) => {
+                on_response_handler(response, &mut server_context).await?;
+            }
+        }
+    }
+
+    server_context.close();
+    Ok(())
+}
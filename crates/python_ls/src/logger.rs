@@ -0,0 +1,46 @@
+use std::{fs, path::PathBuf};
+
+use chrono::Local;
+use fern::Dispatch;
+
+use crate::cmd_args::CmdArgs;
+
+pub fn init_logger(cmd_args: &CmdArgs) {
+    let level: log::LevelFilter = cmd_args.log_level.into();
+    let logger = Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "{} [{}] {}: {}",
+                Local::now().format("%Y-%m-%d %H:%M:%S"),
+                record.level(),
+                record.target(),
+                message
+            ))
+        })
+        .level(level)
+        .level_for("python_parser", log::LevelFilter::Warn);
+
+    let logger = match &cmd_args.log_path {
+        Some(log_path) => {
+            let dir = PathBuf::from(log_path);
+            if let Err(err) = fs::create_dir_all(&dir) {
+                eprintln!("Failed to create log directory {:?}: {}", dir, err);
+                return;
+            }
+            let file_name = format!("python_ls_{}.log", Local::now().format("%Y%m%d_%H%M%S"));
+            match fern::log_file(dir.join(file_name)) {
+                Ok(file) => logger.chain(file),
+                Err(err) => {
+                    eprintln!("Failed to open log file: {}", err);
+                    return;
+                }
+            }
+        }
+        // stdout is the protocol channel, so fall back to stderr
+        None => logger.chain(std::io::stderr()),
+    };
+
+    if let Err(err) = logger.apply() {
+        eprintln!("Failed to initialize logger: {}", err);
+    }
+}
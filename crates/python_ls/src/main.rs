@@ -0,0 +1,9 @@
+use clap::Parser;
+use python_ls::cmd_args::CmdArgs;
+use std::error::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
+    let cmd_args = CmdArgs::parse();
+    python_ls::run_ls(cmd_args).await
+}
@@ -2,7 +2,7 @@ mod fstring;
 mod py;
 
 use crate::parser::CompleteMarker;
-pub use py::{parse_fstring_inner_expr, parse_module_suite};
+pub use py::{parse_fstring_inner_expr, parse_module_suite, parse_type_comment_inner};
 
 type ParseResult = Result<CompleteMarker, ParseFailReason>;
 
@@ -1,7 +1,7 @@
 use crate::{
     grammar::{ParseFailReason, ParseResult, fstring::parse_fstring_or_tstring_expr},
     kind::{BinaryOperator, PyOpKind, PySyntaxKind, PyTokenKind, UNARY_PRIORITY, UnaryOperator},
-    parser::{MarkerEventContainer, PyParser},
+    parser::{CompleteMarker, MarkerEventContainer, PyParser},
     parser_error::PyParseError,
 };
 
@@ -16,6 +16,74 @@ pub fn parse_fstring_inner_expr(p: &mut PyParser) {
     }
 }
 
+/// The text after `# type:`, a type such as `List[int]` or the signature of
+/// a function `(int, *str) -> bool`
+pub fn parse_type_comment_inner(p: &mut PyParser) {
+    let is_signature = p.source_text().contains("->");
+    p.init();
+
+    if is_signature {
+        parse_type_comment_signature(p);
+    } else if parse_expr(p).is_err() {
+        p.push_error(PyParseError::syntax_error_from(
+            &t!("expected type in type comment"),
+            p.current_token_range(),
+        ));
+    }
+
+    if p.current_token() != PyTokenKind::TkEof {
+        p.push_error(PyParseError::syntax_error_from(
+            &t!("unexpected token in type comment"),
+            p.current_token_range(),
+        ));
+        while p.current_token() != PyTokenKind::TkEof {
+            p.bump();
+        }
+    }
+}
+
+fn parse_type_comment_signature(p: &mut PyParser) {
+    if !if_token_bump(p, PyTokenKind::TkLeftParen) {
+        p.push_error(PyParseError::syntax_error_from(
+            &t!("expected '(' in function type comment"),
+            p.current_token_range(),
+        ));
+        return;
+    }
+    while !matches!(
+        p.current_token(),
+        PyTokenKind::TkRightParen | PyTokenKind::TkEof
+    ) {
+        // `*args` and `**kwargs` keep their stars in the signature
+        if matches!(p.current_token(), PyTokenKind::TkMul | PyTokenKind::TkPow) {
+            p.bump();
+        }
+        if parse_single_expr(p).is_err() {
+            p.push_error(PyParseError::syntax_error_from(
+                &t!("expected parameter type in function type comment"),
+                p.current_token_range(),
+            ));
+            return;
+        }
+        if !if_token_bump(p, PyTokenKind::TkComma) {
+            break;
+        }
+    }
+    if !if_token_bump(p, PyTokenKind::TkRightParen) {
+        p.push_error(PyParseError::syntax_error_from(
+            &t!("expected ')' in function type comment"),
+            p.current_token_range(),
+        ));
+        return;
+    }
+    if !if_token_bump(p, PyTokenKind::TkArrow) || parse_single_expr(p).is_err() {
+        p.push_error(PyParseError::syntax_error_from(
+            &t!("expected return type after '->' in function type comment"),
+            p.current_token_range(),
+        ));
+    }
+}
+
 pub fn parse_expr(p: &mut PyParser) -> ParseResult {
     parse_tuple_or_expr(p)
 }
@@ -200,6 +268,12 @@ fn parse_sub_expr(p: &mut PyParser, limit: i32) -> ParseResult {
 
 fn parse_simple_expr(p: &mut PyParser) -> ParseResult {
     match p.current_token() {
+        // `files = !ls`, the lexer only makes them after `=`
+        PyTokenKind::TkLineMagic | PyTokenKind::TkShellEscape => {
+            let m = p.mark(PySyntaxKind::MagicExpr);
+            p.bump();
+            Ok(m.complete(p))
+        }
         PyTokenKind::TkInt
         | PyTokenKind::TkFloat
         | PyTokenKind::TkComplex
@@ -213,12 +287,25 @@ fn parse_simple_expr(p: &mut PyParser) -> ParseResult {
         | PyTokenKind::TkEllipsis => {
             let m = p.mark(PySyntaxKind::LiteralExpr);
             p.bump();
-            Ok(m.complete(p))
+            let cm = m.complete(p);
+            parse_trailers(p, cm)
+        }
+        PyTokenKind::TkFString => {
+            let cm = parse_fstring_or_tstring_expr(p, PySyntaxKind::FStringExpr)?;
+            parse_trailers(p, cm)
+        }
+        PyTokenKind::TkTString => {
+            let cm = parse_fstring_or_tstring_expr(p, PySyntaxKind::TStringExpr)?;
+            parse_trailers(p, cm)
+        }
+        PyTokenKind::TkLeftBracket => {
+            let cm = parse_list_expr(p)?;
+            parse_trailers(p, cm)
+        }
+        PyTokenKind::TkLeftBrace => {
+            let cm = parse_dict_or_set_expr(p)?;
+            parse_trailers(p, cm)
         }
-        PyTokenKind::TkFString => parse_fstring_or_tstring_expr(p, PySyntaxKind::FStringExpr),
-        PyTokenKind::TkTString => parse_fstring_or_tstring_expr(p, PySyntaxKind::TStringExpr),
-        PyTokenKind::TkLeftBracket => parse_list_expr(p),
-        PyTokenKind::TkLeftBrace => parse_dict_or_set_expr(p),
         PyTokenKind::TkLambda => parse_lambda_expr(p),
         PyTokenKind::TkYield => parse_yield_expr(p),
         PyTokenKind::TkAwait => parse_await_expr(p),
@@ -260,7 +347,10 @@ pub fn parse_lambda_expr(p: &mut PyParser) -> ParseResult {
     if_token_bump(p, PyTokenKind::TkLambda);
 
     // Parse parameters (optional)
-    if p.current_token() == PyTokenKind::TkName {
+    if matches!(
+        p.current_token(),
+        PyTokenKind::TkName | PyTokenKind::TkMul | PyTokenKind::TkPow
+    ) {
         parse_lambda_params(p)?;
     }
 
@@ -345,11 +435,11 @@ fn parse_list_expr(p: &mut PyParser) -> ParseResult {
 
 fn parse_list_or_comprehension(p: &mut PyParser) -> ParseResult {
     let mut m = p.mark(PySyntaxKind::ListExpr);
-    p.smart_bump(); // consume '[' and track bracket context
+    p.bump(); // consume '['
 
     // Empty list
     if p.current_token() == PyTokenKind::TkRightBracket {
-        p.smart_bump(); // consume ']'
+        p.bump(); // consume ']'
         return Ok(m.complete(p));
     }
 
@@ -430,7 +520,7 @@ fn parse_list_or_comprehension(p: &mut PyParser) -> ParseResult {
 
     // Expect closing bracket
     if p.current_token() == PyTokenKind::TkRightBracket {
-        p.smart_bump(); // consume ']'
+        p.bump(); // consume ']'
     } else {
         p.push_error(PyParseError::syntax_error_from(
             &t!("expected ']' to close list"),
@@ -677,16 +767,46 @@ fn parse_lambda_params(p: &mut PyParser) -> ParseResult {
     let m = p.mark(PySyntaxKind::Parameters);
 
     loop {
-        if p.current_token() == PyTokenKind::TkName {
-            let param_m = p.mark(PySyntaxKind::Parameter);
-            p.bump(); // parameter name
-            param_m.complete(p);
-        } else {
-            p.push_error(PyParseError::syntax_error_from(
-                &t!("expected parameter name"),
-                p.current_token_range(),
-            ));
-            break;
+        match p.current_token() {
+            // 仅位置参数分隔符 /
+            PyTokenKind::TkDiv => p.bump(),
+            // *args, **kwargs 或者单独的 *
+            PyTokenKind::TkMul | PyTokenKind::TkPow => {
+                let is_pow = p.current_token() == PyTokenKind::TkPow;
+                let param_m = p.mark(PySyntaxKind::Parameter);
+                p.bump();
+                if p.current_token() == PyTokenKind::TkName {
+                    p.bump(); // parameter name
+                } else if is_pow {
+                    p.push_error(PyParseError::syntax_error_from(
+                        &t!("expected parameter name"),
+                        p.current_token_range(),
+                    ));
+                }
+                param_m.complete(p);
+            }
+            PyTokenKind::TkName => {
+                let param_m = p.mark(PySyntaxKind::Parameter);
+                p.bump(); // parameter name
+                // 默认值
+                if p.current_token() == PyTokenKind::TkAssign {
+                    p.bump();
+                    if parse_single_expr(p).is_err() {
+                        p.push_error(PyParseError::syntax_error_from(
+                            &t!("expected default value after '='"),
+                            p.current_token_range(),
+                        ));
+                    }
+                }
+                param_m.complete(p);
+            }
+            _ => {
+                p.push_error(PyParseError::syntax_error_from(
+                    &t!("expected parameter name"),
+                    p.current_token_range(),
+                ));
+                break;
+            }
         }
 
         if p.current_token() == PyTokenKind::TkComma {
@@ -704,7 +824,7 @@ fn parse_lambda_params(p: &mut PyParser) -> ParseResult {
 }
 
 fn parse_suffixed_expr(p: &mut PyParser) -> ParseResult {
-    let mut cm = match p.current_token() {
+    let cm = match p.current_token() {
         PyTokenKind::TkName => parse_name_expr(p)?,
         PyTokenKind::TkLeftParen => parse_parenthesized_expr_or_tuple(p)?,
         _ => {
@@ -716,6 +836,11 @@ fn parse_suffixed_expr(p: &mut PyParser) -> ParseResult {
         }
     };
 
+    parse_trailers(p, cm)
+}
+
+// 属性访问, 下标和调用可以跟在任意原子表达式之后, 例如 "".join(x) 和 [1, 2][0]
+fn parse_trailers(p: &mut PyParser, mut cm: CompleteMarker) -> ParseResult {
     loop {
         match p.current_token() {
             PyTokenKind::TkDot => {
@@ -766,7 +891,7 @@ fn parse_name_expr(p: &mut PyParser) -> ParseResult {
     Ok(m.complete(p))
 }
 
-fn parse_args(p: &mut PyParser) -> ParseResult {
+pub fn parse_args(p: &mut PyParser) -> ParseResult {
     let m = p.mark(PySyntaxKind::Arguments);
 
     if p.current_token() == PyTokenKind::TkLeftParen {
@@ -813,12 +938,12 @@ fn parse_args(p: &mut PyParser) -> ParseResult {
 fn parse_parenthesized_expr_or_tuple(p: &mut PyParser) -> ParseResult {
     let mut m = p.mark(PySyntaxKind::ParenExpr); // Start as paren expr, might change to tuple or generator
     let paren_range = p.current_token_range();
-    p.smart_bump(); // consume '('
+    p.bump(); // consume '('
 
     // Empty parentheses - empty tuple
     if p.current_token() == PyTokenKind::TkRightParen {
         m.set_kind(p, PySyntaxKind::TupleExpr);
-        p.smart_bump(); // consume ')'
+        p.bump(); // consume ')'
         return Ok(m.complete(p));
     }
 
@@ -860,7 +985,7 @@ fn parse_parenthesized_expr_or_tuple(p: &mut PyParser) -> ParseResult {
 
     // Expect closing parenthesis
     if p.current_token() == PyTokenKind::TkRightParen {
-        p.smart_bump(); // consume ')'
+        p.bump(); // consume ')'
     } else {
         p.push_error(PyParseError::syntax_error_from(
             &t!("expected ')' to close parentheses"),
@@ -875,7 +1000,7 @@ fn parse_parenthesized_expr_or_tuple(p: &mut PyParser) -> ParseResult {
 fn parse_argument(p: &mut PyParser) {
     // Check for **kwargs
     if p.current_token() == PyTokenKind::TkPow {
-        let marker = p.mark(PySyntaxKind::StarredExpr);
+        let marker = p.mark(PySyntaxKind::DoubleStarredExpr);
         p.bump(); // consume '**'
 
         if parse_single_expr(p).is_err() {
@@ -950,15 +1075,15 @@ fn parse_subscript_inner(p: &mut PyParser) {
     // Handle slice syntax: [start:end:step] or simple index [expr]
 
     // Parse optional first expression (start or index)
-    if p.current_token() != PyTokenKind::TkColon && p.current_token() != PyTokenKind::TkRightBracket
+    if p.current_token() != PyTokenKind::TkColon
+        && p.current_token() != PyTokenKind::TkRightBracket
+        && parse_expr(p).is_err()
     {
-        if parse_expr(p).is_err() {
-            p.push_error(PyParseError::syntax_error_from(
-                &t!("expected index or slice expression"),
-                p.current_token_range(),
-            ));
-            return;
-        }
+        p.push_error(PyParseError::syntax_error_from(
+            &t!("expected index or slice expression"),
+            p.current_token_range(),
+        ));
+        return;
     }
 
     // Check if this is a slice (contains colon)
@@ -969,13 +1094,12 @@ fn parse_subscript_inner(p: &mut PyParser) {
         // Parse optional end expression
         if p.current_token() != PyTokenKind::TkColon
             && p.current_token() != PyTokenKind::TkRightBracket
+            && parse_expr(p).is_err()
         {
-            if parse_expr(p).is_err() {
-                p.push_error(PyParseError::syntax_error_from(
-                    &t!("expected end expression in slice"),
-                    p.current_token_range(),
-                ));
-            }
+            p.push_error(PyParseError::syntax_error_from(
+                &t!("expected end expression in slice"),
+                p.current_token_range(),
+            ));
         }
 
         // Parse optional step if there's another colon
@@ -983,13 +1107,11 @@ fn parse_subscript_inner(p: &mut PyParser) {
             p.bump(); // consume second ':'
 
             // Parse optional step expression
-            if p.current_token() != PyTokenKind::TkRightBracket {
-                if parse_expr(p).is_err() {
-                    p.push_error(PyParseError::syntax_error_from(
-                        &t!("expected step expression in slice"),
-                        p.current_token_range(),
-                    ));
-                }
+            if p.current_token() != PyTokenKind::TkRightBracket && parse_expr(p).is_err() {
+                p.push_error(PyParseError::syntax_error_from(
+                    &t!("expected step expression in slice"),
+                    p.current_token_range(),
+                ));
             }
         }
 
@@ -8,7 +8,7 @@ use crate::{
     parser::{MarkerEventContainer, PyParser},
     parser_error::PyParseError,
 };
-pub use expr::parse_fstring_inner_expr;
+pub use expr::{parse_fstring_inner_expr, parse_type_comment_inner};
 
 pub fn parse_module_suite(p: &mut PyParser) {
     let m = p.mark(PySyntaxKind::Suite);
@@ -52,6 +52,7 @@ pub fn parse_module_suite(p: &mut PyParser) {
         }
     }
 
+    p.flush_type_comment();
     m.complete(p);
 }
 
@@ -92,5 +93,9 @@ fn is_statement_start_token(token: PyTokenKind) -> bool {
             | PyTokenKind::TkMatch
             | PyTokenKind::TkType
             | PyTokenKind::TkAt // decorators
+            | PyTokenKind::TkLineMagic
+            | PyTokenKind::TkCellMagic
+            | PyTokenKind::TkShellEscape
+            | PyTokenKind::TkHelp
     )
 }
@@ -5,9 +5,10 @@ use crate::{
     kind::{PySyntaxKind, PyTokenKind},
     parser::{CompleteMarker, MarkerEventContainer, PyParser},
     parser_error::PyParseError,
+    text::SourceRange,
 };
 
-use super::expr::parse_expr;
+use super::expr::{parse_args, parse_expr};
 
 // Parse an indented block (suite in Python grammar)
 fn parse_suite(p: &mut PyParser) -> ParseResult {
@@ -36,10 +37,17 @@ fn parse_suite_with_docstring(p: &mut PyParser, expect_docstring: bool) -> Parse
     }
 
     // Parse statements until DEDENT
-    while !matches!(
-        p.current_token(),
-        PyTokenKind::TkDedent | PyTokenKind::TkEof
-    ) {
+    let mut stray_indents = 0;
+    loop {
+        if eat_stray_indentation(p, &mut stray_indents) {
+            continue;
+        }
+        if matches!(
+            p.current_token(),
+            PyTokenKind::TkDedent | PyTokenKind::TkEof
+        ) {
+            break;
+        }
         parse_stat(p)?;
     }
 
@@ -51,6 +59,25 @@ fn parse_suite_with_docstring(p: &mut PyParser, expect_docstring: bool) -> Parse
     Ok(m.complete(p))
 }
 
+/// An indented line which no block opened is an "unexpected indent", its
+/// lines are kept as statements of the enclosing block, so the INDENT is
+/// counted and its DEDENT eaten here before the block can end with it
+fn eat_stray_indentation(p: &mut PyParser, stray_indents: &mut usize) -> bool {
+    match p.current_token() {
+        PyTokenKind::TkIndent => {
+            p.push_error(PyParseError::indentation_error_from(
+                &t!("unexpected indent"),
+                p.current_token_range(),
+            ));
+            *stray_indents += 1;
+        }
+        PyTokenKind::TkDedent if *stray_indents > 0 => *stray_indents -= 1,
+        _ => return false,
+    }
+    p.bump();
+    true
+}
+
 /// Helper function to consume newline and any additional empty lines
 fn consume_newlines_and_check_indent(p: &mut PyParser) -> bool {
     if p.current_token() == PyTokenKind::TkNewline {
@@ -115,7 +142,14 @@ fn recover_to_keywords(p: &mut PyParser, keywords: &[PyTokenKind]) {
 }
 
 pub fn parse_stats(p: &mut PyParser) {
-    while !block_follow(p) {
+    let mut stray_indents = 0;
+    loop {
+        if eat_stray_indentation(p, &mut stray_indents) {
+            continue;
+        }
+        if block_follow(p) {
+            break;
+        }
         let level = p.get_mark_level();
         match parse_stat(p) {
             Ok(_) => {}
@@ -185,6 +219,10 @@ fn parse_stat(p: &mut PyParser) -> ParseResult {
         PyTokenKind::TkAt => parse_decorated(p)?,
         PyTokenKind::TkMatMul => parse_decorated(p)?, // @ can be TkMatMul in statement context
         PyTokenKind::TkNewline => parse_newline(p)?,
+        PyTokenKind::TkLineMagic
+        | PyTokenKind::TkCellMagic
+        | PyTokenKind::TkShellEscape
+        | PyTokenKind::TkHelp => parse_magic(p)?,
         _ => parse_assign_or_expr_stat(p)?,
     };
 
@@ -223,11 +261,17 @@ fn parse_if(p: &mut PyParser) -> ParseResult {
     }
 
     // Handle body: either indented block or simple statement
+    let body_on_next_line = p.current_token() == PyTokenKind::TkNewline;
     if consume_newlines_and_check_indent(p) {
         parse_suite(p)?;
     } else if p.current_token() == PyTokenKind::TkIndent {
         // Direct indented block
         parse_suite(p)?;
+    } else if body_on_next_line {
+        p.push_error(PyParseError::indentation_error_from(
+            &t!("expected indented block after ':'"),
+            p.current_token_range(),
+        ));
     } else {
         // Simple statement on same line
         if parse_stat(p).is_err() {
@@ -264,10 +308,14 @@ fn parse_elif_clause(p: &mut PyParser) -> ParseResult {
     });
 
     // Consume newlines and check for indented block
-    if consume_newlines_and_check_indent(p) {
-        parse_suite(p)?;
-    } else if p.current_token() == PyTokenKind::TkIndent {
+    let body_on_next_line = p.current_token() == PyTokenKind::TkNewline;
+    if consume_newlines_and_check_indent(p) || p.current_token() == PyTokenKind::TkIndent {
         parse_suite(p)?;
+    } else if body_on_next_line {
+        p.push_error(PyParseError::indentation_error_from(
+            &t!("expected indented block after ':'"),
+            p.current_token_range(),
+        ));
     }
 
     Ok(m.complete(p))
@@ -280,10 +328,14 @@ fn parse_else_clause(p: &mut PyParser) -> ParseResult {
     expect_keyword_with_recovery(p, PyTokenKind::TkColon, || t!("expected ':' after 'else'"));
 
     // Consume newlines and check for indented block
-    if consume_newlines_and_check_indent(p) {
-        parse_suite(p)?;
-    } else if p.current_token() == PyTokenKind::TkIndent {
+    let body_on_next_line = p.current_token() == PyTokenKind::TkNewline;
+    if consume_newlines_and_check_indent(p) || p.current_token() == PyTokenKind::TkIndent {
         parse_suite(p)?;
+    } else if body_on_next_line {
+        p.push_error(PyParseError::indentation_error_from(
+            &t!("expected indented block after ':'"),
+            p.current_token_range(),
+        ));
     }
 
     Ok(m.complete(p))
@@ -307,9 +359,7 @@ fn parse_while(p: &mut PyParser) -> ParseResult {
     }
 
     // Consume newlines and parse suite
-    if consume_newlines_and_check_indent(p) {
-        parse_suite(p)?;
-    } else if p.current_token() == PyTokenKind::TkIndent {
+    if consume_newlines_and_check_indent(p) || p.current_token() == PyTokenKind::TkIndent {
         parse_suite(p)?;
     } else {
         p.push_error(PyParseError::syntax_error_from(
@@ -372,11 +422,10 @@ fn parse_for_body(p: &mut PyParser) -> Result<(), ParseFailReason> {
             p.current_token_range(),
         ));
     }
+    parse_type_comment(p);
 
     // Consume newlines and parse suite
-    if consume_newlines_and_check_indent(p) {
-        parse_suite(p)?;
-    } else if p.current_token() == PyTokenKind::TkIndent {
+    if consume_newlines_and_check_indent(p) || p.current_token() == PyTokenKind::TkIndent {
         parse_suite(p)?;
     } else {
         p.push_error(PyParseError::syntax_error_from(
@@ -395,10 +444,9 @@ fn parse_return(p: &mut PyParser) -> ParseResult {
     if !matches!(
         p.current_token(),
         PyTokenKind::TkNewline | PyTokenKind::TkEof | PyTokenKind::TkDedent
-    ) {
-        if parse_expr(p).is_err() {
-            push_expr_error_lazy(p, || t!("expected expression in return statement"));
-        }
+    ) && parse_expr(p).is_err()
+    {
+        push_expr_error_lazy(p, || t!("expected expression in return statement"));
     }
 
     consume_statement_terminator(p);
@@ -474,7 +522,7 @@ fn parse_def(p: &mut PyParser) -> ParseResult {
     // Parameters
     if p.current_token() == PyTokenKind::TkLeftParen {
         let param_m = p.mark(PySyntaxKind::Parameters);
-        p.smart_bump(); // consume '(' and track paren context
+        p.bump(); // consume '('
 
         // Parse parameters with full Python 3.8+ syntax support
         if p.current_token() != PyTokenKind::TkRightParen {
@@ -482,7 +530,7 @@ fn parse_def(p: &mut PyParser) -> ParseResult {
         }
 
         if p.current_token() == PyTokenKind::TkRightParen {
-            p.smart_bump(); // consume ')' and update paren context
+            p.bump(); // consume ')'
         } else {
             p.push_error(PyParseError::syntax_error_from(
                 &t!("expected ')' to close parameter list"),
@@ -520,6 +568,7 @@ fn parse_def(p: &mut PyParser) -> ParseResult {
         ));
         return Err(ParseFailReason::UnexpectedToken);
     }
+    parse_type_comment(p);
 
     // Body
     if p.current_token() == PyTokenKind::TkNewline {
@@ -609,41 +658,9 @@ fn parse_class(p: &mut PyParser) -> ParseResult {
         }
     }
 
-    // Optional inheritance
+    // Optional base classes and keywords, parsed like call arguments
     if p.current_token() == PyTokenKind::TkLeftParen {
-        p.smart_bump(); // consume '('
-
-        // Parse base classes
-        if p.current_token() != PyTokenKind::TkRightParen {
-            loop {
-                if parse_expr(p).is_err() {
-                    p.push_error(PyParseError::syntax_error_from(
-                        &t!("expected base class expression"),
-                        p.current_token_range(),
-                    ));
-                    break;
-                }
-
-                if p.current_token() == PyTokenKind::TkComma {
-                    p.bump();
-                    // Allow trailing comma
-                    if p.current_token() == PyTokenKind::TkRightParen {
-                        break;
-                    }
-                } else {
-                    break;
-                }
-            }
-        }
-
-        if p.current_token() == PyTokenKind::TkRightParen {
-            p.smart_bump(); // consume ')'
-        } else {
-            p.push_error(PyParseError::syntax_error_from(
-                &t!("expected ')' to close base class list"),
-                p.current_token_range(),
-            ));
-        }
+        parse_args(p)?;
     }
 
     // Colon
@@ -822,13 +839,12 @@ fn parse_raise(p: &mut PyParser) -> ParseResult {
     if !matches!(
         p.current_token(),
         PyTokenKind::TkNewline | PyTokenKind::TkEof
-    ) {
-        if parse_expr(p).is_err() {
-            p.push_error(PyParseError::syntax_error_from(
-                "expected exception after 'raise'",
-                p.current_token_range(),
-            ));
-        }
+    ) && parse_expr(p).is_err()
+    {
+        p.push_error(PyParseError::syntax_error_from(
+            "expected exception after 'raise'",
+            p.current_token_range(),
+        ));
     }
 
     consume_statement_terminator(p);
@@ -860,14 +876,12 @@ fn parse_try(p: &mut PyParser) -> ParseResult {
         p.bump(); // consume 'except'
 
         // Optional exception type
-        if p.current_token() != PyTokenKind::TkColon {
-            if parse_expr(p).is_ok() {
-                // Optional 'as' name
-                if p.current_token() == PyTokenKind::TkAs {
+        if p.current_token() != PyTokenKind::TkColon && parse_expr(p).is_ok() {
+            // Optional 'as' name
+            if p.current_token() == PyTokenKind::TkAs {
+                p.bump();
+                if p.current_token() == PyTokenKind::TkName {
                     p.bump();
-                    if p.current_token() == PyTokenKind::TkName {
-                        p.bump();
-                    }
                 }
             }
         }
@@ -962,6 +976,7 @@ fn parse_with_body(p: &mut PyParser) -> Result<(), ParseFailReason> {
             p.current_token_range(),
         ));
     }
+    parse_type_comment(p);
 
     // Consume optional newline after colon
     if p.current_token() == PyTokenKind::TkNewline {
@@ -1113,6 +1128,15 @@ fn parse_async_stmt(p: &mut PyParser) -> ParseResult {
     }
 }
 
+/// An IPython magic, shell escape or help request, the lexer only makes
+/// them in the IPython mode
+fn parse_magic(p: &mut PyParser) -> ParseResult {
+    let m = p.mark(PySyntaxKind::MagicStmt);
+    p.bump(); // consume the magic
+    consume_statement_terminator(p);
+    Ok(m.complete(p))
+}
+
 fn parse_newline(p: &mut PyParser) -> ParseResult {
     // Simply consume the newline token without creating a syntax node
     p.bump(); // consume newline
@@ -1127,6 +1151,48 @@ fn consume_statement_terminator(p: &mut PyParser) {
     }
 }
 
+/// A `# type:` comment at the end of the line (PEP 484), its text after
+/// `# type:` is parsed like an annotation
+fn parse_type_comment(p: &mut PyParser) {
+    let Some(range) = p.take_type_comment() else {
+        return;
+    };
+    let m = p.mark(PySyntaxKind::TypeComment);
+    let comment = &p.origin_text()[range.start_offset..range.end_offset()];
+    let prefix_len = comment.find(':').map_or(comment.len(), |colon| colon + 1);
+    p.eat_token(
+        PyTokenKind::TkTypeCommentStart,
+        SourceRange::new(range.start_offset, prefix_len),
+    );
+
+    // the whitespace after the colon would lex as an indent
+    let content = &comment[prefix_len..];
+    let space_len = content.len() - content.trim_start().len();
+    if space_len > 0 {
+        p.eat_token(
+            PyTokenKind::TkWhitespace,
+            SourceRange::new(range.start_offset + prefix_len, space_len),
+        );
+    }
+    let content_range = SourceRange::new(
+        range.start_offset + prefix_len + space_len,
+        range.length - prefix_len - space_len,
+    );
+    let text = &content[space_len..];
+    let mut errors = Vec::new();
+    PyParser::parse_type_comment(
+        text,
+        content_range,
+        p.parse_config(),
+        p.get_events(),
+        &mut errors,
+    );
+    for error in errors {
+        p.push_error(error);
+    }
+    m.complete(p);
+}
+
 fn parse_assign_or_expr_stat(p: &mut PyParser) -> ParseResult {
     // Check for type annotation pattern first: NAME ':' TYPE ['=' EXPR]
     if p.current_token() == PyTokenKind::TkName && p.peek_next_token() == PyTokenKind::TkColon {
@@ -1188,6 +1254,7 @@ fn parse_assign_or_expr_stat(p: &mut PyParser) -> ParseResult {
                     p.current_token_range(),
                 ));
             }
+            parse_type_comment(p);
         }
         // Augmented assignments
         PyTokenKind::TkPlusAssign
@@ -1331,6 +1398,7 @@ fn parse_function_parameters(p: &mut PyParser) {
                 // Check if this is *args (has a name) or just * (keyword-only separator)
                 if p.current_token() == PyTokenKind::TkName {
                     p.bump(); // consume parameter name
+                    parse_param_annotation(p);
                 }
 
                 param_m.complete(p);
@@ -1351,6 +1419,7 @@ fn parse_function_parameters(p: &mut PyParser) {
 
                 if p.current_token() == PyTokenKind::TkName {
                     p.bump(); // consume parameter name
+                    parse_param_annotation(p);
                 } else {
                     p.push_error(PyParseError::syntax_error_from(
                         "expected parameter name after '**'",
@@ -1367,17 +1436,7 @@ fn parse_function_parameters(p: &mut PyParser) {
                 p.bump(); // consume parameter name
 
                 // Optional type annotation
-                if p.current_token() == PyTokenKind::TkColon {
-                    p.bump(); // consume ':'
-                    let annotation_m = p.mark(PySyntaxKind::TypeAnnotation);
-                    if super::expr::parse_single_expr(p).is_err() {
-                        p.push_error(PyParseError::syntax_error_from(
-                            "expected type annotation after ':'",
-                            p.current_token_range(),
-                        ));
-                    }
-                    annotation_m.complete(p);
-                }
+                parse_param_annotation(p);
 
                 // Optional default value
                 if p.current_token() == PyTokenKind::TkAssign {
@@ -1409,6 +1468,21 @@ fn parse_function_parameters(p: &mut PyParser) {
     }
 }
 
+// 参数的类型注解 `name: annotation`, *args 和 **kwargs 同样适用
+fn parse_param_annotation(p: &mut PyParser) {
+    if p.current_token() == PyTokenKind::TkColon {
+        p.bump(); // consume ':'
+        let annotation_m = p.mark(PySyntaxKind::TypeAnnotation);
+        if super::expr::parse_single_expr(p).is_err() {
+            p.push_error(PyParseError::syntax_error_from(
+                "expected type annotation after ':'",
+                p.current_token_range(),
+            ));
+        }
+        annotation_m.complete(p);
+    }
+}
+
 // Parse class definition with optional decorators
 fn parse_class_with_decorators(p: &mut PyParser) -> ParseResult {
     parse_class(p)
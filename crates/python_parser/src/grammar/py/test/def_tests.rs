@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ParserConfig, PyParser, PySyntaxKind, PySyntaxTree};
+
+    fn parse(code: &str) -> PySyntaxTree {
+        PyParser::parse(code, ParserConfig::default(), None)
+    }
+
+    fn kinds_in(tree: &PySyntaxTree, parent: PySyntaxKind) -> Vec<PySyntaxKind> {
+        let node = tree
+            .get_red_root()
+            .descendants()
+            .find(|node| node.kind() == parent.into())
+            .unwrap();
+        node.children()
+            .map(|child| child.kind().to_syntax())
+            .collect()
+    }
+
+    #[test]
+    fn test_class_bases_and_keywords() {
+        let code = "class A(B, *mixins, metaclass=M, **kw):\n    pass\n";
+        let tree = parse(code);
+        assert!(tree.get_errors().is_empty());
+        assert_eq!(
+            kinds_in(&tree, PySyntaxKind::Arguments),
+            vec![
+                PySyntaxKind::NameExpr,
+                PySyntaxKind::StarredExpr,
+                PySyntaxKind::Keyword,
+                PySyntaxKind::DoubleStarredExpr,
+            ]
+        );
+
+        assert!(parse("class A(B,):\n    pass\n").get_errors().is_empty());
+        assert!(parse("class A():\n    pass\n").get_errors().is_empty());
+        assert_eq!(
+            parse("class A(B:\n    pass\n").get_errors()[0].message,
+            "unclosed bracket '(' opened at line 1"
+        );
+    }
+
+    #[test]
+    fn test_lambda_parameters() {
+        let tree = parse("f = lambda a, b=1, /, c=x + 1, *args, d, **kw: a\n");
+        assert!(tree.get_errors().is_empty());
+        let params = kinds_in(&tree, PySyntaxKind::Parameters);
+        assert_eq!(params, vec![PySyntaxKind::Parameter; 6]);
+
+        // a bare `*` only separates the keyword-only parameters
+        assert!(parse("f = lambda *, key: key\n").get_errors().is_empty());
+        assert!(parse("f = lambda **kw: kw\n").get_errors().is_empty());
+        assert_eq!(
+            parse("f = lambda a=: a\n")
+                .get_errors()
+                .last()
+                .unwrap()
+                .message,
+            "expected default value after '='"
+        );
+        assert_eq!(
+            parse("f = lambda **: 1\n").get_errors()[0].message,
+            "expected parameter name"
+        );
+    }
+
+    #[test]
+    fn test_star_parameter_annotations() {
+        let tree = parse("def f(*args: str, **kwargs: int):\n    pass\n");
+        assert!(tree.get_errors().is_empty());
+        let annotations = tree
+            .get_red_root()
+            .descendants()
+            .filter(|node| node.kind() == PySyntaxKind::TypeAnnotation.into())
+            .map(|node| node.text().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(annotations, vec!["str", "int"]);
+    }
+}
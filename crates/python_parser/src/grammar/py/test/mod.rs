@@ -1 +1,3 @@
+mod def_tests;
 mod fstring_tests;
+mod type_comment_tests;
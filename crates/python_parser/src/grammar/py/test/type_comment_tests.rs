@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod type_comment_parse_tests {
+    use crate::{
+        PyAssignStmt, PyAstNode, PyForStmt, PyFuncDef, PyParameter, PyWithStmt,
+        parser::{ParserConfig, PyParser},
+        syntax::PySyntaxTree,
+    };
+
+    fn parse(code: &str) -> PySyntaxTree {
+        PyParser::parse(code, ParserConfig::default(), None)
+    }
+
+    #[test]
+    fn test_assign_type_comment() {
+        let code = "x = []  # type: List[int]\ny = 1\n";
+        let tree = parse(code);
+        assert!(!tree.has_syntax_errors());
+        // the tree still holds every character of the source
+        assert_eq!(tree.get_red_root().text().to_string(), code);
+
+        let root = tree.get_red_root();
+        let assign = root.descendants().find_map(PyAssignStmt::cast).unwrap();
+        let comment = assign.get_type_comment().unwrap();
+        assert!(!comment.is_signature());
+        assert_eq!(comment.get_type().unwrap().syntax().text(), "List[int]");
+        // the value is not confused with the type
+        assert_eq!(assign.get_value().unwrap().syntax().text(), "[]");
+    }
+
+    #[test]
+    fn test_for_and_with_type_comments() {
+        let code =
+            "for x in xs:  # type: int\n    pass\nwith open(p) as f:  # type: IO[str]\n    pass\n";
+        let tree = parse(code);
+        assert!(!tree.has_syntax_errors());
+        let root = tree.get_red_root();
+        let for_stmt = root.descendants().find_map(PyForStmt::cast).unwrap();
+        let for_type = for_stmt.get_type_comment().unwrap().get_type().unwrap();
+        assert_eq!(for_type.syntax().text(), "int");
+        let with_stmt = root.descendants().find_map(PyWithStmt::cast).unwrap();
+        let with_type = with_stmt.get_type_comment().unwrap().get_type().unwrap();
+        assert_eq!(with_type.syntax().text(), "IO[str]");
+    }
+
+    #[test]
+    fn test_signature_type_comment() {
+        let code = "class A:\n    def f(self, a, *b):\n        # type: (int, *str) -> bool\n        pass\n    def g(self, a):  # type: (A, int) -> None\n        pass\n";
+        let tree = parse(code);
+        assert!(!tree.has_syntax_errors());
+        let root = tree.get_red_root();
+        let funcs: Vec<PyFuncDef> = root.descendants().filter_map(PyFuncDef::cast).collect();
+        // only a comment on the line of the colon is a signature comment
+        assert!(funcs[0].get_type_comment().is_none());
+
+        let comment = funcs[1].get_type_comment().unwrap();
+        assert!(comment.is_signature());
+        let params: Vec<_> = comment
+            .get_param_types()
+            .map(|it| it.syntax().text().to_string())
+            .collect();
+        assert_eq!(params, vec!["A", "int"]);
+        assert_eq!(comment.get_return_type().unwrap().syntax().text(), "None");
+        let annotations: Vec<_> = funcs[1]
+            .syntax()
+            .descendants()
+            .filter_map(PyParameter::cast)
+            .map(|param| {
+                param
+                    .get_type_comment_annotation()
+                    .map(|it| it.syntax().text().to_string())
+            })
+            .collect();
+        assert_eq!(
+            annotations,
+            vec![Some("A".to_string()), Some("int".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_method_signature_skips_self() {
+        let code = "def f(self, a, *args):  # type: (int, *str) -> bool\n    pass\n";
+        let tree = parse(code);
+        let root = tree.get_red_root();
+        let annotations: Vec<_> = root
+            .descendants()
+            .filter_map(PyParameter::cast)
+            .map(|param| {
+                param
+                    .get_type_comment_annotation()
+                    .map(|it| it.syntax().text().to_string())
+            })
+            .collect();
+        assert_eq!(
+            annotations,
+            vec![None, Some("int".to_string()), Some("str".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_ignore_and_plain_comments() {
+        let code =
+            "x = f()  # type: ignore[attr-defined]\ny = 1  # not a type\nz = 2  # type:ignore\n";
+        let tree = parse(code);
+        assert!(!tree.has_syntax_errors());
+        let root = tree.get_red_root();
+        assert!(
+            root.descendants()
+                .filter_map(PyAssignStmt::cast)
+                .all(|assign| assign.get_type_comment().is_none())
+        );
+        assert_eq!(root.text().to_string(), code);
+    }
+
+    #[test]
+    fn test_type_comment_errors() {
+        for code in [
+            "x = []  # type: List[int\ny = 1\n",
+            "x = 1  # type: = 2\ny = 1\n",
+            "x = 1  # type: lambda: (\ny = 1\n",
+        ] {
+            let tree = parse(code);
+            assert!(tree.has_syntax_errors());
+            // a broken type comment ends with its line
+            let root = tree.get_red_root();
+            assert_eq!(root.text().to_string(), code);
+            let suite = root.first_child().unwrap();
+            assert_eq!(suite.children().filter_map(PyAssignStmt::cast).count(), 2);
+        }
+        let tree = parse("def f(a):  # type: (int) bool -> None\n    pass\n");
+        assert!(tree.has_syntax_errors());
+    }
+}
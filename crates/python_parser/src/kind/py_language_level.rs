@@ -2,7 +2,7 @@ use core::fmt;
 
 use crate::PyVersionNumber;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PyLanguageLevel {
     Py3(PyVersionNumber),
 }
@@ -145,14 +145,6 @@ impl Default for PyLanguageLevel {
     }
 }
 
-impl PartialOrd for PyLanguageLevel {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match (self, other) {
-            (PyLanguageLevel::Py3(a), PyLanguageLevel::Py3(b)) => a.partial_cmp(b),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,6 +48,7 @@ pub enum PySyntaxKind {
     // Type annotations
     TypeAlias,      // type alias
     TypeAnnotation, // type annotation
+    TypeComment,    // `# type:` comment (PEP 484)
 
     // Expressions
     NameExpr,    // identifier/name
@@ -140,6 +141,10 @@ pub enum PySyntaxKind {
     EnhancedPattern,    // enhanced pattern matching
     Decorated,          // decorated statement
 
+    // IPython syntax
+    MagicStmt, // a magic, shell escape or help on a line of its own
+    MagicExpr, // the magic or shell escape assigned by `files = !ls`
+
     // Other nodes
     Parameter,  // function parameter
     Parameters, // parameter list
@@ -44,8 +44,9 @@ pub enum PyTokenKind {
     TkWith,     // with
     TkYield,    // yield
 
-    TkWhitespace, // whitespace
-    TkNewline,    // \n
+    TkWhitespace,       // whitespace
+    TkNewline,          // \n
+    TkLineContinuation, // \ and the newline it joins
 
     // Operators
     TkPlus,     // +
@@ -124,8 +125,15 @@ pub enum PyTokenKind {
     TkDedent, // dedentation
 
     // Comments
-    TkComment, // # comment
-    TkShebang, // shebang
+    TkComment,          // # comment
+    TkShebang,          // shebang
+    TkTypeCommentStart, // `# type:` of a type comment
+
+    // IPython syntax, only in the IPython mode
+    TkLineMagic,   // %timeit x
+    TkCellMagic,   // %%bash and the rest of the cell
+    TkShellEscape, // !ls or !!ls
+    TkHelp,        // obj? or ??obj
 
     // Special
     TkEof,        // end of file
@@ -189,6 +197,16 @@ impl PyTokenKind {
         )
     }
 
+    pub fn is_ipython_magic(self) -> bool {
+        matches!(
+            self,
+            PyTokenKind::TkLineMagic
+                | PyTokenKind::TkCellMagic
+                | PyTokenKind::TkShellEscape
+                | PyTokenKind::TkHelp
+        )
+    }
+
     pub fn is_assign_op(self) -> bool {
         matches!(
             self,
@@ -48,9 +48,8 @@ impl Ord for PyVersionNumber {
 
 impl fmt::Display for PyVersionNumber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            PyVersionNumber { major, minor, .. } => write!(f, "Python {}.{}", major, minor),
-        }
+        let PyVersionNumber { major, minor, .. } = *self;
+        write!(f, "Python {}.{}", major, minor)
     }
 }
 
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod version_warning_tests {
     use crate::{
         kind::{PyLanguageLevel, PyVersionNumber},
@@ -0,0 +1,522 @@
+use std::fmt;
+
+use crate::{
+    kind::PyTokenKind,
+    lexer::{LexerConfig, PyLexer, PyTokenData},
+    text::{DecodedSource, Reader, SourceRange, find_coding_cookie},
+};
+
+/// The token types of CPython's `tokenize` module, as named in `tok_name`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CPythonTokenKind {
+    Encoding,
+    Name,
+    Number,
+    String,
+    Op,
+    Comment,
+    Nl,
+    Newline,
+    Indent,
+    Dedent,
+    FStringStart,
+    FStringMiddle,
+    FStringEnd,
+    TStringStart,
+    TStringMiddle,
+    TStringEnd,
+    ErrorToken,
+    EndMarker,
+}
+
+impl CPythonTokenKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            CPythonTokenKind::Encoding => "ENCODING",
+            CPythonTokenKind::Name => "NAME",
+            CPythonTokenKind::Number => "NUMBER",
+            CPythonTokenKind::String => "STRING",
+            CPythonTokenKind::Op => "OP",
+            CPythonTokenKind::Comment => "COMMENT",
+            CPythonTokenKind::Nl => "NL",
+            CPythonTokenKind::Newline => "NEWLINE",
+            CPythonTokenKind::Indent => "INDENT",
+            CPythonTokenKind::Dedent => "DEDENT",
+            CPythonTokenKind::FStringStart => "FSTRING_START",
+            CPythonTokenKind::FStringMiddle => "FSTRING_MIDDLE",
+            CPythonTokenKind::FStringEnd => "FSTRING_END",
+            CPythonTokenKind::TStringStart => "TSTRING_START",
+            CPythonTokenKind::TStringMiddle => "TSTRING_MIDDLE",
+            CPythonTokenKind::TStringEnd => "TSTRING_END",
+            CPythonTokenKind::ErrorToken => "ERRORTOKEN",
+            CPythonTokenKind::EndMarker => "ENDMARKER",
+        }
+    }
+}
+
+/// A token as `tokenize.tokenize` yields it. Lines count from 1 and columns
+/// from 0, in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CPythonToken {
+    pub kind: CPythonTokenKind,
+    pub text: String,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+/// `NAME 1,0-1,6 "import"`, the token with its text quoted as a JSON string
+impl fmt::Display for CPythonToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {},{}-{},{} {:?}",
+            self.kind.name(),
+            self.start.0,
+            self.start.1,
+            self.end.0,
+            self.end.1,
+            self.text
+        )
+    }
+}
+
+/// Tokenize the bytes of a source file into the tokens CPython's `tokenize`
+/// module gives for it. The layout tokens, `NL` and `NEWLINE`, `INDENT` and
+/// `DEDENT`, come from the logical lines rather than from the lexer, and the
+/// f-strings are split into their parts from Python 3.12 on.
+pub fn tokenize_like_cpython(bytes: &[u8], config: LexerConfig) -> Vec<CPythonToken> {
+    let source = DecodedSource::decode(bytes);
+    let mut tokenizer = CPythonTokenizer::new(&source.text, config);
+    tokenizer.tokens.push(CPythonToken {
+        kind: CPythonTokenKind::Encoding,
+        text: encoding_name(bytes, &source),
+        start: (0, 0),
+        end: (0, 0),
+    });
+    tokenizer.tokenize();
+    tokenizer.tokens
+}
+
+/// The name `tokenize.detect_encoding` gives the encoding
+fn encoding_name(bytes: &[u8], source: &DecodedSource) -> String {
+    let start = if source.has_bom { 3 } else { 0 };
+    let Some((name, _)) = find_coding_cookie(&bytes[start..]) else {
+        return "utf-8".to_string();
+    };
+    let normal = name
+        .chars()
+        .take(12)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let normal = normal.replace('_', "-");
+    let is = |names: &[&str]| {
+        names
+            .iter()
+            .any(|it| normal == *it || normal.starts_with(&format!("{}-", it)))
+    };
+    if is(&["utf-8"]) {
+        "utf-8".to_string()
+    } else if is(&["latin-1", "iso-8859-1", "iso-latin-1"]) {
+        "iso-8859-1".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+struct CPythonTokenizer<'a> {
+    text: &'a str,
+    config: LexerConfig,
+    line_starts: Vec<usize>,
+    tokens: Vec<CPythonToken>,
+    /// The columns of the open indentation levels
+    indents: Vec<usize>,
+    /// How many brackets are open, a newline inside them is `NL`
+    depth: usize,
+    /// Whether the logical line has a token other than a comment yet
+    line_has_content: bool,
+}
+
+impl<'a> CPythonTokenizer<'a> {
+    fn new(text: &'a str, config: LexerConfig) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        CPythonTokenizer {
+            text,
+            config,
+            line_starts,
+            tokens: Vec::new(),
+            indents: vec![0],
+            depth: 0,
+            line_has_content: false,
+        }
+    }
+
+    fn tokenize(&mut self) {
+        let lexed = PyLexer::new(Reader::new(self.text), self.config, None).tokenize();
+        for token in &lexed {
+            match token.kind {
+                // a backslash joins the next line to this one
+                PyTokenKind::TkWhitespace
+                | PyTokenKind::TkLineContinuation
+                | PyTokenKind::TkIndent
+                | PyTokenKind::TkDedent
+                | PyTokenKind::TkEof => {}
+                PyTokenKind::TkNewline => {
+                    let kind = if self.depth == 0 && self.line_has_content {
+                        self.line_has_content = false;
+                        CPythonTokenKind::Newline
+                    } else {
+                        CPythonTokenKind::Nl
+                    };
+                    self.push(kind, token.range);
+                }
+                PyTokenKind::TkComment | PyTokenKind::TkShebang => {
+                    self.push(CPythonTokenKind::Comment, token.range)
+                }
+                kind => {
+                    if !self.line_has_content {
+                        self.push_indentation(token.range.start_offset);
+                        self.line_has_content = true;
+                    }
+                    match kind {
+                        PyTokenKind::TkLeftParen
+                        | PyTokenKind::TkLeftBracket
+                        | PyTokenKind::TkLeftBrace => self.depth += 1,
+                        PyTokenKind::TkRightParen
+                        | PyTokenKind::TkRightBracket
+                        | PyTokenKind::TkRightBrace => self.depth = self.depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    self.push_token(token);
+                }
+            }
+        }
+        self.push_end();
+    }
+
+    /// The implicit newline of a last line without one, the dedents of the
+    /// open blocks and the end marker
+    fn push_end(&mut self) {
+        let end = self.text.len();
+        let (mut row, col) = self.position(end);
+        if col > 0 {
+            let kind = if self.depth == 0 && self.line_has_content {
+                CPythonTokenKind::Newline
+            } else {
+                CPythonTokenKind::Nl
+            };
+            self.push_at(kind, "", (row, col), (row, col + 1));
+            row += 1;
+        }
+        for _ in 1..self.indents.len() {
+            self.push_at(CPythonTokenKind::Dedent, "", (row, 0), (row, 0));
+        }
+        self.push_at(CPythonTokenKind::EndMarker, "", (row, 0), (row, 0));
+    }
+
+    /// The `INDENT` or `DEDENT` tokens before the first token of a logical line
+    fn push_indentation(&mut self, offset: usize) {
+        let (row, col) = self.position(offset);
+        let line_start = self.line_starts[row - 1];
+        let indent = &self.text[line_start..offset];
+        let width = indent_width(indent);
+        if width > *self.indents.last().unwrap_or(&0) {
+            self.indents.push(width);
+            self.push_at(CPythonTokenKind::Indent, indent, (row, 0), (row, col));
+            return;
+        }
+        while self.indents.len() > 1 && width < *self.indents.last().unwrap_or(&0) {
+            self.indents.pop();
+            self.push_at(CPythonTokenKind::Dedent, "", (row, col), (row, col));
+        }
+    }
+
+    fn push_token(&mut self, token: &PyTokenData) {
+        let level = self.config.language_level;
+        match token.kind {
+            PyTokenKind::TkFString if level.is_python_312_or_later() => self.push_template_string(
+                token.range,
+                [
+                    CPythonTokenKind::FStringStart,
+                    CPythonTokenKind::FStringMiddle,
+                    CPythonTokenKind::FStringEnd,
+                ],
+            ),
+            PyTokenKind::TkTString if level.is_python_314_or_later() => self.push_template_string(
+                token.range,
+                [
+                    CPythonTokenKind::TStringStart,
+                    CPythonTokenKind::TStringMiddle,
+                    CPythonTokenKind::TStringEnd,
+                ],
+            ),
+            kind => self.push(cpython_kind(kind), token.range),
+        }
+    }
+
+    /// Split an f-string or t-string into its start, middle and end tokens,
+    /// with the tokens of its replacement fields between them
+    fn push_template_string(&mut self, range: SourceRange, kinds: [CPythonTokenKind; 3]) {
+        let token = self.slice(range);
+        let prefix_len = token.find(['\'', '"']).unwrap_or(token.len());
+        let quotes = &token[prefix_len..];
+        let quote_len =
+            if quotes.len() >= 6 && (quotes.starts_with("\"\"\"") || quotes.starts_with("'''")) {
+                3
+            } else {
+                1
+            };
+        if quotes.len() < quote_len * 2 || !quotes.ends_with(&quotes[..quote_len]) {
+            self.push(CPythonTokenKind::ErrorToken, range);
+            return;
+        }
+        let raw = token[..prefix_len].contains(['r', 'R']);
+        let content_start = range.start_offset + prefix_len + quote_len;
+        let content_end = range.end_offset() - quote_len;
+        self.push_range(kinds[0], range.start_offset, content_start);
+        let mut segment = content_start;
+        let mut index = content_start;
+        let bytes = self.text.as_bytes();
+        while index < content_end {
+            match bytes[index] {
+                // `{{` and `}}` end a middle with one brace, the other one is
+                // in no token
+                b'{' | b'}' if index + 1 < content_end && bytes[index + 1] == bytes[index] => {
+                    self.push_range(kinds[1], segment, index + 1);
+                    index += 2;
+                    segment = index;
+                }
+                b'{' => {
+                    self.push_middle(kinds[1], segment, index);
+                    index = self.push_replacement_field(index, content_end, raw, kinds[1]);
+                    segment = index;
+                }
+                b'\\' if !raw => {
+                    let next = skip_escape(bytes, index);
+                    // a middle also ends after a `\N{...}` escape
+                    if bytes[index + 1] == b'N' {
+                        self.push_range(kinds[1], segment, next);
+                        segment = next;
+                    }
+                    index = next;
+                }
+                _ => index += 1,
+            }
+        }
+        self.push_middle(kinds[1], segment, content_end);
+        self.push_range(kinds[2], content_end, range.end_offset());
+    }
+
+    /// Push the `{`, the expression, the conversion, the format spec and the
+    /// `}` of a replacement field, returning the offset after it
+    fn push_replacement_field(
+        &mut self,
+        start: usize,
+        end: usize,
+        raw: bool,
+        middle: CPythonTokenKind,
+    ) -> usize {
+        self.push_range(CPythonTokenKind::Op, start, start + 1);
+        let mut index = self.push_field_expression(start + 1, end);
+        let bytes = self.text.as_bytes();
+        if index < end && bytes[index] == b'!' {
+            self.push_range(CPythonTokenKind::Op, index, index + 1);
+            let name_end = index
+                + 1
+                + bytes[index + 1..end]
+                    .iter()
+                    .take_while(|it| it.is_ascii_alphanumeric())
+                    .count();
+            self.push_range(CPythonTokenKind::Name, index + 1, name_end);
+            index = name_end;
+        }
+        if index < end && bytes[index] == b':' {
+            self.push_range(CPythonTokenKind::Op, index, index + 1);
+            index = self.push_format_spec(index + 1, end, raw, middle);
+        }
+        if index < end && bytes[index] == b'}' {
+            self.push_range(CPythonTokenKind::Op, index, index + 1);
+            index += 1;
+        }
+        index
+    }
+
+    /// Push the tokens of the expression of a replacement field, returning
+    /// the offset of the `!`, `:` or `}` after it
+    fn push_field_expression(&mut self, start: usize, end: usize) -> usize {
+        let reader = Reader::new_with_range(
+            &self.text[start..end],
+            SourceRange::from_start_end(start, end),
+        );
+        let lexed = PyLexer::new(reader, self.config, None).tokenize();
+        let mut depth = 0usize;
+        for token in &lexed {
+            match token.kind {
+                PyTokenKind::TkWhitespace
+                | PyTokenKind::TkLineContinuation
+                | PyTokenKind::TkIndent
+                | PyTokenKind::TkDedent
+                | PyTokenKind::TkNewline
+                | PyTokenKind::TkEof => continue,
+                PyTokenKind::TkRightBrace | PyTokenKind::TkColon if depth == 0 => {
+                    return token.range.start_offset;
+                }
+                PyTokenKind::TkUnknown if depth == 0 && self.slice(token.range) == "!" => {
+                    return token.range.start_offset;
+                }
+                PyTokenKind::TkLeftParen
+                | PyTokenKind::TkLeftBracket
+                | PyTokenKind::TkLeftBrace => depth += 1,
+                PyTokenKind::TkRightParen
+                | PyTokenKind::TkRightBracket
+                | PyTokenKind::TkRightBrace => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.push_token(token);
+        }
+        end
+    }
+
+    /// Push the middles and nested replacement fields of a format spec,
+    /// returning the offset of the `}` which ends it. The spec always ends
+    /// with a middle, an empty one after a nested field.
+    fn push_format_spec(
+        &mut self,
+        start: usize,
+        end: usize,
+        raw: bool,
+        middle: CPythonTokenKind,
+    ) -> usize {
+        let bytes = self.text.as_bytes();
+        let mut segment = start;
+        let mut index = start;
+        while index < end {
+            match bytes[index] {
+                b'{' => {
+                    self.push_middle(middle, segment, index);
+                    index = self.push_replacement_field(index, end, raw, middle);
+                    segment = index;
+                }
+                b'}' => break,
+                b'\\' if !raw => index = skip_escape(bytes, index),
+                _ => index += 1,
+            }
+        }
+        self.push_range(middle, segment, index);
+        index
+    }
+
+    /// Push a middle unless it is empty
+    fn push_middle(&mut self, kind: CPythonTokenKind, start: usize, end: usize) {
+        if start < end {
+            self.push_range(kind, start, end);
+        }
+    }
+
+    fn push(&mut self, kind: CPythonTokenKind, range: SourceRange) {
+        self.push_range(kind, range.start_offset, range.end_offset());
+    }
+
+    /// Push the text between two offsets. The end is one column after the
+    /// last character, so a token ending with a newline ends on its own line.
+    fn push_range(&mut self, kind: CPythonTokenKind, start: usize, end: usize) {
+        let text = &self.text[start..end];
+        let end_position = match text.char_indices().last() {
+            Some((last, _)) => {
+                let (row, col) = self.position(start + last);
+                (row, col + 1)
+            }
+            None => self.position(end),
+        };
+        self.push_at(kind, text, self.position(start), end_position);
+    }
+
+    fn push_at(
+        &mut self,
+        kind: CPythonTokenKind,
+        text: &str,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) {
+        self.tokens.push(CPythonToken {
+            kind,
+            text: text.to_string(),
+            start,
+            end,
+        });
+    }
+
+    fn slice(&self, range: SourceRange) -> &'a str {
+        &self.text[range.start_offset..range.end_offset()]
+    }
+
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|it| *it <= offset) - 1;
+        let column = self.text[self.line_starts[line]..offset].chars().count();
+        (line + 1, column)
+    }
+}
+
+fn cpython_kind(kind: PyTokenKind) -> CPythonTokenKind {
+    match kind {
+        _ if kind.is_keyword() => CPythonTokenKind::Name,
+        PyTokenKind::TkName | PyTokenKind::TkMatch | PyTokenKind::TkCase | PyTokenKind::TkType => {
+            CPythonTokenKind::Name
+        }
+        PyTokenKind::TkInt | PyTokenKind::TkFloat | PyTokenKind::TkComplex => {
+            CPythonTokenKind::Number
+        }
+        PyTokenKind::TkString
+        | PyTokenKind::TkBytesString
+        | PyTokenKind::TkRawBytesString
+        | PyTokenKind::TkRawString
+        | PyTokenKind::TkFString
+        | PyTokenKind::TkTString => CPythonTokenKind::String,
+        PyTokenKind::TkComment | PyTokenKind::TkShebang | PyTokenKind::TkTypeCommentStart => {
+            CPythonTokenKind::Comment
+        }
+        PyTokenKind::TkNewline => CPythonTokenKind::Newline,
+        PyTokenKind::TkIndent => CPythonTokenKind::Indent,
+        PyTokenKind::TkDedent => CPythonTokenKind::Dedent,
+        PyTokenKind::TkEof => CPythonTokenKind::EndMarker,
+        PyTokenKind::None
+        | PyTokenKind::TkWhitespace
+        | PyTokenKind::TkLineContinuation
+        | PyTokenKind::TkUnknown
+        | PyTokenKind::TkErrorToken
+        | PyTokenKind::TkLineMagic
+        | PyTokenKind::TkCellMagic
+        | PyTokenKind::TkShellEscape
+        | PyTokenKind::TkHelp
+        | PyTokenKind::TkFStringText
+        | PyTokenKind::TkFStringExprStart
+        | PyTokenKind::TkFStringExprEnd
+        | PyTokenKind::TkFStringFormatSpec
+        | PyTokenKind::TkFStringConversion => CPythonTokenKind::ErrorToken,
+        _ => CPythonTokenKind::Op,
+    }
+}
+
+/// The width of an indentation, with tabs to the next multiple of 8 and a
+/// form feed starting over, as the CPython tokenizer counts it
+fn indent_width(indent: &str) -> usize {
+    indent.chars().fold(0, |width, ch| match ch {
+        '\t' => (width / 8 + 1) * 8,
+        '\x0c' => 0,
+        _ => width + 1,
+    })
+}
+
+/// The offset after the escape sequence at `index`, a `\N{...}` name holds
+/// braces which do not start a replacement field
+fn skip_escape(bytes: &[u8], index: usize) -> usize {
+    if bytes[index + 1] == b'N' && bytes.get(index + 2) == Some(&b'{') {
+        bytes[index..]
+            .iter()
+            .position(|it| *it == b'}')
+            .map_or(bytes.len(), |it| index + it + 1)
+    } else {
+        (index + 2).min(bytes.len())
+    }
+}
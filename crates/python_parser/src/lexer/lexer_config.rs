@@ -1,16 +1,11 @@
 use crate::kind::PyLanguageLevel;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct LexerConfig {
     pub language_level: PyLanguageLevel,
+    /// Lex the IPython syntax of `.ipy` scripts and notebook cells: magics,
+    /// shell escapes and help become tokens of their own
+    pub ipython_magics: bool,
 }
 
 impl LexerConfig {}
-
-impl Default for LexerConfig {
-    fn default() -> Self {
-        LexerConfig {
-            language_level: PyLanguageLevel::default(),
-        }
-    }
-}
@@ -1,9 +1,11 @@
+mod cpython_tokens;
 mod fstring_lexer;
 mod lexer_config;
 mod py_lexer;
 mod test;
 mod token_data;
 
+pub use cpython_tokens::{CPythonToken, CPythonTokenKind, tokenize_like_cpython};
 pub use fstring_lexer::{FStringLexer, FStringToken};
 pub use lexer_config::LexerConfig;
 pub use py_lexer::PyLexer;
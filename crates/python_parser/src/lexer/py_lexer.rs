@@ -9,9 +9,40 @@ pub enum LexerState {
     TripleString(char, PyTokenKind), // quote character and string type for triple-quoted strings
 }
 
+/// Where IPython syntax may start, in the IPython mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MagicContext {
+    /// The first token of a logical line
+    StatementStart,
+    /// The value of `name = ...` on a logical line
+    AssignValue,
+    None,
+}
+
+/// An indentation level, CPython compares the widths of the lines with tabs
+/// to multiples of 8 and with tabs as one column, and a line which compares
+/// differently under the two mixes tabs and spaces ambiguously
+#[derive(Debug, Clone, Copy)]
+struct IndentLevel {
+    width: usize,
+    alt_width: usize,
+    /// A level made for a line which matched no outer level or indented
+    /// ambiguously, so that the lines after it don't report the same error
+    /// again. It has no INDENT and takes no DEDENT
+    recovered: bool,
+}
+
+impl IndentLevel {
+    const ZERO: IndentLevel = IndentLevel {
+        width: 0,
+        alt_width: 0,
+        recovered: false,
+    };
+}
+
 #[derive(Debug, Clone)]
 struct IndentInfo {
-    indent_stack: Vec<usize>,
+    indent_stack: Vec<IndentLevel>,
     at_line_start: bool,
     pending_dedents: usize,
 }
@@ -23,6 +54,9 @@ pub struct PyLexer<'a> {
     errors: Option<&'a mut Vec<PyParseError>>,
     state: LexerState,
     indent_info: IndentInfo,
+    /// How many brackets are open, a newline inside them does not end the line
+    bracket_depth: usize,
+    magic_context: MagicContext,
 }
 
 impl<'a> PyLexer<'a> {
@@ -46,10 +80,12 @@ impl<'a> PyLexer<'a> {
             errors,
             state,
             indent_info: IndentInfo {
-                indent_stack: vec![0],
+                indent_stack: vec![IndentLevel::ZERO],
                 at_line_start: true,
                 pending_dedents: 0,
             },
+            bracket_depth: 0,
+            magic_context: MagicContext::StatementStart,
         }
     }
 
@@ -75,10 +111,14 @@ impl<'a> PyLexer<'a> {
                 }
             };
 
+            self.update_magic_context(kind);
             if kind == PyTokenKind::TkEof {
                 // Generate final dedents
                 while self.indent_info.indent_stack.len() > 1 {
-                    self.indent_info.indent_stack.pop();
+                    let level = self.indent_info.indent_stack.pop().unwrap();
+                    if level.recovered {
+                        continue;
+                    }
                     tokens.push(PyTokenData::new(
                         PyTokenKind::TkDedent,
                         self.reader.current_range(),
@@ -177,8 +217,15 @@ impl<'a> PyLexer<'a> {
             return self.handle_indentation();
         }
 
+        if self.lexer_config.ipython_magics
+            && let Some(kind) = self.lex_ipython()
+        {
+            return kind;
+        }
+
         match self.reader.current_char() {
             '\n' | '\r' => self.lex_new_line(),
+            '\\' => self.lex_line_continuation(),
             ' ' | '\t' => self.lex_white_space(),
             '-' => {
                 self.reader.bump();
@@ -456,15 +503,83 @@ impl<'a> PyLexer<'a> {
         }
     }
 
+    /// Track the brackets and the statement start, IPython syntax is only
+    /// recognized at the start of a logical line or as an assigned value
+    fn update_magic_context(&mut self, kind: PyTokenKind) {
+        match kind {
+            PyTokenKind::TkLeftParen | PyTokenKind::TkLeftBracket | PyTokenKind::TkLeftBrace => {
+                self.bracket_depth += 1;
+            }
+            PyTokenKind::TkRightParen | PyTokenKind::TkRightBracket | PyTokenKind::TkRightBrace => {
+                self.bracket_depth = self.bracket_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        self.magic_context = match kind {
+            PyTokenKind::TkWhitespace
+            | PyTokenKind::TkLineContinuation
+            | PyTokenKind::TkIndent
+            | PyTokenKind::TkDedent
+            | PyTokenKind::TkComment => return,
+            PyTokenKind::TkNewline if self.bracket_depth == 0 => MagicContext::StatementStart,
+            PyTokenKind::TkNewline => return,
+            PyTokenKind::TkAssign if self.bracket_depth == 0 => MagicContext::AssignValue,
+            _ => MagicContext::None,
+        };
+    }
+
+    /// Lex a magic `%timeit x`, a cell magic `%%bash` which takes the rest
+    /// of the cell, a shell escape `!ls` or a help request `obj?`
+    fn lex_ipython(&mut self) -> Option<PyTokenKind> {
+        let ch = self.reader.current_char();
+        match self.magic_context {
+            MagicContext::StatementStart => match ch {
+                '%' if self.reader.next_char() == '%' => {
+                    self.reader.eat_till_end();
+                    Some(PyTokenKind::TkCellMagic)
+                }
+                '%' => Some(self.lex_to_line_end(PyTokenKind::TkLineMagic)),
+                '!' => Some(self.lex_to_line_end(PyTokenKind::TkShellEscape)),
+                '?' => Some(self.lex_to_line_end(PyTokenKind::TkHelp)),
+                _ if is_help_line(self.reader.tail_text()) => {
+                    Some(self.lex_to_line_end(PyTokenKind::TkHelp))
+                }
+                _ => None,
+            },
+            MagicContext::AssignValue => match ch {
+                '%' => Some(self.lex_to_line_end(PyTokenKind::TkLineMagic)),
+                '!' => Some(self.lex_to_line_end(PyTokenKind::TkShellEscape)),
+                _ => None,
+            },
+            MagicContext::None => None,
+        }
+    }
+
+    fn lex_to_line_end(&mut self, kind: PyTokenKind) -> PyTokenKind {
+        self.reader.eat_while(|ch| ch != '\n' && ch != '\r');
+        kind
+    }
+
     fn handle_indentation(&mut self) -> PyTokenKind {
-        let mut indent_level = 0;
+        let mut width = 0;
+        let mut alt_width = 0;
 
-        // Skip whitespace and count indentation
-        while matches!(self.reader.current_char(), ' ' | '\t') {
-            if self.reader.current_char() == ' ' {
-                indent_level += 1;
-            } else if self.reader.current_char() == '\t' {
-                indent_level += 8; // Tab equals 8 spaces
+        // Skip whitespace and count indentation, a form feed resets it
+        loop {
+            match self.reader.current_char() {
+                ' ' => {
+                    width += 1;
+                    alt_width += 1;
+                }
+                '\t' => {
+                    width = (width / 8 + 1) * 8;
+                    alt_width += 1;
+                }
+                '\x0c' => {
+                    width = 0;
+                    alt_width = 0;
+                }
+                _ => break,
             }
             self.reader.bump();
         }
@@ -472,26 +587,58 @@ impl<'a> PyLexer<'a> {
         // If we hit a comment or newline, ignore this line
         if matches!(self.reader.current_char(), '#' | '\n' | '\r') || self.reader.is_eof() {
             self.indent_info.at_line_start = false;
-            return self.lex();
+            return self.lex_line_start_rest();
         }
 
         self.indent_info.at_line_start = false;
 
-        let current_indent = *self.indent_info.indent_stack.last().unwrap();
+        let current = *self.indent_info.indent_stack.last().unwrap();
 
-        if indent_level > current_indent {
-            // Increased indentation
-            self.indent_info.indent_stack.push(indent_level);
-            PyTokenKind::TkIndent
-        } else if indent_level < current_indent {
+        if width > current.width {
+            // Increased indentation, unless it is a dedent with tabs as one
+            // column, then the line stays in the current block
+            let ambiguous = alt_width <= current.alt_width;
+            if ambiguous {
+                self.tab_error();
+            }
+            self.indent_info.indent_stack.push(IndentLevel {
+                width,
+                alt_width,
+                recovered: ambiguous,
+            });
+            if ambiguous {
+                self.lex_line_start_rest()
+            } else {
+                PyTokenKind::TkIndent
+            }
+        } else if width < current.width {
             // Decreased indentation - may need multiple DEDENTs
             let mut dedent_count = 0;
-            while let Some(&stack_indent) = self.indent_info.indent_stack.last() {
-                if stack_indent <= indent_level {
+            while let Some(&level) = self.indent_info.indent_stack.last() {
+                if level.width <= width {
                     break;
                 }
                 self.indent_info.indent_stack.pop();
-                dedent_count += 1;
+                if !level.recovered {
+                    dedent_count += 1;
+                }
+            }
+
+            let outer = *self.indent_info.indent_stack.last().unwrap();
+            if outer.width != width {
+                // the line stays in the outer block, the lines after it
+                // with the same indentation join it without another error
+                self.push_error(PyParseError::indentation_error_from(
+                    &t!("unindent does not match any outer indentation level"),
+                    self.reader.current_range(),
+                ));
+                self.indent_info.indent_stack.push(IndentLevel {
+                    width,
+                    alt_width,
+                    recovered: true,
+                });
+            } else if outer.alt_width != alt_width {
+                self.tab_error();
             }
 
             if dedent_count > 0 {
@@ -499,15 +646,42 @@ impl<'a> PyLexer<'a> {
                 PyTokenKind::TkDedent
             } else {
                 // Continue with normal lexing
-                self.lex()
+                self.lex_line_start_rest()
             }
         } else {
             // Same indentation level
+            if alt_width != current.alt_width {
+                self.tab_error();
+            }
+            self.lex_line_start_rest()
+        }
+    }
+
+    fn tab_error(&mut self) {
+        self.push_error(PyParseError::tab_error_from(
+            &t!("inconsistent use of tabs and spaces in indentation"),
+            self.reader.current_range(),
+        ));
+    }
+
+    // 行首的缩进空白必须保留为 token, 否则语法树会丢失这部分文本
+    fn lex_line_start_rest(&mut self) -> PyTokenKind {
+        if self.reader.current_range().length > 0 {
+            PyTokenKind::TkWhitespace
+        } else {
             self.lex()
         }
     }
 
     fn lex_new_line(&mut self) -> PyTokenKind {
+        self.eat_new_line();
+
+        // the lines joined inside brackets have no indentation
+        self.indent_info.at_line_start = self.bracket_depth == 0;
+        PyTokenKind::TkNewline
+    }
+
+    fn eat_new_line(&mut self) {
         match self.reader.current_char() {
             // support \n or \n\r
             '\n' => {
@@ -525,9 +699,35 @@ impl<'a> PyLexer<'a> {
             }
             _ => {}
         }
+    }
 
-        self.indent_info.at_line_start = true;
-        PyTokenKind::TkNewline
+    /// A backslash joins the next line to this one, the newline belongs to
+    /// its token so the next line has no indentation. Only a newline may
+    /// follow it, trailing whitespace is still taken as a continuation
+    fn lex_line_continuation(&mut self) -> PyTokenKind {
+        self.reader.bump(); // consume backslash
+        let rest = self.reader.tail_text();
+        let line_rest = rest.trim_start_matches([' ', '\t', '\x0c']);
+        match line_rest.chars().next() {
+            Some('\n' | '\r') => {
+                if line_rest.len() < rest.len() {
+                    self.error(|| t!("unexpected character after line continuation character"));
+                }
+                self.reader
+                    .eat_while(|ch| matches!(ch, ' ' | '\t' | '\x0c'));
+                self.eat_new_line();
+                if self.reader.is_eof() {
+                    self.error(|| t!("unexpected EOF while parsing"));
+                }
+            }
+            None => {
+                self.reader
+                    .eat_while(|ch| matches!(ch, ' ' | '\t' | '\x0c'));
+                self.error(|| t!("unexpected EOF while parsing"));
+            }
+            Some(_) => self.error(|| t!("unexpected character after line continuation character")),
+        }
+        PyTokenKind::TkLineContinuation
     }
 
     fn lex_white_space(&mut self) -> PyTokenKind {
@@ -568,8 +768,9 @@ impl<'a> PyLexer<'a> {
                 if is_fstring {
                     match ch {
                         '{' => {
-                            // Check for escaped brace {{
-                            if self.reader.next_char() != '{' {
+                            // Check for escaped brace {{, which is no escape
+                            // inside a replacement field
+                            if self.reader.next_char() != '{' || brace_depth > 0 {
                                 brace_depth += 1;
                             } else {
                                 self.reader.bump(); // skip first {
@@ -578,8 +779,9 @@ impl<'a> PyLexer<'a> {
                             }
                         }
                         '}' => {
-                            // Check for escaped brace }}
-                            if self.reader.next_char() != '}' {
+                            // Check for escaped brace }}, `{b}}` closes two
+                            // replacement fields
+                            if self.reader.next_char() != '}' || brace_depth > 0 {
                                 if brace_depth > 0 {
                                     brace_depth -= 1;
                                 }
@@ -627,8 +829,9 @@ impl<'a> PyLexer<'a> {
                     // Handle escape sequences
                     match self.reader.current_char() {
                         '\n' | '\r' => {
-                            // Line continuation
-                            self.lex_new_line();
+                            // Line continuation, the string goes on in the
+                            // next line which has no indentation
+                            self.eat_new_line();
                         }
                         _ => {
                             self.reader.bump(); // consume escaped character
@@ -640,8 +843,9 @@ impl<'a> PyLexer<'a> {
                 if is_fstring {
                     match ch {
                         '{' => {
-                            // Check for escaped brace {{
-                            if self.reader.next_char() != '{' {
+                            // Check for escaped brace {{, which is no escape
+                            // inside a replacement field
+                            if self.reader.next_char() != '{' || brace_depth > 0 {
                                 brace_depth += 1;
                             } else {
                                 self.reader.bump(); // skip first {
@@ -650,8 +854,9 @@ impl<'a> PyLexer<'a> {
                             }
                         }
                         '}' => {
-                            // Check for escaped brace }}
-                            if self.reader.next_char() != '}' {
+                            // Check for escaped brace }}, `{b}}` closes two
+                            // replacement fields
+                            if self.reader.next_char() != '}' || brace_depth > 0 {
                                 if brace_depth > 0 {
                                     brace_depth -= 1;
                                 }
@@ -837,4 +1042,27 @@ impl<'a> PyLexer<'a> {
             ))
         }
     }
+
+    fn push_error(&mut self, error: PyParseError) {
+        if let Some(errors) = &mut self.errors {
+            errors.push(error);
+        }
+    }
+}
+
+/// Whether a line asks for help with a trailing `?`: a dotted name, which may
+/// hold `*` wildcards, followed by `?` or `??`
+fn is_help_line(text: &str) -> bool {
+    let line = text
+        .split(['\n', '\r'])
+        .next()
+        .unwrap_or_default()
+        .trim_end();
+    let Some(name) = line.strip_suffix("??").or_else(|| line.strip_suffix('?')) else {
+        return false;
+    };
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| is_name_continue(ch) || ch == '.' || ch == '*')
 }
@@ -0,0 +1,38 @@
+#!/usr/bin/env python3
+"""Module docstring."""
+import os, sys
+from typing import (
+    Any,  # trailing
+    Optional,
+)
+
+
+@decorator(1, key="v")
+class Point:
+    x: int = 0x1F
+    y: float = 1_000.5e-3
+
+    def move(self, dx, dy=2j, *args, **kwargs) -> "Point":
+        if (n := len(args)) >= 2 and not dx:
+            return self
+        total = dx + \
+            dy
+        self.x += total; self.y -= 1
+        return self
+
+
+async def main() -> None:
+    data = {"a": [1, 2], 'b': (3,)}
+    items = [x ** 2 for x in range(10) if x % 3 != 0]
+    mask = ~1 << 2 >> 1 | 3 & 4 ^ 5
+    ratio = 7 // 2 / 1.5 @ m
+    raw, bytes_ = rb"\d+", b'\x00'
+    text = """multi
+line""" + '''other'''
+    await run(...)
+    match data:
+        case {"a": [_, *rest]}:
+            pass
+        case _:
+            pass
+    lambda: None
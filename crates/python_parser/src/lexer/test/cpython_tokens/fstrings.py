@@ -0,0 +1,14 @@
+a = f"{x:>10}"
+b = f"{x:{w}.{p}f}"
+c = f"{x:{w}}"
+d = f"{x=}" f"{x = !r:>5}"
+e = f"" f"{x}" F'{x!r}'
+g = f"{f'{y}'}"
+h = rf"\d{x}" f"""line
+next {x}
+end"""
+i = f"a{{b}}c" f"{{" f"}}{x}}}"
+j = f"{'a' if x else 'b'}" f"{d['k']}" f"{a:{b}{c}}" f"{ x }"
+k = f"{x:%H:%M}" f"{x!s:}"
+m = f"\N{BULLET} {x}\n" f"{x != y}" f"{ {1: 2}[1] }"
+n = f"café {naïve}"
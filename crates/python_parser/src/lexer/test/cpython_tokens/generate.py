@@ -0,0 +1,32 @@
+"""Write the `tokenize` dump of each corpus file next to it, as `<name>.tokens`.
+
+The dumps are checked in and the lexer tests diff the CPython compatible
+tokens against them. Run it with Python 3.12 or later after changing the
+corpus:
+
+    python3.12 generate.py
+"""
+
+import json
+import pathlib
+import sys
+import tokenize
+
+if sys.version_info < (3, 12):
+    sys.exit("the dumps need the f-string tokens of Python 3.12")
+
+for path in sorted(pathlib.Path(__file__).parent.glob("*.py")):
+    if path.name == "generate.py":
+        continue
+    lines = []
+    with path.open("rb") as file:
+        for token in tokenize.tokenize(file.readline):
+            lines.append(
+                "{} {},{}-{},{} {}".format(
+                    tokenize.tok_name[token.type],
+                    *token.start,
+                    *token.end,
+                    json.dumps(token.string, ensure_ascii=False),
+                )
+            )
+    path.with_suffix(".tokens").write_text("\n".join(lines) + "\n", encoding="utf-8")
@@ -0,0 +1,26 @@
+if a:
+    b = 1
+
+    # aligned
+  # shallow
+        # deep
+    if c:
+        pass
+    elif d:
+        e = [
+  1,
+        2]
+
+
+    f
+   
+g
+class C:
+  def m(self):
+      pass
+h
+def t():
+	if x:
+		return \
+			1
+	x
@@ -0,0 +1,3 @@
+# -*- coding: latin-1 -*-
+name = "Jos"  # caf
+value = name
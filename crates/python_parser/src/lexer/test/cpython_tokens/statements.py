@@ -0,0 +1,20 @@
+type Alias[T] = list[T]
+global counter
+try:
+    del items[0], other
+except* (ValueError, TypeError) as error:  # grouped
+    raise RuntimeError("x") from error
+finally:
+    pass
+with open("a") as a, open("b") as b: pass
+numbers = 0o17, 0b1010, 0XFF, 1E5, .5, 5., 3_000j
+message = ("first "
+           "second"  # between
+           'third')
+first, *rest = yield from gen()
+f = lambda x=1, /, *, y: x if y else -x
+print(*args, sep='', **{"end": "\n"})
+assert x is not None and y not in z, "message"
+for i in range(3): continue
+else:
+    while True: break
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        CPythonTokenKind, LexerConfig, PyLanguageLevel, PyVersionNumber, tokenize_like_cpython,
+    };
+
+    /// The corpus of source files with the `tokenize` dumps `generate.py`
+    /// wrote for them
+    fn corpus_files() -> Vec<PathBuf> {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/lexer/test/cpython_tokens");
+        let mut files: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension().is_some_and(|it| it == "py")
+                    && path.file_name().is_some_and(|it| it != "generate.py")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn dump(bytes: &[u8], config: LexerConfig) -> String {
+        tokenize_like_cpython(bytes, config)
+            .iter()
+            .map(|token| format!("{}\n", token))
+            .collect()
+    }
+
+    /// The first line where the dumps differ, with the lines before it
+    fn first_difference(expected: &str, actual: &str) -> Option<String> {
+        let expected: Vec<_> = expected.lines().collect();
+        let actual: Vec<_> = actual.lines().collect();
+        let index = (0..expected.len().max(actual.len()))
+            .find(|index| expected.get(*index) != actual.get(*index))?;
+        let context = expected[index.saturating_sub(3)..index.min(expected.len())]
+            .iter()
+            .map(|line| format!("    {}\n", line))
+            .collect::<String>();
+        Some(format!(
+            "line {}:\n{}  - {}\n  + {}",
+            index + 1,
+            context,
+            expected.get(index).unwrap_or(&"<end>"),
+            actual.get(index).unwrap_or(&"<end>")
+        ))
+    }
+
+    #[test]
+    fn test_cpython_token_corpus() {
+        let files = corpus_files();
+        assert!(!files.is_empty());
+        let mut failures = Vec::new();
+        for path in files {
+            let expected = fs::read_to_string(path.with_extension("tokens")).unwrap();
+            let actual = dump(&fs::read(&path).unwrap(), LexerConfig::default());
+            if let Some(difference) = first_difference(&expected, &actual) {
+                failures.push(format!("{}, {}", path.display(), difference));
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_fstring_is_one_string_before_312() {
+        let config = LexerConfig {
+            language_level: PyLanguageLevel::Py3(PyVersionNumber {
+                major: 3,
+                minor: 11,
+                patch: 0,
+            }),
+            ..Default::default()
+        };
+        let tokens = tokenize_like_cpython(b"s = f\"{x!r:>{w}}\"\n", config);
+        let kinds: Vec<_> = tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                CPythonTokenKind::Encoding,
+                CPythonTokenKind::Name,
+                CPythonTokenKind::Op,
+                CPythonTokenKind::String,
+                CPythonTokenKind::Newline,
+                CPythonTokenKind::EndMarker,
+            ]
+        );
+        assert_eq!(
+            tokens[3].to_string(),
+            "STRING 1,4-1,17 \"f\\\"{x!r:>{w}}\\\"\""
+        );
+    }
+}
@@ -38,8 +38,10 @@ mod lexer_empty_lines_test {
             .position(|(kind, _)| *kind == PyTokenKind::TkColon)
             .unwrap();
         println!("\nTokens around colon (index {}):", colon_idx);
-        for i in (colon_idx.saturating_sub(2))..=(colon_idx + 5).min(tokens.len() - 1) {
-            println!("  {}: {:?} = {:?}", i, tokens[i].0, tokens[i].1);
+        let start = colon_idx.saturating_sub(2);
+        let end = (colon_idx + 5).min(tokens.len() - 1);
+        for (i, (kind, text)) in tokens.iter().enumerate().take(end + 1).skip(start) {
+            println!("  {}: {:?} = {:?}", i, kind, text);
         }
     }
 
@@ -61,8 +63,10 @@ mod lexer_empty_lines_test {
             .position(|(kind, _)| *kind == PyTokenKind::TkColon)
             .unwrap();
         println!("\nTokens around colon (index {}):", colon_idx);
-        for i in (colon_idx.saturating_sub(2))..=(colon_idx + 5).min(tokens.len() - 1) {
-            println!("  {}: {:?} = {:?}", i, tokens[i].0, tokens[i].1);
+        let start = colon_idx.saturating_sub(2);
+        let end = (colon_idx + 5).min(tokens.len() - 1);
+        for (i, (kind, text)) in tokens.iter().enumerate().take(end + 1).skip(start) {
+            println!("  {}: {:?} = {:?}", i, kind, text);
         }
     }
 }
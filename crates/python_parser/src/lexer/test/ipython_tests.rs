@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ParserConfig, PyAstNode, PyParser,
+        kind::{PySyntaxKind, PyTokenKind},
+        lexer::{lexer_config::LexerConfig, py_lexer::PyLexer},
+        text::Reader,
+    };
+
+    /// The tokens other than the whitespace, with their text
+    fn tokenize(input: &str, ipython_magics: bool) -> Vec<(PyTokenKind, &str)> {
+        let config = LexerConfig {
+            ipython_magics,
+            ..Default::default()
+        };
+        let mut errors = Vec::new();
+        let mut lexer = PyLexer::new(Reader::new(input), config, Some(&mut errors));
+        lexer
+            .tokenize()
+            .into_iter()
+            .filter(|token| !matches!(token.kind, PyTokenKind::TkWhitespace | PyTokenKind::TkEof))
+            .map(|token| {
+                let start = token.range.start_offset;
+                (token.kind, &input[start..start + token.range.length])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_line_magic() {
+        assert_eq!(
+            tokenize("%timeit x = [i for i in range(10)]\ny = 1", true)[..2],
+            [
+                (
+                    PyTokenKind::TkLineMagic,
+                    "%timeit x = [i for i in range(10)]"
+                ),
+                (PyTokenKind::TkNewline, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cell_magic() {
+        assert_eq!(
+            tokenize("%%bash\necho hi\nls -la\n", true),
+            [(PyTokenKind::TkCellMagic, "%%bash\necho hi\nls -la\n")]
+        );
+    }
+
+    #[test]
+    fn test_shell_escape() {
+        assert_eq!(
+            tokenize("!ls -la\n", true)[0],
+            (PyTokenKind::TkShellEscape, "!ls -la")
+        );
+        let tokens = tokenize("files = !ls *.py\n", true);
+        assert_eq!(tokens[2], (PyTokenKind::TkShellEscape, "!ls *.py"));
+        let tokens = tokenize("names = %who_ls\n", true);
+        assert_eq!(tokens[2], (PyTokenKind::TkLineMagic, "%who_ls"));
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(
+            tokenize("os.path?\n", true)[0],
+            (PyTokenKind::TkHelp, "os.path?")
+        );
+        assert_eq!(
+            tokenize("np.*load*??\n", true)[0],
+            (PyTokenKind::TkHelp, "np.*load*??")
+        );
+        assert_eq!(
+            tokenize("?print\n", true)[0],
+            (PyTokenKind::TkHelp, "?print")
+        );
+    }
+
+    #[test]
+    fn test_operators_are_not_magics() {
+        let tokens = tokenize("x = 7 % 2\ny = x != 1\nz = (a,\n   !b)\n", true);
+        assert!(tokens.iter().all(|(kind, _)| !kind.is_ipython_magic()));
+        assert!(tokens.contains(&(PyTokenKind::TkMod, "%")));
+        assert!(tokens.contains(&(PyTokenKind::TkNe, "!=")));
+    }
+
+    #[test]
+    fn test_default_mode_has_no_magics() {
+        let tokens = tokenize("%time f()\n!ls\nobj?\n", false);
+        assert!(tokens.iter().all(|(kind, _)| !kind.is_ipython_magic()));
+    }
+
+    #[test]
+    fn test_parse_ipython_script() {
+        let code = "import os\n%matplotlib inline\nfiles = !ls\nfor f in files:\n    %time print(f)\nos.path?\n";
+        let config = ParserConfig::default().with_ipython_magics(true);
+        let tree = PyParser::parse(code, config, None);
+        assert!(tree.get_errors().is_empty(), "{:?}", tree.get_errors());
+        let root = tree.get_module_node();
+        let magic_stmts = root
+            .syntax()
+            .descendants()
+            .filter(|node| node.kind() == PySyntaxKind::MagicStmt.into())
+            .count();
+        let magic_exprs = root
+            .syntax()
+            .descendants()
+            .filter(|node| node.kind() == PySyntaxKind::MagicExpr.into())
+            .count();
+        assert_eq!((magic_stmts, magic_exprs), (3, 1));
+
+        let tree = PyParser::parse(code, ParserConfig::default(), None);
+        assert!(!tree.get_errors().is_empty());
+    }
+}
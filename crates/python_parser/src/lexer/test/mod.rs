@@ -1,3 +1,5 @@
+mod cpython_tokens_tests;
 mod empty_lines_test;
+mod ipython_tests;
 mod string_tests;
 mod test_lexer;
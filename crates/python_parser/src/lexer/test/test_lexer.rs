@@ -129,4 +129,17 @@ rb"raw_bytes"
         assert_eq!(string_tokens[3].0, PyTokenKind::TkFString);
         assert_eq!(string_tokens[4].0, PyTokenKind::TkRawBytesString);
     }
+
+    #[test]
+    fn test_no_indentation_inside_brackets() {
+        let tokens = test_tokenize("def f():\n    x = (1 +\n  2)\n    return x\ny = f()\n");
+        let kinds: Vec<_> = tokens
+            .iter()
+            .map(|(kind, _)| *kind)
+            .filter(|kind| matches!(kind, PyTokenKind::TkIndent | PyTokenKind::TkDedent))
+            .collect();
+        // the line `  2)` neither dedents nor opens a block
+        assert_eq!(kinds, vec![PyTokenKind::TkIndent, PyTokenKind::TkDedent]);
+        assert!(tokens.contains(&(PyTokenKind::TkWhitespace, "  ".to_string())));
+    }
 }
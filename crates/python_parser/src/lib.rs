@@ -7,12 +7,15 @@ mod syntax;
 mod text;
 
 pub use kind::*;
-pub use lexer::{FStringLexer, FStringToken, LexerConfig, LexerState, PyLexer, PyTokenData};
+pub use lexer::{
+    CPythonToken, CPythonTokenKind, FStringLexer, FStringToken, LexerConfig, LexerState, PyLexer,
+    PyTokenData, tokenize_like_cpython,
+};
 pub use parser::{ParserConfig, PyParser};
 pub use parser_error::{PyParseError, PyParseErrorKind};
 pub use syntax::*;
 pub use text::LineIndex;
-pub use text::{Reader, SourceRange};
+pub use text::{DecodedSource, Reader, SourceEncoding, SourceRange};
 
 #[macro_use]
 extern crate rust_i18n;
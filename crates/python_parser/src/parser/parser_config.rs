@@ -12,6 +12,7 @@ impl ParserConfig {
             level,
             lexer_config: LexerConfig {
                 language_level: level,
+                ..Default::default()
             },
         }
     }
@@ -20,11 +21,18 @@ impl ParserConfig {
         self.lexer_config
     }
 
+    /// Accept the IPython syntax of `.ipy` scripts and notebook cells
+    pub fn with_ipython_magics(mut self, enable: bool) -> Self {
+        self.lexer_config.ipython_magics = enable;
+        self
+    }
+
     pub fn with_level(level: PyLanguageLevel) -> Self {
         Self {
             level,
             lexer_config: LexerConfig {
                 language_level: level,
+                ..Default::default()
             },
         }
     }
@@ -36,6 +44,7 @@ impl Default for ParserConfig {
             level: PyLanguageLevel::default(),
             lexer_config: LexerConfig {
                 language_level: PyLanguageLevel::default(),
+                ..Default::default()
             },
         }
     }
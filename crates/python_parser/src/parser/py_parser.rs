@@ -1,16 +1,16 @@
-use rowan::NodeCache;
+use rowan::{NodeCache, TextSize};
 
 use super::{
     marker::{MarkEvent, MarkerEventContainer},
     parser_config::ParserConfig,
 };
 use crate::{
-    grammar::{parse_fstring_inner_expr, parse_module_suite},
-    kind::PyTokenKind,
+    grammar::{parse_fstring_inner_expr, parse_module_suite, parse_type_comment_inner},
+    kind::{PySyntaxKind, PyTokenKind},
     lexer::{PyLexer, PyTokenData},
-    parser_error::PyParseError,
+    parser_error::{PyParseError, PyParseErrorKind},
     syntax::PySyntaxTree,
-    text::SourceRange,
+    text::{DecodedSource, LineIndex, SourceRange},
 };
 use crate::{syntax::PyTreeBuilder, text::Reader};
 
@@ -27,6 +27,14 @@ pub struct PyParser<'a> {
     paren_level: usize,   // ()
     bracket_level: usize, // []
     brace_level: usize,   // {}
+    /// The brackets not closed yet, innermost last
+    open_brackets: Vec<PyTokenData>,
+    /// Whether the tokens are the whole file, whose end inside a bracket is
+    /// reported at the bracket
+    whole_file: bool,
+    // 行尾的 `# type:` 注释先挂起, 由赋值、for、with 和 def 语句取走
+    keep_type_comments: bool,
+    pending_type_comment: Option<SourceRange>,
 }
 
 impl MarkerEventContainer for PyParser<'_> {
@@ -43,7 +51,7 @@ impl MarkerEventContainer for PyParser<'_> {
     }
 
     fn get_events(&mut self) -> &mut Vec<MarkEvent> {
-        &mut self.events
+        self.events
     }
 }
 
@@ -54,12 +62,37 @@ impl<'a> PyParser<'a> {
         config: ParserConfig,
         node_cache: Option<&'a mut NodeCache>,
     ) -> PySyntaxTree {
-        let mut errors: Vec<PyParseError> = Vec::new();
+        Self::parse_with_errors(text, config, node_cache, Vec::new())
+    }
+
+    /// Parse the bytes of a source file, decoded as its BOM and coding
+    /// declaration say. The problems of the encoding are errors of the tree,
+    /// and the decoded source maps the offsets of the tree to the file.
+    pub fn parse_bytes(
+        bytes: &[u8],
+        config: ParserConfig,
+        node_cache: Option<&mut NodeCache>,
+    ) -> (PySyntaxTree, DecodedSource) {
+        let source = DecodedSource::decode(bytes);
+        let tree =
+            PyParser::parse_with_errors(&source.text, config, node_cache, source.errors.clone());
+        (tree, source)
+    }
+
+    /// Parse a text with the errors found before parsing it, such as the
+    /// problems of decoding it
+    pub fn parse_with_errors(
+        text: &'a str,
+        config: ParserConfig,
+        node_cache: Option<&'a mut NodeCache>,
+        mut errors: Vec<PyParseError>,
+    ) -> PySyntaxTree {
         let tokens = {
             let mut lexer =
                 PyLexer::new(Reader::new(text), config.lexer_config(), Some(&mut errors));
             lexer.tokenize()
         };
+        let parser_errors_start = errors.len();
         let mut events: Vec<MarkEvent> = Vec::new();
 
         let mut parser = PyParser {
@@ -74,9 +107,15 @@ impl<'a> PyParser<'a> {
             paren_level: 0,
             bracket_level: 0,
             brace_level: 0,
+            open_brackets: Vec::new(),
+            whole_file: true,
+            keep_type_comments: true,
+            pending_type_comment: None,
         };
 
         parse_module_suite(&mut parser);
+        parser.drop_follow_on_errors(parser_errors_start);
+        parser.report_unclosed_bracket();
         let errors = parser.get_errors();
         let root = {
             let mut builder = PyTreeBuilder::new(text, events, node_cache);
@@ -92,6 +131,42 @@ impl<'a> PyParser<'a> {
         config: ParserConfig,
         events: &mut Vec<MarkEvent>,
         errors: &'a mut Vec<PyParseError>,
+    ) {
+        Self::parse_sub_range(
+            text,
+            range,
+            config,
+            events,
+            errors,
+            parse_fstring_inner_expr,
+        );
+    }
+
+    /// Parse the text after `# type:` of a type comment
+    pub fn parse_type_comment(
+        text: &'a str,
+        range: SourceRange,
+        config: ParserConfig,
+        events: &mut Vec<MarkEvent>,
+        errors: &'a mut Vec<PyParseError>,
+    ) {
+        Self::parse_sub_range(
+            text,
+            range,
+            config,
+            events,
+            errors,
+            parse_type_comment_inner,
+        );
+    }
+
+    fn parse_sub_range(
+        text: &'a str,
+        range: SourceRange,
+        config: ParserConfig,
+        events: &mut Vec<MarkEvent>,
+        errors: &'a mut Vec<PyParseError>,
+        parse: fn(&mut PyParser),
     ) {
         let tokens = {
             let mut lexer = PyLexer::new(
@@ -114,20 +189,39 @@ impl<'a> PyParser<'a> {
             paren_level: 0,
             bracket_level: 0,
             brace_level: 0,
+            open_brackets: Vec::new(),
+            whole_file: false,
+            keep_type_comments: false,
+            pending_type_comment: None,
         };
 
-        parse_fstring_inner_expr(&mut parser);
+        let start = parser.events.len();
+        parse(&mut parser);
+
+        // 出错时未完成的节点在子范围的末尾关闭, 以免吞掉后面的源码
+        let open = parser.events[start..]
+            .iter()
+            .fold(0usize, |open, event| match event {
+                MarkEvent::NodeStart { kind, .. } if *kind != PySyntaxKind::None => open + 1,
+                MarkEvent::NodeEnd => open.saturating_sub(1),
+                _ => open,
+            });
+        for _ in 0..open {
+            parser.events.push(MarkEvent::NodeEnd);
+        }
     }
 
     pub fn init(&mut self) {
-        if self.tokens.is_empty() {
+        // 文件开头的 trivia (注释, 空白, shebang) 同样需要保留在语法树中
+        let mut next_index = 0;
+        self.skip_trivia(&mut next_index);
+        self.parse_trivia_tokens(0, next_index);
+        self.token_index = next_index;
+
+        if self.token_index >= self.tokens.len() {
             self.current_token = PyTokenKind::TkEof;
         } else {
-            self.current_token = self.tokens[0].kind;
-        }
-
-        if is_trivia_kind(self.current_token) {
-            self.bump();
+            self.current_token = self.tokens[self.token_index].kind;
         }
     }
 
@@ -156,6 +250,8 @@ impl<'a> PyParser<'a> {
     }
 
     pub fn skip_bump(&mut self) {
+        self.flush_type_comment();
+        self.track_bracket();
         let start_trivia = self.token_index + 1;
         let mut next_index = start_trivia;
         self.skip_trivia(&mut next_index);
@@ -209,6 +305,8 @@ impl<'a> PyParser<'a> {
     }
 
     pub fn bump(&mut self) {
+        self.flush_type_comment();
+        self.track_bracket();
         if !is_invalid_kind(self.current_token) && self.token_index < self.tokens.len() {
             let token = &self.tokens[self.token_index];
             self.events.push(MarkEvent::EatToken {
@@ -247,8 +345,11 @@ impl<'a> PyParser<'a> {
             return;
         }
 
+        // the lines inside brackets are joined, their newlines are trivia
         let mut kind = self.tokens[*index].kind;
-        while is_trivia_kind(kind) {
+        while is_trivia_kind(kind)
+            || (kind == PyTokenKind::TkNewline && self.in_parentheses_context())
+        {
             *index += 1;
             if *index >= self.tokens.len() {
                 break;
@@ -264,6 +365,15 @@ impl<'a> PyParser<'a> {
         for i in start..end {
             let token = &self.tokens[i];
             match token.kind {
+                PyTokenKind::TkComment
+                    if self.keep_type_comments
+                        && i + 1 == end
+                        && is_type_comment(
+                            &self.text[token.range.start_offset..token.range.end_offset()],
+                        ) =>
+                {
+                    self.pending_type_comment = Some(token.range);
+                }
                 PyTokenKind::TkComment => {
                     // For Python, comments are simple - just consume them
                     self.events.push(MarkEvent::EatToken {
@@ -271,7 +381,10 @@ impl<'a> PyParser<'a> {
                         range: token.range,
                     });
                 }
-                PyTokenKind::TkWhitespace | PyTokenKind::TkShebang => {
+                PyTokenKind::TkWhitespace
+                | PyTokenKind::TkShebang
+                | PyTokenKind::TkLineContinuation
+                | PyTokenKind::TkNewline => {
                     // Simple trivia - just consume
                     self.events.push(MarkEvent::EatToken {
                         kind: token.kind,
@@ -287,10 +400,96 @@ impl<'a> PyParser<'a> {
         }
     }
 
+    /// Take the type comment which ends the current line, the statements
+    /// which can have one take it before consuming their newline
+    pub fn take_type_comment(&mut self) -> Option<SourceRange> {
+        self.pending_type_comment.take()
+    }
+
+    /// A type comment nobody took is an ordinary comment
+    pub fn flush_type_comment(&mut self) {
+        if let Some(range) = self.pending_type_comment.take() {
+            self.events.push(MarkEvent::EatToken {
+                kind: PyTokenKind::TkComment,
+                range,
+            });
+        }
+    }
+
     pub fn push_error(&mut self, err: PyParseError) {
+        // at the end of the file inside a bracket or after a backslash the
+        // error follows from the bracket left open or the backslash, which
+        // is reported instead
+        if self.whole_file
+            && self.current_token == PyTokenKind::TkEof
+            && matches!(
+                err.kind,
+                PyParseErrorKind::SyntaxError | PyParseErrorKind::IndentationError
+            )
+            && (!self.open_brackets.is_empty() || self.ends_with_continuation())
+        {
+            return;
+        }
         self.errors.push(err);
     }
 
+    /// Drop the errors the parser found after a bracket left open at the
+    /// end, they follow from the bracket, which is reported instead. The
+    /// errors of the lexer are kept.
+    fn drop_follow_on_errors(&mut self, parser_errors_start: usize) {
+        let Some(open) = self.open_brackets.first() else {
+            return;
+        };
+        let cutoff = open.range.start_offset;
+        let mut index = 0;
+        self.errors.retain(|error| {
+            let is_follow_on = index >= parser_errors_start
+                && matches!(
+                    error.kind,
+                    PyParseErrorKind::SyntaxError | PyParseErrorKind::IndentationError
+                )
+                && usize::from(error.range.start()) >= cutoff;
+            index += 1;
+            !is_follow_on
+        });
+    }
+
+    /// Whether the file ends with a backslash, which the lexer reports as an
+    /// unexpected end of file
+    fn ends_with_continuation(&self) -> bool {
+        self.tokens
+            .iter()
+            .rev()
+            .find(|token| !matches!(token.kind, PyTokenKind::TkEof | PyTokenKind::TkWhitespace))
+            .is_some_and(|token| token.kind == PyTokenKind::TkLineContinuation)
+    }
+
+    /// Report the innermost bracket left open at the end, like CPython at
+    /// the bracket rather than at the end of the file
+    fn report_unclosed_bracket(&mut self) {
+        let Some(open) = self.open_brackets.last() else {
+            return;
+        };
+        // the end of the file after a backslash is reported already
+        if self.ends_with_continuation() {
+            return;
+        }
+        let range = open.range;
+        let bracket = &self.text[range.start_offset..range.end_offset()];
+        let line = LineIndex::parse(self.text)
+            .get_line(TextSize::new(range.start_offset as u32))
+            .unwrap_or_default()
+            + 1;
+        self.errors.push(PyParseError::syntax_error_from(
+            &t!(
+                "unclosed bracket '%{bracket}' opened at line %{line}",
+                bracket = bracket,
+                line = line
+            ),
+            range,
+        ));
+    }
+
     pub fn has_error(&self) -> bool {
         !self.errors.is_empty()
     }
@@ -314,22 +513,10 @@ impl<'a> PyParser<'a> {
         }
     }
 
-    /// 期待特定token，如果是括号类token则使用smart_bump
+    /// 期待特定token, 括号由 bump 跟踪
     pub fn expect_token(&mut self, expected: PyTokenKind) -> bool {
         if self.current_token() == expected {
-            match expected {
-                PyTokenKind::TkLeftParen
-                | PyTokenKind::TkLeftBracket
-                | PyTokenKind::TkLeftBrace
-                | PyTokenKind::TkRightParen
-                | PyTokenKind::TkRightBracket
-                | PyTokenKind::TkRightBrace => {
-                    self.smart_bump();
-                }
-                _ => {
-                    self.bump();
-                }
-            }
+            self.bump();
             true
         } else {
             false
@@ -364,27 +551,32 @@ impl<'a> PyParser<'a> {
         }
     }
 
-    /// 智能bump：自动处理括号跟踪和上下文相关的换行符跳过
-    pub fn smart_bump(&mut self) {
-        let current = self.current_token();
-
-        // 跟踪括号嵌套
-        match current {
+    /// Track the bracket of the current token before it is consumed, a
+    /// closing bracket closes the innermost open bracket of its kind and
+    /// the ones left open inside it
+    fn track_bracket(&mut self) {
+        let Some(&token) = self.tokens.get(self.token_index) else {
+            return;
+        };
+        match token.kind {
             PyTokenKind::TkLeftParen | PyTokenKind::TkLeftBracket | PyTokenKind::TkLeftBrace => {
-                self.enter_paren_context(current);
+                self.enter_paren_context(token.kind);
+                self.open_brackets.push(token);
             }
             PyTokenKind::TkRightParen | PyTokenKind::TkRightBracket | PyTokenKind::TkRightBrace => {
-                self.exit_paren_context(current);
+                let Some(index) = self
+                    .open_brackets
+                    .iter()
+                    .rposition(|open| closing_bracket(open.kind) == token.kind)
+                else {
+                    return;
+                };
+                for open in self.open_brackets.split_off(index) {
+                    self.exit_paren_context(closing_bracket(open.kind));
+                }
             }
             _ => {}
         }
-
-        self.bump();
-
-        // 在括号内自动跳过换行符
-        if self.in_parentheses_context() && self.current_token() == PyTokenKind::TkNewline {
-            self.bump();
-        }
     }
 
     /// Check if a feature is supported by the current language level and emit warning if not
@@ -456,7 +648,7 @@ impl<'a> PyParser<'a> {
     }
 
     pub fn parse_config(&self) -> ParserConfig {
-        self.parse_config.clone()
+        self.parse_config
     }
 
     pub fn eat_token(&mut self, kind: PyTokenKind, range: SourceRange) {
@@ -467,10 +659,40 @@ impl<'a> PyParser<'a> {
 fn is_trivia_kind(kind: PyTokenKind) -> bool {
     matches!(
         kind,
-        PyTokenKind::TkComment | PyTokenKind::TkWhitespace | PyTokenKind::TkShebang
+        PyTokenKind::TkComment
+            | PyTokenKind::TkWhitespace
+            | PyTokenKind::TkShebang
+            | PyTokenKind::TkLineContinuation
     )
 }
 
+fn closing_bracket(open: PyTokenKind) -> PyTokenKind {
+    match open {
+        PyTokenKind::TkLeftParen => PyTokenKind::TkRightParen,
+        PyTokenKind::TkLeftBracket => PyTokenKind::TkRightBracket,
+        _ => PyTokenKind::TkRightBrace,
+    }
+}
+
+/// `# type: int`, but not `# type: ignore` which only silences errors
+fn is_type_comment(text: &str) -> bool {
+    let Some(content) = text
+        .trim_start_matches('#')
+        .trim_start()
+        .strip_prefix("type:")
+    else {
+        return false;
+    };
+    let content = content.trim();
+    match content.strip_prefix("ignore") {
+        Some(rest) => rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_'),
+        None => !content.is_empty(),
+    }
+}
+
 fn is_invalid_kind(kind: PyTokenKind) -> bool {
     matches!(
         kind,
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use rowan::TextRange;
+
+    use crate::{
+        ParserConfig, PyParseError, PyParseErrorKind, PyParser, PySyntaxKind, PySyntaxTree,
+    };
+
+    fn parse(code: &str) -> PySyntaxTree {
+        PyParser::parse(code, ParserConfig::default(), None)
+    }
+
+    fn errors(code: &str) -> Vec<(PyParseErrorKind, String)> {
+        parse(code)
+            .get_errors()
+            .iter()
+            .map(|PyParseError { kind, message, .. }| (kind.clone(), message.clone()))
+            .collect()
+    }
+
+    /// The texts of the statements of each suite, innermost suites first
+    fn suites(code: &str) -> Vec<Vec<String>> {
+        let tree = parse(code);
+        let mut suites: Vec<_> = tree
+            .get_red_root()
+            .descendants()
+            .filter(|node| node.kind() == PySyntaxKind::Suite.into())
+            .map(|suite| {
+                suite
+                    .children()
+                    .filter(|stat| stat.kind() != PySyntaxKind::Suite.into())
+                    .map(|stat| stat.text().to_string())
+                    .filter(|text| !text.contains(':'))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        suites.reverse();
+        suites
+    }
+
+    #[test]
+    fn test_unexpected_indent() {
+        let code = "x = 1\n    y = 2\n    z = 3\nw = 4\n";
+        assert_eq!(
+            errors(code),
+            vec![(
+                PyParseErrorKind::IndentationError,
+                "unexpected indent".to_string()
+            )]
+        );
+        // the indented lines stay statements of the module
+        assert_eq!(suites(code), vec![vec!["x = 1", "y = 2", "z = 3", "w = 4"]]);
+        let tree = parse(code);
+        assert!(tree.has_syntax_errors());
+        assert_eq!(tree.get_red_root().text().to_string(), code);
+    }
+
+    #[test]
+    fn test_unindent_mismatch() {
+        let code = "def f():\n    if x:\n        a\n      b\n      c\n    d\ne\n";
+        let tree = parse(code);
+        assert_eq!(
+            errors(code),
+            vec![(
+                PyParseErrorKind::IndentationError,
+                "unindent does not match any outer indentation level".to_string()
+            )]
+        );
+        assert_eq!(
+            tree.get_errors()[0].range,
+            TextRange::new(29.into(), 35.into())
+        );
+        // the lines at the unknown level join the outer block
+        assert_eq!(
+            suites(code),
+            vec![vec!["a"], vec!["b", "c", "d"], vec!["e"]]
+        );
+    }
+
+    #[test]
+    fn test_tab_error() {
+        let tab_error = vec![(
+            PyParseErrorKind::TabError,
+            "inconsistent use of tabs and spaces in indentation".to_string(),
+        )];
+        // a tab is 8 columns, but only 1 with tabs as one column
+        assert_eq!(errors("if x:\n    a\n\tb\n"), tab_error);
+        assert_eq!(suites("if x:\n    a\n\tb\n"), vec![vec!["a", "b"], vec![]]);
+        assert_eq!(errors("if x:\n\ta\n        b\n"), tab_error);
+        assert_eq!(errors("if x:\n        a\n\tb\n"), tab_error);
+        // consistent under both sizes
+        assert!(errors("if x:\n\tif y:\n\t    a\n\tb\n").is_empty());
+        assert!(errors("if x:\n  \ta\n  \tb\n").is_empty());
+    }
+
+    #[test]
+    fn test_expected_indented_block() {
+        let code = "if x:\nb\n";
+        assert_eq!(
+            errors(code),
+            vec![(
+                PyParseErrorKind::IndentationError,
+                "expected indented block after ':'".to_string()
+            )]
+        );
+        assert_eq!(suites(code), vec![vec!["b"]]);
+        assert_eq!(errors("if x:\n    a\nelse:\nb\n").len(), 1);
+        assert!(errors("if x: a\nelse: b\n").is_empty());
+    }
+
+    #[test]
+    fn test_form_feed() {
+        // a form feed resets the indentation
+        assert_eq!(
+            errors("if x:\n\ta\n\x0c    b\n"),
+            vec![(
+                PyParseErrorKind::IndentationError,
+                "unindent does not match any outer indentation level".to_string()
+            )]
+        );
+        assert!(errors("\x0cx = 1\n").is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use rowan::TextRange;
+
+    use crate::{ParserConfig, PyAssignStmt, PyAstNode, PyParser, PySyntaxTree, PyTokenKind};
+
+    fn parse(code: &str) -> PySyntaxTree {
+        PyParser::parse(code, ParserConfig::default(), None)
+    }
+
+    fn messages(code: &str) -> Vec<String> {
+        parse(code)
+            .get_errors()
+            .iter()
+            .map(|error| error.message.clone())
+            .collect()
+    }
+
+    fn assign_values(code: &str) -> Vec<String> {
+        let tree = parse(code);
+        assert_eq!(tree.get_red_root().text().to_string(), code);
+        tree.get_red_root()
+            .descendants()
+            .filter_map(PyAssignStmt::cast)
+            .filter_map(|assign| assign.get_value())
+            .map(|value| value.syntax().text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_implicit_joining() {
+        for code in [
+            "x = [\n    1,\n  2]\nif x:\n    y = 1\n",
+            "def f():\n    x = (1 +\n  2)\n    return x\n",
+            "print(a,\n\n      # the second one\n      b)\n",
+            "d = {\n    'a': [1,\n2],\n}\n",
+            "class A(\n    Base,\n):\n    pass\n",
+        ] {
+            assert!(messages(code).is_empty(), "{:?}", code);
+            assert_eq!(parse(code).get_red_root().text().to_string(), code);
+        }
+        assert_eq!(
+            assign_values("x = [\n    1,\n  2]\ny = 3\n"),
+            vec!["[\n    1,\n  2]", "3"]
+        );
+    }
+
+    #[test]
+    fn test_backslash_continuation() {
+        let code = "if x:\n    y = 1 + \\\n  2\n    z = y\n";
+        assert!(messages(code).is_empty());
+        assert_eq!(assign_values(code), vec!["1 + \\\n  2", "y"]);
+        // the backslash and its newline are one trivia token
+        let tree = parse(code);
+        let continuation = tree
+            .get_red_root()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|token| token.kind() == PyTokenKind::TkLineContinuation.into())
+            .unwrap();
+        assert_eq!(continuation.text(), "\\\n");
+
+        // a continuation in a string goes on with the string
+        assert_eq!(
+            assign_values("s = 'ab\\\n  cd' + x\nt = 1\n"),
+            vec!["'ab\\\n  cd' + x", "1"]
+        );
+        assert!(messages("s = 'ab\\\n  cd' + x\nt = 1\n").is_empty());
+    }
+
+    #[test]
+    fn test_backslash_errors() {
+        let unexpected = "unexpected character after line continuation character";
+        // trailing whitespace is still taken as a continuation
+        assert_eq!(messages("x = 1 + \\  \n2\n"), vec![unexpected]);
+        assert_eq!(assign_values("x = 1 + \\  \n2\n"), vec!["1 + \\  \n2"]);
+        assert_eq!(messages("x = 1 \\ # note\ny = 2\n"), vec![unexpected]);
+        assert_eq!(messages("x = 1 \\y\n"), vec![unexpected]);
+        let tree = parse("x = 1 \\ # note\n");
+        assert_eq!(
+            tree.get_errors()[0].range,
+            TextRange::new(6.into(), 7.into())
+        );
+
+        // the statement left unfinished is not reported on top of it
+        for code in [
+            "x = 1 + \\",
+            "x = 1 + \\\n",
+            "x = 1\\   ",
+            "if x:\n    y = \\",
+            "x = (1 + \\",
+        ] {
+            assert_eq!(
+                messages(code),
+                vec!["unexpected EOF while parsing"],
+                "{:?}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_unclosed_bracket() {
+        let code = "x = (1, [2,\n";
+        let tree = parse(code);
+        assert_eq!(
+            messages(code),
+            vec!["unclosed bracket '[' opened at line 1"]
+        );
+        assert_eq!(
+            tree.get_errors()[0].range,
+            TextRange::new(8.into(), 9.into())
+        );
+
+        // the errors after the bracket follow from it and are not reported
+        assert_eq!(
+            messages("import os\n\nprint(os.sep,\n\ndef f():\n    pass\n"),
+            vec!["unclosed bracket '(' opened at line 3"]
+        );
+        assert_eq!(
+            messages("class A(B:\n    pass\n"),
+            vec!["unclosed bracket '(' opened at line 1"]
+        );
+        // the errors before it are
+        assert_eq!(
+            messages("def\nf(a,\n"),
+            vec![
+                "expected function name after 'def'",
+                "unclosed bracket '(' opened at line 2"
+            ]
+        );
+        // a closing bracket closes the brackets left open inside it
+        assert!(
+            messages("f(a[1)\ny = 2\n")
+                .iter()
+                .all(|message| !message.starts_with("unclosed"))
+        );
+    }
+}
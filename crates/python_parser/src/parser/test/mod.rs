@@ -1,5 +1,8 @@
 mod fstring_test;
+mod indentation_tests;
+mod line_continuation_tests;
 mod multi_empty_lines_tests;
+mod source_encoding_tests;
 
 #[cfg(test)]
 use crate::{ParserConfig, PyParser};
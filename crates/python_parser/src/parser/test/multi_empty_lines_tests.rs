@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod multi_empty_lines_tests {
     use crate::{ParserConfig, PyParser, parser::test::print_ast_code};
 
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use crate::{DecodedSource, ParserConfig, PyParser};
+
+    fn parse_bytes(bytes: &[u8]) -> (crate::PySyntaxTree, DecodedSource) {
+        PyParser::parse_bytes(bytes, ParserConfig::default(), None)
+    }
+
+    #[test]
+    fn test_utf8_with_bom() {
+        let (tree, source) = parse_bytes(b"\xEF\xBB\xBFname = 'caf\xC3\xA9'\n");
+        assert!(tree.get_errors().is_empty());
+        assert!(source.has_bom);
+        assert_eq!(source.get_encoding_name(), "UTF-8");
+        assert_eq!(source.text, "name = 'café'\n");
+        assert_eq!(tree.get_red_root().text().to_string(), source.text);
+        // the offsets of the text are after the BOM in the file
+        assert_eq!(source.to_text_offset(3), 0);
+        assert_eq!(source.to_text_offset(18), source.text.len());
+    }
+
+    #[test]
+    fn test_coding_cookie() {
+        let bytes = b"#!/usr/bin/env python\n# -*- coding: latin-1 -*-\ns = '\xE9t\xE9'\nx = 1\n";
+        let (tree, source) = parse_bytes(bytes);
+        assert!(tree.get_errors().is_empty());
+        assert_eq!(source.get_encoding_name(), "ISO-8859-1");
+        assert!(source.text.contains("s = 'été'"));
+        // each `é` is one byte in the file and two in the text
+        let x = bytes.iter().position(|b| *b == b'x').unwrap();
+        assert_eq!(&source.text[source.to_text_offset(x)..], "x = 1\n");
+
+        let (_, source) = parse_bytes(b"# vim: set fileencoding=utf_8 :\n");
+        assert_eq!(source.get_encoding_name(), "UTF-8");
+        let (_, source) = parse_bytes(b"\n# coding=cp1252\n");
+        assert_eq!(source.get_encoding_name(), "windows-1252");
+        // Python's latin-1 is ISO-8859-1, not the windows-1252 of WHATWG
+        let (_, source) = parse_bytes(b"# coding: iso8859_1\ns = '\x80'\n");
+        assert_eq!(source.get_encoding_name(), "ISO-8859-1");
+        assert!(source.text.contains("s = '\u{80}'"));
+        let (_, source) = parse_bytes(b"# coding: cp1252\ns = '\x80'\n");
+        assert!(source.text.contains("s = '\u{20AC}'"));
+        // only the first two lines, and the second one after a comment
+        let (_, source) = parse_bytes(b"import os\n# coding: latin-1\n");
+        assert_eq!(source.get_encoding_name(), "UTF-8");
+        let (_, source) = parse_bytes(b"\n\n# coding: latin-1\n");
+        assert_eq!(source.get_encoding_name(), "UTF-8");
+    }
+
+    #[test]
+    fn test_encoding_problems() {
+        let code = b"# coding: klingon\nx = 1\n";
+        let (tree, source) = parse_bytes(code);
+        let errors = tree.get_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "unknown encoding: klingon");
+        assert_eq!(&source.text[errors[0].range], "klingon");
+
+        let (tree, _) = parse_bytes(b"\xEF\xBB\xBF# coding: latin-1\nx = 1\n");
+        assert_eq!(
+            tree.get_errors()[0].message,
+            "encoding problem: latin-1 with BOM"
+        );
+        let (tree, _) = parse_bytes(b"# coding: utf-16\nx = 1\n");
+        assert_eq!(tree.get_errors()[0].message, "encoding problem: utf-16");
+    }
+
+    #[test]
+    fn test_undecodable_bytes() {
+        let bytes = b"a = '\xFF\xFE'\nb = 'ok'\n";
+        let (tree, source) = parse_bytes(bytes);
+        let errors = tree.get_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].message, "invalid UTF-8 byte sequence");
+        assert_eq!(source.text, "a = '\u{FFFD}\u{FFFD}'\nb = 'ok'\n");
+        // the replacement characters map back to the bytes they stand for
+        assert_eq!(
+            usize::from(errors[1].range.start()),
+            source.to_text_offset(6)
+        );
+        let b = bytes.iter().position(|b| *b == b'b').unwrap();
+        assert_eq!(&source.text[source.to_text_offset(b)..], "b = 'ok'\n");
+    }
+}
@@ -5,6 +5,10 @@ use crate::text::SourceRange;
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PyParseErrorKind {
     SyntaxError,
+    /// A `SyntaxError` of the indentation, as CPython's `IndentationError`
+    IndentationError,
+    /// An `IndentationError` of tabs and spaces mixed ambiguously
+    TabError,
     DocError,
     VersionWarning, // Warning for features that require newer Python versions
 }
@@ -33,6 +37,22 @@ impl PyParseError {
         }
     }
 
+    pub fn indentation_error_from(message: &str, range: SourceRange) -> Self {
+        PyParseError {
+            kind: PyParseErrorKind::IndentationError,
+            message: message.to_string(),
+            range: range.into(),
+        }
+    }
+
+    pub fn tab_error_from(message: &str, range: SourceRange) -> Self {
+        PyParseError {
+            kind: PyParseErrorKind::TabError,
+            message: message.to_string(),
+            range: range.into(),
+        }
+    }
+
     pub fn doc_error_from(message: &str, range: SourceRange) -> Self {
         PyParseError {
             kind: PyParseErrorKind::DocError,
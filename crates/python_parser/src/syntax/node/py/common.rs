@@ -1,7 +1,11 @@
-use super::{PyExpr, PyNameExpr, PyStat};
+use super::{PyExpr, PyFuncDef, PyNameExpr, PyStat};
 use crate::{
-    kind::PySyntaxKind,
-    syntax::traits::{PyAstNode, PySyntaxNode},
+    PyNameToken, PyStringToken,
+    kind::{PySyntaxKind, PyTokenKind},
+    syntax::{
+        PySyntaxToken,
+        traits::{PyAstNode, PySyntaxNode},
+    },
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -101,6 +105,35 @@ impl PyAstNode for PyArguments {
         &self.syntax
     }
 
+    fn can_cast(kind: PySyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        kind == PySyntaxKind::Parameters
+    }
+
+    fn cast(syntax: PySyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if Self::can_cast(syntax.kind().into()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyCallArgList {
+    syntax: PySyntaxNode,
+}
+
+impl PyAstNode for PyCallArgList {
+    fn syntax(&self) -> &PySyntaxNode {
+        &self.syntax
+    }
+
     fn can_cast(kind: PySyntaxKind) -> bool
     where
         Self: Sized,
@@ -120,6 +153,93 @@ impl PyAstNode for PyArguments {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyKeyword {
+    syntax: PySyntaxNode,
+}
+
+impl PyAstNode for PyKeyword {
+    fn syntax(&self) -> &PySyntaxNode {
+        &self.syntax
+    }
+
+    fn can_cast(kind: PySyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        kind == PySyntaxKind::Keyword
+    }
+
+    fn cast(syntax: PySyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if Self::can_cast(syntax.kind().into()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyTypeAnnotation {
+    syntax: PySyntaxNode,
+}
+
+impl PyAstNode for PyTypeAnnotation {
+    fn syntax(&self) -> &PySyntaxNode {
+        &self.syntax
+    }
+
+    fn can_cast(kind: PySyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        kind == PySyntaxKind::TypeAnnotation
+    }
+
+    fn cast(syntax: PySyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if Self::can_cast(syntax.kind().into()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyTypeComment {
+    syntax: PySyntaxNode,
+}
+
+impl PyAstNode for PyTypeComment {
+    fn syntax(&self) -> &PySyntaxNode {
+        &self.syntax
+    }
+
+    fn can_cast(kind: PySyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        kind == PySyntaxKind::TypeComment
+    }
+
+    fn cast(syntax: PySyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if Self::can_cast(syntax.kind().into()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PyDecorator {
     syntax: PySyntaxNode,
@@ -149,6 +269,64 @@ impl PyAstNode for PyDecorator {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyDecorators {
+    syntax: PySyntaxNode,
+}
+
+impl PyAstNode for PyDecorators {
+    fn syntax(&self) -> &PySyntaxNode {
+        &self.syntax
+    }
+
+    fn can_cast(kind: PySyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        kind == PySyntaxKind::Decorators
+    }
+
+    fn cast(syntax: PySyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if Self::can_cast(syntax.kind().into()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyDocstring {
+    syntax: PySyntaxNode,
+}
+
+impl PyAstNode for PyDocstring {
+    fn syntax(&self) -> &PySyntaxNode {
+        &self.syntax
+    }
+
+    fn can_cast(kind: PySyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        kind == PySyntaxKind::Docstring
+    }
+
+    fn cast(syntax: PySyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if Self::can_cast(syntax.kind().into()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PyCaseClause {
     syntax: PySyntaxNode,
@@ -192,26 +370,51 @@ impl PySuite {
 }
 
 impl PyParameter {
-    pub fn get_name(&self) -> Option<PyNameExpr> {
-        self.syntax().children().find_map(PyNameExpr::cast)
+    pub fn get_name(&self) -> Option<PyNameToken> {
+        self.token()
     }
 
     pub fn get_annotation(&self) -> Option<PyExpr> {
-        self.syntax().children().filter_map(PyExpr::cast).last()
+        self.child::<PyTypeAnnotation>()?.get_expr()
     }
 
     pub fn get_default_value(&self) -> Option<PyExpr> {
-        // 默认值通常是最后一个表达式（如果存在注解的话）
-        let exprs: Vec<_> = self.syntax().children().filter_map(PyExpr::cast).collect();
-        let count = exprs.len();
-        if count > 1 {
-            exprs.into_iter().nth(count - 2)
-        } else if count == 1 {
-            // 如果只有一个表达式，可能是默认值或注解，需要根据语法判断
-            None
-        } else {
-            None
+        // 注解被包裹在 TypeAnnotation 中，直接子表达式即为默认值
+        self.child()
+    }
+
+    /// The type of the parameter in the signature type comment of its
+    /// function, which leaves out `self` and `cls` of methods
+    pub fn get_type_comment_annotation(&self) -> Option<PyExpr> {
+        let params = self.get_parent::<PyArguments>()?;
+        let func = params.get_parent::<PyFuncDef>()?;
+        let types: Vec<PyExpr> = func.get_type_comment()?.get_param_types().collect();
+        // `(...) -> int` only gives the return type
+        if types.len() == 1 && types[0].syntax().text() == "..." {
+            return None;
         }
+        let args: Vec<PyParameter> = params
+            .get_args()
+            .filter(|param| !param.is_star_separator())
+            .collect();
+        let skipped = args.len().checked_sub(types.len()).filter(|it| *it <= 1)?;
+        let index = args.iter().position(|param| param == self)?;
+        types.get(index.checked_sub(skipped)?).cloned()
+    }
+
+    /// `*args`
+    pub fn is_vararg(&self) -> bool {
+        self.token_by_kind(PyTokenKind::TkMul).is_some() && self.get_name().is_some()
+    }
+
+    /// `**kwargs`
+    pub fn is_kwarg(&self) -> bool {
+        self.token_by_kind(PyTokenKind::TkPow).is_some()
+    }
+
+    /// A bare `*` which only marks the start of keyword-only parameters
+    pub fn is_star_separator(&self) -> bool {
+        self.token_by_kind(PyTokenKind::TkMul).is_some() && self.get_name().is_none()
     }
 }
 
@@ -220,26 +423,117 @@ impl PyArguments {
         self.syntax().children().filter_map(PyParameter::cast)
     }
 
+    /// The `/` token separating positional-only parameters
+    pub fn get_posonly_separator(&self) -> Option<PySyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == PyTokenKind::TkDiv.into())
+    }
+
     pub fn get_posonlyargs(&self) -> impl Iterator<Item = PyParameter> + '_ {
         // 位置参数在 / 符号之前
-        self.syntax()
-            .children()
-            .filter_map(PyParameter::cast)
-            .take_while(|_| {
-                // 这里需要更精确的逻辑来识别 / 分隔符
-                true
-            })
+        let separator = self
+            .get_posonly_separator()
+            .map(|token| token.text_range().start());
+        self.get_args()
+            .take_while(move |param| separator.is_some_and(|pos| param.get_position() < pos))
     }
 
     pub fn get_kwonlyargs(&self) -> impl Iterator<Item = PyParameter> + '_ {
-        // 关键字参数在 * 符号之后
+        // 关键字参数在 * 或 *args 之后
+        let star = self
+            .get_args()
+            .find(|param| param.is_vararg() || param.is_star_separator())
+            .map(|param| param.get_position());
+        self.get_args().filter(move |param| {
+            star.is_some_and(|pos| param.get_position() > pos) && !param.is_kwarg()
+        })
+    }
+
+    pub fn get_vararg(&self) -> Option<PyParameter> {
+        self.get_args().find(|param| param.is_vararg())
+    }
+
+    pub fn get_kwarg(&self) -> Option<PyParameter> {
+        self.get_args().find(|param| param.is_kwarg())
+    }
+}
+
+impl PyCallArgList {
+    /// Positional arguments, including `*args` and `**kwargs` unpacking
+    pub fn get_args(&self) -> impl Iterator<Item = PyExpr> + '_ {
+        self.syntax().children().filter_map(PyExpr::cast)
+    }
+
+    pub fn get_keywords(&self) -> impl Iterator<Item = PyKeyword> + '_ {
+        self.syntax().children().filter_map(PyKeyword::cast)
+    }
+}
+
+impl PyKeyword {
+    pub fn get_name(&self) -> Option<PyNameToken> {
+        self.child::<PyNameExpr>()?.get_name_token()
+    }
+
+    pub fn get_value(&self) -> Option<PyExpr> {
+        self.syntax().children().filter_map(PyExpr::cast).nth(1)
+    }
+}
+
+impl PyTypeAnnotation {
+    pub fn get_expr(&self) -> Option<PyExpr> {
+        self.child()
+    }
+}
+
+impl PyTypeComment {
+    /// `(int, str) -> bool` rather than a single type
+    pub fn is_signature(&self) -> bool {
+        self.token_by_kind(PyTokenKind::TkArrow).is_some()
+    }
+
+    /// The type of an assignment, `for` or `with` target
+    pub fn get_type(&self) -> Option<PyExpr> {
+        if self.is_signature() {
+            return None;
+        }
+        self.child()
+    }
+
+    /// The types of the parameters of a signature, `*` and `**` are kept as
+    /// tokens before their type
+    pub fn get_param_types(&self) -> impl Iterator<Item = PyExpr> + '_ {
         self.syntax()
-            .children()
-            .filter_map(PyParameter::cast)
-            .skip_while(|_| {
-                // 这里需要更精确的逻辑来识别 * 分隔符
-                true
-            })
+            .children_with_tokens()
+            .take_while(|it| it.kind() != PyTokenKind::TkArrow.into())
+            .filter_map(|it| it.into_node())
+            .filter_map(PyExpr::cast)
+    }
+
+    pub fn get_return_type(&self) -> Option<PyExpr> {
+        self.syntax()
+            .children_with_tokens()
+            .skip_while(|it| it.kind() != PyTokenKind::TkArrow.into())
+            .filter_map(|it| it.into_node())
+            .find_map(PyExpr::cast)
+    }
+}
+
+impl PyDecorators {
+    pub fn get_decorators(&self) -> impl Iterator<Item = PyDecorator> + '_ {
+        self.syntax().children().filter_map(PyDecorator::cast)
+    }
+}
+
+impl PyDocstring {
+    pub fn get_string_token(&self) -> Option<PyStringToken> {
+        self.token()
+    }
+
+    /// The docstring value with quotes removed and escapes processed
+    pub fn get_content(&self) -> Option<String> {
+        Some(self.get_string_token()?.get_value())
     }
 }
 
@@ -1,8 +1,8 @@
 use rowan::NodeOrToken;
 
-use super::PyArguments;
+use super::{PyArguments, PyCallArgList};
 use crate::{
-    PyLiteralToken, PyNameToken, PyTokenKind,
+    PyGeneralToken, PyLiteralToken, PyNameToken, PyTokenKind,
     kind::PySyntaxKind,
     syntax::traits::{PyAstNode, PySyntaxNode},
 };
@@ -72,6 +72,10 @@ py_expr_ast!(
     PyDictCompExpr: DictCompExpr,
     PySetCompExpr: SetCompExpr,
     PyGeneratorExpr: GeneratorExpr,
+    PyFStringExpr: FStringExpr,
+    PyTStringExpr: TStringExpr,
+    // IPython expressions
+    PyMagicExpr: MagicExpr,
     // Python 3.9+ expressions
     PyDictMergeExpr: DictMergeExpr,
     PyDictUpdateExpr: DictUpdateExpr,
@@ -114,6 +118,9 @@ pub enum PyExpr {
     DictCompExpr(PyDictCompExpr),
     SetCompExpr(PySetCompExpr),
     GeneratorExpr(PyGeneratorExpr),
+    FStringExpr(PyFStringExpr),
+    TStringExpr(PyTStringExpr),
+    MagicExpr(PyMagicExpr),
 }
 
 impl PyAstNode for PyExpr {
@@ -148,6 +155,9 @@ impl PyAstNode for PyExpr {
             PyExpr::DictCompExpr(node) => node.syntax(),
             PyExpr::SetCompExpr(node) => node.syntax(),
             PyExpr::GeneratorExpr(node) => node.syntax(),
+            PyExpr::FStringExpr(node) => node.syntax(),
+            PyExpr::TStringExpr(node) => node.syntax(),
+            PyExpr::MagicExpr(node) => node.syntax(),
         }
     }
 
@@ -186,6 +196,9 @@ impl PyAstNode for PyExpr {
                 | PySyntaxKind::DictCompExpr
                 | PySyntaxKind::SetCompExpr
                 | PySyntaxKind::GeneratorExpr
+                | PySyntaxKind::FStringExpr
+                | PySyntaxKind::TStringExpr
+                | PySyntaxKind::MagicExpr
         )
     }
 
@@ -229,6 +242,9 @@ impl PyAstNode for PyExpr {
             PySyntaxKind::DictCompExpr => PyDictCompExpr::cast(syntax).map(PyExpr::DictCompExpr),
             PySyntaxKind::SetCompExpr => PySetCompExpr::cast(syntax).map(PyExpr::SetCompExpr),
             PySyntaxKind::GeneratorExpr => PyGeneratorExpr::cast(syntax).map(PyExpr::GeneratorExpr),
+            PySyntaxKind::FStringExpr => PyFStringExpr::cast(syntax).map(PyExpr::FStringExpr),
+            PySyntaxKind::TStringExpr => PyTStringExpr::cast(syntax).map(PyExpr::TStringExpr),
+            PySyntaxKind::MagicExpr => PyMagicExpr::cast(syntax).map(PyExpr::MagicExpr),
             _ => None,
         }
     }
@@ -345,10 +361,13 @@ impl PyAttributeExpr {
     }
 
     pub fn get_attr(&self) -> Option<String> {
-        self.syntax()
-            .children_with_tokens()
-            .find_map(|child| child.into_token())
-            .map(|token| token.text().to_string())
+        self.get_attr_token()
+            .map(|token| token.get_name_text().to_string())
+    }
+
+    // 点号之后的属性名
+    pub fn get_attr_token(&self) -> Option<PyNameToken> {
+        self.token()
     }
 }
 
@@ -411,20 +430,14 @@ impl PyBinaryExpr {
 
     pub fn get_op(&self) -> Option<String> {
         // 二元操作符通常在两个表达式之间
-        self.syntax()
-            .children_with_tokens()
-            .find_map(|child| child.into_token())
-            .map(|token| token.text().to_string())
+        first_op_token(self.syntax())
     }
 }
 
 impl PyBoolOpExpr {
     pub fn get_op(&self) -> Option<String> {
         // and 或 or
-        self.syntax()
-            .children_with_tokens()
-            .find_map(|child| child.into_token())
-            .map(|token| token.text().to_string())
+        first_op_token(self.syntax())
     }
 
     pub fn get_values(&self) -> impl Iterator<Item = PyExpr> + '_ {
@@ -432,6 +445,20 @@ impl PyBoolOpExpr {
     }
 }
 
+// 跳过空白和注释, 取第一个操作符 token
+fn first_op_token(syntax: &PySyntaxNode) -> Option<String> {
+    syntax
+        .children_with_tokens()
+        .filter_map(|child| child.into_token())
+        .find(|token| {
+            !matches!(
+                token.kind().into(),
+                PyTokenKind::TkWhitespace | PyTokenKind::TkComment | PyTokenKind::TkNewline
+            )
+        })
+        .map(|token| token.text().to_string())
+}
+
 impl PyCompareExpr {
     pub fn get_left(&self) -> Option<PyExpr> {
         self.syntax().children().find_map(PyExpr::cast)
@@ -447,8 +474,14 @@ impl PyCallExpr {
         self.syntax().children().find_map(PyExpr::cast)
     }
 
+    pub fn get_args_list(&self) -> Option<PyCallArgList> {
+        self.child()
+    }
+
     pub fn get_args(&self) -> impl Iterator<Item = PyExpr> + '_ {
-        self.syntax().children().filter_map(PyExpr::cast).skip(1)
+        self.get_args_list()
+            .into_iter()
+            .flat_map(|args| args.children::<PyExpr>())
     }
 }
 
@@ -520,3 +553,17 @@ impl PyAssignExpr {
         self.syntax().children().filter_map(PyExpr::cast).nth(1)
     }
 }
+
+impl PyFStringExpr {
+    // 花括号内嵌入的表达式
+    pub fn get_exprs(&self) -> impl Iterator<Item = PyExpr> + '_ {
+        self.syntax().children().filter_map(PyExpr::cast)
+    }
+}
+
+impl PyMagicExpr {
+    // 赋值右侧的 `!ls` 或 `%who_ls`
+    pub fn get_magic_token(&self) -> Option<PyGeneralToken> {
+        super::stat::magic_token(self.syntax())
+    }
+}
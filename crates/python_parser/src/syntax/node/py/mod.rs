@@ -22,7 +22,13 @@ pub enum PyAst {
     Suite(PySuite),
     Parameter(PyParameter),
     Arguments(PyArguments),
+    CallArgList(PyCallArgList),
+    Keyword(PyKeyword),
+    TypeAnnotation(PyTypeAnnotation),
+    TypeComment(PyTypeComment),
     Decorator(PyDecorator),
+    Decorators(PyDecorators),
+    Docstring(PyDocstring),
     CaseClause(PyCaseClause),
 
     // Statement types
@@ -66,6 +72,8 @@ pub enum PyAst {
     // Python 3.14+ Experimental Features
     Decorated(PyDecorated),
     AsyncCompStmt(PyAsyncCompStmt),
+    // IPython syntax
+    MagicStmt(PyMagicStmt),
 
     // Expression types
     NameExpr(PyNameExpr),
@@ -97,6 +105,9 @@ pub enum PyAst {
     DictCompExpr(PyDictCompExpr),
     SetCompExpr(PySetCompExpr),
     GeneratorExpr(PyGeneratorExpr),
+    FStringExpr(PyFStringExpr),
+    TStringExpr(PyTStringExpr),
+    MagicExpr(PyMagicExpr),
     // Python 3.9+ Features
     DictMergeExpr(PyDictMergeExpr),
     UnionTypeExpr(PyUnionTypeExpr),
@@ -126,7 +137,13 @@ impl PyAstNode for PyAst {
             PyAst::Suite(node) => node.syntax(),
             PyAst::Parameter(node) => node.syntax(),
             PyAst::Arguments(node) => node.syntax(),
+            PyAst::CallArgList(node) => node.syntax(),
+            PyAst::Keyword(node) => node.syntax(),
+            PyAst::TypeAnnotation(node) => node.syntax(),
+            PyAst::TypeComment(node) => node.syntax(),
             PyAst::Decorator(node) => node.syntax(),
+            PyAst::Decorators(node) => node.syntax(),
+            PyAst::Docstring(node) => node.syntax(),
             PyAst::CaseClause(node) => node.syntax(),
 
             // Statement types
@@ -170,6 +187,7 @@ impl PyAstNode for PyAst {
             // Python 3.14+ Experimental Features
             PyAst::Decorated(node) => node.syntax(),
             PyAst::AsyncCompStmt(node) => node.syntax(),
+            PyAst::MagicStmt(node) => node.syntax(),
 
             // Expression types
             PyAst::NameExpr(node) => node.syntax(),
@@ -201,6 +219,9 @@ impl PyAstNode for PyAst {
             PyAst::DictCompExpr(node) => node.syntax(),
             PyAst::SetCompExpr(node) => node.syntax(),
             PyAst::GeneratorExpr(node) => node.syntax(),
+            PyAst::FStringExpr(node) => node.syntax(),
+            PyAst::TStringExpr(node) => node.syntax(),
+            PyAst::MagicExpr(node) => node.syntax(),
             // Python 3.9+ Features
             PyAst::DictMergeExpr(node) => node.syntax(),
             PyAst::UnionTypeExpr(node) => node.syntax(),
@@ -233,8 +254,14 @@ impl PyAstNode for PyAst {
             PySyntaxKind::Module
             | PySyntaxKind::Suite
             | PySyntaxKind::Parameter
+            | PySyntaxKind::Parameters
             | PySyntaxKind::Arguments
+            | PySyntaxKind::Keyword
+            | PySyntaxKind::TypeAnnotation
+            | PySyntaxKind::TypeComment
             | PySyntaxKind::Decorator
+            | PySyntaxKind::Decorators
+            | PySyntaxKind::Docstring
             | PySyntaxKind::CaseClause
             // Statement types
             | PySyntaxKind::ExprStmt
@@ -277,6 +304,7 @@ impl PyAstNode for PyAst {
             // Python 3.14+ Experimental Features
             | PySyntaxKind::Decorated
             | PySyntaxKind::AsyncCompStmt
+            | PySyntaxKind::MagicStmt
             // Expression types
             | PySyntaxKind::NameExpr
             | PySyntaxKind::LiteralExpr
@@ -306,6 +334,9 @@ impl PyAstNode for PyAst {
             | PySyntaxKind::DictCompExpr
             | PySyntaxKind::SetCompExpr
             | PySyntaxKind::GeneratorExpr
+            | PySyntaxKind::FStringExpr
+            | PySyntaxKind::TStringExpr
+            | PySyntaxKind::MagicExpr
             // Python 3.9+ Features
             | PySyntaxKind::DictMergeExpr
             | PySyntaxKind::UnionType
@@ -338,12 +369,28 @@ impl PyAstNode for PyAst {
             PyKind::Syntax(PySyntaxKind::Parameter) => {
                 PyParameter::cast(syntax).map(PyAst::Parameter)
             }
-            PyKind::Syntax(PySyntaxKind::Arguments) => {
+            PyKind::Syntax(PySyntaxKind::Parameters) => {
                 PyArguments::cast(syntax).map(PyAst::Arguments)
             }
+            PyKind::Syntax(PySyntaxKind::Arguments) => {
+                PyCallArgList::cast(syntax).map(PyAst::CallArgList)
+            }
+            PyKind::Syntax(PySyntaxKind::Keyword) => PyKeyword::cast(syntax).map(PyAst::Keyword),
+            PyKind::Syntax(PySyntaxKind::TypeAnnotation) => {
+                PyTypeAnnotation::cast(syntax).map(PyAst::TypeAnnotation)
+            }
+            PyKind::Syntax(PySyntaxKind::TypeComment) => {
+                PyTypeComment::cast(syntax).map(PyAst::TypeComment)
+            }
             PyKind::Syntax(PySyntaxKind::Decorator) => {
                 PyDecorator::cast(syntax).map(PyAst::Decorator)
             }
+            PyKind::Syntax(PySyntaxKind::Decorators) => {
+                PyDecorators::cast(syntax).map(PyAst::Decorators)
+            }
+            PyKind::Syntax(PySyntaxKind::Docstring) => {
+                PyDocstring::cast(syntax).map(PyAst::Docstring)
+            }
             PyKind::Syntax(PySyntaxKind::CaseClause) => {
                 PyCaseClause::cast(syntax).map(PyAst::CaseClause)
             }
@@ -496,6 +543,18 @@ impl PyAstNode for PyAst {
             PyKind::Syntax(PySyntaxKind::GeneratorExpr) => {
                 PyGeneratorExpr::cast(syntax).map(PyAst::GeneratorExpr)
             }
+            PyKind::Syntax(PySyntaxKind::FStringExpr) => {
+                PyFStringExpr::cast(syntax).map(PyAst::FStringExpr)
+            }
+            PyKind::Syntax(PySyntaxKind::TStringExpr) => {
+                PyTStringExpr::cast(syntax).map(PyAst::TStringExpr)
+            }
+            PyKind::Syntax(PySyntaxKind::MagicStmt) => {
+                PyMagicStmt::cast(syntax).map(PyAst::MagicStmt)
+            }
+            PyKind::Syntax(PySyntaxKind::MagicExpr) => {
+                PyMagicExpr::cast(syntax).map(PyAst::MagicExpr)
+            }
 
             _ => None,
         }
@@ -1,7 +1,11 @@
-use super::{PyArguments, PyCaseClause, PyDecorator, PyExpr, PyNameExpr, PySuite};
+use super::{
+    PyArguments, PyCallArgList, PyCaseClause, PyDecorator, PyDecorators, PyDocstring, PyExpr,
+    PyKeyword, PyNameExpr, PySuite, PyTypeAnnotation, PyTypeComment,
+};
 use crate::{
+    PyGeneralToken, PyNameToken, PyTokenKind,
     kind::PySyntaxKind,
-    syntax::traits::{PyAstNode, PySyntaxNode},
+    syntax::traits::{PyAstNode, PyAstToken, PySyntaxNode},
 };
 
 macro_rules! py_stat_ast {
@@ -79,7 +83,9 @@ py_stat_ast!(
     PyGenericClassDef: GenericClassDef,
     // Python 3.14+ Experimental Features
     PyDecorated: Decorated,
-    PyAsyncCompStmt: AsyncCompStmt
+    PyAsyncCompStmt: AsyncCompStmt,
+    // IPython syntax
+    PyMagicStmt: MagicStmt
 );
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -124,6 +130,8 @@ pub enum PyStat {
     // Python 3.14+ Experimental Features
     Decorated(PyDecorated),
     AsyncCompStmt(PyAsyncCompStmt),
+    // IPython syntax
+    MagicStmt(PyMagicStmt),
 }
 
 impl PyAstNode for PyStat {
@@ -169,6 +177,7 @@ impl PyAstNode for PyStat {
             // Python 3.14+ Experimental Features
             PyStat::Decorated(node) => node.syntax(),
             PyStat::AsyncCompStmt(node) => node.syntax(),
+            PyStat::MagicStmt(node) => node.syntax(),
         }
     }
 
@@ -218,6 +227,7 @@ impl PyAstNode for PyStat {
                 // Python 3.14+ Experimental Features
                 | PySyntaxKind::Decorated
                 | PySyntaxKind::AsyncCompStmt
+                | PySyntaxKind::MagicStmt
         )
     }
 
@@ -274,6 +284,7 @@ impl PyAstNode for PyStat {
             // Python 3.14+ Experimental Features
             PySyntaxKind::Decorated => PyDecorated::cast(syntax).map(PyStat::Decorated),
             PySyntaxKind::AsyncCompStmt => PyAsyncCompStmt::cast(syntax).map(PyStat::AsyncCompStmt),
+            PySyntaxKind::MagicStmt => PyMagicStmt::cast(syntax).map(PyStat::MagicStmt),
             _ => None,
         }
     }
@@ -294,6 +305,11 @@ impl PyAssignStmt {
     pub fn get_value(&self) -> Option<PyExpr> {
         self.syntax().children().filter_map(PyExpr::cast).last()
     }
+
+    /// `x = []  # type: List[int]`
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.child()
+    }
 }
 
 impl PyAnnAssignStmt {
@@ -321,68 +337,119 @@ impl PyAugAssignStmt {
 }
 
 impl PyFuncDef {
-    pub fn get_name(&self) -> Option<PyNameExpr> {
-        self.syntax().children().find_map(PyNameExpr::cast)
+    pub fn get_name(&self) -> Option<PyNameToken> {
+        self.token()
     }
 
     pub fn get_parameters(&self) -> Option<PyArguments> {
-        self.syntax().children().find_map(PyArguments::cast)
+        self.child()
     }
 
     pub fn get_return_annotation(&self) -> Option<PyExpr> {
-        // 返回类型注解通常在参数列表之后
-        self.syntax().children().filter_map(PyExpr::cast).last()
+        // 返回类型注解被包裹在参数列表之后的 TypeAnnotation 中
+        self.child::<PyTypeAnnotation>()?.get_expr()
+    }
+
+    /// `# type: (int, str) -> bool` after the colon
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.child()
     }
 
     pub fn get_body(&self) -> Option<PySuite> {
-        self.syntax().children().find_map(PySuite::cast)
+        self.child()
+    }
+
+    pub fn get_docstring(&self) -> Option<PyDocstring> {
+        self.get_body()?.child()
+    }
+
+    /// `async def` is parsed as an AsyncFuncDef wrapping the FuncDef
+    pub fn is_async(&self) -> bool {
+        self.get_parent::<PyAsyncFuncDef>().is_some()
     }
 
     pub fn get_decorators(&self) -> impl Iterator<Item = PyDecorator> + '_ {
-        self.syntax().children().filter_map(PyDecorator::cast)
+        let definition = match self.get_parent::<PyAsyncFuncDef>() {
+            Some(async_def) => async_def.syntax().clone(),
+            None => self.syntax().clone(),
+        };
+        decorators_before(&definition)
     }
 }
 
 impl PyAsyncFuncDef {
-    pub fn get_name(&self) -> Option<PyNameExpr> {
-        self.syntax().children().find_map(PyNameExpr::cast)
+    pub fn get_func_def(&self) -> Option<PyFuncDef> {
+        self.child()
+    }
+
+    pub fn get_name(&self) -> Option<PyNameToken> {
+        self.get_func_def()?.get_name()
     }
 
     pub fn get_parameters(&self) -> Option<PyArguments> {
-        self.syntax().children().find_map(PyArguments::cast)
+        self.get_func_def()?.get_parameters()
     }
 
     pub fn get_return_annotation(&self) -> Option<PyExpr> {
-        self.syntax().children().filter_map(PyExpr::cast).last()
+        self.get_func_def()?.get_return_annotation()
+    }
+
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.get_func_def()?.get_type_comment()
     }
 
     pub fn get_body(&self) -> Option<PySuite> {
-        self.syntax().children().find_map(PySuite::cast)
+        self.get_func_def()?.get_body()
     }
 
     pub fn get_decorators(&self) -> impl Iterator<Item = PyDecorator> + '_ {
-        self.syntax().children().filter_map(PyDecorator::cast)
+        decorators_before(self.syntax())
     }
 }
 
 impl PyClassDef {
-    pub fn get_name(&self) -> Option<PyNameExpr> {
-        self.syntax().children().find_map(PyNameExpr::cast)
+    pub fn get_name(&self) -> Option<PyNameToken> {
+        self.token()
+    }
+
+    pub fn get_arguments(&self) -> Option<PyCallArgList> {
+        self.child()
     }
 
     pub fn get_bases(&self) -> impl Iterator<Item = PyExpr> + '_ {
-        self.syntax().children().filter_map(PyExpr::cast)
+        self.get_arguments()
+            .into_iter()
+            .flat_map(|arguments| arguments.children::<PyExpr>())
+    }
+
+    pub fn get_keywords(&self) -> impl Iterator<Item = PyKeyword> + '_ {
+        self.get_arguments()
+            .into_iter()
+            .flat_map(|arguments| arguments.children::<PyKeyword>())
     }
 
     pub fn get_body(&self) -> Option<PySuite> {
-        self.syntax().children().find_map(PySuite::cast)
+        self.child()
+    }
+
+    pub fn get_docstring(&self) -> Option<PyDocstring> {
+        self.get_body()?.child()
     }
 
     pub fn get_decorators(&self) -> impl Iterator<Item = PyDecorator> + '_ {
-        self.syntax().children().filter_map(PyDecorator::cast)
+        decorators_before(self.syntax())
     }
 }
 
+// 装饰器列表是被装饰定义的前一个兄弟节点
+fn decorators_before(definition: &PySyntaxNode) -> impl Iterator<Item = PyDecorator> + use<> {
+    definition
+        .prev_sibling()
+        .and_then(PyDecorators::cast)
+        .into_iter()
+        .flat_map(|decorators| decorators.children::<PyDecorator>())
+}
+
 impl PyIfStmt {
     pub fn get_test(&self) -> Option<PyExpr> {
         self.syntax().children().find_map(PyExpr::cast)
@@ -431,6 +498,10 @@ impl PyForStmt {
     pub fn get_else_clause(&self) -> Option<PyElseStmt> {
         self.syntax().children().find_map(PyElseStmt::cast)
     }
+
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.child()
+    }
 }
 
 impl PyAsyncForStmt {
@@ -449,6 +520,10 @@ impl PyAsyncForStmt {
     pub fn get_else_clause(&self) -> Option<PyElseStmt> {
         self.syntax().children().find_map(PyElseStmt::cast)
     }
+
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.child()
+    }
 }
 
 impl PyWithStmt {
@@ -459,6 +534,10 @@ impl PyWithStmt {
     pub fn get_body(&self) -> Option<PySuite> {
         self.syntax().children().find_map(PySuite::cast)
     }
+
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.child()
+    }
 }
 
 impl PyAsyncWithStmt {
@@ -469,6 +548,10 @@ impl PyAsyncWithStmt {
     pub fn get_body(&self) -> Option<PySuite> {
         self.syntax().children().find_map(PySuite::cast)
     }
+
+    pub fn get_type_comment(&self) -> Option<PyTypeComment> {
+        self.child()
+    }
 }
 
 impl PyTryStmt {
@@ -580,3 +663,18 @@ impl PyElseStmt {
         self.syntax().children().find_map(PySuite::cast)
     }
 }
+
+impl PyMagicStmt {
+    // 魔法命令、shell 命令或帮助的整行文本
+    pub fn get_magic_token(&self) -> Option<PyGeneralToken> {
+        magic_token(self.syntax())
+    }
+}
+
+pub(super) fn magic_token(syntax: &PySyntaxNode) -> Option<PyGeneralToken> {
+    syntax
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| PyTokenKind::from(it.kind()).is_ipython_magic())
+        .and_then(PyGeneralToken::cast)
+}
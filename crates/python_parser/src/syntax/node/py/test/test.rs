@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod test {
     use crate::ParserConfig;
 
@@ -602,13 +603,13 @@ match value:
         println!("✅ Function calls and attribute access");
         println!("✅ Basic import statements");
         println!("✅ Assignment statements (=, +=, -=, *=, etc.)");
-        println!("");
+        println!();
         println!("⚠️ Statement-level parsing has basic implementation but needs testing:");
         println!("   - Function definitions (def) - implemented");
         println!("   - Class definitions (class) - implemented");
         println!("   - Control flow (if/elif/else) - implemented");
         println!("   - Compound statements with indentation - implemented");
-        println!("");
+        println!();
         println!("❌ Still not implemented:");
         println!("   - for/while loops");
         println!("   - try/except/finally");
@@ -1066,4 +1067,162 @@ with (
         println!("   for Python 3.14 with optimized performance!");
         println!("🚀 Ready for grammar implementation phase!");
     }
+
+    #[test]
+    fn test_func_def_accessors() {
+        use crate::{PyAstNode, PyFuncDef, PyParser};
+        let code = r#"@dec
+async def foo(a, b: int = 1, /, c=2, *args, d, e: str = "x", **kw) -> int:
+    """Doc here."""
+    return a
+"#;
+        let tree = PyParser::parse(code, ParserConfig::default(), None);
+        let func = tree
+            .get_red_root()
+            .descendants()
+            .find_map(PyFuncDef::cast)
+            .unwrap();
+        assert_eq!(func.get_name().unwrap().get_name_text(), "foo");
+        assert!(func.is_async());
+        assert_eq!(func.get_decorators().count(), 1);
+        assert_eq!(func.get_return_annotation().unwrap().get_text(), "int");
+        assert_eq!(
+            func.get_docstring().unwrap().get_content().unwrap(),
+            "Doc here."
+        );
+
+        let params = func.get_parameters().unwrap();
+        let names = |iter: &mut dyn Iterator<Item = crate::PyParameter>| {
+            iter.filter_map(|param| param.get_name())
+                .map(|name| name.get_name_text().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&mut params.get_posonlyargs()), vec!["a", "b"]);
+        assert_eq!(names(&mut params.get_kwonlyargs()), vec!["d", "e"]);
+        assert_eq!(
+            params
+                .get_vararg()
+                .unwrap()
+                .get_name()
+                .unwrap()
+                .get_name_text(),
+            "args"
+        );
+        assert_eq!(
+            params
+                .get_kwarg()
+                .unwrap()
+                .get_name()
+                .unwrap()
+                .get_name_text(),
+            "kw"
+        );
+
+        let b = params.get_args().nth(1).unwrap();
+        assert_eq!(b.get_annotation().unwrap().get_text(), "int");
+        assert_eq!(b.get_default_value().unwrap().get_text(), "1");
+        let a = params.get_args().next().unwrap();
+        assert!(a.get_annotation().is_none());
+        assert!(a.get_default_value().is_none());
+    }
+
+    #[test]
+    fn test_class_def_accessors() {
+        use crate::{PyAstNode, PyClassDef, PyParser};
+        let code = r#"class A(B, metaclass=M):
+    """Cls doc."""
+    x = 1
+"#;
+        let tree = PyParser::parse(code, ParserConfig::default(), None);
+        assert!(tree.get_errors().is_empty());
+        let class = tree
+            .get_red_root()
+            .descendants()
+            .find_map(PyClassDef::cast)
+            .unwrap();
+        assert_eq!(class.get_name().unwrap().get_name_text(), "A");
+        let bases: Vec<_> = class.get_bases().map(|base| base.get_text()).collect();
+        assert_eq!(bases, vec!["B"]);
+        let keyword = class.get_keywords().next().unwrap();
+        assert_eq!(keyword.get_name().unwrap().get_name_text(), "metaclass");
+        assert_eq!(keyword.get_value().unwrap().get_text(), "M");
+        assert_eq!(
+            class.get_docstring().unwrap().get_content().unwrap(),
+            "Cls doc."
+        );
+    }
+
+    #[test]
+    fn test_star_param_annotation_and_lambda_params() {
+        use crate::{PyAstNode, PyFuncDef, PyLambdaExpr, PyParser};
+        let code =
+            "def f(*args: str, **kwargs: int):\n    pass\ng = lambda a, b=1, *c, d, **e: a\n";
+        let tree = PyParser::parse(code, ParserConfig::default(), None);
+        assert!(tree.get_errors().is_empty());
+
+        let root = tree.get_red_root();
+        let params = root
+            .descendants()
+            .find_map(PyFuncDef::cast)
+            .unwrap()
+            .get_parameters()
+            .unwrap();
+        assert_eq!(
+            params
+                .get_vararg()
+                .unwrap()
+                .get_annotation()
+                .unwrap()
+                .get_text(),
+            "str"
+        );
+        assert_eq!(
+            params
+                .get_kwarg()
+                .unwrap()
+                .get_annotation()
+                .unwrap()
+                .get_text(),
+            "int"
+        );
+
+        let lambda_params = root
+            .descendants()
+            .find_map(PyLambdaExpr::cast)
+            .unwrap()
+            .get_parameters()
+            .unwrap();
+        assert_eq!(lambda_params.get_args().count(), 5);
+        assert_eq!(
+            lambda_params
+                .get_args()
+                .nth(1)
+                .unwrap()
+                .get_default_value()
+                .unwrap()
+                .get_text(),
+            "1"
+        );
+        assert_eq!(lambda_params.get_kwonlyargs().count(), 1);
+    }
+
+    #[test]
+    fn test_literal_trailers() {
+        use crate::{PyAstNode, PyCallExpr, PyExpr, PyParser};
+        let code = "s = \"a {}\".format(x)\ny = [1, 2][0]\nz = {}.get(1)\n";
+        let tree = PyParser::parse(code, ParserConfig::default(), None);
+        assert!(tree.get_errors().is_empty());
+
+        let root = tree.get_red_root();
+        let call = root.descendants().find_map(PyCallExpr::cast).unwrap();
+        let Some(PyExpr::AttributeExpr(attribute)) = call.get_func() else {
+            panic!("expected attribute call");
+        };
+        assert!(matches!(
+            attribute.get_value(),
+            Some(PyExpr::LiteralExpr(_))
+        ));
+        assert_eq!(attribute.get_attr().unwrap(), "format");
+        assert_eq!(root.descendants().filter_map(PyCallExpr::cast).count(), 2);
+    }
 }
@@ -6,6 +6,6 @@ mod py_tokens;
 
 // Python exports (new primary interface)
 #[allow(unused)]
-pub use py_number_analyzer::{IntegerOrLarge, float_token_value, int_token_value};
+pub use py_number_analyzer::{FloatOrLarge, IntegerOrLarge, float_token_value, int_token_value};
 pub use py_string_analyzer::string_token_value;
 pub use py_tokens::*;
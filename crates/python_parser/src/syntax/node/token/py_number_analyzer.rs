@@ -1,10 +1,13 @@
+use std::fmt;
+
 use crate::{
     parser_error::{PyParseError, PyParseErrorKind},
     syntax::PySyntaxToken,
 };
 
 pub fn float_token_value(token: &PySyntaxToken) -> Result<FloatOrLarge, PyParseError> {
-    let text = token.text();
+    let text = token.text().replace('_', "");
+    let text = text.as_str();
 
     // Python float formats: 3.14, .5, 5., 1e10, 2.5e-3, 0x1.Ap3 (hex float)
     let hex = text.starts_with("0x") || text.starts_with("0X");
@@ -54,28 +57,15 @@ pub fn float_token_value(token: &PySyntaxToken) -> Result<FloatOrLarge, PyParseE
         }
         value
     } else {
-        // Standard decimal floating-point
-        let (float_part, exponent_part) =
-            if let Some(pos) = text.find('e').or_else(|| text.find('E')) {
-                (&text[..pos], &text[(pos + 1)..])
-            } else {
-                (text, "")
-            };
-
-        let mut value = float_part.parse::<f64>().map_err(|e| {
+        // Standard decimal floating-point, the exponent is parsed with the
+        // digits so the value is rounded once
+        text.parse::<f64>().map_err(|e| {
             PyParseError::new(
                 PyParseErrorKind::SyntaxError,
-                &format!("The float literal '{}' is invalid: {}", text, e),
+                &format!("The float literal '{}' is invalid: {}", token.text(), e),
                 token.text_range(),
             )
-        })?;
-
-        if !exponent_part.is_empty()
-            && let Ok(exp) = exponent_part.parse::<i32>()
-        {
-            value *= 10f64.powi(exp);
-        }
-        value
+        })?
     };
 
     // Check for special float values
@@ -100,10 +90,11 @@ enum IntegerRepr {
     Oct,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IntegerOrLarge {
     Int(i64),
-    Large,
+    // Decimal digits of an integer that does not fit in i64
+    Large(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -120,7 +111,23 @@ impl IntegerOrLarge {
     }
 
     pub fn is_large(&self) -> bool {
-        matches!(self, IntegerOrLarge::Large)
+        matches!(self, IntegerOrLarge::Large(_))
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            IntegerOrLarge::Int(value) => Some(*value),
+            IntegerOrLarge::Large(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for IntegerOrLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegerOrLarge::Int(value) => write!(f, "{}", value),
+            IntegerOrLarge::Large(digits) => write!(f, "{}", digits),
+        }
     }
 }
 
@@ -151,7 +158,9 @@ impl FloatOrLarge {
 }
 
 pub fn int_token_value(token: &PySyntaxToken) -> Result<IntegerOrLarge, PyParseError> {
-    let text = token.text();
+    // Digit separators (PEP 515) carry no value
+    let text = token.text().replace('_', "");
+    let text = text.as_str();
 
     // Determine the representation
     let repr = if text.starts_with("0x") || text.starts_with("0X") {
@@ -170,9 +179,6 @@ pub fn int_token_value(token: &PySyntaxToken) -> Result<IntegerOrLarge, PyParseE
         IntegerRepr::Normal
     };
 
-    // Python integers don't have unsigned suffixes like C/C++
-    let text = text;
-
     // Try to parse as signed integer first
     let signed_value = match repr {
         IntegerRepr::Hex => {
@@ -203,7 +209,16 @@ pub fn int_token_value(token: &PySyntaxToken) -> Result<IntegerOrLarge, PyParseE
                 *e.kind(),
                 std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow
             ) {
-                Ok(IntegerOrLarge::Large)
+                let (digits, radix) = match repr {
+                    IntegerRepr::Hex => (&text[2..], 16),
+                    IntegerRepr::Bin => (&text[2..], 2),
+                    IntegerRepr::Oct if text.starts_with("0o") || text.starts_with("0O") => {
+                        (&text[2..], 8)
+                    }
+                    IntegerRepr::Oct => (&text[1..], 8),
+                    IntegerRepr::Normal => (text, 10),
+                };
+                Ok(IntegerOrLarge::Large(large_int_to_decimal(digits, radix)))
             } else {
                 Err(PyParseError::new(
                     PyParseErrorKind::SyntaxError,
@@ -214,3 +229,40 @@ pub fn int_token_value(token: &PySyntaxToken) -> Result<IntegerOrLarge, PyParseE
         }
     }
 }
+
+/// Convert the digits of an arbitrarily large integer literal to decimal.
+/// Digits are accumulated into base 10^9 limbs, least significant first.
+fn large_int_to_decimal(digits: &str, radix: u32) -> String {
+    const LIMB_BASE: u64 = 1_000_000_000;
+
+    let negative = digits.starts_with('-');
+    let mut limbs: Vec<u64> = vec![0];
+    for c in digits.trim_start_matches(['-', '+']).chars() {
+        let Some(digit) = c.to_digit(radix) else {
+            continue;
+        };
+        let mut carry = digit as u64;
+        for limb in limbs.iter_mut() {
+            let value = *limb * radix as u64 + carry;
+            *limb = value % LIMB_BASE;
+            carry = value / LIMB_BASE;
+        }
+        while carry > 0 {
+            limbs.push(carry % LIMB_BASE);
+            carry /= LIMB_BASE;
+        }
+    }
+
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    let mut iter = limbs.iter().rev();
+    if let Some(first) = iter.next() {
+        result.push_str(&first.to_string());
+    }
+    for limb in iter {
+        result.push_str(&format!("{:09}", limb));
+    }
+    result
+}
@@ -21,7 +21,7 @@ fn raw_string_value(token: &PySyntaxToken) -> Result<String, PyParseError> {
 
     // Raw strings: r"..." or R"..." or r'...' or R'...'
     // Find the quote character
-    let quote_start = text.find(|c| c == '"' || c == '\'').unwrap_or(1);
+    let quote_start = text.find(['"', '\'']).unwrap_or(1);
     let quote_char = text.chars().nth(quote_start).unwrap();
 
     // Check for triple quotes
@@ -44,7 +44,7 @@ fn bytes_string_value(token: &PySyntaxToken) -> Result<String, PyParseError> {
     let text = token.text();
 
     // Skip the 'b' or 'B' prefix
-    let quote_start = text.find(|c| c == '"' || c == '\'').unwrap_or(1);
+    let quote_start = text.find(['"', '\'']).unwrap_or(1);
     let quote_char = text.chars().nth(quote_start).unwrap();
 
     // Check for triple quotes
@@ -67,7 +67,7 @@ fn raw_bytes_string_value(token: &PySyntaxToken) -> Result<String, PyParseError>
     let text = token.text();
 
     // Skip the 'rb' or 'br' or 'RB' or 'BR' prefix
-    let quote_start = text.find(|c| c == '"' || c == '\'').unwrap_or(2);
+    let quote_start = text.find(['"', '\'']).unwrap_or(2);
     let quote_char = text.chars().nth(quote_start).unwrap();
 
     // Check for triple quotes
@@ -90,7 +90,7 @@ fn f_string_value(token: &PySyntaxToken) -> Result<String, PyParseError> {
     // F-strings need special handling for expressions inside {}
     // For now, return the raw content without processing expressions
     let text = token.text();
-    let quote_start = text.find(|c| c == '"' || c == '\'').unwrap_or(1);
+    let quote_start = text.find(['"', '\'']).unwrap_or(1);
     let quote_char = text.chars().nth(quote_start).unwrap();
 
     let is_triple = text.len() > quote_start + 2
@@ -271,7 +271,7 @@ fn normal_string_value(token: &PySyntaxToken) -> Result<String, PyParseError> {
                             // Read up to 2 more octal digits
                             for _ in 0..2 {
                                 if let Some(&digit) = chars.peek() {
-                                    if digit >= '0' && digit <= '7' {
+                                    if ('0'..='7').contains(&digit) {
                                         octal.push(digit);
                                         chars.next();
                                     } else {
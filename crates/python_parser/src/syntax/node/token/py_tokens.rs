@@ -526,11 +526,7 @@ impl PyAstToken for PyCommentToken {
 impl PyCommentToken {
     pub fn get_comment_text(&self) -> &str {
         let text = self.token.text();
-        if text.starts_with('#') {
-            &text[1..]
-        } else {
-            text
-        }
+        text.strip_prefix('#').unwrap_or(text)
     }
 }
 
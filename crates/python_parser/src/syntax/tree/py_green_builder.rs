@@ -153,6 +153,7 @@ impl PyGreenNodeBuilder<'_> {
                 PyGreenElement::Token {
                     kind: PyTokenKind::TkWhitespace
                         | PyTokenKind::TkNewline
+                        | PyTokenKind::TkLineContinuation
                         | PyTokenKind::TkComment,
                     ..
                 } | PyGreenElement::Node {
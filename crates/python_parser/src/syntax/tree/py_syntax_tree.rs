@@ -5,7 +5,7 @@ use crate::{
     syntax::{PySyntaxNode, node::PyModule, traits::PyAstNode},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PySyntaxTree {
     // store GreenNode instead of SyntaxNode, because SyntaxNode is not send and sync
     root: GreenNode,
@@ -32,8 +32,13 @@ impl PySyntaxTree {
     }
 
     pub fn has_syntax_errors(&self) -> bool {
-        self.errors
-            .iter()
-            .any(|e| e.kind == PyParseErrorKind::SyntaxError)
+        self.errors.iter().any(|e| {
+            matches!(
+                e.kind,
+                PyParseErrorKind::SyntaxError
+                    | PyParseErrorKind::IndentationError
+                    | PyParseErrorKind::TabError
+            )
+        })
     }
 }
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod test {
     use crate::{ParserConfig, PyParser};
 
@@ -69,4 +70,23 @@ Syntax(Module)@0..42
 
         assert_ast!(code, expected);
     }
+
+    #[test]
+    fn test_tree_keeps_line_indentation() {
+        let code = "def f():\n    \"\"\"a\n    b\"\"\"\n    x = 1\n\n    # c\n    return x\n";
+        let tree = PyParser::parse(code, ParserConfig::default(), None);
+        assert_eq!(tree.get_red_root().text().to_string(), code);
+    }
+
+    #[test]
+    fn test_tree_keeps_leading_trivia() {
+        for code in [
+            "# region\nx = 1\n",
+            "#!/usr/bin/env python\n# c\nx = 1\n",
+            "# only",
+        ] {
+            let tree = PyParser::parse(code, ParserConfig::default(), None);
+            assert_eq!(tree.get_red_root().text().to_string(), code);
+        }
+    }
 }
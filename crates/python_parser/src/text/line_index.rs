@@ -1,6 +1,6 @@
 use rowan::TextSize;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LineIndex {
     line_offsets: Vec<TextSize>,
     line_only_ascii_vec: Vec<bool>,
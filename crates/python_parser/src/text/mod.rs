@@ -1,8 +1,11 @@
 mod line_index;
 mod reader;
+mod source_encoding;
 mod test;
 mod text_range;
 
 pub use line_index::LineIndex;
 pub use reader::Reader;
+pub(crate) use source_encoding::find_coding_cookie;
+pub use source_encoding::{DecodedSource, SourceEncoding};
 pub use text_range::SourceRange;
@@ -0,0 +1,288 @@
+use encoding_rs::{DecoderResult, Encoding, UTF_8};
+
+use crate::{parser_error::PyParseError, text::SourceRange};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// The text of a source file decoded from its bytes. The encoding comes from
+/// the UTF-8 BOM or the PEP 263 coding declaration, and is UTF-8 without them.
+#[derive(Debug, Clone)]
+pub struct DecodedSource {
+    /// The decoded text, without the BOM
+    pub text: String,
+    pub encoding: SourceEncoding,
+    pub has_bom: bool,
+    /// The problems of the declaration and the undecodable bytes, with ranges
+    /// in the decoded text
+    pub errors: Vec<PyParseError>,
+    /// `(text offset, byte offset)` pairs, from each of which on a character
+    /// has as many bytes in the text as in the file until the next pair
+    offsets: Vec<(usize, usize)>,
+}
+
+/// The encoding of a source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEncoding {
+    /// ISO-8859-1, each byte is the code point of its value. WHATWG has no
+    /// such encoding, its `latin1` label is windows-1252.
+    Latin1,
+    Whatwg(&'static Encoding),
+}
+
+impl SourceEncoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceEncoding::Latin1 => "ISO-8859-1",
+            SourceEncoding::Whatwg(encoding) => encoding.name(),
+        }
+    }
+
+    fn is_ascii_compatible(&self) -> bool {
+        match self {
+            SourceEncoding::Latin1 => true,
+            SourceEncoding::Whatwg(encoding) => encoding.is_ascii_compatible(),
+        }
+    }
+}
+
+impl DecodedSource {
+    pub fn decode(bytes: &[u8]) -> DecodedSource {
+        let has_bom = bytes.starts_with(UTF8_BOM);
+        let start = if has_bom { UTF8_BOM.len() } else { 0 };
+        let cookie = find_coding_cookie(&bytes[start..]);
+
+        // the problems of the declaration are found before decoding, but
+        // their ranges are only known in the text after it
+        let mut problems = Vec::new();
+        let mut encoding = SourceEncoding::Whatwg(UTF_8);
+        if let Some((name, range)) = cookie {
+            let range = (range.0 + start, range.1 + start);
+            match lookup_encoding(name) {
+                None => problems.push((
+                    t!("unknown encoding: %{name}", name = name).to_string(),
+                    range,
+                )),
+                Some(found) if !found.is_ascii_compatible() => problems.push((
+                    t!("encoding problem: %{name}", name = name).to_string(),
+                    range,
+                )),
+                Some(found) if has_bom && found != SourceEncoding::Whatwg(UTF_8) => {
+                    problems.push((
+                        t!("encoding problem: %{name} with BOM", name = name).to_string(),
+                        range,
+                    ))
+                }
+                Some(found) if !has_bom => encoding = found,
+                Some(_) => {}
+            }
+        }
+
+        let mut source = match encoding {
+            SourceEncoding::Latin1 => decode_latin1(bytes, start),
+            SourceEncoding::Whatwg(encoding) => decode_with(bytes, start, encoding, &mut problems),
+        };
+        source.has_bom = has_bom;
+        source.errors = problems
+            .into_iter()
+            .map(|(message, (start, end))| {
+                let start = source.to_text_offset(start);
+                let end = source.to_text_offset(end);
+                PyParseError::syntax_error_from(&message, SourceRange::from_start_end(start, end))
+            })
+            .collect();
+        source
+    }
+
+    pub fn get_encoding_name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    /// The offset in the text of an offset in the file
+    pub fn to_text_offset(&self, byte_offset: usize) -> usize {
+        let index = self
+            .offsets
+            .partition_point(|(_, byte)| *byte <= byte_offset)
+            .saturating_sub(1);
+        let (text, byte) = self.offsets[index];
+        text + byte_offset.saturating_sub(byte)
+    }
+}
+
+/// Decode the bytes from `start` on, adding a problem with the byte range of
+/// each undecodable sequence, which becomes U+FFFD in the text
+fn decode_with(
+    bytes: &[u8],
+    start: usize,
+    encoding: &'static Encoding,
+    problems: &mut Vec<(String, (usize, usize))>,
+) -> DecodedSource {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut text = String::with_capacity(bytes.len());
+    let mut offsets = vec![(0, start)];
+    let mut position = start;
+    loop {
+        let text_start = text.len();
+        let rest = bytes.len() - position;
+        text.reserve(
+            decoder
+                .max_utf8_buffer_length_without_replacement(rest)
+                .unwrap_or(rest),
+        );
+        let (result, read) =
+            decoder.decode_to_string_without_replacement(&bytes[position..], &mut text, true);
+        let mut byte = position;
+        for (index, c) in text[text_start..].char_indices() {
+            let byte_len = encoded_len(encoding, c);
+            byte += byte_len;
+            if byte_len != c.len_utf8() {
+                offsets.push((text_start + index + c.len_utf8(), byte));
+            }
+        }
+        position += read;
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => {}
+            DecoderResult::Malformed(bad, extra) => {
+                let end = position - extra as usize;
+                let bad_start = end - bad as usize;
+                problems.push((
+                    t!(
+                        "invalid %{encoding} byte sequence",
+                        encoding = encoding.name()
+                    )
+                    .to_string(),
+                    (bad_start, end),
+                ));
+                text.push(char::REPLACEMENT_CHARACTER);
+                offsets.push((text.len(), end));
+            }
+        }
+    }
+    DecodedSource {
+        text,
+        encoding: SourceEncoding::Whatwg(encoding),
+        has_bom: false,
+        errors: Vec::new(),
+        offsets,
+    }
+}
+
+/// Decode the bytes from `start` on as ISO-8859-1, where every byte is a
+/// character
+fn decode_latin1(bytes: &[u8], start: usize) -> DecodedSource {
+    let mut text = String::with_capacity(bytes.len() - start);
+    let mut offsets = vec![(0, start)];
+    for (index, byte) in bytes[start..].iter().enumerate() {
+        text.push(*byte as char);
+        if !byte.is_ascii() {
+            offsets.push((text.len(), start + index + 1));
+        }
+    }
+    DecodedSource {
+        text,
+        encoding: SourceEncoding::Latin1,
+        has_bom: false,
+        errors: Vec::new(),
+        offsets,
+    }
+}
+
+/// The number of bytes a character decoded from `encoding` took
+fn encoded_len(encoding: &'static Encoding, c: char) -> usize {
+    if c.is_ascii() || encoding.is_single_byte() {
+        1
+    } else if encoding == UTF_8 {
+        c.len_utf8()
+    } else {
+        let mut buf = [0; 4];
+        encoding.encode(c.encode_utf8(&mut buf)).0.len()
+    }
+}
+
+/// Find the encoding name of a PEP 263 declaration, a comment matching
+/// `coding[:=]\s*([-\w.]+)` on the first line, or on the second line when the
+/// first one is blank or a comment, with its byte range
+pub(crate) fn find_coding_cookie(bytes: &[u8]) -> Option<(&str, (usize, usize))> {
+    let mut line_start = 0;
+    for _ in 0..2 {
+        let line_end = bytes[line_start..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(bytes.len(), |it| line_start + it);
+        let line = &bytes[line_start..line_end];
+        let indent = line
+            .iter()
+            .take_while(|b| matches!(b, b' ' | b'\t' | b'\x0c'))
+            .count();
+        match line.get(indent) {
+            Some(b'#') => {
+                if let Some((start, end)) = find_cookie_in_comment(line) {
+                    let name = std::str::from_utf8(&line[start..end]).ok()?;
+                    return Some((name, (line_start + start, line_start + end)));
+                }
+            }
+            None | Some(b'\r') => {}
+            Some(_) => return None,
+        }
+        if line_end == bytes.len() {
+            break;
+        }
+        line_start = line_end + 1;
+    }
+    None
+}
+
+fn find_cookie_in_comment(line: &[u8]) -> Option<(usize, usize)> {
+    let mut search = 0;
+    while let Some(found) = line[search..]
+        .windows(b"coding".len())
+        .position(|it| it == b"coding")
+    {
+        let mut index = search + found + b"coding".len();
+        search = index;
+        if !matches!(line.get(index), Some(b':' | b'=')) {
+            continue;
+        }
+        index += 1;
+        while matches!(line.get(index), Some(b' ' | b'\t')) {
+            index += 1;
+        }
+        let len = line[index..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+            .count();
+        if len > 0 {
+            return Some((index, index + len));
+        }
+    }
+    None
+}
+
+/// Look up an encoding by its Python name, such as `utf_8` or `latin-1`.
+/// The names Python gives ISO-8859-1 come first, the other ones are looked up
+/// as WHATWG labels.
+fn lookup_encoding(name: &str) -> Option<SourceEncoding> {
+    let label = name.to_ascii_lowercase().replace('_', "-");
+    if matches!(
+        label.as_str(),
+        "latin-1"
+            | "latin1"
+            | "latin"
+            | "l1"
+            | "iso-8859-1"
+            | "iso-8859-1-1987"
+            | "iso8859-1"
+            | "iso8859"
+            | "iso-latin-1"
+            | "8859"
+            | "cp819"
+            | "ibm819"
+            | "iso-ir-100"
+            | "csisolatin1"
+    ) {
+        return Some(SourceEncoding::Latin1);
+    }
+    Encoding::for_label(label.as_bytes())
+        .or_else(|| Encoding::for_label(label.replace('-', "").as_bytes()))
+        .map(SourceEncoding::Whatwg)
+}
@@ -0,0 +1,22 @@
+use std::fs;
+
+use python_code_analysis::PyConfig;
+
+const SCHEMA_PATH: &str = "crates/python_code_analysis/resources/schema.json";
+
+/// Write the JSON schema of the settings, run from the workspace root after
+/// changing `PyConfig`
+fn main() {
+    let current_dir = std::env::current_dir().unwrap();
+    let workspace_dir = current_dir
+        .ancestors()
+        .find(|dir| dir.join("crates").exists())
+        .expect("Unable to find crates directory");
+
+    let schema = schemars::schema_for!(PyConfig);
+    let mut json = serde_json::to_string_pretty(&schema).expect("Unable to serialize schema");
+    json.push('\n');
+    let path = workspace_dir.join(SCHEMA_PATH);
+    fs::write(&path, json).unwrap_or_else(|_| panic!("Unable to write {}", path.display()));
+    println!("Wrote {}", path.display());
+}